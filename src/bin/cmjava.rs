@@ -1,12 +1,8 @@
-use std::{path::PathBuf, rc::Rc};
+use std::{ffi::OsString, path::PathBuf, process::ExitCode};
 
+use anyhow::{anyhow, Context};
 use clap::Parser;
-use cmjava::{
-    class::{ArgumentKind, Class, MethodCode, SimpleArgumentKind},
-    classloader::load_class,
-    executor::run,
-    heap::Heap,
-};
+use cmjava::{classloader::jar::JarFile, Vm, VmError};
 use tracing::Level;
 use tracing_error::ErrorLayer;
 use tracing_subscriber::{
@@ -18,10 +14,59 @@ use tracing_subscriber::{
 #[command(name = "cmjava")]
 #[command(version = clap::crate_version!(), long_version = long_version())]
 struct Cli {
-    #[clap(value_delimiter = ' ')]
-    class_files: Vec<PathBuf>,
+    /// Directories and JARs to search for classes,
+    /// separated by ':' (also accepted as '-cp' or '-classpath')
+    #[arg(long, value_name = "CLASS_PATH", default_value = ".")]
+    class_path: OsString,
+    /// Run the Main-Class of a JAR (also accepted as '-jar'),
+    /// all following arguments are passed to the main method
+    #[arg(long, value_name = "JAR_FILE", conflicts_with = "class_path")]
+    jar: Option<PathBuf>,
+    /// Set a system property
+    #[arg(short = 'D', value_name = "NAME=VALUE")]
+    properties: Vec<String>,
     #[arg(short, long)]
     verbose: bool,
+    /// Class containing the main method, e.g. org.example.Main
+    #[arg(required_unless_present = "jar")]
+    main_class: Option<String>,
+    /// Arguments passed to the main method
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    args: Vec<String>,
+}
+
+/// Translate the options java accepts with a single dash
+/// (e.g. '-cp') into their long form.
+///
+/// Only options before the main class (or the JAR) are touched,
+/// the remaining ones are arguments of the java program.
+fn normalize_args(args: impl Iterator<Item = OsString>) -> Vec<OsString> {
+    let mut args = args.into_iter();
+    // program name
+    let mut normalized: Vec<OsString> = args.next().into_iter().collect();
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("-cp" | "-classpath" | "--class-path") => {
+                normalized.push("--class-path".into());
+                normalized.extend(args.next());
+            },
+            Some("-jar" | "--jar") => {
+                normalized.push("--jar".into());
+                normalized.extend(args.next());
+                // everything after the JAR is a program argument
+                normalized.push("--".into());
+                break;
+            },
+            Some(option) if option.starts_with('-') => normalized.push(arg),
+            // main class
+            _ => {
+                normalized.push(arg);
+                break;
+            },
+        }
+    }
+    normalized.extend(args);
+    normalized
 }
 
 fn log_setup(debug: bool) {
@@ -99,38 +144,52 @@ fn long_version() -> &'static str {
     )
 }
 
-fn main() -> anyhow::Result<()> {
-    let cli = Cli::parse();
+fn main() -> anyhow::Result<ExitCode> {
+    let cli = Cli::parse_from(normalize_args(std::env::args_os()));
     log_setup(cli.verbose);
 
-    let mut heap = Heap::default();
-    let mut bytecode_classes: Vec<Rc<dyn Class>> = Vec::new();
-    for class_file in cli.class_files {
-        bytecode_classes.push(load_class(class_file, &mut heap));
+    let mut vm = Vm::new();
+    let (main_class, args) = match &cli.jar {
+        Some(jar) => {
+            let main_class = JarFile::open(jar)?
+                .manifest()?
+                .main_class
+                .with_context(|| {
+                    format!("no Main-Class in manifest of {}", jar.display())
+                })?;
+            vm.set_class_path(vec![jar.clone()])
+                .map_err(|e| anyhow!(e.to_string()))?;
+            // the positional main class is the first program argument
+            let args = cli.main_class.into_iter().chain(cli.args).collect();
+            (main_class, args)
+        },
+        None => {
+            vm.set_class_path(std::env::split_paths(&cli.class_path).collect())
+                .map_err(|e| anyhow!(e.to_string()))?;
+            let main_class = cli.main_class.expect("required without jar");
+            (main_class, cli.args)
+        },
+    };
+    for property in &cli.properties {
+        let (key, value) = property.split_once('=').unwrap_or((property, ""));
+        vm.set_system_property(key, value);
     }
 
-    let main_descriptor = (
-        vec![ArgumentKind::Array {
-            dimensions: 1,
-            kind: SimpleArgumentKind::Class("java/lang/String".to_string()),
-        }],
-        None,
-    );
-    let main_class = bytecode_classes.last().unwrap().clone();
-    let main = &main_class
-        .get_method(
-            "main",
-            (main_descriptor.0.as_ref(), main_descriptor.1.as_ref()),
-            false,
-        )
-        .0
-        .code;
-    let main = if let MethodCode::Bytecode(code) = main {
-        code
-    } else {
-        panic!("main method is not bytecode");
-    };
-    run(main, &mut heap, main_class);
+    // VmError may contain a java exception, which isn't Send/Sync,
+    // so it can't be converted to anyhow::Error directly
+    let binary_name = main_class.replace('.', "/");
+    vm.load_class_by_name(&binary_name).map_err(|e| {
+        anyhow!("Could not find or load main class {}: {}", main_class, e)
+    })?;
 
-    Ok(())
+    match vm.run_main(&binary_name, &args) {
+        Ok(()) => Ok(ExitCode::SUCCESS),
+        // like java, only use the lowest 8 bits of the status
+        Err(VmError::Exit(status)) => Ok(ExitCode::from(status as u8)),
+        Err(e @ VmError::Exception(_)) => {
+            eprintln!("{}", e);
+            Ok(ExitCode::FAILURE)
+        },
+        Err(e) => Err(anyhow!(e.to_string())),
+    }
 }
@@ -3,13 +3,14 @@ use cmjava::{
         attribute_parser::parse_attributes,
         class_creator::create_bytecode_class, file_parser::parse,
     },
-    heap::Heap,
+    heap::{ClassLoaderId, Heap},
 };
 
 fn main() {
     let mut heap = Heap::default();
     let raw_class = parse("tests/data/hello_world/Main.class").unwrap();
-    let class = parse_attributes(raw_class);
-    let bytecode_class = create_bytecode_class(&class, &mut heap);
+    let class = parse_attributes(raw_class).unwrap();
+    let bytecode_class =
+        create_bytecode_class(&class, ClassLoaderId::APPLICATION, &mut heap);
     println!("{:#?}", bytecode_class)
 }
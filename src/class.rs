@@ -1,13 +1,18 @@
 pub mod access_flags;
+pub mod attributes;
 pub mod builtin_classes;
 pub mod bytecode_classes;
 
 use core::fmt;
 use std::{any::Any, borrow::Cow, cell::RefCell, ops::Range, rc::Rc};
 
-use crate::executor::{
-    frame_stack::StackValue, local_variables::VariableValueOrValue, Frame,
-    OpCode, RuntimeError,
+use crate::{
+    classloader::parse_class_identifier,
+    executor::{
+        frame_stack::StackValue, local_variables::VariableValueOrValue,
+        native_context::NativeContext, resolve_class, OpCode, RuntimeError,
+    },
+    heap::{ClassLoaderId, Heap},
 };
 
 macro_rules! class_identifier {
@@ -136,7 +141,12 @@ macro_rules! class_identifier {
 pub(crate) use class_identifier;
 use enumflags2::BitFlags;
 
-use self::access_flags::ClassAccessFlag;
+use self::{
+    access_flags::{
+        ClassAccessFlag, FieldAccessFlag, MemberAccess, MethodAccessFlag,
+    },
+    attributes::{ClassAttributes, FieldAttributes, MethodAttributes},
+};
 
 #[derive(Eq, Hash, PartialEq, Clone, Debug)]
 pub struct ClassIdentifier {
@@ -173,6 +183,38 @@ impl fmt::Display for ClassIdentifier {
 }
 
 impl ClassIdentifier {
+    /// Returns the name of the identified class
+    /// in the format of `java.lang.Class.getName()`,
+    /// e.g. `java.lang.String` or `[Ljava.lang.String;`.
+    pub fn java_name(&self) -> String {
+        let package = self.package.join(".");
+        let qualify = |name: &str| {
+            if package.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}.{}", package, name)
+            }
+        };
+
+        match &self.class_name {
+            ClassName::Plain(name) => qualify(name),
+            ClassName::Array { dimensions, name } => {
+                let component = match name {
+                    ArrayName::Byte => "B".to_string(),
+                    ArrayName::Char => "C".to_string(),
+                    ArrayName::Double => "D".to_string(),
+                    ArrayName::Float => "F".to_string(),
+                    ArrayName::Int => "I".to_string(),
+                    ArrayName::Long => "J".to_string(),
+                    ArrayName::Class(name) => format!("L{};", qualify(name)),
+                    ArrayName::Short => "S".to_string(),
+                    ArrayName::Boolean => "Z".to_string(),
+                };
+                format!("{}{}", "[".repeat(*dimensions), component)
+            },
+        }
+    }
+
     /// Returns the dimension and array class name of this identifier.
     ///
     /// # Panics
@@ -297,14 +339,34 @@ pub struct Method {
     pub parameters: Vec<ArgumentKind>,
     pub return_type: Option<ArgumentKind>,
     pub is_static: bool,
-    // TODO flags
-    // TODO attributes
+    pub access_flags: BitFlags<MethodAccessFlag>,
+    pub attributes: MethodAttributes,
 }
 
 #[derive(Debug, Clone)]
 pub enum MethodCode {
     Bytecode(Code),
-    Rust(for<'a> fn(&'a mut Frame) -> RustMethodReturn),
+    Rust(RustMethod),
+}
+
+/// Signature of builtin methods (and native methods implemented in Rust).
+pub type RustMethod =
+    for<'a, 'b> fn(&'a mut NativeContext<'b>) -> RustMethodReturn;
+
+/// Code of `abstract` methods, which are invoked
+/// if the class of "this" doesn't implement them.
+pub fn abstract_method(context: &mut NativeContext) -> RustMethodReturn {
+    let message = format!(
+        "Receiver class {} does not define or inherit an implementation \
+         of the resolved method {} of {}",
+        context.this().class().class_identifier().java_name(),
+        context.method().name,
+        context.class.class_identifier().java_name()
+    );
+    context.throw_new(
+        &class_identifier!(java / lang, AbstractMethodError),
+        &message,
+    )
 }
 
 pub type ReturnValue = FieldValue;
@@ -312,24 +374,32 @@ pub type ReturnValue = FieldValue;
 pub enum RustMethodReturn {
     Void,
     Value(ReturnValue),
+    /// Throw the given exception in the calling method.
+    Throw(Rc<dyn ClassInstance>),
 }
 
 pub trait Class {
     fn methods(&self) -> &[Rc<Method>];
     fn static_fields(&self) -> &[Rc<Field>];
     fn instance_fields(&self) -> &[FieldDescriptor];
-    // TODO flags
     fn class_identifier(&self) -> &ClassIdentifier;
     fn super_class(&self) -> Option<Rc<dyn Class>>;
-    // TODO how are interfaces represented?
-    fn interfaces(&self) -> &[Rc<dyn std::any::Any>];
-    // TODO attributes
+    fn interfaces(&self) -> &[Rc<dyn Class>];
 
     fn as_any(&self) -> &dyn Any;
 
     /// self and cls must be the same!
     fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance>;
 
+    /// The loader which defined this class.
+    fn class_loader(&self) -> ClassLoaderId {
+        ClassLoaderId::BOOTSTRAP
+    }
+
+    fn access_flags(&self) -> BitFlags<ClassAccessFlag> {
+        ClassAccessFlag::Public.into()
+    }
+
     fn has_acc_super(&self) -> bool {
         // true for any class version java 8 or higher
         // assumption: builtin classes are written against java 8 behavior
@@ -340,6 +410,13 @@ pub trait Class {
 }
 
 impl dyn Class {
+    /// Whether `self` and `other` are the same class,
+    /// i.e. have the same name and have been defined by the same loader.
+    pub fn is_same_class(&self, other: &dyn Class) -> bool {
+        self.class_identifier() == other.class_identifier()
+            && self.class_loader() == other.class_loader()
+    }
+
     /// (Recursively) lookup method in self (and superclasses/interfaces).
     ///
     /// Returns the resolved method and the class this method is declared in.
@@ -349,22 +426,68 @@ impl dyn Class {
         method_descriptor: (&[ArgumentKind], Option<&ArgumentKind>),
         recurse: bool,
     ) -> (Rc<Method>, Rc<dyn Class>) {
+        self.find_method(method_name, method_descriptor, recurse)
+            .unwrap_or_else(|| {
+                panic!(
+                    "could not resolve method {} {:?}",
+                    method_name, method_descriptor
+                )
+            })
+    }
+
+    /// Like [`get_method`](Self::get_method),
+    /// but returns `None` if the method does not exist.
+    pub fn find_method(
+        self: &Rc<Self>,
+        method_name: &str,
+        method_descriptor: (&[ArgumentKind], Option<&ArgumentKind>),
+        recurse: bool,
+    ) -> Option<(Rc<Method>, Rc<dyn Class>)> {
         match self.methods().iter().find(|element| {
             element.name == method_name
                 && element.parameters == method_descriptor.0
                 && element.return_type.as_ref() == method_descriptor.1
         }) {
-            Some(m) => (m.clone(), self.clone()),
-            None => match self.super_class() {
-                Some(c) if recurse => {
-                    c.get_method(method_name, method_descriptor, recurse)
-                },
-                _ => panic!(
-                    "could not resolve method {} {:?}",
-                    method_name, method_descriptor
-                ),
-            },
+            Some(m) => Some((m.clone(), self.clone())),
+            // superclasses are searched before the default methods
+            // of interfaces
+            None if recurse => self
+                .super_class()
+                .and_then(|c| {
+                    c.find_method(method_name, method_descriptor, recurse)
+                })
+                .or_else(|| {
+                    self.interfaces().iter().find_map(|i| {
+                        i.find_method(method_name, method_descriptor, recurse)
+                    })
+                }),
+            None => None,
+        }
+    }
+
+    /// Look up the field `name` (JVMS §5.4.3.2):
+    /// among the fields declared by `self`,
+    /// then those of its superinterfaces (recursively)
+    /// and then those of its superclass (recursively).
+    ///
+    /// Returns the class declaring the field, which may be static or not.
+    pub fn lookup_field(self: &Rc<Self>, name: &str) -> Option<Rc<dyn Class>> {
+        let declares_field =
+            self.static_fields().iter().any(|field| field.name == name)
+                || self
+                    .instance_fields()
+                    .iter()
+                    .any(|field| field.name == name);
+        if declares_field {
+            return Some(self.clone());
         }
+        self.interfaces()
+            .iter()
+            .find_map(|interface| interface.lookup_field(name))
+            .or_else(|| {
+                self.super_class()
+                    .and_then(|super_class| super_class.lookup_field(name))
+            })
     }
 
     pub fn get_static_field(&self, field_name: &str) -> Option<Rc<Field>> {
@@ -374,6 +497,19 @@ impl dyn Class {
             .cloned()
     }
 
+    /// Whether instances of `self` are instances of `other`,
+    /// i.e. `self` is `other`, extends it or implements it.
+    pub fn is_assignable_to(&self, other: &dyn Class) -> bool {
+        self.is_same_class(other)
+            || self
+                .super_class()
+                .is_some_and(|class| class.is_assignable_to(other))
+            || self
+                .interfaces()
+                .iter()
+                .any(|interface| interface.is_assignable_to(other))
+    }
+
     pub fn is_super_class_of(&self, other: &Rc<dyn Class>) -> bool {
         // idea: if self is superclass of other,
         // at some point other's parent must be self
@@ -381,7 +517,7 @@ impl dyn Class {
             // other must be Object, so self cannot be superclass
             None => false,
             Some(other) => {
-                if other.class_identifier() == self.class_identifier() {
+                if other.is_same_class(self) {
                     true
                 } else {
                     self.is_super_class_of(&other)
@@ -390,6 +526,127 @@ impl dyn Class {
         }
     }
 
+    /// Returns the host of the nest `self` belongs to (JVMS §5.4.4),
+    /// loading it if necessary.
+    ///
+    /// Classes without a `NestHost` attribute,
+    /// or whose host can't be loaded, is in another runtime package
+    /// or doesn't list them as `NestMembers`, are their own host.
+    pub fn nest_host(self: &Rc<Self>, heap: &mut Heap) -> Rc<dyn Class> {
+        let host_name = self
+            .as_any()
+            .downcast_ref::<BytecodeClass>()
+            .and_then(|class| class.attributes.nest_host.as_deref());
+        let host_name = match host_name {
+            Some(host_name) => parse_class_identifier(host_name),
+            None => return self.clone(),
+        };
+        match resolve_class(heap, self.class_loader(), &host_name) {
+            Ok(host)
+                if host.class_loader() == self.class_loader()
+                    && host.class_identifier().package
+                        == self.class_identifier().package
+                    && host
+                        .as_any()
+                        .downcast_ref::<BytecodeClass>()
+                        .is_some_and(|host| {
+                            host.attributes.nest_members.iter().any(|member| {
+                                parse_class_identifier(member)
+                                    == *self.class_identifier()
+                            })
+                        }) =>
+            {
+                host
+            },
+            _ => self.clone(),
+        }
+    }
+
+    /// Whether `self` and `other` belong to the same nest,
+    /// so that they may access each other's private members.
+    pub fn is_nestmate_of(
+        self: &Rc<Self>,
+        other: &Rc<dyn Class>,
+        heap: &mut Heap,
+    ) -> bool {
+        self.nest_host(heap)
+            .is_same_class(other.nest_host(heap).as_ref())
+    }
+
+    /// Returns the simple name of `self` like `Class.getSimpleName()`,
+    /// e.g. `Inner` for `org.example.Outer$Inner`
+    /// or the empty string for anonymous classes.
+    ///
+    /// Array classes aren't supported, since their name
+    /// depends on the component class.
+    pub fn simple_name(&self) -> String {
+        let inner_class = self
+            .as_any()
+            .downcast_ref::<BytecodeClass>()
+            .and_then(|class| {
+                let name = self.class_identifier().to_string();
+                class
+                    .attributes
+                    .inner_classes
+                    .iter()
+                    .find(|inner_class| inner_class.inner_class == name)
+            });
+        match inner_class {
+            Some(inner_class) => {
+                inner_class.inner_name.clone().unwrap_or_default()
+            },
+            None => {
+                let java_name = self.class_identifier().java_name();
+                match java_name.rsplit_once('.') {
+                    Some((_, name)) => name.to_string(),
+                    None => java_name,
+                }
+            },
+        }
+    }
+
+    /// Whether `self` and `other` are in the same runtime package,
+    /// i.e. have the same package and have been defined by the same loader.
+    pub fn is_same_runtime_package(&self, other: &dyn Class) -> bool {
+        self.class_identifier().package == other.class_identifier().package
+            && self.class_loader() == other.class_loader()
+    }
+
+    /// Whether code in `self` may access a member of `declaring_class`
+    /// with the given access (JVMS §5.4.4).
+    ///
+    /// `objectref_class` is the class of the object
+    /// an instance member is accessed on, `None` for static members.
+    /// Subclasses in other packages may only access protected instance
+    /// members on instances of themselves or their subclasses.
+    pub fn can_access(
+        self: &Rc<Self>,
+        declaring_class: &Rc<dyn Class>,
+        access: MemberAccess,
+        objectref_class: Option<&Rc<dyn Class>>,
+        heap: &mut Heap,
+    ) -> bool {
+        match access {
+            MemberAccess::Public => true,
+            MemberAccess::Protected => {
+                self.is_same_runtime_package(declaring_class.as_ref())
+                    || (self.is_same_class(declaring_class.as_ref())
+                        || self.is_sub_class_of(declaring_class))
+                        && objectref_class.map_or(true, |class| {
+                            class.is_same_class(self.as_ref())
+                                || class.is_sub_class_of(self)
+                        })
+            },
+            MemberAccess::Package => {
+                self.is_same_runtime_package(declaring_class.as_ref())
+            },
+            MemberAccess::Private => {
+                self.is_same_class(declaring_class.as_ref())
+                    || self.is_nestmate_of(declaring_class, heap)
+            },
+        }
+    }
+
     pub fn is_sub_class_of(&self, other: &Rc<dyn Class>) -> bool {
         // idea: if self is subclass of other,
         // at some point self's parent must be other
@@ -397,7 +654,7 @@ impl dyn Class {
             // self must be Object, so cannot be subclass
             None => false,
             Some(self_parent) => {
-                if self_parent.class_identifier() == other.class_identifier() {
+                if self_parent.is_same_class(other.as_ref()) {
                     true
                 } else {
                     self_parent.is_sub_class_of(other)
@@ -421,38 +678,26 @@ pub struct BytecodeClass {
     // TODO flags
     pub class_identifier: ClassIdentifier,
     pub super_class: Rc<dyn Class>,
-    // TODO how are interfaces represented?
-    pub interfaces: Vec<Rc<dyn std::any::Any>>,
+    pub interfaces: Vec<Rc<dyn Class>>,
     pub access_flags: BitFlags<ClassAccessFlag>,
+    pub class_loader: ClassLoaderId,
+    pub attributes: ClassAttributes,
 }
 
 #[derive(Debug)]
 pub struct FieldDescriptor {
     pub name: String,
-    // TODO flags
-    // TODO attributes
-    pub kind: FieldKind,
-}
-
-#[derive(Debug)]
-pub enum FieldKind {
-    Byte,
-    Short,
-    Int,
-    Long,
-    Char,
-    Float,
-    Double,
-    Boolean,
-    Reference,
+    pub access_flags: BitFlags<FieldAccessFlag>,
+    pub attributes: Rc<FieldAttributes>,
+    pub field_type: ArgumentKind,
 }
 
 #[derive(Debug)]
 pub struct Field {
     pub name: String,
-    // TODO flags
-    // TODO attributes
-    // TODO data type
+    pub access_flags: BitFlags<FieldAccessFlag>,
+    pub attributes: Rc<FieldAttributes>,
+    pub field_type: ArgumentKind,
     pub value: RefCell<FieldValue>,
 }
 
@@ -518,6 +763,25 @@ impl FieldValue {
     pub fn reference() -> Self {
         Self::Reference(None)
     }
+
+    /// Returns the default value of a field of type `field_type`,
+    /// e.g. 0 or null.
+    pub fn default_for(field_type: &ArgumentKind) -> Self {
+        match field_type {
+            ArgumentKind::Simple(SimpleArgumentKind::Byte) => Self::byte(),
+            ArgumentKind::Simple(SimpleArgumentKind::Char) => Self::char(),
+            ArgumentKind::Simple(SimpleArgumentKind::Double) => Self::double(),
+            ArgumentKind::Simple(SimpleArgumentKind::Float) => Self::float(),
+            ArgumentKind::Simple(SimpleArgumentKind::Int) => Self::int(),
+            ArgumentKind::Simple(SimpleArgumentKind::Long) => Self::long(),
+            ArgumentKind::Simple(SimpleArgumentKind::Short) => Self::short(),
+            ArgumentKind::Simple(SimpleArgumentKind::Boolean) => {
+                Self::boolean()
+            },
+            ArgumentKind::Simple(SimpleArgumentKind::Class(_))
+            | ArgumentKind::Array { .. } => Self::reference(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -525,7 +789,8 @@ pub struct Code {
     pub stack_depth: usize,
     pub local_variable_count: usize,
     pub exception_table: Vec<ExceptionTable>,
-    // TODO attributes
+    // the attributes of the Code attribute, e.g. the line numbers,
+    // are kept in the MethodAttributes
     pub byte_code: Vec<OpCode>,
 }
 
@@ -544,16 +809,25 @@ pub trait ClassInstance {
 }
 
 impl dyn ClassInstance {
-    pub fn get_field(&self, class: &ClassIdentifier, name: &str) -> Rc<Field> {
-        let self_field = self.instance_fields().iter().find(|f| f.name == name);
-        match self_field {
-            Some(field) if self.class().class_identifier() == class => {
-                field.clone()
-            },
-            _ => match self.parent_instance() {
-                None => panic!("NoSuchFieldError"),
-                Some(i) => i.get_field(class, name),
-            },
+    /// Returns the instance field `name` declared by `class`,
+    /// which is the class of `self` or one of its superclasses.
+    ///
+    /// The field has to be resolved before,
+    /// e.g. by [`lookup_field`](<dyn Class>::lookup_field).
+    pub fn get_field(&self, class: &dyn Class, name: &str) -> Rc<Field> {
+        if self.class().is_same_class(class) {
+            return self
+                .instance_fields()
+                .iter()
+                .find(|field| field.name == name)
+                .unwrap_or_else(|| {
+                    panic!("{} has no field {}", class.class_identifier(), name)
+                })
+                .clone();
+        }
+        match self.parent_instance() {
+            Some(instance) => instance.get_field(class, name),
+            None => panic!("object is no {}", class.class_identifier()),
         }
     }
 
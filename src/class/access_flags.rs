@@ -1,4 +1,4 @@
-use enumflags2::bitflags;
+use enumflags2::{bitflags, BitFlags};
 
 #[bitflags]
 #[derive(Clone, Copy, Debug)]
@@ -12,6 +12,8 @@ pub enum ClassAccessFlag {
     Synthetic = 0x1000,
     Annotation = 0x2000,
     Enum = 0x4000,
+    /// Set for `module-info` classes, which describe a module.
+    Module = 0x8000,
 }
 
 #[bitflags]
@@ -46,3 +48,76 @@ pub enum MethodAccessFlag {
     Strict = 0x0800,
     Synthetic = 0x1000,
 }
+
+/// Flags of an `InnerClasses` entry.
+#[bitflags]
+#[derive(Clone, Copy, Debug)]
+#[repr(u16)]
+pub enum NestedClassAccessFlag {
+    Public = 0x0001,
+    Private = 0x0002,
+    Protected = 0x0004,
+    Static = 0x0008,
+    Final = 0x0010,
+    Interface = 0x0200,
+    Abstract = 0x0400,
+    Synthetic = 0x1000,
+    Annotation = 0x2000,
+    Enum = 0x4000,
+}
+
+/// Who may access a field or method (JVMS §5.4.4),
+/// given by its access flags.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemberAccess {
+    Public,
+    Protected,
+    /// Neither public, protected nor private.
+    Package,
+    Private,
+}
+
+impl MemberAccess {
+    fn from_flags(public: bool, protected: bool, private: bool) -> Self {
+        if public {
+            MemberAccess::Public
+        } else if protected {
+            MemberAccess::Protected
+        } else if private {
+            MemberAccess::Private
+        } else {
+            MemberAccess::Package
+        }
+    }
+}
+
+impl From<BitFlags<FieldAccessFlag>> for MemberAccess {
+    fn from(flags: BitFlags<FieldAccessFlag>) -> Self {
+        MemberAccess::from_flags(
+            flags.contains(FieldAccessFlag::Public),
+            flags.contains(FieldAccessFlag::Protected),
+            flags.contains(FieldAccessFlag::Private),
+        )
+    }
+}
+
+impl From<BitFlags<MethodAccessFlag>> for MemberAccess {
+    fn from(flags: BitFlags<MethodAccessFlag>) -> Self {
+        MemberAccess::from_flags(
+            flags.contains(MethodAccessFlag::Public),
+            flags.contains(MethodAccessFlag::Protected),
+            flags.contains(MethodAccessFlag::Private),
+        )
+    }
+}
+
+impl std::fmt::Display for MemberAccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            MemberAccess::Public => "public",
+            MemberAccess::Protected => "protected",
+            MemberAccess::Package => "package-private",
+            MemberAccess::Private => "private",
+        })
+    }
+}
@@ -0,0 +1,168 @@
+//! Attributes of classes, methods and fields (JVMS §4.7),
+//! which aren't needed to execute code,
+//! but describe it for tooling and reflection.
+
+use enumflags2::BitFlags;
+
+use crate::class::{access_flags::NestedClassAccessFlag, ClassIdentifier};
+
+#[derive(Debug, Clone, Default)]
+pub struct ClassAttributes {
+    /// Name of the source file, e.g. `Main.java`.
+    pub source_file: Option<String>,
+    /// Generic signature, e.g. `<T:Ljava/lang/Object;>Ljava/lang/Object;`.
+    pub signature: Option<String>,
+    /// Nested classes, which are members of or referenced by the class.
+    pub inner_classes: Vec<InnerClass>,
+    /// Set for local and anonymous classes.
+    pub enclosing_method: Option<EnclosingMethod>,
+    pub bootstrap_methods: Vec<BootstrapMethod>,
+    pub deprecated: bool,
+    /// Name of the class whose nest the class belongs to.
+    pub nest_host: Option<String>,
+    /// Names of the other classes of the nest, if the class is its host.
+    pub nest_members: Vec<String>,
+    /// Set for records.
+    pub record_components: Option<Vec<RecordComponent>>,
+    /// Names of the classes allowed to extend a sealed class.
+    pub permitted_subclasses: Vec<String>,
+    /// Annotations retained at runtime.
+    pub annotations: Vec<Annotation>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MethodAttributes {
+    /// Checked exceptions declared by `throws`.
+    pub exceptions: Vec<ClassIdentifier>,
+    pub signature: Option<String>,
+    pub deprecated: bool,
+    pub line_numbers: Vec<LineNumber>,
+    pub local_variables: Vec<LocalVariable>,
+    /// Local variables of generic types,
+    /// whose `descriptor` is their signature.
+    pub local_variable_types: Vec<LocalVariable>,
+    /// Annotations retained at runtime.
+    pub annotations: Vec<Annotation>,
+    /// Annotations retained at runtime of each parameter.
+    pub parameter_annotations: Vec<Vec<Annotation>>,
+    /// Default value of an element of an annotation interface.
+    pub annotation_default: Option<ElementValue>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FieldAttributes {
+    /// Initial value of a static field.
+    pub constant_value: Option<ConstantValue>,
+    pub signature: Option<String>,
+    pub deprecated: bool,
+    /// Annotations retained at runtime.
+    pub annotations: Vec<Annotation>,
+}
+
+/// The line of the source code starting at the instruction `start_pc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineNumber {
+    pub start_pc: u16,
+    pub line_number: u16,
+}
+
+/// A local variable, which is valid from the instruction `start_pc`
+/// for `length` bytes of code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalVariable {
+    pub start_pc: u16,
+    pub length: u16,
+    pub name: String,
+    pub descriptor: String,
+    /// The slot in the local variables.
+    pub index: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InnerClass {
+    /// e.g. `org/example/Outer$Inner`
+    pub inner_class: String,
+    /// Not set for local and anonymous classes.
+    pub outer_class: Option<String>,
+    /// The simple name, not set for anonymous classes.
+    pub inner_name: Option<String>,
+    pub access_flags: BitFlags<NestedClassAccessFlag>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnclosingMethod {
+    pub class: String,
+    /// Name and descriptor, not set outside of methods,
+    /// e.g. in a field initializer.
+    pub method: Option<(String, String)>,
+}
+
+/// A bootstrap method called to link `invokedynamic` instructions
+/// and dynamic constants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootstrapMethod {
+    /// Constant pool index of the `MethodHandle`.
+    pub method_handle: u16,
+    /// Constant pool indices of the static arguments.
+    pub arguments: Vec<u16>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordComponent {
+    pub name: String,
+    pub descriptor: String,
+    pub signature: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstantValue {
+    Int(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    String(String),
+}
+
+/// An annotation (JVMS §4.7.16), e.g. `@Handler(value = "a")`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    /// Field descriptor of the annotation interface,
+    /// e.g. `Lorg/example/Handler;`.
+    pub type_name: String,
+    /// Names and values of the elements, which are given explicitly.
+    pub elements: Vec<(String, ElementValue)>,
+}
+
+impl Annotation {
+    /// Returns the value of the element `name`, if given explicitly.
+    pub fn element(&self, name: &str) -> Option<&ElementValue> {
+        self.elements
+            .iter()
+            .find(|(element_name, _)| element_name == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// The value of an annotation element (JVMS §4.7.16.1).
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementValue {
+    Byte(i8),
+    Char(u16),
+    Double(f64),
+    Float(f32),
+    Int(i32),
+    Long(i64),
+    Short(i16),
+    Boolean(bool),
+    String(String),
+    /// An enum constant, whose type is a field descriptor.
+    Enum {
+        type_name: String,
+        const_name: String,
+    },
+    /// A class literal given by its return descriptor,
+    /// e.g. `Ljava/lang/String;` or `V`.
+    Class(String),
+    Annotation(Annotation),
+    Array(Vec<ElementValue>),
+}
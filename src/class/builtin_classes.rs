@@ -1,18 +1,41 @@
+pub mod annotation;
 pub mod array;
+pub mod boxed;
+pub mod class_loader;
+pub mod class_object;
+pub mod enum_class;
 pub mod file_input_stream;
 pub mod input_stream;
 pub mod object;
+pub mod primitive;
 pub mod print_stream;
+pub mod proxy;
+pub mod record;
+pub mod reflect;
 pub mod string;
+pub mod string_builder;
 pub mod system;
 pub mod throwable;
 
 pub use crate::class::builtin_classes::{
+    annotation::{AnnotationInstance, AnnotationProxyClass},
+    boxed::{BoxClass, BoxInstance},
+    class_loader::{ClassLoaderClass, ClassLoaderInstance},
+    class_object::{ClassClass, ClassObject},
+    enum_class::{EnumClass, EnumInstance},
     file_input_stream::{FileInputStream, FileInputStreamInstance},
     input_stream::{InputStream, InputStreamInstance},
     object::{ObjectClass, ObjectInstance},
+    primitive::PrimitiveClass,
     print_stream::{PrintStream, PrintStreamInstance},
+    proxy::{
+        DynamicProxyClass, InvocationHandlerInterface, ProxyClass,
+        ProxyInstance,
+    },
+    record::{RecordClass, RecordInstance},
+    reflect::{MemberClass, MemberInstance},
     string::{StringClass, StringInstance},
-    system::SystemClass,
+    string_builder::{StringBuilder, StringBuilderInstance},
+    system::{SystemClass, SystemExit},
     throwable::{ThrowableClass, ThrowableInstance},
 };
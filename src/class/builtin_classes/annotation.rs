@@ -0,0 +1,403 @@
+//! Instances of annotation interfaces,
+//! which `Class.getAnnotation()` creates from the parsed annotations.
+
+use std::{any::Any, rc::Rc};
+
+use crate::{
+    class::{
+        access_flags::MethodAccessFlag,
+        attributes::{Annotation, ElementValue},
+        builtin_classes::array::ObjectArray,
+        class_identifier, ArgumentKind, Class, ClassIdentifier, ClassInstance,
+        Field, FieldDescriptor, FieldValue, Method, MethodCode,
+        RustMethodReturn, SimpleArgumentKind,
+    },
+    classloader::parse_class_or_array_identifier,
+    executor::{native_context::NativeContext, new_exception, resolve_class},
+    heap::{ClassLoaderId, Heap},
+};
+
+/// A class implementing an annotation interface,
+/// whose methods return the elements of the annotation.
+pub struct AnnotationProxyClass {
+    class_identifier: ClassIdentifier,
+    object_class: Rc<dyn Class>,
+    /// The implemented annotation interface.
+    interfaces: Vec<Rc<dyn Class>>,
+    methods: Vec<Rc<Method>>,
+}
+
+impl AnnotationProxyClass {
+    pub fn new(
+        class_identifier: ClassIdentifier,
+        annotation_type: Rc<dyn Class>,
+        object_class: Rc<dyn Class>,
+    ) -> Self {
+        let mut methods: Vec<_> = annotation_type
+            .methods()
+            .iter()
+            .filter(|method| {
+                method.access_flags.contains(MethodAccessFlag::Abstract)
+            })
+            .map(|method| {
+                Rc::new(Method {
+                    code: MethodCode::Rust(element),
+                    name: method.name.clone(),
+                    parameters: vec![],
+                    return_type: method.return_type.clone(),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public
+                        | MethodAccessFlag::Final,
+                    attributes: Default::default(),
+                })
+            })
+            .collect();
+        methods.push(Rc::new(Method {
+            code: MethodCode::Rust(annotation_type_method),
+            name: "annotationType".to_owned(),
+            parameters: vec![],
+            return_type: Some(ArgumentKind::Simple(SimpleArgumentKind::Class(
+                "java/lang/Class".to_string(),
+            ))),
+            is_static: false,
+            access_flags: MethodAccessFlag::Public | MethodAccessFlag::Final,
+            attributes: Default::default(),
+        }));
+        Self {
+            class_identifier,
+            object_class,
+            interfaces: vec![annotation_type],
+            methods,
+        }
+    }
+
+    /// The implemented annotation interface.
+    pub fn annotation_type(&self) -> &Rc<dyn Class> {
+        &self.interfaces[0]
+    }
+
+    /// Create an instance returning the elements of `annotation`.
+    pub fn new_annotation(
+        self: &Rc<Self>,
+        annotation: Annotation,
+    ) -> AnnotationInstance {
+        AnnotationInstance {
+            class: self.clone(),
+            object_instance: self
+                .object_class
+                .new_instance(self.object_class.clone()),
+            annotation,
+        }
+    }
+}
+
+/// Returns the value of the element `name` of `annotation`,
+/// given explicitly or by its default.
+fn element_value<'a>(
+    annotation_type: &'a Rc<dyn Class>,
+    annotation: &'a Annotation,
+    name: &str,
+) -> Option<&'a ElementValue> {
+    annotation.element(name).or_else(|| {
+        annotation_type
+            .methods()
+            .iter()
+            .find(|method| method.name == name)
+            .and_then(|method| method.attributes.annotation_default.as_ref())
+    })
+}
+
+/// Returns the value of the element named like the executed method.
+fn element(context: &mut NativeContext) -> RustMethodReturn {
+    let this = context.this();
+    let name = context.method().name.clone();
+    let kind = context
+        .method()
+        .return_type
+        .clone()
+        .expect("annotation elements have a type");
+    let (annotation_type, value) = this.with_parent_instance(
+        "annotation",
+        |instance: &AnnotationInstance| {
+            let annotation_type = instance.class.annotation_type().clone();
+            let value =
+                element_value(&annotation_type, &instance.annotation, &name)
+                    .cloned();
+            (annotation_type, value)
+        },
+    );
+    let Some(value) = value else {
+        return context.throw_new(
+            &class_identifier!(
+                java / lang / annotation,
+                IncompleteAnnotationException
+            ),
+            &format!(
+                "{} missing element {}",
+                annotation_type.class_identifier().java_name(),
+                name
+            ),
+        );
+    };
+    match to_field_value(
+        context.heap,
+        annotation_type.class_loader(),
+        &value,
+        &kind,
+    ) {
+        Ok(value) => RustMethodReturn::Value(value),
+        Err(e) => RustMethodReturn::Throw(e),
+    }
+}
+
+fn annotation_type_method(context: &mut NativeContext) -> RustMethodReturn {
+    let annotation_type = context.this().with_parent_instance(
+        "annotation",
+        |instance: &AnnotationInstance| {
+            instance.class.annotation_type().clone()
+        },
+    );
+    RustMethodReturn::Value(FieldValue::Reference(Some(
+        context.heap.class_object(&annotation_type),
+    )))
+}
+
+/// Returns the class name of the field descriptor `descriptor`,
+/// e.g. `java/lang/String` for `Ljava/lang/String;`.
+fn descriptor_class(descriptor: &str) -> &str {
+    descriptor
+        .strip_prefix('L')
+        .and_then(|name| name.strip_suffix(';'))
+        .unwrap_or(descriptor)
+}
+
+/// Create the instance of the annotation interface of `annotation`,
+/// which is resolved by `loader`.
+pub fn new_annotation_instance(
+    heap: &mut Heap,
+    loader: ClassLoaderId,
+    annotation: &Annotation,
+) -> Result<Rc<dyn ClassInstance>, Rc<dyn ClassInstance>> {
+    let annotation_type = resolve_class(
+        heap,
+        loader,
+        &parse_class_or_array_identifier(descriptor_class(
+            &annotation.type_name,
+        )),
+    )?;
+    let proxy_class = heap.annotation_proxy_class(&annotation_type);
+    Ok(Rc::new(proxy_class.new_annotation(annotation.clone())))
+}
+
+/// Convert the element value `value` of type `kind` to a Java value,
+/// resolving classes by `loader`.
+///
+/// Class literals of primitive types aren't supported,
+/// since there are no class objects for them.
+fn to_field_value(
+    heap: &mut Heap,
+    loader: ClassLoaderId,
+    value: &ElementValue,
+    kind: &ArgumentKind,
+) -> Result<FieldValue, Rc<dyn ClassInstance>> {
+    Ok(match value {
+        ElementValue::Byte(b) => FieldValue::Byte(*b),
+        ElementValue::Char(c) => FieldValue::Char(*c),
+        ElementValue::Double(d) => FieldValue::Double(*d),
+        ElementValue::Float(f) => FieldValue::Float(*f),
+        ElementValue::Int(i) => FieldValue::Int(*i),
+        ElementValue::Long(l) => FieldValue::Long(*l),
+        ElementValue::Short(s) => FieldValue::Short(*s),
+        ElementValue::Boolean(z) => FieldValue::Boolean((*z).into()),
+        ElementValue::String(s) => {
+            FieldValue::Reference(Some(Rc::new(heap.new_string(s.clone()))))
+        },
+        ElementValue::Enum {
+            type_name,
+            const_name,
+        } => {
+            let enum_class = resolve_class(
+                heap,
+                loader,
+                &parse_class_or_array_identifier(descriptor_class(type_name)),
+            )?;
+            match enum_class.get_static_field(const_name) {
+                Some(field) => field.value.borrow().clone(),
+                None => {
+                    return Err(new_exception(
+                        heap,
+                        &class_identifier!(
+                            java / lang,
+                            EnumConstantNotPresentException
+                        ),
+                        &format!(
+                            "{}.{}",
+                            enum_class.class_identifier().java_name(),
+                            const_name
+                        ),
+                    ));
+                },
+            }
+        },
+        ElementValue::Class(descriptor) => {
+            let class = resolve_class(
+                heap,
+                loader,
+                &parse_class_or_array_identifier(descriptor_class(descriptor)),
+            )?;
+            FieldValue::Reference(Some(heap.class_object(&class)))
+        },
+        ElementValue::Annotation(annotation) => FieldValue::Reference(Some(
+            new_annotation_instance(heap, loader, annotation)?,
+        )),
+        ElementValue::Array(values) => {
+            let ArgumentKind::Array {
+                dimensions: 1,
+                kind: component,
+            } = kind
+            else {
+                panic!("array element value of type {:?}", kind);
+            };
+            let elements = values
+                .iter()
+                .map(|value| {
+                    to_field_value(
+                        heap,
+                        loader,
+                        value,
+                        &ArgumentKind::Simple(component.clone()),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            FieldValue::Reference(Some(new_array(
+                heap, loader, component, elements,
+            )?))
+        },
+    })
+}
+
+/// Create an array of `component`s containing `elements`.
+fn new_array(
+    heap: &mut Heap,
+    loader: ClassLoaderId,
+    component: &SimpleArgumentKind,
+    elements: Vec<FieldValue>,
+) -> Result<Rc<dyn ClassInstance>, Rc<dyn ClassInstance>> {
+    macro_rules! primitive_array {
+        ($new_array:ident, $variant:ident) => {{
+            let array = heap.$new_array(elements.len());
+            for (index, element) in elements.into_iter().enumerate() {
+                if let FieldValue::$variant(value) = element {
+                    array.set(index, value).unwrap();
+                }
+            }
+            Rc::new(array)
+        }};
+    }
+
+    Ok(match component {
+        SimpleArgumentKind::Byte => primitive_array!(new_byte_array, Byte),
+        SimpleArgumentKind::Char => primitive_array!(new_char_array, Char),
+        SimpleArgumentKind::Double => {
+            primitive_array!(new_double_array, Double)
+        },
+        SimpleArgumentKind::Float => primitive_array!(new_float_array, Float),
+        SimpleArgumentKind::Int => primitive_array!(new_int_array, Int),
+        SimpleArgumentKind::Long => primitive_array!(new_long_array, Long),
+        SimpleArgumentKind::Short => primitive_array!(new_short_array, Short),
+        SimpleArgumentKind::Boolean => {
+            let array = heap.new_boolean_array(elements.len());
+            for (index, element) in elements.into_iter().enumerate() {
+                if let FieldValue::Boolean(value) = element {
+                    array.set(index, value != 0).unwrap();
+                }
+            }
+            Rc::new(array)
+        },
+        SimpleArgumentKind::Class(name) => {
+            let array_class = resolve_class(
+                heap,
+                loader,
+                &parse_class_or_array_identifier(&format!("[L{};", name)),
+            )?;
+            let object_array: &ObjectArray =
+                array_class.as_ref().try_into().unwrap();
+            let array = object_array
+                .new_instance_from_ref(elements.len(), array_class.clone())
+                .unwrap();
+            for (index, element) in elements.into_iter().enumerate() {
+                if let FieldValue::Reference(value) = element {
+                    array.set(index, value).unwrap();
+                }
+            }
+            Rc::new(array)
+        },
+    })
+}
+
+impl Class for AnnotationProxyClass {
+    fn methods(&self) -> &[Rc<Method>] {
+        self.methods.as_slice()
+    }
+
+    fn static_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn instance_fields(&self) -> &[FieldDescriptor] {
+        &[]
+    }
+
+    fn class_identifier(&self) -> &ClassIdentifier {
+        &self.class_identifier
+    }
+
+    fn super_class(&self) -> Option<Rc<dyn Class>> {
+        Some(self.object_class.clone())
+    }
+
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
+        &self.interfaces
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance> {
+        // make sure that self and cls really are equal
+        let _cls_ref: &Self =
+            cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
+
+        panic!("annotations cannot be created with new");
+    }
+
+    fn class_loader(&self) -> ClassLoaderId {
+        self.annotation_type().class_loader()
+    }
+}
+
+pub struct AnnotationInstance {
+    class: Rc<AnnotationProxyClass>,
+    object_instance: Rc<dyn ClassInstance>,
+    /// The annotation, whose elements are returned.
+    pub annotation: Annotation,
+}
+
+impl ClassInstance for AnnotationInstance {
+    fn class(&self) -> Rc<dyn Class> {
+        self.class.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn instance_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn parent_instance(&self) -> Option<Rc<dyn ClassInstance>> {
+        Some(self.object_instance.clone())
+    }
+}
@@ -2,10 +2,12 @@ use std::{any::Any, cell::RefCell, rc::Rc};
 
 use crate::{
     class::{
-        class_identifier, ArrayName, Class, ClassIdentifier, ClassInstance,
-        ClassName, Field, FieldDescriptor,
+        access_flags::MethodAccessFlag, class_identifier, ArgumentKind,
+        ArrayName, Class, ClassIdentifier, ClassInstance, ClassName, Field,
+        FieldDescriptor, FieldValue, Method, MethodCode, RustMethodReturn,
+        SimpleArgumentKind,
     },
-    executor::RuntimeError,
+    executor::{native_context::NativeContext, RuntimeError},
 };
 
 pub type ObjectArray = Array<ObjectArrayKind>;
@@ -13,34 +15,39 @@ pub type ObjectArrayInstance = ArrayInstance<ObjectArrayKind>;
 
 pub struct ObjectArrayKind {
     array_class_identifier: ClassIdentifier,
+    component_class: Rc<dyn Class>,
 }
 
 impl ObjectArrayKind {
     pub fn new(class: Rc<dyn Class>) -> ObjectArrayKind {
         let package = class.class_identifier().package.clone();
-        match &class.class_identifier().class_name {
-            ClassName::Array { dimensions, name } => ObjectArrayKind {
-                array_class_identifier: ClassIdentifier {
-                    package,
-                    class_name: ClassName::Array {
-                        dimensions: 1 + dimensions,
-                        name: name.clone(),
-                    },
-                },
+        let class_name = match &class.class_identifier().class_name {
+            ClassName::Array { dimensions, name } => ClassName::Array {
+                dimensions: 1 + dimensions,
+                name: name.clone(),
             },
-            ClassName::Plain(class_name) => ObjectArrayKind {
-                array_class_identifier: ClassIdentifier {
-                    package,
-                    class_name: ClassName::Array {
-                        dimensions: 1,
-                        name: ArrayName::Class(class_name.clone()),
-                    },
-                },
+            ClassName::Plain(class_name) => ClassName::Array {
+                dimensions: 1,
+                name: ArrayName::Class(class_name.clone()),
             },
+        };
+        ObjectArrayKind {
+            array_class_identifier: ClassIdentifier {
+                package,
+                class_name,
+            },
+            component_class: class,
         }
     }
 }
 
+impl ObjectArray {
+    /// The class of the elements, which may be an array class itself.
+    pub fn component_class(&self) -> Rc<dyn Class> {
+        self.kind.component_class.clone()
+    }
+}
+
 impl ArrayKind for ObjectArrayKind {
     type Value = Option<Rc<dyn ClassInstance>>;
 
@@ -320,6 +327,7 @@ pub trait ArrayKind {
 pub struct Array<K> {
     kind: K,
     object_class: Rc<dyn Class>,
+    methods: Vec<Rc<Method>>,
 }
 
 pub struct ArrayInstance<K: ArrayKind> {
@@ -332,7 +340,21 @@ pub struct ArrayInstance<K: ArrayKind> {
 
 impl<K: ArrayKind + 'static> Array<K> {
     pub fn new(kind: K, object_class: Rc<dyn Class>) -> Array<K> {
-        Array { kind, object_class }
+        Array {
+            kind,
+            object_class,
+            methods: vec![Rc::new(Method {
+                code: MethodCode::Rust(clone::<K>),
+                name: "clone".to_owned(),
+                parameters: vec![],
+                return_type: Some(ArgumentKind::Simple(
+                    SimpleArgumentKind::Class("java/lang/Object".to_string()),
+                )),
+                is_static: false,
+                access_flags: MethodAccessFlag::Public.into(),
+                attributes: Default::default(),
+            })],
+        }
     }
 
     pub fn new_instance(self: &Rc<Self>, length: usize) -> ArrayInstance<K> {
@@ -365,9 +387,26 @@ impl<K: ArrayKind + 'static> Array<K> {
     }
 }
 
+/// Returns a shallow copy of the array.
+fn clone<K: ArrayKind + 'static>(
+    context: &mut NativeContext,
+) -> RustMethodReturn {
+    let this = context.this();
+    let array: &ArrayInstance<K> = this.as_ref().try_into().unwrap();
+    let class = this.class();
+    let array_class: &Array<K> = class.as_ref().try_into().unwrap();
+    let copy = array_class
+        .new_instance_from_ref(array.length(), class.clone())
+        .unwrap();
+    copy.values
+        .borrow_mut()
+        .clone_from_slice(&array.values.borrow());
+    RustMethodReturn::Value(FieldValue::Reference(Some(Rc::new(copy))))
+}
+
 impl<K: ArrayKind + 'static> Class for Array<K> {
-    fn methods(&self) -> &[Rc<crate::class::Method>] {
-        &[]
+    fn methods(&self) -> &[Rc<Method>] {
+        self.methods.as_slice()
     }
 
     fn static_fields(&self) -> &[Rc<crate::class::Field>] {
@@ -386,7 +425,7 @@ impl<K: ArrayKind + 'static> Class for Array<K> {
         Some(self.object_class.clone())
     }
 
-    fn interfaces(&self) -> &[Rc<dyn std::any::Any>] {
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
         &[]
     }
 
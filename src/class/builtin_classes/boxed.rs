@@ -0,0 +1,502 @@
+//! The wrapper classes of the primitive types, e.g. `java.lang.Integer`,
+//! whose instances box primitive values.
+
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use crate::{
+    class::{
+        access_flags::{ClassAccessFlag, FieldAccessFlag, MethodAccessFlag},
+        builtin_classes::string::floating_point_string,
+        class_identifier, ArgumentKind, Class, ClassIdentifier, ClassInstance,
+        Field, FieldDescriptor, FieldValue, Method, MethodCode,
+        RustMethodReturn, SimpleArgumentKind,
+    },
+    executor::native_context::NativeContext,
+    heap::{ClassLoaderId, Heap},
+};
+
+/// The wrapper class of a primitive type, e.g. `java.lang.Integer`.
+///
+/// Unlike in the JDK, the numeric wrapper classes extend `Object`
+/// instead of `java.lang.Number`,
+/// but they all have the `xxxValue()` conversion methods.
+pub struct BoxClass {
+    class_identifier: ClassIdentifier,
+    object_class: Rc<dyn Class>,
+    static_fields: Vec<Rc<Field>>,
+    methods: Vec<Rc<Method>>,
+}
+
+impl BoxClass {
+    /// Create the wrapper class of `primitive`,
+    /// whose `TYPE` field holds `type_object`,
+    /// the class object of the primitive type.
+    pub fn new(
+        primitive: SimpleArgumentKind,
+        type_object: Rc<dyn ClassInstance>,
+        object_class: Rc<dyn Class>,
+    ) -> Self {
+        let class_identifier = box_class_identifier(&primitive);
+        let primitive_kind = ArgumentKind::Simple(primitive.clone());
+        let box_kind = ArgumentKind::Simple(SimpleArgumentKind::Class(
+            class_identifier.to_string(),
+        ));
+        let method = |code, name: &str, parameters, return_type| {
+            Rc::new(Method {
+                code: MethodCode::Rust(code),
+                name: name.to_owned(),
+                parameters,
+                return_type: Some(return_type),
+                is_static: false,
+                access_flags: MethodAccessFlag::Public.into(),
+                attributes: Default::default(),
+            })
+        };
+
+        let mut methods = vec![
+            Rc::new(Method {
+                code: MethodCode::Rust(value_of),
+                name: "valueOf".to_owned(),
+                parameters: vec![primitive_kind],
+                return_type: Some(box_kind),
+                is_static: true,
+                access_flags: MethodAccessFlag::Public
+                    | MethodAccessFlag::Static,
+                attributes: Default::default(),
+            }),
+            method(
+                to_string,
+                "toString",
+                vec![],
+                ArgumentKind::Simple(SimpleArgumentKind::Class(
+                    "java/lang/String".to_string(),
+                )),
+            ),
+            method(
+                hash_code,
+                "hashCode",
+                vec![],
+                ArgumentKind::Simple(SimpleArgumentKind::Int),
+            ),
+            method(
+                equals,
+                "equals",
+                vec![ArgumentKind::Simple(SimpleArgumentKind::Class(
+                    "java/lang/Object".to_string(),
+                ))],
+                ArgumentKind::Simple(SimpleArgumentKind::Boolean),
+            ),
+        ];
+        let value_methods = match primitive {
+            SimpleArgumentKind::Boolean => {
+                vec![(SimpleArgumentKind::Boolean, "booleanValue")]
+            },
+            SimpleArgumentKind::Char => {
+                vec![(SimpleArgumentKind::Char, "charValue")]
+            },
+            _ => vec![
+                (SimpleArgumentKind::Byte, "byteValue"),
+                (SimpleArgumentKind::Short, "shortValue"),
+                (SimpleArgumentKind::Int, "intValue"),
+                (SimpleArgumentKind::Long, "longValue"),
+                (SimpleArgumentKind::Float, "floatValue"),
+                (SimpleArgumentKind::Double, "doubleValue"),
+            ],
+        };
+        methods.extend(value_methods.into_iter().map(|(kind, name)| {
+            method(primitive_value, name, vec![], ArgumentKind::Simple(kind))
+        }));
+
+        let static_field = |name: &str, field_type: &str, value| {
+            Rc::new(Field {
+                name: name.into(),
+                access_flags: FieldAccessFlag::Public
+                    | FieldAccessFlag::Static
+                    | FieldAccessFlag::Final,
+                attributes: Default::default(),
+                field_type: ArgumentKind::Simple(SimpleArgumentKind::Class(
+                    field_type.to_string(),
+                )),
+                value: RefCell::new(FieldValue::Reference(value)),
+            })
+        };
+        let mut static_fields =
+            vec![static_field("TYPE", "java/lang/Class", Some(type_object))];
+        if primitive == SimpleArgumentKind::Boolean {
+            // set by the static initializer,
+            // since the boxes can only be created once the class exists
+            static_fields.push(static_field("TRUE", "java/lang/Boolean", None));
+            static_fields.push(static_field(
+                "FALSE",
+                "java/lang/Boolean",
+                None,
+            ));
+            methods.push(Rc::new(Method {
+                code: MethodCode::Rust(initialize_boolean),
+                name: "<clinit>".to_owned(),
+                parameters: vec![],
+                return_type: None,
+                is_static: true,
+                access_flags: MethodAccessFlag::Static.into(),
+                attributes: Default::default(),
+            }));
+        }
+
+        Self {
+            class_identifier,
+            object_class,
+            static_fields,
+            methods,
+        }
+    }
+}
+
+/// Returns the identifier of the wrapper class of `primitive`,
+/// e.g. `java/lang/Integer` for `int`.
+///
+/// # Panics
+///
+/// This function will panic if `primitive` is a class.
+pub fn box_class_identifier(primitive: &SimpleArgumentKind) -> ClassIdentifier {
+    match primitive {
+        SimpleArgumentKind::Boolean => class_identifier!(java / lang, Boolean),
+        SimpleArgumentKind::Byte => class_identifier!(java / lang, Byte),
+        SimpleArgumentKind::Char => class_identifier!(java / lang, Character),
+        SimpleArgumentKind::Short => class_identifier!(java / lang, Short),
+        SimpleArgumentKind::Int => class_identifier!(java / lang, Integer),
+        SimpleArgumentKind::Long => class_identifier!(java / lang, Long),
+        SimpleArgumentKind::Float => class_identifier!(java / lang, Float),
+        SimpleArgumentKind::Double => class_identifier!(java / lang, Double),
+        SimpleArgumentKind::Class(name) => {
+            panic!("{} is no primitive type", name)
+        },
+    }
+}
+
+/// Returns the primitive type of `value`.
+///
+/// # Panics
+///
+/// This function will panic if `value` is a reference.
+pub fn primitive_kind(value: &FieldValue) -> SimpleArgumentKind {
+    match value {
+        FieldValue::Boolean(_) => SimpleArgumentKind::Boolean,
+        FieldValue::Byte(_) => SimpleArgumentKind::Byte,
+        FieldValue::Char(_) => SimpleArgumentKind::Char,
+        FieldValue::Short(_) => SimpleArgumentKind::Short,
+        FieldValue::Int(_) => SimpleArgumentKind::Int,
+        FieldValue::Long(_) => SimpleArgumentKind::Long,
+        FieldValue::Float(_) => SimpleArgumentKind::Float,
+        FieldValue::Double(_) => SimpleArgumentKind::Double,
+        FieldValue::Reference(_) => panic!("references are not primitive"),
+    }
+}
+
+/// Box the primitive `value`, e.g. into an `Integer` for an int.
+pub fn box_value(heap: &Heap, value: FieldValue) -> Rc<dyn ClassInstance> {
+    let class = heap
+        .find_class(
+            ClassLoaderId::BOOTSTRAP,
+            &box_class_identifier(&primitive_kind(&value)),
+        )
+        .expect("wrapper classes are builtin classes")
+        .clone();
+    new_box(class, value)
+}
+
+fn new_box(class: Rc<dyn Class>, value: FieldValue) -> Rc<dyn ClassInstance> {
+    let box_class: &BoxClass = class.as_any().downcast_ref().unwrap();
+    Rc::new(BoxInstance {
+        object_instance: box_class
+            .object_class
+            .new_instance(box_class.object_class.clone()),
+        class,
+        value,
+    })
+}
+
+/// Returns the primitive value boxed by `object`,
+/// or `None` if it is no instance of a wrapper class.
+pub fn unbox(object: &Rc<dyn ClassInstance>) -> Option<FieldValue> {
+    object
+        .as_any()
+        .downcast_ref::<BoxInstance>()
+        .map(|instance| instance.value.clone())
+}
+
+/// Convert `value` to the primitive type `kind`,
+/// if this is an identity or widening conversion (JLS §5.1.2),
+/// e.g. from int to long, but not from long to int.
+pub fn widen(
+    value: &FieldValue,
+    kind: &SimpleArgumentKind,
+) -> Option<FieldValue> {
+    use SimpleArgumentKind::*;
+
+    let allowed: &[SimpleArgumentKind] = match primitive_kind(value) {
+        Boolean => &[Boolean],
+        Byte => &[Byte, Short, Int, Long, Float, Double],
+        Short => &[Short, Int, Long, Float, Double],
+        Char => &[Char, Int, Long, Float, Double],
+        Int => &[Int, Long, Float, Double],
+        Long => &[Long, Float, Double],
+        Float => &[Float, Double],
+        Double => &[Double],
+        Class(_) => unreachable!("values have primitive types"),
+    };
+    allowed.contains(kind).then(|| convert(value, kind))
+}
+
+/// Convert the numeric `value` to `kind`
+/// like the conversion bytecodes (e.g. `l2i`) do.
+fn convert(value: &FieldValue, kind: &SimpleArgumentKind) -> FieldValue {
+    enum Number {
+        Integral(i64),
+        Floating(f64),
+    }
+
+    let number = match *value {
+        FieldValue::Byte(b) => Number::Integral(b.into()),
+        FieldValue::Short(s) => Number::Integral(s.into()),
+        FieldValue::Char(c) => Number::Integral(c.into()),
+        FieldValue::Int(i) => Number::Integral(i.into()),
+        FieldValue::Long(l) => Number::Integral(l),
+        FieldValue::Float(f) => Number::Floating(f.into()),
+        FieldValue::Double(d) => Number::Floating(d),
+        FieldValue::Boolean(_) | FieldValue::Reference(_) => {
+            return value.clone();
+        },
+    };
+    // narrowing to byte, short and char goes through int,
+    // like d2i followed by i2b does
+    let int = match number {
+        Number::Integral(i) => i as i32,
+        Number::Floating(f) => f as i32,
+    };
+    match kind {
+        SimpleArgumentKind::Byte => FieldValue::Byte(int as i8),
+        SimpleArgumentKind::Short => FieldValue::Short(int as i16),
+        SimpleArgumentKind::Char => FieldValue::Char(int as u16),
+        SimpleArgumentKind::Int => FieldValue::Int(int),
+        SimpleArgumentKind::Long => FieldValue::Long(match number {
+            Number::Integral(i) => i,
+            Number::Floating(f) => f as i64,
+        }),
+        SimpleArgumentKind::Float => FieldValue::Float(match number {
+            Number::Integral(i) => i as f32,
+            Number::Floating(f) => f as f32,
+        }),
+        SimpleArgumentKind::Double => FieldValue::Double(match number {
+            Number::Integral(i) => i as f64,
+            Number::Floating(f) => f,
+        }),
+        SimpleArgumentKind::Boolean | SimpleArgumentKind::Class(_) => {
+            panic!("cannot convert {:?} to {:?}", value, kind)
+        },
+    }
+}
+
+/// Returns the value boxed by `this`.
+fn this_value(context: &NativeContext) -> FieldValue {
+    context
+        .this()
+        .with_parent_instance("wrapper class", |instance: &BoxInstance| {
+            instance.value.clone()
+        })
+}
+
+/// `valueOf(value)`, which boxes its primitive argument.
+fn value_of(context: &mut NativeContext) -> RustMethodReturn {
+    let value = match &context.method().parameters[0] {
+        ArgumentKind::Simple(SimpleArgumentKind::Boolean) => {
+            FieldValue::Boolean((context.int(0) & 1) as u8)
+        },
+        ArgumentKind::Simple(SimpleArgumentKind::Byte) => {
+            FieldValue::Byte(context.int(0) as i8)
+        },
+        ArgumentKind::Simple(SimpleArgumentKind::Char) => {
+            FieldValue::Char(context.int(0) as u16)
+        },
+        ArgumentKind::Simple(SimpleArgumentKind::Short) => {
+            FieldValue::Short(context.int(0) as i16)
+        },
+        ArgumentKind::Simple(SimpleArgumentKind::Int) => {
+            FieldValue::Int(context.int(0))
+        },
+        ArgumentKind::Simple(SimpleArgumentKind::Long) => {
+            FieldValue::Long(context.long(0))
+        },
+        ArgumentKind::Simple(SimpleArgumentKind::Float) => {
+            FieldValue::Float(context.float(0))
+        },
+        ArgumentKind::Simple(SimpleArgumentKind::Double) => {
+            FieldValue::Double(context.double(0))
+        },
+        kind => panic!("cannot box {:?}", kind),
+    };
+    RustMethodReturn::Value(FieldValue::Reference(Some(new_box(
+        context.class.clone(),
+        value,
+    ))))
+}
+
+/// The static initializer of `Boolean`, which creates `TRUE` and `FALSE`.
+fn initialize_boolean(context: &mut NativeContext) -> RustMethodReturn {
+    for (name, value) in [("TRUE", 1), ("FALSE", 0)] {
+        let field = context
+            .class
+            .get_static_field(name)
+            .expect("Boolean has the field");
+        let value = new_box(context.class.clone(), FieldValue::Boolean(value));
+        field.value.replace(FieldValue::Reference(Some(value)));
+    }
+    RustMethodReturn::Void
+}
+
+/// `xxxValue()`, which converts the boxed value
+/// to the return type of the method.
+fn primitive_value(context: &mut NativeContext) -> RustMethodReturn {
+    let value = this_value(context);
+    let kind = match &context.method().return_type {
+        Some(ArgumentKind::Simple(kind)) => kind,
+        kind => panic!("{:?} is no primitive type", kind),
+    };
+    RustMethodReturn::Value(convert(&value, kind))
+}
+
+fn to_string(context: &mut NativeContext) -> RustMethodReturn {
+    let string = match this_value(context) {
+        FieldValue::Boolean(b) => (b != 0).to_string(),
+        FieldValue::Char(c) => char::from_u32(c.into())
+            .unwrap_or(char::REPLACEMENT_CHARACTER)
+            .to_string(),
+        FieldValue::Byte(b) => b.to_string(),
+        FieldValue::Short(s) => s.to_string(),
+        FieldValue::Int(i) => i.to_string(),
+        FieldValue::Long(l) => l.to_string(),
+        FieldValue::Float(f) => floating_point_string(f),
+        FieldValue::Double(d) => floating_point_string(d),
+        FieldValue::Reference(_) => unreachable!("boxes hold primitives"),
+    };
+    RustMethodReturn::Value(FieldValue::Reference(Some(Rc::new(
+        context.heap.new_string(string),
+    ))))
+}
+
+/// Returns the hash code like the JDK does, e.g. the value for ints.
+fn hash_code(context: &mut NativeContext) -> RustMethodReturn {
+    let hash_code = match this_value(context) {
+        FieldValue::Boolean(b) => {
+            if b != 0 {
+                1231
+            } else {
+                1237
+            }
+        },
+        FieldValue::Char(c) => c.into(),
+        FieldValue::Byte(b) => b.into(),
+        FieldValue::Short(s) => s.into(),
+        FieldValue::Int(i) => i,
+        FieldValue::Long(l) => (l ^ (l >> 32)) as i32,
+        FieldValue::Float(f) => f.to_bits() as i32,
+        FieldValue::Double(d) => {
+            let bits = d.to_bits();
+            (bits ^ (bits >> 32)) as i32
+        },
+        FieldValue::Reference(_) => unreachable!("boxes hold primitives"),
+    };
+    RustMethodReturn::Value(FieldValue::Int(hash_code))
+}
+
+/// Whether the argument boxes the same value of the same type.
+///
+/// Floating point values are compared by their bits,
+/// so that `NaN` equals itself.
+fn equals(context: &mut NativeContext) -> RustMethodReturn {
+    let value = this_value(context);
+    let equal = context
+        .reference(0)
+        .and_then(|other| unbox(&other))
+        .is_some_and(|other| match (&value, &other) {
+            (FieldValue::Boolean(a), FieldValue::Boolean(b)) => a == b,
+            (FieldValue::Byte(a), FieldValue::Byte(b)) => a == b,
+            (FieldValue::Char(a), FieldValue::Char(b)) => a == b,
+            (FieldValue::Short(a), FieldValue::Short(b)) => a == b,
+            (FieldValue::Int(a), FieldValue::Int(b)) => a == b,
+            (FieldValue::Long(a), FieldValue::Long(b)) => a == b,
+            (FieldValue::Float(a), FieldValue::Float(b)) => {
+                a.to_bits() == b.to_bits()
+            },
+            (FieldValue::Double(a), FieldValue::Double(b)) => {
+                a.to_bits() == b.to_bits()
+            },
+            _ => false,
+        });
+    RustMethodReturn::Value(FieldValue::Boolean(equal.into()))
+}
+
+impl Class for BoxClass {
+    fn methods(&self) -> &[Rc<Method>] {
+        self.methods.as_slice()
+    }
+
+    fn static_fields(&self) -> &[Rc<Field>] {
+        self.static_fields.as_slice()
+    }
+
+    fn instance_fields(&self) -> &[FieldDescriptor] {
+        &[]
+    }
+
+    fn class_identifier(&self) -> &ClassIdentifier {
+        &self.class_identifier
+    }
+
+    fn super_class(&self) -> Option<Rc<dyn Class>> {
+        Some(self.object_class.clone())
+    }
+
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
+        &[]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn access_flags(&self) -> enumflags2::BitFlags<ClassAccessFlag> {
+        ClassAccessFlag::Public | ClassAccessFlag::Final
+    }
+
+    fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance> {
+        // make sure that self and cls really are equal
+        let _cls_ref: &Self =
+            cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
+
+        panic!("boxes are created by valueOf(), not by new");
+    }
+}
+
+pub struct BoxInstance {
+    class: Rc<dyn Class>,
+    object_instance: Rc<dyn ClassInstance>,
+    value: FieldValue,
+}
+
+impl ClassInstance for BoxInstance {
+    fn class(&self) -> Rc<dyn Class> {
+        self.class.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn instance_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn parent_instance(&self) -> Option<Rc<dyn ClassInstance>> {
+        Some(self.object_instance.clone())
+    }
+}
@@ -0,0 +1,409 @@
+use std::{any::Any, cell::OnceCell, rc::Rc};
+
+use crate::{
+    class::{
+        access_flags::MethodAccessFlag,
+        builtin_classes::{array::ByteArrayInstance, reflect::new_throwable},
+        class_identifier, ArgumentKind, Class, ClassIdentifier, ClassInstance,
+        Field, FieldDescriptor, FieldValue, Method, MethodCode,
+        RustMethodReturn, SimpleArgumentKind,
+    },
+    classloader::{
+        define_class, delegate_class_loading, file_parser::parse_bytes,
+        parse_class_identifier, ClassLoadingError,
+    },
+    executor::native_context::NativeContext,
+    heap::{ClassLoaderId, Heap},
+};
+
+/// `java.lang.ClassLoader`, which can be subclassed
+/// to define classes from bytes obtained by the program itself.
+///
+/// Each instance has its own namespace in the heap,
+/// see [`ClassLoaderId`].
+/// `loadClass` and `findClass` can be overridden by bytecode classes.
+pub struct ClassLoaderClass {
+    class_identifier: ClassIdentifier,
+    object_class: Rc<dyn Class>,
+    methods: Vec<Rc<Method>>,
+}
+
+impl ClassLoaderClass {
+    pub fn new(object_class: Rc<dyn Class>) -> Self {
+        let class_kind = ArgumentKind::Simple(SimpleArgumentKind::Class(
+            "java/lang/Class".to_string(),
+        ));
+        let bytes = ArgumentKind::Array {
+            dimensions: 1,
+            kind: SimpleArgumentKind::Byte,
+        };
+        let int = ArgumentKind::Simple(SimpleArgumentKind::Int);
+        let string = ArgumentKind::Simple(SimpleArgumentKind::Class(
+            "java/lang/String".to_string(),
+        ));
+
+        Self {
+            class_identifier: class_identifier!(java / lang, ClassLoader),
+            object_class,
+            methods: vec![
+                Rc::new(Method {
+                    code: MethodCode::Rust(init),
+                    name: "<init>".to_owned(),
+                    parameters: vec![],
+                    return_type: None,
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(init_with_parent),
+                    name: "<init>".to_owned(),
+                    parameters: vec![ArgumentKind::Simple(
+                        SimpleArgumentKind::Class(
+                            "java/lang/ClassLoader".to_string(),
+                        ),
+                    )],
+                    return_type: None,
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(load_class),
+                    name: "loadClass".to_owned(),
+                    parameters: vec![string.clone()],
+                    return_type: Some(class_kind.clone()),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(find_class),
+                    name: "findClass".to_owned(),
+                    parameters: vec![string.clone()],
+                    return_type: Some(class_kind.clone()),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(define_named_class),
+                    name: "defineClass".to_owned(),
+                    parameters: vec![
+                        string,
+                        bytes.clone(),
+                        int.clone(),
+                        int.clone(),
+                    ],
+                    return_type: Some(class_kind.clone()),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(define_unnamed_class),
+                    name: "defineClass".to_owned(),
+                    parameters: vec![bytes, int.clone(), int],
+                    return_type: Some(class_kind),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+            ],
+        }
+    }
+}
+
+/// `ClassLoader()`, using the application class loader as parent
+fn init(context: &mut NativeContext) -> RustMethodReturn {
+    register(context, ClassLoaderId::APPLICATION)
+}
+
+/// `ClassLoader(ClassLoader parent)`,
+/// where `null` stands for the bootstrap class loader
+fn init_with_parent(context: &mut NativeContext) -> RustMethodReturn {
+    let parent = match context.reference(0) {
+        None => ClassLoaderId::BOOTSTRAP,
+        Some(parent) => loader_id(&parent),
+    };
+    register(context, parent)
+}
+
+/// Create the namespace of `this` in the heap.
+fn register(
+    context: &mut NativeContext,
+    parent: ClassLoaderId,
+) -> RustMethodReturn {
+    let this = context.this();
+    let id = context.heap.create_class_loader(parent, vec![]);
+    this.with_parent_instance(
+        "ClassLoader",
+        |instance: &ClassLoaderInstance| {
+            instance
+                .id
+                .set(id)
+                .expect("class loader is initialized once")
+        },
+    );
+    context.heap.set_class_loader_instance(id, this);
+    RustMethodReturn::Void
+}
+
+/// The id of the class loader `instance` (or one of its subclasses).
+pub fn loader_id(instance: &Rc<dyn ClassInstance>) -> ClassLoaderId {
+    instance
+        .with_parent_instance(
+            "ClassLoader",
+            |instance: &ClassLoaderInstance| instance.id.get().copied(),
+        )
+        .expect("class loader has been initialized")
+}
+
+/// Returns the `java.lang.ClassLoader` instance of `loader`,
+/// or null for the bootstrap class loader.
+///
+/// The instance of the application class loader is created on first use.
+pub fn class_loader_object(
+    heap: &mut Heap,
+    loader: ClassLoaderId,
+) -> Option<Rc<dyn ClassInstance>> {
+    if loader == ClassLoaderId::BOOTSTRAP {
+        return None;
+    }
+    if let Some(instance) = heap.class_loader_instance(loader) {
+        return Some(instance);
+    }
+    let class = heap
+        .find_class(
+            ClassLoaderId::BOOTSTRAP,
+            &class_identifier!(java / lang, ClassLoader),
+        )
+        .expect("ClassLoader is a builtin class")
+        .clone();
+    let instance = class.new_instance(class.clone());
+    instance.with_parent_instance(
+        "ClassLoader",
+        |instance: &ClassLoaderInstance| instance.id.set(loader).unwrap(),
+    );
+    heap.set_class_loader_instance(loader, instance.clone());
+    Some(instance)
+}
+
+/// `loadClass(String name)`, delegating to the parent first
+fn load_class(context: &mut NativeContext) -> RustMethodReturn {
+    let Some(name) = context.string(0) else {
+        return RustMethodReturn::Throw(new_throwable(
+            context.heap,
+            &class_identifier!(java / lang, NullPointerException),
+        ));
+    };
+    let id = loader_id(&context.this());
+    match delegate_class_loading(&name.replace('.', "/"), id, context.heap) {
+        Ok(class) => RustMethodReturn::Value(FieldValue::Reference(Some(
+            context.heap.class_object(&class),
+        ))),
+        Err(e) => throw_loading_error(context, e),
+    }
+}
+
+/// `findClass(String name)`, which finds nothing by default
+fn find_class(context: &mut NativeContext) -> RustMethodReturn {
+    let class_not_found =
+        class_identifier!(java / lang, ClassNotFoundException);
+    match context.string(0) {
+        Some(name) => context.throw_new(&class_not_found, &name),
+        None => RustMethodReturn::Throw(new_throwable(
+            context.heap,
+            &class_not_found,
+        )),
+    }
+}
+
+/// Throw the exception corresponding to `error`,
+/// e.g. `ClassNotFoundException` if the class doesn't exist.
+pub fn throw_loading_error(
+    context: &mut NativeContext,
+    error: ClassLoadingError,
+) -> RustMethodReturn {
+    match error {
+        ClassLoadingError::NotFound(name) => context.throw_new(
+            &class_identifier!(java / lang, ClassNotFoundException),
+            &name.replace('/', "."),
+        ),
+        ClassLoadingError::Format(e) => context.throw_new(
+            &class_identifier!(java / lang, ClassFormatError),
+            &e.to_string(),
+        ),
+        ClassLoadingError::Verify(e) => context.throw_new(
+            &class_identifier!(java / lang, VerifyError),
+            &e.to_string(),
+        ),
+        ClassLoadingError::Invalid(e) => context.throw_new(
+            &class_identifier!(java / lang, ClassNotFoundException),
+            &e.to_string(),
+        ),
+        ClassLoadingError::Exception(e) => RustMethodReturn::Throw(e),
+    }
+}
+
+/// `defineClass(String name, byte[] b, int off, int len)`
+fn define_named_class(context: &mut NativeContext) -> RustMethodReturn {
+    let name = context.string(0);
+    define(context, name, 1)
+}
+
+/// `defineClass(byte[] b, int off, int len)`
+fn define_unnamed_class(context: &mut NativeContext) -> RustMethodReturn {
+    define(context, None, 0)
+}
+
+/// Define the class in the byte array argument at `bytes_index`
+/// (followed by offset and length) and return its class object.
+///
+/// If `name` is given, it has to match the name of the defined class.
+fn define(
+    context: &mut NativeContext,
+    name: Option<String>,
+    bytes_index: usize,
+) -> RustMethodReturn {
+    let Some(bytes) = context.reference(bytes_index) else {
+        return RustMethodReturn::Throw(new_throwable(
+            context.heap,
+            &class_identifier!(java / lang, NullPointerException),
+        ));
+    };
+    let bytes: &ByteArrayInstance = bytes.as_ref().try_into().unwrap();
+    let offset = context.int(bytes_index + 1);
+    let length = context.int(bytes_index + 2);
+    let range = usize::try_from(offset)
+        .ok()
+        .zip(usize::try_from(length).ok())
+        .map(|(offset, length)| offset..offset + length)
+        .filter(|range| range.end <= bytes.length());
+    let Some(range) = range else {
+        return context.throw_new(
+            &class_identifier!(java / lang, IndexOutOfBoundsException),
+            &format!(
+                "Range [{}, {} + {}) out of bounds for length {}",
+                offset,
+                offset,
+                length,
+                bytes.length()
+            ),
+        );
+    };
+    let content: Vec<u8> = range
+        .map(|index| bytes.get(index).expect("index is in range") as u8)
+        .collect();
+
+    let raw_class = match parse_bytes(&content) {
+        Ok(raw_class) => raw_class,
+        Err(e) => {
+            return context.throw_new(
+                &class_identifier!(java / lang, ClassFormatError),
+                &e.to_string(),
+            );
+        },
+    };
+    let class_name = raw_class.class_name().unwrap_or_default().to_string();
+    let class_identifier = parse_class_identifier(&class_name);
+    if let Some(name) = name.map(|name| name.replace('.', "/")) {
+        if name != class_name {
+            return context.throw_new(
+                &class_identifier!(java / lang, NoClassDefFoundError),
+                &format!("{} (wrong name: {})", class_name, name),
+            );
+        }
+    }
+    let id = loader_id(&context.this());
+    if context.heap.find_class(id, &class_identifier).is_some() {
+        return context.throw_new(
+            &class_identifier!(java / lang, LinkageError),
+            &format!(
+                "duplicate class definition for {}",
+                class_identifier.java_name()
+            ),
+        );
+    }
+
+    match define_class(raw_class, id, context.heap) {
+        Ok(class) => RustMethodReturn::Value(FieldValue::Reference(Some(
+            context.heap.class_object(&class),
+        ))),
+        // e.g. the super class
+        Err(ClassLoadingError::NotFound(name)) => context.throw_new(
+            &class_identifier!(java / lang, NoClassDefFoundError),
+            &name,
+        ),
+        Err(e) => throw_loading_error(context, e),
+    }
+}
+
+impl Class for ClassLoaderClass {
+    fn methods(&self) -> &[Rc<Method>] {
+        self.methods.as_slice()
+    }
+
+    fn static_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn instance_fields(&self) -> &[FieldDescriptor] {
+        &[]
+    }
+
+    fn class_identifier(&self) -> &crate::class::ClassIdentifier {
+        &self.class_identifier
+    }
+
+    fn super_class(&self) -> Option<Rc<dyn Class>> {
+        Some(self.object_class.clone())
+    }
+
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
+        &[]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance> {
+        // make sure that self and cls really are equal
+        let _cls_ref: &Self =
+            cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
+
+        Rc::new(ClassLoaderInstance {
+            class: cls.clone(),
+            object_instance: self
+                .object_class
+                .new_instance(self.object_class.clone()),
+            id: OnceCell::new(),
+        })
+    }
+}
+
+pub struct ClassLoaderInstance {
+    class: Rc<dyn Class>,
+    object_instance: Rc<dyn ClassInstance>,
+    /// Set by the constructor.
+    id: OnceCell<ClassLoaderId>,
+}
+
+impl ClassInstance for ClassLoaderInstance {
+    fn class(&self) -> Rc<dyn Class> {
+        self.class.clone()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn instance_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn parent_instance(&self) -> Option<Rc<dyn ClassInstance>> {
+        Some(self.object_instance.clone())
+    }
+}
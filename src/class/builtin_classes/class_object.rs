@@ -0,0 +1,607 @@
+use std::{any::Any, rc::Rc};
+
+use crate::{
+    class::{
+        access_flags::{ClassAccessFlag, MethodAccessFlag},
+        attributes::Annotation,
+        builtin_classes::{
+            annotation::new_annotation_instance,
+            array::ObjectArray,
+            class_loader::{class_loader_object, throw_loading_error},
+            reflect::{new_member_instance, new_throwable, Member},
+            PrimitiveClass,
+        },
+        class_identifier, ArgumentKind, ArrayName, BytecodeClass, Class,
+        ClassIdentifier, ClassInstance, ClassName, Field, FieldDescriptor,
+        FieldValue, Method, MethodCode, RustMethodReturn, SimpleArgumentKind,
+    },
+    classloader::{load_class_by_name, parse_class_or_array_identifier},
+    executor::{initialize_class, native_context::NativeContext},
+    heap::{ClassLoaderId, Heap},
+};
+
+type Exception = Rc<dyn ClassInstance>;
+
+/// `java.lang.Class`, whose instances represent loaded classes.
+pub struct ClassClass {
+    class_identifier: ClassIdentifier,
+    object_class: Rc<dyn Class>,
+    methods: Vec<Rc<Method>>,
+}
+
+impl ClassClass {
+    pub fn new(object_class: Rc<dyn Class>) -> Self {
+        let class_kind = |name: &str| {
+            ArgumentKind::Simple(SimpleArgumentKind::Class(name.to_string()))
+        };
+        let boolean_kind = ArgumentKind::Simple(SimpleArgumentKind::Boolean);
+        let member_array_kind = |name: &str| ArgumentKind::Array {
+            dimensions: 1,
+            kind: SimpleArgumentKind::Class(name.to_string()),
+        };
+        let method = |code, name: &str, parameters, return_type| {
+            Rc::new(Method {
+                code: MethodCode::Rust(code),
+                name: name.to_owned(),
+                parameters,
+                return_type: Some(return_type),
+                is_static: false,
+                access_flags: MethodAccessFlag::Public.into(),
+                attributes: Default::default(),
+            })
+        };
+        Self {
+            class_identifier: class_identifier!(java / lang, Class),
+            object_class,
+            methods: vec![
+                Rc::new(Method {
+                    code: MethodCode::Rust(for_name),
+                    name: "forName".to_owned(),
+                    parameters: vec![class_kind("java/lang/String")],
+                    return_type: Some(class_kind("java/lang/Class")),
+                    is_static: true,
+                    access_flags: MethodAccessFlag::Public
+                        | MethodAccessFlag::Static,
+                    attributes: Default::default(),
+                }),
+                method(
+                    get_name,
+                    "getName",
+                    vec![],
+                    class_kind("java/lang/String"),
+                ),
+                method(
+                    get_simple_name,
+                    "getSimpleName",
+                    vec![],
+                    class_kind("java/lang/String"),
+                ),
+                method(
+                    to_string,
+                    "toString",
+                    vec![],
+                    class_kind("java/lang/String"),
+                ),
+                method(
+                    get_superclass,
+                    "getSuperclass",
+                    vec![],
+                    class_kind("java/lang/Class"),
+                ),
+                method(
+                    get_interfaces,
+                    "getInterfaces",
+                    vec![],
+                    ArgumentKind::Array {
+                        dimensions: 1,
+                        kind: SimpleArgumentKind::Class(
+                            "java/lang/Class".to_string(),
+                        ),
+                    },
+                ),
+                method(
+                    get_class_loader,
+                    "getClassLoader",
+                    vec![],
+                    class_kind("java/lang/ClassLoader"),
+                ),
+                method(
+                    is_instance,
+                    "isInstance",
+                    vec![class_kind("java/lang/Object")],
+                    boolean_kind.clone(),
+                ),
+                method(
+                    is_assignable_from,
+                    "isAssignableFrom",
+                    vec![class_kind("java/lang/Class")],
+                    boolean_kind.clone(),
+                ),
+                method(is_array, "isArray", vec![], boolean_kind.clone()),
+                method(
+                    get_component_type,
+                    "getComponentType",
+                    vec![],
+                    class_kind("java/lang/Class"),
+                ),
+                method(
+                    get_annotation,
+                    "getAnnotation",
+                    vec![class_kind("java/lang/Class")],
+                    class_kind("java/lang/annotation/Annotation"),
+                ),
+                method(
+                    get_declared_methods,
+                    "getDeclaredMethods",
+                    vec![],
+                    member_array_kind("java/lang/reflect/Method"),
+                ),
+                method(
+                    get_declared_constructors,
+                    "getDeclaredConstructors",
+                    vec![],
+                    member_array_kind("java/lang/reflect/Constructor"),
+                ),
+                method(
+                    get_declared_fields,
+                    "getDeclaredFields",
+                    vec![],
+                    member_array_kind("java/lang/reflect/Field"),
+                ),
+                method(
+                    is_annotation_present,
+                    "isAnnotationPresent",
+                    vec![class_kind("java/lang/Class")],
+                    boolean_kind,
+                ),
+            ],
+        }
+    }
+
+    /// Create the object representing `represented`.
+    ///
+    /// Use [`Heap::class_object`](crate::heap::Heap::class_object),
+    /// so that there is only one object per class.
+    pub fn new_class_object(
+        self: &Rc<Self>,
+        represented: Rc<dyn Class>,
+    ) -> ClassObject {
+        ClassObject {
+            class: self.clone(),
+            object_instance: self
+                .object_class
+                .new_instance(self.object_class.clone()),
+            represented,
+        }
+    }
+}
+
+/// Returns the class represented by the class object `object`.
+pub fn represented_class(object: &Rc<dyn ClassInstance>) -> Rc<dyn Class> {
+    object.with_parent_instance("Class", |instance: &ClassObject| {
+        instance.represented.clone()
+    })
+}
+
+/// Returns `class` as a reference to its class object,
+/// or null if there is no class.
+fn class_object_value(
+    context: &mut NativeContext,
+    class: Option<Rc<dyn Class>>,
+) -> RustMethodReturn {
+    RustMethodReturn::Value(FieldValue::Reference(
+        class.map(|class| context.heap.class_object(&class)),
+    ))
+}
+
+fn string_value(
+    context: &mut NativeContext,
+    string: String,
+) -> RustMethodReturn {
+    RustMethodReturn::Value(FieldValue::Reference(Some(Rc::new(
+        context.heap.new_string(string),
+    ))))
+}
+
+/// `forName(String name)`, which loads the class named like
+/// `java.lang.String` or `[Ljava.lang.String;` and initializes it.
+///
+/// Classes are loaded by the application class loader,
+/// not the loader of the caller.
+fn for_name(context: &mut NativeContext) -> RustMethodReturn {
+    let Some(name) = context.string(0) else {
+        return RustMethodReturn::Throw(null_pointer_exception(context.heap));
+    };
+    let name = name.replace('.', "/");
+    let loader = ClassLoaderId::APPLICATION;
+
+    let identifier = parse_class_or_array_identifier(&name);
+    let class = match &identifier.class_name {
+        ClassName::Array {
+            name: array_name, ..
+        } => {
+            // array classes are created once their component class is loaded
+            if let ArrayName::Class(component) = array_name {
+                let component = ClassIdentifier {
+                    package: identifier.package.clone(),
+                    class_name: ClassName::Plain(component.clone()),
+                };
+                if let Err(e) = load_class_by_name(
+                    &component.to_string(),
+                    loader,
+                    context.heap,
+                ) {
+                    return throw_loading_error(context, e);
+                }
+            }
+            match context.heap.find_array_class(loader, &identifier) {
+                Some(class) => class,
+                None => {
+                    return context.throw_new(
+                        &class_identifier!(java / lang, ClassNotFoundException),
+                        &name.replace('/', "."),
+                    );
+                },
+            }
+        },
+        ClassName::Plain(_) => {
+            match load_class_by_name(&name, loader, context.heap) {
+                Ok(class) => class,
+                Err(e) => return throw_loading_error(context, e),
+            }
+        },
+    };
+    if let Err(e) = initialize_class(context.heap, &class) {
+        return RustMethodReturn::Throw(e);
+    }
+    class_object_value(context, Some(class))
+}
+
+fn get_name(context: &mut NativeContext) -> RustMethodReturn {
+    let name = represented_class(&context.this())
+        .class_identifier()
+        .java_name();
+    string_value(context, name)
+}
+
+/// Returns the simple name of `class`,
+/// e.g. `int[]` for an array of ints.
+fn simple_name(heap: &Heap, class: &Rc<dyn Class>) -> String {
+    match component_type(heap, class) {
+        Some(component) => format!("{}[]", simple_name(heap, &component)),
+        None => class.simple_name(),
+    }
+}
+
+fn get_simple_name(context: &mut NativeContext) -> RustMethodReturn {
+    let name = simple_name(context.heap, &represented_class(&context.this()));
+    string_value(context, name)
+}
+
+/// Returns `class` like `Class.toString()` does,
+/// e.g. `class java.lang.String`, `interface java.lang.Runnable` or `int`.
+pub fn class_description(class: &Rc<dyn Class>) -> String {
+    let name = class.class_identifier().java_name();
+    if class.as_any().is::<PrimitiveClass>() {
+        name
+    } else if class.access_flags().contains(ClassAccessFlag::Interface) {
+        format!("interface {}", name)
+    } else {
+        format!("class {}", name)
+    }
+}
+
+fn to_string(context: &mut NativeContext) -> RustMethodReturn {
+    let string = class_description(&represented_class(&context.this()));
+    string_value(context, string)
+}
+
+/// Returns the superclass,
+/// or null for `Object`, interfaces and primitive types.
+fn get_superclass(context: &mut NativeContext) -> RustMethodReturn {
+    let class = represented_class(&context.this());
+    let super_class =
+        if class.access_flags().contains(ClassAccessFlag::Interface) {
+            None
+        } else {
+            class.super_class()
+        };
+    class_object_value(context, super_class)
+}
+
+/// Returns the interfaces directly implemented by the class
+/// or extended by the interface.
+fn get_interfaces(context: &mut NativeContext) -> RustMethodReturn {
+    let class = represented_class(&context.this());
+    let interfaces = class
+        .interfaces()
+        .iter()
+        .map(|interface| Some(context.heap.class_object(interface)))
+        .collect();
+    let class_class = context
+        .heap
+        .find_class(
+            ClassLoaderId::BOOTSTRAP,
+            &class_identifier!(java / lang, Class),
+        )
+        .expect("Class is a builtin class")
+        .clone();
+    let array = context.heap.new_object_array(&class_class, interfaces);
+    RustMethodReturn::Value(FieldValue::Reference(Some(Rc::new(array))))
+}
+
+/// Returns the class loader which defined the class,
+/// or null for the bootstrap class loader.
+fn get_class_loader(context: &mut NativeContext) -> RustMethodReturn {
+    let class = represented_class(&context.this());
+    RustMethodReturn::Value(FieldValue::Reference(class_loader_object(
+        context.heap,
+        class.class_loader(),
+    )))
+}
+
+/// Whether the argument is an instance of the class.
+fn is_instance(context: &mut NativeContext) -> RustMethodReturn {
+    let class = represented_class(&context.this());
+    let is_instance = context
+        .reference(0)
+        .is_some_and(|object| object.class().is_assignable_to(class.as_ref()));
+    RustMethodReturn::Value(FieldValue::Boolean(is_instance.into()))
+}
+
+/// Whether instances of the argument class are instances of the class.
+fn is_assignable_from(context: &mut NativeContext) -> RustMethodReturn {
+    let other = match argument_class(context) {
+        Ok(class) => class,
+        Err(e) => return RustMethodReturn::Throw(e),
+    };
+    let class = represented_class(&context.this());
+    let is_assignable = other.is_assignable_to(class.as_ref());
+    RustMethodReturn::Value(FieldValue::Boolean(is_assignable.into()))
+}
+
+fn is_array(context: &mut NativeContext) -> RustMethodReturn {
+    let class = represented_class(&context.this());
+    let is_array =
+        matches!(class.class_identifier().class_name, ClassName::Array { .. });
+    RustMethodReturn::Value(FieldValue::Boolean(is_array.into()))
+}
+
+/// Returns the class of the elements of the array class `class`,
+/// or `None` if it is no array class.
+fn component_type(heap: &Heap, class: &Rc<dyn Class>) -> Option<Rc<dyn Class>> {
+    if let Ok(object_array) = <&ObjectArray>::try_from(class.as_ref()) {
+        return Some(object_array.component_class());
+    }
+    let name = match &class.class_identifier().class_name {
+        ClassName::Array { name, .. } => match name {
+            ArrayName::Boolean => "boolean",
+            ArrayName::Byte => "byte",
+            ArrayName::Char => "char",
+            ArrayName::Double => "double",
+            ArrayName::Float => "float",
+            ArrayName::Int => "int",
+            ArrayName::Long => "long",
+            ArrayName::Short => "short",
+            ArrayName::Class(_) => unreachable!("object arrays are handled"),
+        },
+        ClassName::Plain(_) => return None,
+    };
+    Some(heap.primitive_class(name).expect("primitive classes exist"))
+}
+
+/// Returns the class of the elements if the class is an array class,
+/// otherwise null.
+fn get_component_type(context: &mut NativeContext) -> RustMethodReturn {
+    let class = represented_class(&context.this());
+    let component = component_type(context.heap, &class);
+    class_object_value(context, component)
+}
+
+/// Returns the annotation of `class`, whose type is `annotation_type`.
+fn find_annotation(
+    class: &Rc<dyn Class>,
+    annotation_type: &Rc<dyn Class>,
+) -> Option<Annotation> {
+    let type_name = format!("L{};", annotation_type.class_identifier());
+    class
+        .as_any()
+        .downcast_ref::<BytecodeClass>()?
+        .attributes
+        .annotations
+        .iter()
+        .find(|annotation| annotation.type_name == type_name)
+        .cloned()
+}
+
+/// Returns the class represented by the first argument,
+/// or a `NullPointerException` if it is null.
+fn argument_class(
+    context: &mut NativeContext,
+) -> Result<Rc<dyn Class>, Exception> {
+    match context.reference(0) {
+        Some(argument) => Ok(represented_class(&argument)),
+        None => Err(null_pointer_exception(context.heap)),
+    }
+}
+
+fn null_pointer_exception(heap: &mut Heap) -> Exception {
+    new_throwable(heap, &class_identifier!(java / lang, NullPointerException))
+}
+
+fn get_annotation(context: &mut NativeContext) -> RustMethodReturn {
+    let annotation_type = match argument_class(context) {
+        Ok(class) => class,
+        Err(e) => return RustMethodReturn::Throw(e),
+    };
+    let class = represented_class(&context.this());
+    let Some(annotation) = find_annotation(&class, &annotation_type) else {
+        return RustMethodReturn::Value(FieldValue::Reference(None));
+    };
+    match new_annotation_instance(
+        context.heap,
+        class.class_loader(),
+        &annotation,
+    ) {
+        Ok(instance) => {
+            RustMethodReturn::Value(FieldValue::Reference(Some(instance)))
+        },
+        Err(e) => RustMethodReturn::Throw(e),
+    }
+}
+
+fn is_annotation_present(context: &mut NativeContext) -> RustMethodReturn {
+    let annotation_type = match argument_class(context) {
+        Ok(class) => class,
+        Err(e) => return RustMethodReturn::Throw(e),
+    };
+    let class = represented_class(&context.this());
+    let is_present = find_annotation(&class, &annotation_type).is_some();
+    RustMethodReturn::Value(FieldValue::Boolean(is_present.into()))
+}
+
+/// Returns the `Method`, `Constructor` or `Field` objects `members`
+/// of `class` as array.
+fn member_array(
+    context: &mut NativeContext,
+    class: &Rc<dyn Class>,
+    member_class: &ClassIdentifier,
+    members: Vec<Member>,
+) -> RustMethodReturn {
+    let members = members
+        .into_iter()
+        .map(|member| {
+            Some(new_member_instance(context.heap, class.clone(), member))
+        })
+        .collect();
+    let member_class = context
+        .heap
+        .find_class(ClassLoaderId::BOOTSTRAP, member_class)
+        .expect("reflection classes are builtin classes")
+        .clone();
+    let array = context.heap.new_object_array(&member_class, members);
+    RustMethodReturn::Value(FieldValue::Reference(Some(Rc::new(array))))
+}
+
+/// Returns the methods declared by the class,
+/// excluding constructors and the static initializer.
+fn get_declared_methods(context: &mut NativeContext) -> RustMethodReturn {
+    let class = represented_class(&context.this());
+    let methods = class
+        .methods()
+        .iter()
+        .filter(|method| !method.name.starts_with('<'))
+        .map(|method| Member::Method(method.clone()))
+        .collect();
+    member_array(
+        context,
+        &class,
+        &class_identifier!(java / lang / reflect, Method),
+        methods,
+    )
+}
+
+fn get_declared_constructors(context: &mut NativeContext) -> RustMethodReturn {
+    let class = represented_class(&context.this());
+    let constructors = class
+        .methods()
+        .iter()
+        .filter(|method| method.name == "<init>")
+        .map(|method| Member::Method(method.clone()))
+        .collect();
+    member_array(
+        context,
+        &class,
+        &class_identifier!(java / lang / reflect, Constructor),
+        constructors,
+    )
+}
+
+/// Returns the fields declared by the class,
+/// the static fields before the instance fields.
+fn get_declared_fields(context: &mut NativeContext) -> RustMethodReturn {
+    let class = represented_class(&context.this());
+    let static_fields = class
+        .static_fields()
+        .iter()
+        .map(|field| Member::StaticField(field.clone()));
+    let instance_fields =
+        class
+            .instance_fields()
+            .iter()
+            .map(|field| Member::InstanceField {
+                name: field.name.clone(),
+                access_flags: field.access_flags,
+                field_type: field.field_type.clone(),
+            });
+    let fields = static_fields.chain(instance_fields).collect();
+    member_array(
+        context,
+        &class,
+        &class_identifier!(java / lang / reflect, Field),
+        fields,
+    )
+}
+
+impl Class for ClassClass {
+    fn methods(&self) -> &[Rc<Method>] {
+        self.methods.as_slice()
+    }
+
+    fn static_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn instance_fields(&self) -> &[FieldDescriptor] {
+        &[]
+    }
+
+    fn class_identifier(&self) -> &crate::class::ClassIdentifier {
+        &self.class_identifier
+    }
+
+    fn super_class(&self) -> Option<Rc<dyn Class>> {
+        Some(self.object_class.clone())
+    }
+
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
+        &[]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance> {
+        // make sure that self and cls really are equal
+        let _cls_ref: &Self =
+            cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
+
+        panic!("class objects cannot be created with new");
+    }
+}
+
+pub struct ClassObject {
+    class: Rc<dyn Class>,
+    object_instance: Rc<dyn ClassInstance>,
+    /// The class represented by this object.
+    pub represented: Rc<dyn Class>,
+}
+
+impl ClassInstance for ClassObject {
+    fn class(&self) -> Rc<dyn Class> {
+        self.class.clone()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn instance_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn parent_instance(&self) -> Option<Rc<dyn ClassInstance>> {
+        Some(self.object_instance.clone())
+    }
+}
@@ -0,0 +1,335 @@
+//! `java.lang.Enum`, the superclass of all enum classes.
+
+use std::{any::Any, cell::OnceCell, rc::Rc};
+
+use crate::{
+    class::{
+        access_flags::MethodAccessFlag,
+        builtin_classes::{
+            array::ObjectArrayInstance, reflect::new_throwable, ClassObject,
+            StringInstance,
+        },
+        class_identifier, ArgumentKind, Class, ClassIdentifier, ClassInstance,
+        Field, FieldDescriptor, FieldValue, Method, MethodCode,
+        RustMethodReturn, SimpleArgumentKind,
+    },
+    executor::{invoke_static, native_context::NativeContext, ReturnValue},
+};
+
+pub struct EnumClass {
+    class_identifier: ClassIdentifier,
+    object_class: Rc<dyn Class>,
+    methods: Vec<Rc<Method>>,
+}
+
+impl EnumClass {
+    pub fn new(object_class: Rc<dyn Class>) -> Self {
+        let class_kind = |name: &str| {
+            ArgumentKind::Simple(SimpleArgumentKind::Class(name.to_string()))
+        };
+        let method = |code, name: &str, parameters, return_type| {
+            Rc::new(Method {
+                code: MethodCode::Rust(code),
+                name: name.to_owned(),
+                parameters,
+                return_type,
+                is_static: false,
+                access_flags: MethodAccessFlag::Public
+                    | MethodAccessFlag::Final,
+                attributes: Default::default(),
+            })
+        };
+
+        Self {
+            class_identifier: class_identifier!(java / lang, Enum),
+            object_class,
+            methods: vec![
+                Rc::new(Method {
+                    code: MethodCode::Rust(init),
+                    name: "<init>".to_owned(),
+                    parameters: vec![
+                        class_kind("java/lang/String"),
+                        ArgumentKind::Simple(SimpleArgumentKind::Int),
+                    ],
+                    return_type: None,
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Protected.into(),
+                    attributes: Default::default(),
+                }),
+                method(
+                    name,
+                    "name",
+                    vec![],
+                    Some(class_kind("java/lang/String")),
+                ),
+                method(
+                    ordinal,
+                    "ordinal",
+                    vec![],
+                    Some(ArgumentKind::Simple(SimpleArgumentKind::Int)),
+                ),
+                Rc::new(Method {
+                    code: MethodCode::Rust(name),
+                    name: "toString".to_owned(),
+                    parameters: vec![],
+                    return_type: Some(class_kind("java/lang/String")),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                method(
+                    compare_to,
+                    "compareTo",
+                    vec![class_kind("java/lang/Enum")],
+                    Some(ArgumentKind::Simple(SimpleArgumentKind::Int)),
+                ),
+                method(
+                    get_declaring_class,
+                    "getDeclaringClass",
+                    vec![],
+                    Some(class_kind("java/lang/Class")),
+                ),
+                Rc::new(Method {
+                    code: MethodCode::Rust(value_of),
+                    name: "valueOf".to_owned(),
+                    parameters: vec![
+                        class_kind("java/lang/Class"),
+                        class_kind("java/lang/String"),
+                    ],
+                    return_type: Some(class_kind("java/lang/Enum")),
+                    is_static: true,
+                    access_flags: MethodAccessFlag::Public
+                        | MethodAccessFlag::Static,
+                    attributes: Default::default(),
+                }),
+            ],
+        }
+    }
+}
+
+fn init(context: &mut NativeContext) -> RustMethodReturn {
+    let name = context.reference(0);
+    let ordinal = context.int(1);
+    context
+        .this()
+        .with_parent_instance("Enum", |instance: &EnumInstance| {
+            instance
+                .constant
+                .set((name.clone(), ordinal))
+                .expect("the enum constant has not been initialized");
+        });
+
+    RustMethodReturn::Void
+}
+
+/// Returns the name and ordinal of the enum constant `instance`.
+pub(crate) fn constant(
+    instance: &Rc<dyn ClassInstance>,
+) -> (Option<Rc<dyn ClassInstance>>, i32) {
+    instance.with_parent_instance("Enum", |instance: &EnumInstance| {
+        instance
+            .constant
+            .get()
+            .expect("the enum constant has been initialized")
+            .clone()
+    })
+}
+
+fn name(context: &mut NativeContext) -> RustMethodReturn {
+    let (name, _) = constant(&context.this());
+    RustMethodReturn::Value(FieldValue::Reference(name))
+}
+
+fn ordinal(context: &mut NativeContext) -> RustMethodReturn {
+    let (_, ordinal) = constant(&context.this());
+    RustMethodReturn::Value(FieldValue::Int(ordinal))
+}
+
+/// Returns the enum class of the constant `instance`,
+/// which is the superclass of constants with a class body.
+fn declaring_class(instance: &Rc<dyn ClassInstance>) -> Rc<dyn Class> {
+    let class = instance.class();
+    match class.super_class() {
+        Some(super_class)
+            if super_class.class_identifier()
+                != &class_identifier!(java / lang, Enum) =>
+        {
+            super_class
+        },
+        _ => class,
+    }
+}
+
+fn compare_to(context: &mut NativeContext) -> RustMethodReturn {
+    let this = context.this();
+    let Some(other) = context.reference(0) else {
+        return RustMethodReturn::Throw(new_throwable(
+            context.heap,
+            &class_identifier!(java / lang, NullPointerException),
+        ));
+    };
+    let (this_class, other_class) =
+        (declaring_class(&this), declaring_class(&other));
+    if !this_class.is_same_class(other_class.as_ref()) {
+        return context.throw_new(
+            &class_identifier!(java / lang, ClassCastException),
+            &format!(
+                "class {} cannot be cast to class {}",
+                other_class.class_identifier().java_name(),
+                this_class.class_identifier().java_name()
+            ),
+        );
+    }
+    let ((_, this_ordinal), (_, other_ordinal)) =
+        (constant(&this), constant(&other));
+    RustMethodReturn::Value(FieldValue::Int(this_ordinal - other_ordinal))
+}
+
+fn get_declaring_class(context: &mut NativeContext) -> RustMethodReturn {
+    let class = declaring_class(&context.this());
+    RustMethodReturn::Value(FieldValue::Reference(Some(
+        context.heap.class_object(&class),
+    )))
+}
+
+/// Returns the constant of the enum class named `name`,
+/// looking it up in the array returned by `values()`.
+fn value_of(context: &mut NativeContext) -> RustMethodReturn {
+    let Some(enum_class) = context.reference(0) else {
+        return RustMethodReturn::Throw(new_throwable(
+            context.heap,
+            &class_identifier!(java / lang, NullPointerException),
+        ));
+    };
+    let enum_class = enum_class
+        .with_parent_instance("Class", |instance: &ClassObject| {
+            instance.represented.clone()
+        });
+    let enum_name = enum_class.class_identifier().java_name();
+
+    let values_type = ArgumentKind::Array {
+        dimensions: 1,
+        kind: SimpleArgumentKind::Class(
+            enum_class.class_identifier().to_string(),
+        ),
+    };
+    let descriptor = (&[][..], Some(&values_type));
+    if enum_class
+        .find_method("values", descriptor, false)
+        .is_none()
+    {
+        return context.throw_new(
+            &class_identifier!(java / lang, IllegalArgumentException),
+            &format!("{} is not an enum class", enum_name),
+        );
+    }
+    let Some(name) = context.string(1) else {
+        return context.throw_new(
+            &class_identifier!(java / lang, NullPointerException),
+            "Name is null",
+        );
+    };
+    let values = match invoke_static(
+        context.heap,
+        &enum_class,
+        "values",
+        descriptor,
+        vec![],
+    ) {
+        Ok(ReturnValue::Reference(Some(values))) => values,
+        Ok(value) => panic!("values() returned {:?}", value),
+        Err(e) => return RustMethodReturn::Throw(e),
+    };
+    let values: &ObjectArrayInstance = values
+        .as_ref()
+        .try_into()
+        .expect("values() returns an array of constants");
+    for index in 0..values.length() {
+        let Some(value) = values.get(index).unwrap() else {
+            continue;
+        };
+        let (Some(constant_name), _) = constant(&value) else {
+            continue;
+        };
+        if constant_name
+            .as_any()
+            .downcast_ref::<StringInstance>()
+            .is_some_and(|constant_name| constant_name.string == name)
+        {
+            return RustMethodReturn::Value(FieldValue::Reference(Some(value)));
+        }
+    }
+    context.throw_new(
+        &class_identifier!(java / lang, IllegalArgumentException),
+        &format!("No enum constant {}.{}", enum_name, name),
+    )
+}
+
+impl Class for EnumClass {
+    fn methods(&self) -> &[Rc<Method>] {
+        self.methods.as_slice()
+    }
+
+    fn static_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn instance_fields(&self) -> &[FieldDescriptor] {
+        &[]
+    }
+
+    fn class_identifier(&self) -> &ClassIdentifier {
+        &self.class_identifier
+    }
+
+    fn super_class(&self) -> Option<Rc<dyn Class>> {
+        Some(self.object_class.clone())
+    }
+
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
+        &[]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance> {
+        // make sure that self and cls really are equal
+        let _cls_ref: &Self =
+            cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
+
+        Rc::new(EnumInstance {
+            class: cls.clone(),
+            object_instance: self
+                .object_class
+                .new_instance(self.object_class.clone()),
+            constant: OnceCell::new(),
+        })
+    }
+}
+
+pub struct EnumInstance {
+    class: Rc<dyn Class>,
+    object_instance: Rc<dyn ClassInstance>,
+    /// The name and ordinal, set by the constructor.
+    constant: OnceCell<(Option<Rc<dyn ClassInstance>>, i32)>,
+}
+
+impl ClassInstance for EnumInstance {
+    fn class(&self) -> Rc<dyn Class> {
+        self.class.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn instance_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn parent_instance(&self) -> Option<Rc<dyn ClassInstance>> {
+        Some(self.object_instance.clone())
+    }
+}
@@ -6,24 +6,24 @@ use std::{
 
 use crate::{
     class::{
-        class_identifier, ArgumentKind, Class, ClassIdentifier, ClassInstance,
-        Field, FieldDescriptor, Method, MethodCode, ReturnValue,
-        RustMethodReturn, SimpleArgumentKind,
+        access_flags::MethodAccessFlag, class_identifier, ArgumentKind, Class,
+        ClassIdentifier, ClassInstance, Field, FieldDescriptor, Method,
+        MethodCode, ReturnValue, RustMethodReturn, SimpleArgumentKind,
     },
-    executor::Frame,
+    executor::native_context::NativeContext,
 };
 
+/// `java.io.FileInputStream`, which can only read from stdin.
 pub struct FileInputStream {
     class_identifier: ClassIdentifier,
-    // TODO inherit InputStream
-    object_class: Rc<dyn Class>,
+    input_stream_class: Rc<dyn Class>,
     methods: Vec<Rc<Method>>,
 }
 
 impl FileInputStream {
-    pub fn new(object_class: Rc<dyn Class>) -> FileInputStream {
+    pub fn new(input_stream_class: Rc<dyn Class>) -> FileInputStream {
         FileInputStream {
-            object_class,
+            input_stream_class,
             class_identifier: class_identifier!(java / io, FileInputStream),
             methods: vec![Rc::new(Method {
                 code: MethodCode::Rust(read),
@@ -33,6 +33,8 @@ impl FileInputStream {
                     SimpleArgumentKind::Int,
                 )),
                 is_static: false,
+                access_flags: MethodAccessFlag::Public.into(),
+                attributes: Default::default(),
             })],
         }
     }
@@ -40,20 +42,26 @@ impl FileInputStream {
     pub fn new_instance(self: &Rc<Self>) -> FileInputStreamInstance {
         FileInputStreamInstance {
             class: self.clone(),
-            object_instance: self
-                .object_class
-                .new_instance(self.object_class.clone()),
+            input_stream_instance: self
+                .input_stream_class
+                .new_instance(self.input_stream_class.clone()),
         }
     }
 }
 
-fn read(_frame: &mut Frame) -> RustMethodReturn {
-    let input = io::stdin()
-        .bytes()
-        .next()
-        .expect("some input on stdin")
-        .unwrap();
-    RustMethodReturn::Value(ReturnValue::Int(input.into()))
+/// Read the next byte from stdin,
+/// returning -1 if the end of the stream has been reached.
+fn read(context: &mut NativeContext) -> RustMethodReturn {
+    match io::stdin().bytes().next() {
+        None => RustMethodReturn::Value(ReturnValue::Int(-1)),
+        Some(Ok(input)) => {
+            RustMethodReturn::Value(ReturnValue::Int(input.into()))
+        },
+        Some(Err(e)) => context.throw_new(
+            &class_identifier!(java / io, IOException),
+            &e.to_string(),
+        ),
+    }
 }
 
 impl Class for FileInputStream {
@@ -74,10 +82,10 @@ impl Class for FileInputStream {
     }
 
     fn super_class(&self) -> Option<Rc<dyn Class>> {
-        Some(self.object_class.clone())
+        Some(self.input_stream_class.clone())
     }
 
-    fn interfaces(&self) -> &[Rc<dyn std::any::Any>] {
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
         &[]
     }
 
@@ -90,13 +98,18 @@ impl Class for FileInputStream {
         let _cls_ref: &Self =
             cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
 
-        todo!()
+        Rc::new(FileInputStreamInstance {
+            class: cls.clone(),
+            input_stream_instance: self
+                .input_stream_class
+                .new_instance(self.input_stream_class.clone()),
+        })
     }
 }
 
 pub struct FileInputStreamInstance {
     class: Rc<dyn Class>,
-    object_instance: Rc<dyn ClassInstance>,
+    input_stream_instance: Rc<dyn ClassInstance>,
 }
 
 impl ClassInstance for FileInputStreamInstance {
@@ -113,6 +126,6 @@ impl ClassInstance for FileInputStreamInstance {
     }
 
     fn parent_instance(&self) -> Option<Rc<dyn ClassInstance>> {
-        Some(self.object_instance.clone())
+        Some(self.input_stream_instance.clone())
     }
 }
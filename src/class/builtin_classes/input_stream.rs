@@ -1,51 +1,73 @@
-//! TODO: Replace with abstract class once this feature exists
+//! `java.io.InputStream`, the abstract superclass of byte input streams.
 
 use std::{any::Any, rc::Rc};
 
-use super::FileInputStream;
-use crate::class::{
-    class_identifier, Class, ClassIdentifier, ClassInstance, Field,
-    FieldDescriptor, Method,
+use enumflags2::BitFlags;
+
+use crate::{
+    class::{
+        abstract_method,
+        access_flags::{ClassAccessFlag, MethodAccessFlag},
+        class_identifier, ArgumentKind, Class, ClassIdentifier, ClassInstance,
+        Field, FieldDescriptor, Method, MethodCode, RustMethodReturn,
+        SimpleArgumentKind,
+    },
+    executor::native_context::NativeContext,
 };
 
 pub struct InputStream {
     class_identifier: ClassIdentifier,
     object_class: Rc<dyn Class>,
-    file_input_stream: FileInputStream,
+    methods: Vec<Rc<Method>>,
 }
 
 impl InputStream {
     pub fn new(object_class: Rc<dyn Class>) -> Self {
         InputStream {
             class_identifier: class_identifier!(java / io, InputStream),
-            file_input_stream: FileInputStream::new(object_class.clone()),
             object_class,
+            methods: vec![
+                Rc::new(Method {
+                    code: MethodCode::Rust(init),
+                    name: "<init>".to_owned(),
+                    parameters: vec![],
+                    return_type: None,
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(abstract_method),
+                    name: "read".to_owned(),
+                    parameters: vec![],
+                    return_type: Some(ArgumentKind::Simple(
+                        SimpleArgumentKind::Int,
+                    )),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public
+                        | MethodAccessFlag::Abstract,
+                    attributes: Default::default(),
+                }),
+            ],
         }
     }
 }
 
-impl InputStream {
-    pub fn new_instance(self: &Rc<Self>) -> InputStreamInstance {
-        InputStreamInstance {
-            class: self.clone(),
-            object_instance: self
-                .object_class
-                .new_instance(self.object_class.clone()),
-        }
-    }
+fn init(_context: &mut NativeContext) -> RustMethodReturn {
+    RustMethodReturn::Void
 }
 
 impl Class for InputStream {
     fn methods(&self) -> &[Rc<Method>] {
-        self.file_input_stream.methods()
+        self.methods.as_slice()
     }
 
     fn static_fields(&self) -> &[Rc<Field>] {
-        self.file_input_stream.static_fields()
+        &[]
     }
 
     fn instance_fields(&self) -> &[FieldDescriptor] {
-        self.file_input_stream.instance_fields()
+        &[]
     }
 
     fn class_identifier(&self) -> &crate::class::ClassIdentifier {
@@ -56,20 +78,31 @@ impl Class for InputStream {
         Some(self.object_class.clone())
     }
 
-    fn interfaces(&self) -> &[Rc<dyn std::any::Any>] {
-        self.file_input_stream.interfaces()
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
+        &[]
     }
 
     fn as_any(&self) -> &dyn Any {
         self
     }
 
+    fn access_flags(&self) -> BitFlags<ClassAccessFlag> {
+        ClassAccessFlag::Public | ClassAccessFlag::Abstract
+    }
+
+    /// Create the part of an instance of a subclass belonging to
+    /// `InputStream`, which can't be instantiated on its own.
     fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance> {
         // make sure that self and cls really are equal
         let _cls_ref: &Self =
             cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
 
-        todo!()
+        Rc::new(InputStreamInstance {
+            class: cls.clone(),
+            object_instance: self
+                .object_class
+                .new_instance(self.object_class.clone()),
+        })
     }
 }
 
@@ -2,10 +2,11 @@ use std::{any::Any, rc::Rc};
 
 use crate::{
     class::{
-        class_identifier, Class, ClassIdentifier, ClassInstance, Field,
-        FieldDescriptor, Method, MethodCode, RustMethodReturn,
+        access_flags::MethodAccessFlag, class_identifier, ArgumentKind, Class,
+        ClassIdentifier, ClassInstance, Field, FieldDescriptor, FieldValue,
+        Method, MethodCode, RustMethodReturn, SimpleArgumentKind,
     },
-    executor::Frame,
+    executor::{native_context::NativeContext, ReturnValue},
 };
 
 pub struct ObjectClass {
@@ -17,13 +18,70 @@ impl ObjectClass {
     pub fn new() -> Self {
         Self {
             class_identifier: class_identifier!(java / lang, Object),
-            methods: vec![Rc::new(Method {
-                code: MethodCode::Rust(init),
-                name: "<init>".to_owned(),
-                parameters: vec![],
-                return_type: None,
-                is_static: false,
-            })],
+            methods: vec![
+                Rc::new(Method {
+                    code: MethodCode::Rust(init),
+                    name: "<init>".to_owned(),
+                    parameters: vec![],
+                    return_type: None,
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(hash_code),
+                    name: "hashCode".to_owned(),
+                    parameters: vec![],
+                    return_type: Some(ArgumentKind::Simple(
+                        SimpleArgumentKind::Int,
+                    )),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(equals),
+                    name: "equals".to_owned(),
+                    parameters: vec![ArgumentKind::Simple(
+                        SimpleArgumentKind::Class(
+                            "java/lang/Object".to_string(),
+                        ),
+                    )],
+                    return_type: Some(ArgumentKind::Simple(
+                        SimpleArgumentKind::Boolean,
+                    )),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(get_class),
+                    name: "getClass".to_owned(),
+                    parameters: vec![],
+                    return_type: Some(ArgumentKind::Simple(
+                        SimpleArgumentKind::Class(
+                            "java/lang/Class".to_string(),
+                        ),
+                    )),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public
+                        | MethodAccessFlag::Final,
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(to_string),
+                    name: "toString".to_owned(),
+                    parameters: vec![],
+                    return_type: Some(ArgumentKind::Simple(
+                        SimpleArgumentKind::Class(
+                            "java/lang/String".to_string(),
+                        ),
+                    )),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+            ],
         }
     }
 }
@@ -34,10 +92,72 @@ impl Default for ObjectClass {
     }
 }
 
-fn init(_frame: &mut Frame) -> RustMethodReturn {
+fn init(_context: &mut NativeContext) -> RustMethodReturn {
     RustMethodReturn::Void
 }
 
+/// Identity hash code of `object`, derived from its address.
+pub fn identity_hash_code(object: &Rc<dyn ClassInstance>) -> i32 {
+    // the lowest bits are always zero due to alignment,
+    // so drop them to get more distinct hash codes
+    ((Rc::as_ptr(object) as *const () as usize) >> 3) as i32
+}
+
+/// Returns whether `a` and `b` refer to the same object.
+#[allow(clippy::ptr_eq)]
+pub fn is_same_object(
+    a: &Rc<dyn ClassInstance>,
+    b: &Rc<dyn ClassInstance>,
+) -> bool {
+    // compare only the addresses, not the vtables
+    Rc::as_ptr(a) as *const () == Rc::as_ptr(b) as *const ()
+}
+
+fn hash_code(context: &mut NativeContext) -> RustMethodReturn {
+    RustMethodReturn::Value(FieldValue::Int(identity_hash_code(
+        &context.this(),
+    )))
+}
+
+/// Returns the `Class` object of the runtime class of `this`.
+fn get_class(context: &mut NativeContext) -> RustMethodReturn {
+    let class = context.this().class();
+    RustMethodReturn::Value(FieldValue::Reference(Some(
+        context.heap.class_object(&class),
+    )))
+}
+
+fn equals(context: &mut NativeContext) -> RustMethodReturn {
+    let equal = context
+        .reference(0)
+        .is_some_and(|other| is_same_object(&context.this(), &other));
+    RustMethodReturn::Value(FieldValue::Boolean(equal.into()))
+}
+
+/// Returns `<class name>@<hex hash code>`,
+/// where the hash code is determined by a virtual call to `hashCode()`.
+fn to_string(context: &mut NativeContext) -> RustMethodReturn {
+    let this = context.this();
+
+    let hash_code = match context.invoke_virtual(
+        this.clone(),
+        "hashCode",
+        (&[], Some(&ArgumentKind::Simple(SimpleArgumentKind::Int))),
+        vec![],
+    ) {
+        Ok(ReturnValue::Int(i)) => i,
+        Ok(v) => panic!("hashCode() returned {:?} instead of int", v),
+        Err(e) => return RustMethodReturn::Throw(e),
+    };
+
+    let string = context.heap.new_string(format!(
+        "{}@{:x}",
+        this.class().class_identifier().java_name(),
+        hash_code
+    ));
+    RustMethodReturn::Value(FieldValue::Reference(Some(Rc::new(string))))
+}
+
 impl Class for ObjectClass {
     fn methods(&self) -> &[Rc<Method>] {
         self.methods.as_slice()
@@ -59,7 +179,7 @@ impl Class for ObjectClass {
         None
     }
 
-    fn interfaces(&self) -> &[Rc<dyn std::any::Any>] {
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
         &[]
     }
 
@@ -0,0 +1,71 @@
+//! The classes of primitive types, e.g. `int`,
+//! which are the component types of primitive arrays.
+
+use std::{any::Any, rc::Rc};
+
+use enumflags2::BitFlags;
+
+use crate::class::{
+    access_flags::ClassAccessFlag, Class, ClassIdentifier, ClassInstance,
+    ClassName, Field, FieldDescriptor, Method,
+};
+
+pub struct PrimitiveClass {
+    class_identifier: ClassIdentifier,
+}
+
+impl PrimitiveClass {
+    /// Create the class of the primitive type `name`, e.g. `int`.
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            class_identifier: ClassIdentifier {
+                package: (&[][..]).into(),
+                class_name: ClassName::Plain(name.into()),
+            },
+        }
+    }
+}
+
+impl Class for PrimitiveClass {
+    fn methods(&self) -> &[Rc<Method>] {
+        &[]
+    }
+
+    fn static_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn instance_fields(&self) -> &[FieldDescriptor] {
+        &[]
+    }
+
+    fn class_identifier(&self) -> &ClassIdentifier {
+        &self.class_identifier
+    }
+
+    fn super_class(&self) -> Option<Rc<dyn Class>> {
+        None
+    }
+
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
+        &[]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn access_flags(&self) -> BitFlags<ClassAccessFlag> {
+        ClassAccessFlag::Public
+            | ClassAccessFlag::Final
+            | ClassAccessFlag::Abstract
+    }
+
+    fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance> {
+        // make sure that self and cls really are equal
+        let _cls_ref: &Self =
+            cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
+
+        panic!("primitive types have no instances");
+    }
+}
@@ -2,11 +2,13 @@ use std::{any::Any, rc::Rc};
 
 use crate::{
     class::{
-        builtin_classes::StringInstance, class_identifier, ArgumentKind, Class,
-        ClassIdentifier, ClassInstance, Field, FieldDescriptor, Method,
-        MethodCode, RustMethodReturn, SimpleArgumentKind,
+        access_flags::MethodAccessFlag,
+        builtin_classes::string::{floating_point_string, string_value_of},
+        class_identifier, ArgumentKind, Class, ClassIdentifier, ClassInstance,
+        Field, FieldDescriptor, Method, MethodCode, RustMethodReturn,
+        SimpleArgumentKind,
     },
-    executor::{local_variables::VariableValueOrValue, Frame},
+    executor::native_context::NativeContext,
 };
 
 pub struct PrintStream {
@@ -31,6 +33,8 @@ impl PrintStream {
                     )],
                     return_type: None,
                     is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
                 }),
                 Rc::new(Method {
                     code: MethodCode::Rust(println),
@@ -42,6 +46,8 @@ impl PrintStream {
                     )],
                     return_type: None,
                     is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
                 }),
                 Rc::new(Method {
                     code: MethodCode::Rust(println_boolean),
@@ -51,6 +57,8 @@ impl PrintStream {
                     )],
                     return_type: None,
                     is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
                 }),
                 Rc::new(Method {
                     code: MethodCode::Rust(println_char),
@@ -60,6 +68,8 @@ impl PrintStream {
                     )],
                     return_type: None,
                     is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
                 }),
                 Rc::new(Method {
                     code: MethodCode::Rust(println_double),
@@ -69,6 +79,8 @@ impl PrintStream {
                     )],
                     return_type: None,
                     is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
                 }),
                 Rc::new(Method {
                     code: MethodCode::Rust(println_float),
@@ -78,6 +90,8 @@ impl PrintStream {
                     )],
                     return_type: None,
                     is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
                 }),
                 Rc::new(Method {
                     code: MethodCode::Rust(println_int),
@@ -87,6 +101,8 @@ impl PrintStream {
                     )],
                     return_type: None,
                     is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
                 }),
                 Rc::new(Method {
                     code: MethodCode::Rust(println_long),
@@ -96,6 +112,8 @@ impl PrintStream {
                     )],
                     return_type: None,
                     is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
                 }),
             ],
         }
@@ -111,99 +129,53 @@ impl PrintStream {
     }
 }
 
-fn println(frame: &mut Frame) -> RustMethodReturn {
-    let string = frame.local_variables.get(1);
-    let string: Rc<dyn ClassInstance> = match string {
-        VariableValueOrValue::Reference(s) => s.expect("null pointer"),
-        _ => panic!("local variables have string to print at index 1"),
-    };
-    let b: &StringInstance = match string.as_any().downcast_ref() {
-        Some(s) => s,
-        None => panic!("paramter is not a string but {:?}", string),
-    };
-    println!("{}", b.string);
+fn println(context: &mut NativeContext) -> RustMethodReturn {
+    println!("{}", context.string(0).as_deref().unwrap_or("null"));
 
     RustMethodReturn::Void
 }
 
-fn println_boolean(frame: &mut Frame) -> RustMethodReturn {
-    let boolean = frame.local_variables.get(1);
-    let boolean = match boolean {
-        VariableValueOrValue::Int(b) => b,
-        _ => panic!("local variables have int (boolean) to print at index 1"),
-    };
-    match boolean {
-        0 => println!("false"),
-        1 => println!("true"),
-        _ => panic!("invalid boolean value encoding: '{}'", boolean),
-    }
+fn println_boolean(context: &mut NativeContext) -> RustMethodReturn {
+    println!("{}", context.boolean(0));
 
     RustMethodReturn::Void
 }
 
-fn println_char(frame: &mut Frame) -> RustMethodReturn {
-    let c = frame.local_variables.get(1);
-    let c = match c {
-        VariableValueOrValue::Int(c) => c,
-        _ => panic!("local variables have int (char) to print at index 1"),
-    };
-    println!(
-        "{}",
-        char::from_u32(c as u32).unwrap_or(char::REPLACEMENT_CHARACTER)
-    );
+fn println_char(context: &mut NativeContext) -> RustMethodReturn {
+    println!("{}", context.char(0));
 
     RustMethodReturn::Void
 }
 
-fn println_double(frame: &mut Frame) -> RustMethodReturn {
-    let double = frame.local_variables.get(1);
-    let double: f64 = match double {
-        VariableValueOrValue::Double(d) => d,
-        _ => panic!("local variables have double to print at index 1"),
-    };
-    println!("{}", double);
+fn println_double(context: &mut NativeContext) -> RustMethodReturn {
+    println!("{}", floating_point_string(context.double(0)));
 
     RustMethodReturn::Void
 }
 
-fn println_float(frame: &mut Frame) -> RustMethodReturn {
-    let float = frame.local_variables.get(1);
-    let float: f32 = match float {
-        VariableValueOrValue::Float(f) => f,
-        _ => panic!("local variables have float to print at index 1"),
-    };
-    println!("{}", float);
+fn println_float(context: &mut NativeContext) -> RustMethodReturn {
+    println!("{}", floating_point_string(context.float(0)));
 
     RustMethodReturn::Void
 }
 
-fn println_int(frame: &mut Frame) -> RustMethodReturn {
-    let int = frame.local_variables.get(1);
-    let int: i32 = int.try_into().unwrap();
-    println!("{}", int);
+fn println_int(context: &mut NativeContext) -> RustMethodReturn {
+    println!("{}", context.int(0));
 
     RustMethodReturn::Void
 }
 
-fn println_long(frame: &mut Frame) -> RustMethodReturn {
-    let long = frame.local_variables.get(1);
-    let long: i64 = match long {
-        VariableValueOrValue::Long(l) => l,
-        _ => panic!("local variables have long to print at index 1"),
-    };
-    println!("{}", long);
+fn println_long(context: &mut NativeContext) -> RustMethodReturn {
+    println!("{}", context.long(0));
 
     RustMethodReturn::Void
 }
 
-fn println_object(frame: &mut Frame) -> RustMethodReturn {
-    let object: Option<Rc<dyn ClassInstance>> =
-        frame.local_variables.get(1).try_into().unwrap();
-    match object {
-        None => println!("null"),
-        Some(object) => {
-            println!("{}@{:p}", object.class().class_identifier(), object)
-        },
+fn println_object(context: &mut NativeContext) -> RustMethodReturn {
+    let object = context.reference(0);
+    match string_value_of(context.heap, object) {
+        Ok(string) => println!("{}", string),
+        Err(e) => return RustMethodReturn::Throw(e),
     }
 
     RustMethodReturn::Void
@@ -230,7 +202,7 @@ impl Class for PrintStream {
         None
     }
 
-    fn interfaces(&self) -> &[Rc<dyn std::any::Any>] {
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
         &[]
     }
 
@@ -0,0 +1,723 @@
+//! `java.lang.reflect.Proxy`, which creates classes at runtime
+//! implementing interfaces by calling an `InvocationHandler`.
+
+use std::{any::Any, rc::Rc};
+
+use enumflags2::BitFlags;
+
+use crate::{
+    class::{
+        abstract_method,
+        access_flags::{ClassAccessFlag, MethodAccessFlag},
+        builtin_classes::{
+            array::ObjectArrayInstance,
+            boxed::{box_class_identifier, box_value, primitive_kind, unbox},
+            class_loader::loader_id,
+            class_object::represented_class,
+            reflect::{new_member_instance, new_throwable, type_class, Member},
+            throwable::set_cause,
+            SystemExit,
+        },
+        class_identifier, ArgumentKind, Class, ClassIdentifier, ClassInstance,
+        Field, FieldDescriptor, FieldValue, Method, MethodCode,
+        RustMethodReturn, SimpleArgumentKind,
+    },
+    executor::{
+        frame_stack::StackValue, native_context::NativeContext, new_exception,
+        op_code::typed_value, resolve_class, ReturnValue,
+    },
+    heap::{ClassLoaderId, Heap},
+};
+
+type Exception = Rc<dyn ClassInstance>;
+
+fn class_kind(name: &str) -> ArgumentKind {
+    ArgumentKind::Simple(SimpleArgumentKind::Class(name.to_string()))
+}
+
+/// The parameters and return type of `InvocationHandler.invoke()`.
+fn invoke_descriptor() -> (Vec<ArgumentKind>, ArgumentKind) {
+    (
+        vec![
+            class_kind("java/lang/Object"),
+            class_kind("java/lang/reflect/Method"),
+            ArgumentKind::Array {
+                dimensions: 1,
+                kind: SimpleArgumentKind::Class("java/lang/Object".to_string()),
+            },
+        ],
+        class_kind("java/lang/Object"),
+    )
+}
+
+/// `java.lang.reflect.InvocationHandler`, the interface
+/// handling the method calls on proxy instances.
+pub struct InvocationHandlerInterface {
+    class_identifier: ClassIdentifier,
+    object_class: Rc<dyn Class>,
+    methods: Vec<Rc<Method>>,
+}
+
+impl InvocationHandlerInterface {
+    pub fn new(object_class: Rc<dyn Class>) -> Self {
+        let (parameters, return_type) = invoke_descriptor();
+        Self {
+            class_identifier: class_identifier!(
+                java / lang / reflect,
+                InvocationHandler
+            ),
+            object_class,
+            methods: vec![Rc::new(Method {
+                code: MethodCode::Rust(abstract_method),
+                name: "invoke".to_owned(),
+                parameters,
+                return_type: Some(return_type),
+                is_static: false,
+                access_flags: MethodAccessFlag::Public
+                    | MethodAccessFlag::Abstract,
+                attributes: Default::default(),
+            })],
+        }
+    }
+}
+
+impl Class for InvocationHandlerInterface {
+    fn methods(&self) -> &[Rc<Method>] {
+        self.methods.as_slice()
+    }
+
+    fn static_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn instance_fields(&self) -> &[FieldDescriptor] {
+        &[]
+    }
+
+    fn class_identifier(&self) -> &ClassIdentifier {
+        &self.class_identifier
+    }
+
+    fn super_class(&self) -> Option<Rc<dyn Class>> {
+        Some(self.object_class.clone())
+    }
+
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
+        &[]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn access_flags(&self) -> BitFlags<ClassAccessFlag> {
+        ClassAccessFlag::Public
+            | ClassAccessFlag::Interface
+            | ClassAccessFlag::Abstract
+    }
+
+    fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance> {
+        // make sure that self and cls really are equal
+        let _cls_ref: &Self =
+            cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
+
+        panic!("interfaces cannot be instantiated");
+    }
+}
+
+/// `java.lang.reflect.Proxy`, the superclass of all proxy classes.
+pub struct ProxyClass {
+    class_identifier: ClassIdentifier,
+    object_class: Rc<dyn Class>,
+    methods: Vec<Rc<Method>>,
+}
+
+impl ProxyClass {
+    pub fn new(object_class: Rc<dyn Class>) -> Self {
+        let static_method = |code, name: &str, parameters, return_type| {
+            Rc::new(Method {
+                code: MethodCode::Rust(code),
+                name: name.to_owned(),
+                parameters,
+                return_type: Some(return_type),
+                is_static: true,
+                access_flags: MethodAccessFlag::Public
+                    | MethodAccessFlag::Static,
+                attributes: Default::default(),
+            })
+        };
+        Self {
+            class_identifier: class_identifier!(java / lang / reflect, Proxy),
+            object_class,
+            methods: vec![
+                static_method(
+                    new_proxy_instance,
+                    "newProxyInstance",
+                    vec![
+                        class_kind("java/lang/ClassLoader"),
+                        ArgumentKind::Array {
+                            dimensions: 1,
+                            kind: SimpleArgumentKind::Class(
+                                "java/lang/Class".to_string(),
+                            ),
+                        },
+                        class_kind("java/lang/reflect/InvocationHandler"),
+                    ],
+                    class_kind("java/lang/Object"),
+                ),
+                static_method(
+                    get_invocation_handler,
+                    "getInvocationHandler",
+                    vec![class_kind("java/lang/Object")],
+                    class_kind("java/lang/reflect/InvocationHandler"),
+                ),
+                static_method(
+                    is_proxy_class,
+                    "isProxyClass",
+                    vec![class_kind("java/lang/Class")],
+                    ArgumentKind::Simple(SimpleArgumentKind::Boolean),
+                ),
+            ],
+        }
+    }
+}
+
+/// `newProxyInstance(ClassLoader loader, Class<?>[] interfaces,
+/// InvocationHandler h)`, which creates an instance of the proxy class
+/// implementing `interfaces` defined by `loader`.
+fn new_proxy_instance(context: &mut NativeContext) -> RustMethodReturn {
+    match try_new_proxy_instance(context) {
+        Ok(proxy) => {
+            RustMethodReturn::Value(FieldValue::Reference(Some(proxy)))
+        },
+        Err(e) => RustMethodReturn::Throw(e),
+    }
+}
+
+fn try_new_proxy_instance(
+    context: &mut NativeContext,
+) -> Result<Rc<dyn ClassInstance>, Exception> {
+    let null_pointer = |heap: &mut Heap| {
+        new_throwable(
+            heap,
+            &class_identifier!(java / lang, NullPointerException),
+        )
+    };
+    let loader = context
+        .reference(0)
+        .map_or(ClassLoaderId::BOOTSTRAP, |loader| loader_id(&loader));
+    let Some(handler) = context.reference(2) else {
+        return Err(null_pointer(context.heap));
+    };
+    let Some(interface_array) = context.reference(1) else {
+        return Err(null_pointer(context.heap));
+    };
+    let interface_array =
+        <&ObjectArrayInstance>::try_from(interface_array.as_ref())
+            .expect("interfaces are passed as Class[]");
+
+    let mut interfaces: Vec<Rc<dyn Class>> = Vec::new();
+    for index in 0..interface_array.length() {
+        let Some(class_object) = interface_array.get(index).unwrap() else {
+            return Err(null_pointer(context.heap));
+        };
+        let interface = represented_class(&class_object);
+        if let Some(message) =
+            invalid_interface(context.heap, loader, &interfaces, &interface)
+        {
+            return Err(new_exception(
+                context.heap,
+                &class_identifier!(java / lang, IllegalArgumentException),
+                &message,
+            ));
+        }
+        interfaces.push(interface);
+    }
+
+    let proxy_class = context.heap.proxy_class(loader, interfaces);
+    Ok(Rc::new(proxy_class.new_proxy(handler)))
+}
+
+/// Returns why a proxy class defined by `loader` can't implement
+/// `interface` in addition to `interfaces`, if it can't.
+fn invalid_interface(
+    heap: &mut Heap,
+    loader: ClassLoaderId,
+    interfaces: &[Rc<dyn Class>],
+    interface: &Rc<dyn Class>,
+) -> Option<String> {
+    let name = interface.class_identifier().java_name();
+    let is_public = |class: &Rc<dyn Class>| {
+        class.access_flags().contains(ClassAccessFlag::Public)
+    };
+    if !interface
+        .access_flags()
+        .contains(ClassAccessFlag::Interface)
+    {
+        Some(format!("{} is not an interface", name))
+    } else if !resolve_class(heap, loader, interface.class_identifier())
+        .is_ok_and(|class| class.is_same_class(interface.as_ref()))
+    {
+        Some(format!(
+            "{} referenced from a method is not visible from class loader",
+            name
+        ))
+    } else if interfaces
+        .iter()
+        .any(|other| other.is_same_class(interface.as_ref()))
+    {
+        Some(format!("repeated interface: {}", name))
+    } else if !is_public(interface)
+        && interfaces.iter().any(|other| {
+            !is_public(other)
+                && !other.is_same_runtime_package(interface.as_ref())
+        })
+    {
+        Some("non-public interfaces from different packages".to_string())
+    } else {
+        None
+    }
+}
+
+/// `getInvocationHandler(Object proxy)`
+fn get_invocation_handler(context: &mut NativeContext) -> RustMethodReturn {
+    let Some(proxy) = context.reference(0) else {
+        return RustMethodReturn::Throw(new_throwable(
+            context.heap,
+            &class_identifier!(java / lang, NullPointerException),
+        ));
+    };
+    match proxy.as_any().downcast_ref::<ProxyInstance>() {
+        Some(proxy) => RustMethodReturn::Value(FieldValue::Reference(Some(
+            proxy.handler.clone(),
+        ))),
+        None => context.throw_new(
+            &class_identifier!(java / lang, IllegalArgumentException),
+            "not a proxy instance",
+        ),
+    }
+}
+
+/// `isProxyClass(Class<?> cl)`, whether the class has been created
+/// by `newProxyInstance()`.
+fn is_proxy_class(context: &mut NativeContext) -> RustMethodReturn {
+    let Some(class_object) = context.reference(0) else {
+        return RustMethodReturn::Throw(new_throwable(
+            context.heap,
+            &class_identifier!(java / lang, NullPointerException),
+        ));
+    };
+    let is_proxy_class = represented_class(&class_object)
+        .as_any()
+        .is::<DynamicProxyClass>();
+    RustMethodReturn::Value(FieldValue::Boolean(is_proxy_class.into()))
+}
+
+impl Class for ProxyClass {
+    fn methods(&self) -> &[Rc<Method>] {
+        self.methods.as_slice()
+    }
+
+    fn static_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn instance_fields(&self) -> &[FieldDescriptor] {
+        &[]
+    }
+
+    fn class_identifier(&self) -> &ClassIdentifier {
+        &self.class_identifier
+    }
+
+    fn super_class(&self) -> Option<Rc<dyn Class>> {
+        Some(self.object_class.clone())
+    }
+
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
+        &[]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance> {
+        // make sure that self and cls really are equal
+        let _cls_ref: &Self =
+            cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
+
+        panic!("proxies are created by Proxy.newProxyInstance()");
+    }
+}
+
+/// A class created at runtime by `Proxy.newProxyInstance()`,
+/// whose methods pass the calls on to the `InvocationHandler`
+/// of the instance.
+pub struct DynamicProxyClass {
+    class_identifier: ClassIdentifier,
+    class_loader: ClassLoaderId,
+    access_flags: BitFlags<ClassAccessFlag>,
+    proxy_class: Rc<dyn Class>,
+    object_class: Rc<dyn Class>,
+    interfaces: Vec<Rc<dyn Class>>,
+    methods: Vec<Rc<Method>>,
+    /// The methods implemented by `methods` (at the same index)
+    /// with the classes declaring them.
+    proxied_methods: Vec<(Rc<dyn Class>, Rc<Method>)>,
+}
+
+/// Whether `a` and `b` have the same name and descriptor.
+fn same_signature(a: &Method, b: &Method) -> bool {
+    a.name == b.name
+        && a.parameters == b.parameters
+        && a.return_type == b.return_type
+}
+
+/// Add the instance methods of `interface` and its superinterfaces
+/// to `methods`, unless a method with the same signature has been added.
+fn add_interface_methods(
+    methods: &mut Vec<(Rc<dyn Class>, Rc<Method>)>,
+    interface: &Rc<dyn Class>,
+) {
+    for method in interface.methods() {
+        if method.is_static
+            || method.name.starts_with('<')
+            || method.access_flags.contains(MethodAccessFlag::Private)
+            || methods
+                .iter()
+                .any(|(_, added)| same_signature(added, method))
+        {
+            continue;
+        }
+        methods.push((interface.clone(), method.clone()));
+    }
+    for super_interface in interface.interfaces() {
+        add_interface_methods(methods, super_interface);
+    }
+}
+
+impl DynamicProxyClass {
+    pub fn new(
+        class_identifier: ClassIdentifier,
+        class_loader: ClassLoaderId,
+        interfaces: Vec<Rc<dyn Class>>,
+        proxy_class: Rc<dyn Class>,
+        object_class: Rc<dyn Class>,
+    ) -> Self {
+        // like in the JDK, these methods of Object are proxied as well
+        let mut proxied_methods: Vec<_> = ["hashCode", "equals", "toString"]
+            .into_iter()
+            .map(|name| {
+                let method = object_class
+                    .methods()
+                    .iter()
+                    .find(|method| method.name == name)
+                    .unwrap_or_else(|| panic!("Object declares {}", name));
+                (object_class.clone(), method.clone())
+            })
+            .collect();
+        for interface in &interfaces {
+            add_interface_methods(&mut proxied_methods, interface);
+        }
+        let methods = proxied_methods
+            .iter()
+            .map(|(_, method)| {
+                Rc::new(Method {
+                    code: MethodCode::Rust(invoke_handler),
+                    name: method.name.clone(),
+                    parameters: method.parameters.clone(),
+                    return_type: method.return_type.clone(),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public
+                        | MethodAccessFlag::Final,
+                    attributes: Default::default(),
+                })
+            })
+            .collect();
+        let access_flags = if interfaces.iter().all(|interface| {
+            interface.access_flags().contains(ClassAccessFlag::Public)
+        }) {
+            ClassAccessFlag::Public | ClassAccessFlag::Final
+        } else {
+            ClassAccessFlag::Final.into()
+        };
+        Self {
+            class_identifier,
+            class_loader,
+            access_flags,
+            proxy_class,
+            object_class,
+            interfaces,
+            methods,
+            proxied_methods,
+        }
+    }
+
+    /// Create an instance passing the method calls to `handler`.
+    pub fn new_proxy(
+        self: &Rc<Self>,
+        handler: Rc<dyn ClassInstance>,
+    ) -> ProxyInstance {
+        ProxyInstance {
+            class: self.clone(),
+            object_instance: self
+                .object_class
+                .new_instance(self.object_class.clone()),
+            handler,
+        }
+    }
+
+    /// Returns the method implemented by `method` of this class
+    /// and the class declaring it.
+    fn proxied_method(&self, method: &Method) -> (Rc<dyn Class>, Rc<Method>) {
+        self.proxied_methods
+            .iter()
+            .find(|(_, proxied)| same_signature(proxied, method))
+            .unwrap_or_else(|| {
+                panic!("{} is declared by the proxy class", method.name)
+            })
+            .clone()
+    }
+}
+
+/// Call `invoke()` of the handler of the proxy instance
+/// with the `Method` object of the implemented method
+/// and the boxed arguments.
+fn invoke_handler(context: &mut NativeContext) -> RustMethodReturn {
+    match try_invoke_handler(context) {
+        Ok(value) => value,
+        Err(e) => RustMethodReturn::Throw(e),
+    }
+}
+
+fn try_invoke_handler(
+    context: &mut NativeContext,
+) -> Result<RustMethodReturn, Exception> {
+    let proxy = context.this();
+    let ((declaring_class, method), handler) =
+        proxy.with_parent_instance("Proxy", |instance: &ProxyInstance| {
+            (
+                instance.class.proxied_method(context.method()),
+                instance.handler.clone(),
+            )
+        });
+
+    // like in the JDK, methods without parameters get null
+    let argument_array = if method.parameters.is_empty() {
+        None
+    } else {
+        let arguments = method
+            .parameters
+            .iter()
+            .enumerate()
+            .map(|(index, parameter)| {
+                match typed_value(parameter, context.argument(index).into()) {
+                    FieldValue::Reference(reference) => reference,
+                    value => Some(box_value(context.heap, value)),
+                }
+            })
+            .collect();
+        let object_class = context
+            .heap
+            .find_class(
+                ClassLoaderId::BOOTSTRAP,
+                &class_identifier!(java / lang, Object),
+            )
+            .expect("Object is a builtin class")
+            .clone();
+        let array: Rc<dyn ClassInstance> =
+            Rc::new(context.heap.new_object_array(&object_class, arguments));
+        Some(array)
+    };
+    let method_object = new_member_instance(
+        context.heap,
+        declaring_class.clone(),
+        Member::Method(method.clone()),
+    );
+
+    let (parameters, return_type) = invoke_descriptor();
+    let result = match context.invoke_virtual(
+        handler,
+        "invoke",
+        (&parameters, Some(&return_type)),
+        vec![
+            StackValue::Reference(Some(proxy)),
+            StackValue::Reference(Some(method_object)),
+            StackValue::Reference(argument_array),
+        ],
+    ) {
+        Ok(ReturnValue::Reference(result)) => result,
+        Ok(value) => {
+            panic!("invoke() returned {:?} instead of an object", value)
+        },
+        Err(e) => return Err(undeclared_throwable(context.heap, &method, e)),
+    };
+    return_value(
+        context.heap,
+        declaring_class.class_loader(),
+        method.return_type.as_ref(),
+        result,
+    )
+}
+
+/// Wrap the exception `exception` thrown by the handler
+/// in an `UndeclaredThrowableException`,
+/// if it is a checked exception not declared by `method`.
+fn undeclared_throwable(
+    heap: &mut Heap,
+    method: &Method,
+    exception: Exception,
+) -> Exception {
+    if exception.as_any().is::<SystemExit>() {
+        return exception;
+    }
+    let mut class = Some(exception.class());
+    while let Some(current) = class {
+        let class_identifier = current.class_identifier();
+        if *class_identifier == class_identifier!(java / lang, RuntimeException)
+            || *class_identifier == class_identifier!(java / lang, Error)
+            || method.attributes.exceptions.contains(class_identifier)
+        {
+            return exception;
+        }
+        class = current.super_class();
+    }
+    let wrapper = new_throwable(
+        heap,
+        &class_identifier!(java / lang / reflect, UndeclaredThrowableException),
+    );
+    set_cause(&wrapper, exception);
+    wrapper
+}
+
+/// Returns `result` of the handler as value of type `return_type`
+/// (or `void` for `None`), which is resolved by `loader`.
+///
+/// Boxed values are unboxed for primitive types.
+/// Throws a `NullPointerException` if they are null
+/// and a `ClassCastException` if `result` has another type.
+fn return_value(
+    heap: &mut Heap,
+    loader: ClassLoaderId,
+    return_type: Option<&ArgumentKind>,
+    result: Option<Rc<dyn ClassInstance>>,
+) -> Result<RustMethodReturn, Exception> {
+    let Some(return_type) = return_type else {
+        return Ok(RustMethodReturn::Void);
+    };
+    let (object, class_name) = match (return_type, result) {
+        (ArgumentKind::Simple(SimpleArgumentKind::Class(_)), None)
+        | (ArgumentKind::Array { .. }, None) => {
+            return Ok(RustMethodReturn::Value(FieldValue::Reference(None)));
+        },
+        (_, None) => {
+            return Err(new_throwable(
+                heap,
+                &class_identifier!(java / lang, NullPointerException),
+            ));
+        },
+        (
+            ArgumentKind::Simple(SimpleArgumentKind::Class(_))
+            | ArgumentKind::Array { .. },
+            Some(object),
+        ) => {
+            let class = type_class(heap, loader, Some(return_type))?;
+            if object.class().is_assignable_to(class.as_ref()) {
+                return Ok(RustMethodReturn::Value(FieldValue::Reference(
+                    Some(object),
+                )));
+            }
+            (object, class.class_identifier().java_name())
+        },
+        (ArgumentKind::Simple(primitive), Some(object)) => {
+            match unbox(&object)
+                .filter(|value| primitive_kind(value) == *primitive)
+            {
+                Some(value) => return Ok(RustMethodReturn::Value(value)),
+                None => (object, box_class_identifier(primitive).java_name()),
+            }
+        },
+    };
+    Err(new_exception(
+        heap,
+        &class_identifier!(java / lang, ClassCastException),
+        &format!(
+            "class {} cannot be cast to class {}",
+            object.class().class_identifier().java_name(),
+            class_name
+        ),
+    ))
+}
+
+impl Class for DynamicProxyClass {
+    fn methods(&self) -> &[Rc<Method>] {
+        self.methods.as_slice()
+    }
+
+    fn static_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn instance_fields(&self) -> &[FieldDescriptor] {
+        &[]
+    }
+
+    fn class_identifier(&self) -> &ClassIdentifier {
+        &self.class_identifier
+    }
+
+    fn super_class(&self) -> Option<Rc<dyn Class>> {
+        Some(self.proxy_class.clone())
+    }
+
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
+        &self.interfaces
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn access_flags(&self) -> BitFlags<ClassAccessFlag> {
+        self.access_flags
+    }
+
+    fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance> {
+        // make sure that self and cls really are equal
+        let _cls_ref: &Self =
+            cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
+
+        panic!("proxies are created by Proxy.newProxyInstance()");
+    }
+
+    fn class_loader(&self) -> ClassLoaderId {
+        self.class_loader
+    }
+}
+
+/// An instance of a [`DynamicProxyClass`].
+pub struct ProxyInstance {
+    class: Rc<DynamicProxyClass>,
+    object_instance: Rc<dyn ClassInstance>,
+    /// The `InvocationHandler` called by the methods.
+    handler: Rc<dyn ClassInstance>,
+}
+
+impl ClassInstance for ProxyInstance {
+    fn class(&self) -> Rc<dyn Class> {
+        self.class.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn instance_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn parent_instance(&self) -> Option<Rc<dyn ClassInstance>> {
+        Some(self.object_instance.clone())
+    }
+}
@@ -0,0 +1,109 @@
+//! `java.lang.Record`, the superclass of all records.
+//!
+//! `toString`, `equals` and `hashCode` of records are linked
+//! by the bootstrap method in [`crate::executor::call_site::object_methods`].
+
+use std::{any::Any, rc::Rc};
+
+use crate::{
+    class::{
+        access_flags::MethodAccessFlag, class_identifier, Class,
+        ClassIdentifier, ClassInstance, Field, FieldDescriptor, Method,
+        MethodCode, RustMethodReturn,
+    },
+    executor::native_context::NativeContext,
+};
+
+pub struct RecordClass {
+    class_identifier: ClassIdentifier,
+    object_class: Rc<dyn Class>,
+    methods: Vec<Rc<Method>>,
+}
+
+impl RecordClass {
+    pub fn new(object_class: Rc<dyn Class>) -> Self {
+        Self {
+            class_identifier: class_identifier!(java / lang, Record),
+            object_class,
+            methods: vec![Rc::new(Method {
+                code: MethodCode::Rust(init),
+                name: "<init>".to_owned(),
+                parameters: vec![],
+                return_type: None,
+                is_static: false,
+                access_flags: MethodAccessFlag::Protected.into(),
+                attributes: Default::default(),
+            })],
+        }
+    }
+}
+
+fn init(_context: &mut NativeContext) -> RustMethodReturn {
+    RustMethodReturn::Void
+}
+
+impl Class for RecordClass {
+    fn methods(&self) -> &[Rc<Method>] {
+        self.methods.as_slice()
+    }
+
+    fn static_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn instance_fields(&self) -> &[FieldDescriptor] {
+        &[]
+    }
+
+    fn class_identifier(&self) -> &ClassIdentifier {
+        &self.class_identifier
+    }
+
+    fn super_class(&self) -> Option<Rc<dyn Class>> {
+        Some(self.object_class.clone())
+    }
+
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
+        &[]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance> {
+        // make sure that self and cls really are equal
+        let _cls_ref: &Self =
+            cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
+
+        Rc::new(RecordInstance {
+            class: cls.clone(),
+            object_instance: self
+                .object_class
+                .new_instance(self.object_class.clone()),
+        })
+    }
+}
+
+pub struct RecordInstance {
+    class: Rc<dyn Class>,
+    object_instance: Rc<dyn ClassInstance>,
+}
+
+impl ClassInstance for RecordInstance {
+    fn class(&self) -> Rc<dyn Class> {
+        self.class.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn instance_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn parent_instance(&self) -> Option<Rc<dyn ClassInstance>> {
+        Some(self.object_instance.clone())
+    }
+}
@@ -0,0 +1,1058 @@
+//! `java.lang.reflect.Method`, `Constructor` and `Field`,
+//! which represent the members of classes.
+
+use std::{any::Any, cell::Cell, rc::Rc};
+
+use enumflags2::BitFlags;
+
+use crate::{
+    class::{
+        access_flags::{
+            ClassAccessFlag, FieldAccessFlag, MemberAccess, MethodAccessFlag,
+        },
+        builtin_classes::{
+            array::ObjectArrayInstance,
+            boxed::{box_value, unbox, widen},
+            class_object::class_description,
+            throwable::set_cause,
+            SystemExit,
+        },
+        class_identifier, ArgumentKind, Class, ClassIdentifier, ClassInstance,
+        Field, FieldDescriptor, FieldValue, Method, MethodCode, RustMethod,
+        RustMethodReturn, SimpleArgumentKind,
+    },
+    classloader::parse_class_or_array_identifier,
+    executor::{
+        frame_stack::StackValue, initialize_class, invoke_method,
+        native_context::NativeContext, new_exception, op_code::typed_value,
+        resolve_class, ReturnValue,
+    },
+    heap::{ClassLoaderId, Heap},
+};
+
+type Exception = Rc<dyn ClassInstance>;
+
+/// `java.lang.reflect.Method`, `Constructor` or `Field`.
+pub struct MemberClass {
+    class_identifier: ClassIdentifier,
+    object_class: Rc<dyn Class>,
+    methods: Vec<Rc<Method>>,
+}
+
+fn class_kind(name: &str) -> ArgumentKind {
+    ArgumentKind::Simple(SimpleArgumentKind::Class(name.to_string()))
+}
+
+fn object_array_kind() -> ArgumentKind {
+    ArgumentKind::Array {
+        dimensions: 1,
+        kind: SimpleArgumentKind::Class("java/lang/Object".to_string()),
+    }
+}
+
+fn public_method(
+    code: RustMethod,
+    name: &str,
+    parameters: Vec<ArgumentKind>,
+    return_type: Option<ArgumentKind>,
+) -> Rc<Method> {
+    Rc::new(Method {
+        code: MethodCode::Rust(code),
+        name: name.to_owned(),
+        parameters,
+        return_type,
+        is_static: false,
+        access_flags: MethodAccessFlag::Public.into(),
+        attributes: Default::default(),
+    })
+}
+
+/// The methods of `Method` and `Constructor` describing the parameters.
+fn executable_methods() -> Vec<Rc<Method>> {
+    vec![
+        public_method(
+            get_parameter_types,
+            "getParameterTypes",
+            vec![],
+            Some(ArgumentKind::Array {
+                dimensions: 1,
+                kind: SimpleArgumentKind::Class("java/lang/Class".to_string()),
+            }),
+        ),
+        public_method(
+            get_parameter_count,
+            "getParameterCount",
+            vec![],
+            Some(ArgumentKind::Simple(SimpleArgumentKind::Int)),
+        ),
+    ]
+}
+
+impl MemberClass {
+    fn new(
+        class_identifier: ClassIdentifier,
+        object_class: Rc<dyn Class>,
+        mut methods: Vec<Rc<Method>>,
+    ) -> Self {
+        methods.extend([
+            public_method(
+                get_name,
+                "getName",
+                vec![],
+                Some(class_kind("java/lang/String")),
+            ),
+            public_method(
+                get_declaring_class,
+                "getDeclaringClass",
+                vec![],
+                Some(class_kind("java/lang/Class")),
+            ),
+            public_method(
+                get_modifiers,
+                "getModifiers",
+                vec![],
+                Some(ArgumentKind::Simple(SimpleArgumentKind::Int)),
+            ),
+            public_method(
+                set_accessible,
+                "setAccessible",
+                vec![ArgumentKind::Simple(SimpleArgumentKind::Boolean)],
+                None,
+            ),
+        ]);
+        Self {
+            class_identifier,
+            object_class,
+            methods,
+        }
+    }
+
+    /// Create `java.lang.reflect.Method`.
+    pub fn method(object_class: Rc<dyn Class>) -> Self {
+        let mut methods = executable_methods();
+        methods.extend([
+            public_method(
+                get_return_type,
+                "getReturnType",
+                vec![],
+                Some(class_kind("java/lang/Class")),
+            ),
+            public_method(
+                invoke,
+                "invoke",
+                vec![class_kind("java/lang/Object"), object_array_kind()],
+                Some(class_kind("java/lang/Object")),
+            ),
+        ]);
+        Self::new(
+            class_identifier!(java / lang / reflect, Method),
+            object_class,
+            methods,
+        )
+    }
+
+    /// Create `java.lang.reflect.Constructor`.
+    pub fn constructor(object_class: Rc<dyn Class>) -> Self {
+        let mut methods = executable_methods();
+        methods.push(public_method(
+            new_instance,
+            "newInstance",
+            vec![object_array_kind()],
+            Some(class_kind("java/lang/Object")),
+        ));
+        Self::new(
+            class_identifier!(java / lang / reflect, Constructor),
+            object_class,
+            methods,
+        )
+    }
+
+    /// Create `java.lang.reflect.Field`.
+    pub fn field(object_class: Rc<dyn Class>) -> Self {
+        Self::new(
+            class_identifier!(java / lang / reflect, Field),
+            object_class,
+            vec![
+                public_method(
+                    get_type,
+                    "getType",
+                    vec![],
+                    Some(class_kind("java/lang/Class")),
+                ),
+                public_method(
+                    get,
+                    "get",
+                    vec![class_kind("java/lang/Object")],
+                    Some(class_kind("java/lang/Object")),
+                ),
+                public_method(
+                    set,
+                    "set",
+                    vec![
+                        class_kind("java/lang/Object"),
+                        class_kind("java/lang/Object"),
+                    ],
+                    None,
+                ),
+            ],
+        )
+    }
+}
+
+/// The member of a class represented by a [`MemberInstance`].
+#[derive(Clone)]
+pub enum Member {
+    /// A method or a constructor (named `<init>`).
+    Method(Rc<Method>),
+    StaticField(Rc<Field>),
+    /// An instance field, whose values are held by the instances.
+    InstanceField {
+        name: String,
+        access_flags: BitFlags<FieldAccessFlag>,
+        field_type: ArgumentKind,
+    },
+}
+
+impl Member {
+    fn name(&self) -> &str {
+        match self {
+            Member::Method(method) => &method.name,
+            Member::StaticField(field) => &field.name,
+            Member::InstanceField { name, .. } => name,
+        }
+    }
+
+    /// The access flags, as returned by `getModifiers()`.
+    fn modifiers(&self) -> i32 {
+        match self {
+            Member::Method(method) => method.access_flags.bits().into(),
+            Member::StaticField(field) => field.access_flags.bits().into(),
+            Member::InstanceField { access_flags, .. } => {
+                access_flags.bits().into()
+            },
+        }
+    }
+
+    fn access(&self) -> MemberAccess {
+        match self {
+            Member::Method(method) => method.access_flags.into(),
+            Member::StaticField(field) => field.access_flags.into(),
+            Member::InstanceField { access_flags, .. } => {
+                (*access_flags).into()
+            },
+        }
+    }
+
+    /// The type of the field.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the member is a method.
+    fn field_type(&self) -> &ArgumentKind {
+        match self {
+            Member::StaticField(field) => &field.field_type,
+            Member::InstanceField { field_type, .. } => field_type,
+            Member::Method(method) => panic!("{} is no field", method.name),
+        }
+    }
+}
+
+/// Create the `Method`, `Constructor` or `Field` object
+/// representing `member` of `declaring_class`.
+pub fn new_member_instance(
+    heap: &Heap,
+    declaring_class: Rc<dyn Class>,
+    member: Member,
+) -> Rc<dyn ClassInstance> {
+    let class_identifier = match &member {
+        Member::Method(method) if method.name == "<init>" => {
+            class_identifier!(java / lang / reflect, Constructor)
+        },
+        Member::Method(_) => class_identifier!(java / lang / reflect, Method),
+        Member::StaticField(_) | Member::InstanceField { .. } => {
+            class_identifier!(java / lang / reflect, Field)
+        },
+    };
+    let class = heap
+        .find_class(ClassLoaderId::BOOTSTRAP, &class_identifier)
+        .expect("reflection classes are builtin classes")
+        .clone();
+    let member_class: &MemberClass = class.as_any().downcast_ref().unwrap();
+    Rc::new(MemberInstance {
+        object_instance: member_class
+            .object_class
+            .new_instance(member_class.object_class.clone()),
+        class,
+        declaring_class,
+        member,
+        accessible: Cell::new(false),
+    })
+}
+
+/// Returns the class declaring the member represented by `this`,
+/// the member and whether it has been made accessible.
+fn this_member(context: &NativeContext) -> (Rc<dyn Class>, Member, bool) {
+    context.this().with_parent_instance(
+        "AccessibleObject",
+        |instance: &MemberInstance| {
+            (
+                instance.declaring_class.clone(),
+                instance.member.clone(),
+                instance.accessible.get(),
+            )
+        },
+    )
+}
+
+/// Returns the names of `modifiers` like `Modifier.toString()`,
+/// e.g. `public static final`.
+fn modifier_names(modifiers: i32) -> String {
+    [
+        (0x0001, "public"),
+        (0x0004, "protected"),
+        (0x0002, "private"),
+        (0x0400, "abstract"),
+        (0x0008, "static"),
+        (0x0010, "final"),
+        (0x0080, "transient"),
+        (0x0040, "volatile"),
+        (0x0020, "synchronized"),
+        (0x0100, "native"),
+        (0x0800, "strictfp"),
+        (0x0200, "interface"),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| modifiers & flag != 0)
+    .map(|(_, name)| name)
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Check that the caller of the executed method,
+/// i.e. the class using reflection,
+/// may access `member` of `declaring_class`,
+/// unless it has been made accessible.
+///
+/// Instance members are accessed on the object given as first argument,
+/// which may be `null` (reported by `target_object` afterwards).
+fn check_access(
+    context: &mut NativeContext,
+    declaring_class: &Rc<dyn Class>,
+    member: &Member,
+    accessible: bool,
+) -> Result<(), Exception> {
+    // called from Rust code, which is trusted
+    let Some(caller) = context.caller.clone() else {
+        return Ok(());
+    };
+    let class_accessible = declaring_class
+        .access_flags()
+        .contains(ClassAccessFlag::Public)
+        || caller.is_same_runtime_package(declaring_class.as_ref());
+    let objectref_class = match member {
+        Member::InstanceField { .. } => context.reference(0),
+        Member::Method(method)
+            if !method.is_static && method.name != "<init>" =>
+        {
+            context.reference(0)
+        },
+        _ => None,
+    }
+    .map(|object| object.class());
+    if accessible
+        || class_accessible
+            && caller.can_access(
+                declaring_class,
+                member.access(),
+                objectref_class.as_ref(),
+                context.heap,
+            )
+    {
+        return Ok(());
+    }
+    let message = format!(
+        "{} cannot access a member of {} with modifiers \"{}\"",
+        class_description(&caller),
+        class_description(declaring_class),
+        modifier_names(member.modifiers())
+    );
+    Err(new_exception(
+        context.heap,
+        &class_identifier!(java / lang, IllegalAccessException),
+        &message,
+    ))
+}
+
+/// Create an instance of the builtin throwable class `class`
+/// without a message.
+pub fn new_throwable(heap: &mut Heap, class: &ClassIdentifier) -> Exception {
+    let class = heap
+        .find_class(ClassLoaderId::BOOTSTRAP, class)
+        .unwrap_or_else(|| panic!("exception class {} exists", class))
+        .clone();
+    let throwable = class.new_instance(class.clone());
+    let (init, defining_class) = class.get_method("<init>", (&[], None), true);
+    match invoke_method(
+        heap,
+        &init,
+        defining_class,
+        vec![StackValue::Reference(Some(throwable.clone()))],
+    ) {
+        Err(e) => e,
+        Ok(_) => throwable,
+    }
+}
+
+/// Wrap the exception `target` thrown by an invoked method or constructor
+/// in an `InvocationTargetException`.
+///
+/// `System.exit()` isn't wrapped, so that it still exits.
+fn invocation_target_exception(
+    heap: &mut Heap,
+    target: Exception,
+) -> Exception {
+    if target.as_any().is::<SystemExit>() {
+        return target;
+    }
+    let exception = new_throwable(
+        heap,
+        &class_identifier!(java / lang / reflect, InvocationTargetException),
+    );
+    set_cause(&exception, target);
+    exception
+}
+
+/// Returns the name and descriptor of the primitive type `kind`,
+/// e.g. `("int", "I")`, or `None` for classes.
+fn primitive_name(
+    kind: &SimpleArgumentKind,
+) -> Option<(&'static str, &'static str)> {
+    match kind {
+        SimpleArgumentKind::Boolean => Some(("boolean", "Z")),
+        SimpleArgumentKind::Byte => Some(("byte", "B")),
+        SimpleArgumentKind::Char => Some(("char", "C")),
+        SimpleArgumentKind::Short => Some(("short", "S")),
+        SimpleArgumentKind::Int => Some(("int", "I")),
+        SimpleArgumentKind::Long => Some(("long", "J")),
+        SimpleArgumentKind::Float => Some(("float", "F")),
+        SimpleArgumentKind::Double => Some(("double", "D")),
+        SimpleArgumentKind::Class(_) => None,
+    }
+}
+
+/// Returns the binary name of the class of `kind` values,
+/// e.g. `java/lang/String` or `[I`, or `None` for primitive types.
+fn binary_class_name(kind: &ArgumentKind) -> Option<String> {
+    match kind {
+        ArgumentKind::Simple(SimpleArgumentKind::Class(name)) => {
+            Some(name.clone())
+        },
+        ArgumentKind::Simple(_) => None,
+        ArgumentKind::Array { dimensions, kind } => {
+            let component = match primitive_name(kind) {
+                Some((_, descriptor)) => descriptor.to_string(),
+                None => match kind {
+                    SimpleArgumentKind::Class(name) => format!("L{};", name),
+                    _ => unreachable!("{:?} is a class", kind),
+                },
+            };
+            Some(format!("{}{}", "[".repeat(*dimensions), component))
+        },
+    }
+}
+
+/// Returns the name of the class of `kind` values like `Class.getName()`,
+/// e.g. `int`, `java.lang.String` or `[I`.
+fn type_name(kind: &ArgumentKind) -> String {
+    match (binary_class_name(kind), kind) {
+        (Some(name), _) => parse_class_or_array_identifier(&name).java_name(),
+        (None, ArgumentKind::Simple(kind)) => primitive_name(kind)
+            .expect("classes have a binary name")
+            .0
+            .to_string(),
+        (None, ArgumentKind::Array { .. }) => {
+            unreachable!("arrays have a binary name")
+        },
+    }
+}
+
+/// Returns the class of `kind` values (or `void` for `None`),
+/// which is loaded by `loader` if necessary.
+pub fn type_class(
+    heap: &mut Heap,
+    loader: ClassLoaderId,
+    kind: Option<&ArgumentKind>,
+) -> Result<Rc<dyn Class>, Exception> {
+    let name = match kind.map(|kind| (binary_class_name(kind), kind)) {
+        None => "void",
+        Some((Some(name), _)) => {
+            return resolve_class(
+                heap,
+                loader,
+                &parse_class_or_array_identifier(&name),
+            );
+        },
+        Some((None, ArgumentKind::Simple(kind))) => {
+            primitive_name(kind).expect("classes have a binary name").0
+        },
+        Some((None, ArgumentKind::Array { .. })) => {
+            unreachable!("arrays have a binary name")
+        },
+    };
+    Ok(heap.primitive_class(name).expect("primitive classes exist"))
+}
+
+/// Returns `value` as a value of type `kind`,
+/// unboxing and widening it for primitive types,
+/// or `None` if it has another type.
+fn unbox_value(
+    heap: &mut Heap,
+    loader: ClassLoaderId,
+    kind: &ArgumentKind,
+    value: Option<Rc<dyn ClassInstance>>,
+) -> Result<Option<FieldValue>, Exception> {
+    match kind {
+        ArgumentKind::Simple(SimpleArgumentKind::Class(_))
+        | ArgumentKind::Array { .. } => {
+            let Some(object) = value else {
+                return Ok(Some(FieldValue::Reference(None)));
+            };
+            let class = type_class(heap, loader, Some(kind))?;
+            Ok(object
+                .class()
+                .is_assignable_to(class.as_ref())
+                .then_some(FieldValue::Reference(Some(object))))
+        },
+        ArgumentKind::Simple(primitive) => Ok(value
+            .and_then(|object| unbox(&object))
+            .and_then(|value| widen(&value, primitive))),
+    }
+}
+
+/// Box `value` returned by a method with `return_type`,
+/// or return null for void methods.
+fn box_return_value(
+    heap: &Heap,
+    value: ReturnValue,
+    return_type: Option<&ArgumentKind>,
+) -> Option<Rc<dyn ClassInstance>> {
+    let value = match value {
+        ReturnValue::Void => return None,
+        ReturnValue::Reference(r) => return r,
+        ReturnValue::Int(i) => StackValue::Int(i),
+        ReturnValue::Long(l) => StackValue::Long(l),
+        ReturnValue::Float(f) => StackValue::Float(f),
+        ReturnValue::Double(d) => StackValue::Double(d),
+    };
+    let return_type = return_type.expect("the method returns a value");
+    Some(box_value(heap, typed_value(return_type, value)))
+}
+
+/// Unbox the elements of the `Object[]` `arguments`
+/// to pass them to `method` of `declaring_class`.
+fn method_arguments(
+    context: &mut NativeContext,
+    declaring_class: &Rc<dyn Class>,
+    method: &Method,
+    arguments: Option<Rc<dyn ClassInstance>>,
+) -> Result<Vec<StackValue>, Exception> {
+    // null is treated like an empty array
+    let arguments = match arguments {
+        None => Vec::new(),
+        Some(array) => {
+            let array = <&ObjectArrayInstance>::try_from(array.as_ref())
+                .expect("arguments are passed as Object[]");
+            (0..array.length())
+                .map(|index| array.get(index).unwrap())
+                .collect()
+        },
+    };
+    if arguments.len() != method.parameters.len() {
+        return Err(new_exception(
+            context.heap,
+            &class_identifier!(java / lang, IllegalArgumentException),
+            "wrong number of arguments",
+        ));
+    }
+    let mut values = Vec::new();
+    for (parameter, argument) in method.parameters.iter().zip(arguments) {
+        match unbox_value(
+            context.heap,
+            declaring_class.class_loader(),
+            parameter,
+            argument,
+        )? {
+            Some(value) => values.push(value.into()),
+            None => {
+                return Err(new_exception(
+                    context.heap,
+                    &class_identifier!(java / lang, IllegalArgumentException),
+                    "argument type mismatch",
+                ));
+            },
+        }
+    }
+    Ok(values)
+}
+
+/// Returns the object given as first argument,
+/// whose instance member of `declaring_class` is accessed.
+///
+/// Throws a `NullPointerException` if it is null,
+/// or an `IllegalArgumentException` with the message returned by
+/// `mismatch_message` if it is no instance of `declaring_class`.
+fn target_object(
+    context: &mut NativeContext,
+    declaring_class: &Rc<dyn Class>,
+    mismatch_message: impl FnOnce(&Rc<dyn ClassInstance>) -> String,
+) -> Result<Rc<dyn ClassInstance>, Exception> {
+    let Some(object) = context.reference(0) else {
+        return Err(new_throwable(
+            context.heap,
+            &class_identifier!(java / lang, NullPointerException),
+        ));
+    };
+    if !object.class().is_assignable_to(declaring_class.as_ref()) {
+        return Err(new_exception(
+            context.heap,
+            &class_identifier!(java / lang, IllegalArgumentException),
+            &mismatch_message(&object),
+        ));
+    }
+    Ok(object)
+}
+
+fn reference_result(
+    result: Result<Option<Rc<dyn ClassInstance>>, Exception>,
+) -> RustMethodReturn {
+    match result {
+        Ok(reference) => {
+            RustMethodReturn::Value(FieldValue::Reference(reference))
+        },
+        Err(e) => RustMethodReturn::Throw(e),
+    }
+}
+
+/// Returns the name of the method or field,
+/// or the name of the declaring class for constructors.
+fn get_name(context: &mut NativeContext) -> RustMethodReturn {
+    let (declaring_class, member, _) = this_member(context);
+    let name = match &member {
+        Member::Method(method) if method.name == "<init>" => {
+            declaring_class.class_identifier().java_name()
+        },
+        member => member.name().to_string(),
+    };
+    RustMethodReturn::Value(FieldValue::Reference(Some(Rc::new(
+        context.heap.new_string(name),
+    ))))
+}
+
+fn get_declaring_class(context: &mut NativeContext) -> RustMethodReturn {
+    let (declaring_class, _, _) = this_member(context);
+    RustMethodReturn::Value(FieldValue::Reference(Some(
+        context.heap.class_object(&declaring_class),
+    )))
+}
+
+fn get_modifiers(context: &mut NativeContext) -> RustMethodReturn {
+    let (_, member, _) = this_member(context);
+    RustMethodReturn::Value(FieldValue::Int(member.modifiers()))
+}
+
+/// `setAccessible(boolean flag)`, which turns off access checks
+/// of `invoke`, `newInstance`, `get` and `set` if `flag` is true.
+fn set_accessible(context: &mut NativeContext) -> RustMethodReturn {
+    let flag = context.int(0) != 0;
+    context.this().with_parent_instance(
+        "AccessibleObject",
+        |instance: &MemberInstance| instance.accessible.set(flag),
+    );
+    RustMethodReturn::Void
+}
+
+/// Returns the method or constructor represented by `this`
+/// and the class declaring it.
+fn this_method(context: &NativeContext) -> (Rc<dyn Class>, Rc<Method>) {
+    match this_member(context) {
+        (declaring_class, Member::Method(method), _) => {
+            (declaring_class, method)
+        },
+        (_, member, _) => panic!("{} is no method", member.name()),
+    }
+}
+
+fn get_parameter_types(context: &mut NativeContext) -> RustMethodReturn {
+    let (declaring_class, method) = this_method(context);
+    let mut classes = Vec::new();
+    for parameter in &method.parameters {
+        match type_class(
+            context.heap,
+            declaring_class.class_loader(),
+            Some(parameter),
+        ) {
+            Ok(class) => classes.push(Some(context.heap.class_object(&class))),
+            Err(e) => return RustMethodReturn::Throw(e),
+        }
+    }
+    let class_class = context
+        .heap
+        .find_class(
+            ClassLoaderId::BOOTSTRAP,
+            &class_identifier!(java / lang, Class),
+        )
+        .expect("Class is a builtin class")
+        .clone();
+    let array = context.heap.new_object_array(&class_class, classes);
+    RustMethodReturn::Value(FieldValue::Reference(Some(Rc::new(array))))
+}
+
+fn get_parameter_count(context: &mut NativeContext) -> RustMethodReturn {
+    let (_, method) = this_method(context);
+    RustMethodReturn::Value(FieldValue::Int(method.parameters.len() as i32))
+}
+
+/// Returns the class of `kind` values (`void` for `None`) as class object.
+fn type_class_object(
+    context: &mut NativeContext,
+    declaring_class: &Rc<dyn Class>,
+    kind: Option<&ArgumentKind>,
+) -> RustMethodReturn {
+    reference_result(
+        type_class(context.heap, declaring_class.class_loader(), kind)
+            .map(|class| Some(context.heap.class_object(&class))),
+    )
+}
+
+fn get_return_type(context: &mut NativeContext) -> RustMethodReturn {
+    let (declaring_class, method) = this_method(context);
+    type_class_object(context, &declaring_class, method.return_type.as_ref())
+}
+
+/// `invoke(Object obj, Object... args)`, which invokes the method
+/// on `obj` (ignored for static methods) with the unboxed `args`
+/// and returns the boxed result.
+///
+/// Like `invokevirtual`, the method to invoke is selected
+/// by the class of `obj`, unless it is private.
+/// Exceptions thrown by the method are wrapped in an
+/// `InvocationTargetException`.
+fn invoke(context: &mut NativeContext) -> RustMethodReturn {
+    reference_result(try_invoke(context))
+}
+
+fn try_invoke(
+    context: &mut NativeContext,
+) -> Result<Option<Rc<dyn ClassInstance>>, Exception> {
+    let (declaring_class, member, accessible) = this_member(context);
+    let (declaring_class, method) = match member {
+        Member::Method(method) => (declaring_class, method),
+        member => panic!("{} is no method", member.name()),
+    };
+    check_access(
+        context,
+        &declaring_class,
+        &Member::Method(method.clone()),
+        accessible,
+    )?;
+
+    let (selected, defining_class, mut arguments) = if method.is_static {
+        initialize_class(context.heap, &declaring_class)?;
+        (method.clone(), declaring_class.clone(), Vec::new())
+    } else {
+        let object = target_object(context, &declaring_class, |_| {
+            "object is not an instance of declaring class".to_string()
+        })?;
+        let (selected, defining_class) =
+            if method.access_flags.contains(MethodAccessFlag::Private) {
+                (method.clone(), declaring_class.clone())
+            } else {
+                object.class().get_method(
+                    &method.name,
+                    (&method.parameters, method.return_type.as_ref()),
+                    true,
+                )
+            };
+        (
+            selected,
+            defining_class,
+            vec![StackValue::Reference(Some(object))],
+        )
+    };
+    let argument_array = context.reference(1);
+    arguments.extend(method_arguments(
+        context,
+        &declaring_class,
+        &method,
+        argument_array,
+    )?);
+
+    let value =
+        invoke_method(context.heap, &selected, defining_class, arguments)
+            .map_err(|e| invocation_target_exception(context.heap, e))?;
+    Ok(box_return_value(
+        context.heap,
+        value,
+        method.return_type.as_ref(),
+    ))
+}
+
+/// `newInstance(Object... args)`, which creates an instance
+/// of the declaring class and initializes it
+/// by invoking the constructor with the unboxed `args`.
+///
+/// Exceptions thrown by the constructor are wrapped in an
+/// `InvocationTargetException`.
+fn new_instance(context: &mut NativeContext) -> RustMethodReturn {
+    reference_result(try_new_instance(context))
+}
+
+fn try_new_instance(
+    context: &mut NativeContext,
+) -> Result<Option<Rc<dyn ClassInstance>>, Exception> {
+    let (declaring_class, member, accessible) = this_member(context);
+    check_access(context, &declaring_class, &member, accessible)?;
+    let Member::Method(constructor) = member else {
+        panic!("{} is no constructor", member.name());
+    };
+    // interfaces are abstract as well
+    if declaring_class
+        .access_flags()
+        .contains(ClassAccessFlag::Abstract)
+    {
+        return Err(new_throwable(
+            context.heap,
+            &class_identifier!(java / lang, InstantiationException),
+        ));
+    }
+    initialize_class(context.heap, &declaring_class)?;
+    let argument_array = context.reference(0);
+    let arguments = method_arguments(
+        context,
+        &declaring_class,
+        &constructor,
+        argument_array,
+    )?;
+
+    let object = declaring_class.new_instance(declaring_class.clone());
+    let mut all_arguments = vec![StackValue::Reference(Some(object.clone()))];
+    all_arguments.extend(arguments);
+    invoke_method(context.heap, &constructor, declaring_class, all_arguments)
+        .map_err(|e| invocation_target_exception(context.heap, e))?;
+    Ok(Some(object))
+}
+
+fn get_type(context: &mut NativeContext) -> RustMethodReturn {
+    let (declaring_class, member, _) = this_member(context);
+    type_class_object(context, &declaring_class, Some(member.field_type()))
+}
+
+/// Describes the field `name` of `declaring_class`
+/// like the messages of the JDK do,
+/// e.g. `static final int field org.example.Main.MAX`.
+fn field_description(
+    declaring_class: &Rc<dyn Class>,
+    access_flags: BitFlags<FieldAccessFlag>,
+    field_type: &ArgumentKind,
+    name: &str,
+) -> String {
+    let mut description = String::new();
+    if access_flags.contains(FieldAccessFlag::Static) {
+        description.push_str("static ");
+    }
+    if access_flags.contains(FieldAccessFlag::Final) {
+        description.push_str("final ");
+    }
+    format!(
+        "{}{} field {}.{}",
+        description,
+        type_name(field_type),
+        declaring_class.class_identifier().java_name(),
+        name
+    )
+}
+
+/// Returns the field `member` of `declaring_class`,
+/// which for instance fields is the field of the object
+/// given as first argument.
+///
+/// Static fields are initialized first.
+fn member_field(
+    context: &mut NativeContext,
+    declaring_class: &Rc<dyn Class>,
+    member: Member,
+) -> Result<Rc<Field>, Exception> {
+    match member {
+        Member::StaticField(field) => {
+            initialize_class(context.heap, declaring_class)?;
+            Ok(field)
+        },
+        Member::InstanceField {
+            name,
+            access_flags,
+            field_type,
+        } => {
+            let object = target_object(context, declaring_class, |object| {
+                format!(
+                    "Can not set {} to {}",
+                    field_description(
+                        declaring_class,
+                        access_flags,
+                        &field_type,
+                        &name
+                    ),
+                    object.class().class_identifier().java_name()
+                )
+            })?;
+            Ok(object.get_field(declaring_class.as_ref(), &name))
+        },
+        Member::Method(method) => panic!("{} is no field", method.name),
+    }
+}
+
+/// `get(Object obj)`, which returns the (boxed) value of the field
+/// of `obj` (ignored for static fields).
+fn get(context: &mut NativeContext) -> RustMethodReturn {
+    reference_result(try_get(context))
+}
+
+fn try_get(
+    context: &mut NativeContext,
+) -> Result<Option<Rc<dyn ClassInstance>>, Exception> {
+    let (declaring_class, member, accessible) = this_member(context);
+    check_access(context, &declaring_class, &member, accessible)?;
+    let field = member_field(context, &declaring_class, member)?;
+    let value = field.value.borrow().clone();
+    Ok(match value {
+        FieldValue::Reference(reference) => reference,
+        value => Some(box_value(context.heap, value)),
+    })
+}
+
+/// `set(Object obj, Object value)`, which sets the field of `obj`
+/// (ignored for static fields) to the unboxed `value`.
+///
+/// Final fields can only be set if they are instance fields,
+/// which have been made accessible.
+fn set(context: &mut NativeContext) -> RustMethodReturn {
+    match try_set(context) {
+        Ok(()) => RustMethodReturn::Void,
+        Err(e) => RustMethodReturn::Throw(e),
+    }
+}
+
+fn try_set(context: &mut NativeContext) -> Result<(), Exception> {
+    let (declaring_class, member, accessible) = this_member(context);
+    check_access(context, &declaring_class, &member, accessible)?;
+    let field = member_field(context, &declaring_class, member)?;
+    let value = context.reference(1);
+    let value_name = match &value {
+        Some(object) => object.class().class_identifier().java_name(),
+        None => "null value".to_string(),
+    };
+    let message = format!(
+        "Can not set {} to {}",
+        field_description(
+            &declaring_class,
+            field.access_flags,
+            &field.field_type,
+            &field.name
+        ),
+        value_name
+    );
+
+    if field.access_flags.contains(FieldAccessFlag::Final)
+        && (field.access_flags.contains(FieldAccessFlag::Static) || !accessible)
+    {
+        return Err(new_exception(
+            context.heap,
+            &class_identifier!(java / lang, IllegalAccessException),
+            &message,
+        ));
+    }
+    match unbox_value(
+        context.heap,
+        declaring_class.class_loader(),
+        &field.field_type,
+        value,
+    )? {
+        Some(value) => {
+            field.value.replace(value);
+            Ok(())
+        },
+        None => Err(new_exception(
+            context.heap,
+            &class_identifier!(java / lang, IllegalArgumentException),
+            &message,
+        )),
+    }
+}
+
+impl Class for MemberClass {
+    fn methods(&self) -> &[Rc<Method>] {
+        self.methods.as_slice()
+    }
+
+    fn static_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn instance_fields(&self) -> &[FieldDescriptor] {
+        &[]
+    }
+
+    fn class_identifier(&self) -> &ClassIdentifier {
+        &self.class_identifier
+    }
+
+    fn super_class(&self) -> Option<Rc<dyn Class>> {
+        Some(self.object_class.clone())
+    }
+
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
+        &[]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn access_flags(&self) -> BitFlags<ClassAccessFlag> {
+        ClassAccessFlag::Public | ClassAccessFlag::Final
+    }
+
+    fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance> {
+        // make sure that self and cls really are equal
+        let _cls_ref: &Self =
+            cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
+
+        panic!("members are obtained from their class, not created by new");
+    }
+}
+
+pub struct MemberInstance {
+    class: Rc<dyn Class>,
+    object_instance: Rc<dyn ClassInstance>,
+    declaring_class: Rc<dyn Class>,
+    member: Member,
+    /// Whether access checks have been turned off by `setAccessible`.
+    accessible: Cell<bool>,
+}
+
+impl ClassInstance for MemberInstance {
+    fn class(&self) -> Rc<dyn Class> {
+        self.class.clone()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn instance_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn parent_instance(&self) -> Option<Rc<dyn ClassInstance>> {
+        Some(self.object_instance.clone())
+    }
+}
@@ -1,13 +1,19 @@
-use std::{any::Any, rc::Rc};
+use std::{any::Any, fmt::LowerExp, rc::Rc};
 
-use crate::class::{
-    class_identifier, Class, ClassIdentifier, ClassInstance, Field,
-    FieldDescriptor, Method,
+use crate::{
+    class::{
+        access_flags::MethodAccessFlag, class_identifier, ArgumentKind, Class,
+        ClassIdentifier, ClassInstance, Field, FieldDescriptor, FieldValue,
+        Method, MethodCode, RustMethodReturn, SimpleArgumentKind,
+    },
+    executor::{invoke_virtual, native_context::NativeContext, ReturnValue},
+    heap::Heap,
 };
 
 pub struct StringClass {
     class_identifier: ClassIdentifier,
     object_class: Rc<dyn Class>,
+    methods: Vec<Rc<Method>>,
 }
 
 impl StringClass {
@@ -15,6 +21,65 @@ impl StringClass {
         StringClass {
             class_identifier: class_identifier!(java / lang, String),
             object_class,
+            methods: vec![
+                Rc::new(Method {
+                    code: MethodCode::Rust(to_string),
+                    name: "toString".to_owned(),
+                    parameters: vec![],
+                    return_type: Some(ArgumentKind::Simple(
+                        SimpleArgumentKind::Class(
+                            "java/lang/String".to_string(),
+                        ),
+                    )),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(equals),
+                    name: "equals".to_owned(),
+                    parameters: vec![ArgumentKind::Simple(
+                        SimpleArgumentKind::Class(
+                            "java/lang/Object".to_string(),
+                        ),
+                    )],
+                    return_type: Some(ArgumentKind::Simple(
+                        SimpleArgumentKind::Boolean,
+                    )),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(hash_code),
+                    name: "hashCode".to_owned(),
+                    parameters: vec![],
+                    return_type: Some(ArgumentKind::Simple(
+                        SimpleArgumentKind::Int,
+                    )),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(value_of_object),
+                    name: "valueOf".to_owned(),
+                    parameters: vec![ArgumentKind::Simple(
+                        SimpleArgumentKind::Class(
+                            "java/lang/Object".to_string(),
+                        ),
+                    )],
+                    return_type: Some(ArgumentKind::Simple(
+                        SimpleArgumentKind::Class(
+                            "java/lang/String".to_string(),
+                        ),
+                    )),
+                    is_static: true,
+                    access_flags: MethodAccessFlag::Public
+                        | MethodAccessFlag::Static,
+                    attributes: Default::default(),
+                }),
+            ],
         }
     }
 
@@ -29,9 +94,152 @@ impl StringClass {
     }
 }
 
+/// Convert `object` to a string, like `String.valueOf(Object)` does.
+///
+/// This calls `toString()` virtually,
+/// so that classes overriding it are respected.
+/// Exceptions thrown by `toString()` are returned as error.
+pub fn string_value_of(
+    heap: &mut Heap,
+    object: Option<Rc<dyn ClassInstance>>,
+) -> Result<String, Rc<dyn ClassInstance>> {
+    let object = match object {
+        None => return Ok("null".to_string()),
+        Some(object) => object,
+    };
+    // fast path, strings are their own string representation
+    if let Some(string) = object.as_any().downcast_ref::<StringInstance>() {
+        return Ok(string.string.clone());
+    }
+
+    match invoke_virtual(
+        heap,
+        object,
+        "toString",
+        (
+            &[],
+            Some(&ArgumentKind::Simple(SimpleArgumentKind::Class(
+                "java/lang/String".to_string(),
+            ))),
+        ),
+        vec![],
+    )? {
+        ReturnValue::Reference(None) => Ok("null".to_string()),
+        ReturnValue::Reference(Some(string)) => {
+            match string.as_any().downcast_ref::<StringInstance>() {
+                Some(s) => Ok(s.string.clone()),
+                None => panic!("toString() returned {:?}", string),
+            }
+        },
+        v => panic!("toString() returned {:?} instead of a String", v),
+    }
+}
+
+/// Convert a float or double to a string,
+/// like `Float.toString` and `Double.toString` do.
+///
+/// Values from 10^-3 up to 10^7 are written as decimals
+/// and all others in scientific notation, e.g. `1.0E7`,
+/// both with at least one digit after the point.
+pub fn floating_point_string<T>(value: T) -> String
+where
+    T: Copy + Into<f64> + LowerExp,
+{
+    let double: f64 = value.into();
+    if double.is_nan() {
+        return "NaN".to_string();
+    }
+    if double.is_infinite() {
+        let sign = if double < 0.0 { "-" } else { "" };
+        return format!("{}Infinity", sign);
+    }
+    if double == 0.0 {
+        let sign = if double.is_sign_negative() { "-" } else { "" };
+        return format!("{}0.0", sign);
+    }
+
+    // the shortest digits distinguishing the value from its neighbours,
+    // but at least two, e.g. 1.4E-45 instead of 1.0E-45
+    let mut scientific = format!("{:e}", value);
+    if !scientific.contains('.') {
+        scientific = format!("{:.1e}", value);
+    }
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("scientific notation has an exponent");
+    let exponent: i32 = exponent.parse().expect("exponent is an integer");
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let digits = mantissa.replace('.', "");
+    let digits = digits.trim_end_matches('0');
+
+    if !(-3..7).contains(&exponent) {
+        let (first, rest) = digits.split_at(1);
+        let rest = if rest.is_empty() { "0" } else { rest };
+        return format!("{}{}.{}E{}", sign, first, rest, exponent);
+    }
+    if exponent < 0 {
+        let zeros = "0".repeat(exponent.unsigned_abs() as usize - 1);
+        return format!("{}0.{}{}", sign, zeros, digits);
+    }
+    let point = exponent as usize + 1;
+    if point < digits.len() {
+        format!("{}{}.{}", sign, &digits[..point], &digits[point..])
+    } else {
+        let zeros = "0".repeat(point - digits.len());
+        format!("{}{}{}.0", sign, digits, zeros)
+    }
+}
+
+fn to_string(context: &mut NativeContext) -> RustMethodReturn {
+    RustMethodReturn::Value(FieldValue::Reference(Some(context.this())))
+}
+
+/// Returns the content of the string `this`.
+fn content(this: &Rc<dyn ClassInstance>) -> String {
+    this.with_parent_instance("String", |string: &StringInstance| {
+        string.string.clone()
+    })
+}
+
+/// Returns whether the argument is a string with the same characters.
+fn equals(context: &mut NativeContext) -> RustMethodReturn {
+    let this = content(&context.this());
+    let equal = context.reference(0).is_some_and(|other| {
+        other
+            .as_any()
+            .downcast_ref::<StringInstance>()
+            .is_some_and(|other| other.string == this)
+    });
+    RustMethodReturn::Value(FieldValue::Boolean(equal.into()))
+}
+
+/// Returns `s[0]*31^(n-1) + s[1]*31^(n-2) + ... + s[n-1]`
+/// of the UTF-16 code units `s`.
+fn hash_code(context: &mut NativeContext) -> RustMethodReturn {
+    let hash = content(&context.this())
+        .encode_utf16()
+        .fold(0i32, |hash, c| hash.wrapping_mul(31).wrapping_add(c.into()));
+    RustMethodReturn::Value(FieldValue::Int(hash))
+}
+
+fn value_of_object(context: &mut NativeContext) -> RustMethodReturn {
+    let object = context.reference(0);
+
+    let string = match string_value_of(context.heap, object) {
+        Ok(string) => string,
+        Err(e) => return RustMethodReturn::Throw(e),
+    };
+    RustMethodReturn::Value(FieldValue::Reference(Some(Rc::new(
+        context.heap.new_string(string),
+    ))))
+}
+
 impl Class for StringClass {
     fn methods(&self) -> &[Rc<Method>] {
-        &[]
+        self.methods.as_slice()
     }
 
     fn static_fields(&self) -> &[Rc<Field>] {
@@ -50,7 +258,7 @@ impl Class for StringClass {
         Some(self.object_class.clone())
     }
 
-    fn interfaces(&self) -> &[Rc<dyn std::any::Any>] {
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
         &[]
     }
 
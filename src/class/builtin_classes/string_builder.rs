@@ -0,0 +1,228 @@
+use std::{any::Any, cell::RefCell, rc::Rc};
+
+use crate::{
+    class::{
+        access_flags::MethodAccessFlag,
+        builtin_classes::string::{floating_point_string, string_value_of},
+        class_identifier, ArgumentKind, Class, ClassIdentifier, ClassInstance,
+        Field, FieldDescriptor, FieldValue, Method, MethodCode,
+        RustMethodReturn, SimpleArgumentKind,
+    },
+    executor::native_context::NativeContext,
+};
+
+pub struct StringBuilder {
+    class_identifier: ClassIdentifier,
+    object_class: Rc<dyn Class>,
+    methods: Vec<Rc<Method>>,
+}
+
+impl StringBuilder {
+    pub fn new(object_class: Rc<dyn Class>) -> StringBuilder {
+        let string_builder_kind = ArgumentKind::Simple(
+            SimpleArgumentKind::Class("java/lang/StringBuilder".to_string()),
+        );
+        let append = |code, parameter| {
+            Rc::new(Method {
+                code: MethodCode::Rust(code),
+                name: "append".to_owned(),
+                parameters: vec![ArgumentKind::Simple(parameter)],
+                return_type: Some(string_builder_kind.clone()),
+                is_static: false,
+                access_flags: MethodAccessFlag::Public.into(),
+                attributes: Default::default(),
+            })
+        };
+
+        StringBuilder {
+            class_identifier: class_identifier!(java / lang, StringBuilder),
+            object_class,
+            methods: vec![
+                Rc::new(Method {
+                    code: MethodCode::Rust(init),
+                    name: "<init>".to_owned(),
+                    parameters: vec![],
+                    return_type: None,
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(init_string),
+                    name: "<init>".to_owned(),
+                    parameters: vec![ArgumentKind::Simple(
+                        SimpleArgumentKind::Class(
+                            "java/lang/String".to_string(),
+                        ),
+                    )],
+                    return_type: None,
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                append(
+                    append_object,
+                    SimpleArgumentKind::Class("java/lang/String".to_string()),
+                ),
+                append(
+                    append_object,
+                    SimpleArgumentKind::Class("java/lang/Object".to_string()),
+                ),
+                append(append_boolean, SimpleArgumentKind::Boolean),
+                append(append_char, SimpleArgumentKind::Char),
+                append(append_int, SimpleArgumentKind::Int),
+                append(append_long, SimpleArgumentKind::Long),
+                append(append_float, SimpleArgumentKind::Float),
+                append(append_double, SimpleArgumentKind::Double),
+                Rc::new(Method {
+                    code: MethodCode::Rust(to_string),
+                    name: "toString".to_owned(),
+                    parameters: vec![],
+                    return_type: Some(ArgumentKind::Simple(
+                        SimpleArgumentKind::Class(
+                            "java/lang/String".to_string(),
+                        ),
+                    )),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+            ],
+        }
+    }
+}
+
+/// Append `string` to the StringBuilder `this`
+/// and return `this`, so that calls can be chained.
+fn append(this: Rc<dyn ClassInstance>, string: &str) -> RustMethodReturn {
+    this.with_parent_instance(
+        "StringBuilder",
+        |instance: &StringBuilderInstance| {
+            instance.string.borrow_mut().push_str(string)
+        },
+    );
+
+    RustMethodReturn::Value(FieldValue::Reference(Some(this)))
+}
+
+fn init(_context: &mut NativeContext) -> RustMethodReturn {
+    RustMethodReturn::Void
+}
+
+fn init_string(context: &mut NativeContext) -> RustMethodReturn {
+    match append_object(context) {
+        RustMethodReturn::Throw(e) => RustMethodReturn::Throw(e),
+        _ => RustMethodReturn::Void,
+    }
+}
+
+fn append_object(context: &mut NativeContext) -> RustMethodReturn {
+    let object = context.reference(0);
+    match string_value_of(context.heap, object) {
+        Ok(string) => append(context.this(), &string),
+        Err(e) => RustMethodReturn::Throw(e),
+    }
+}
+
+fn append_boolean(context: &mut NativeContext) -> RustMethodReturn {
+    append(context.this(), &context.boolean(0).to_string())
+}
+
+fn append_char(context: &mut NativeContext) -> RustMethodReturn {
+    append(context.this(), &context.char(0).to_string())
+}
+
+fn append_int(context: &mut NativeContext) -> RustMethodReturn {
+    append(context.this(), &context.int(0).to_string())
+}
+
+fn append_long(context: &mut NativeContext) -> RustMethodReturn {
+    append(context.this(), &context.long(0).to_string())
+}
+
+fn append_float(context: &mut NativeContext) -> RustMethodReturn {
+    append(context.this(), &floating_point_string(context.float(0)))
+}
+
+fn append_double(context: &mut NativeContext) -> RustMethodReturn {
+    append(context.this(), &floating_point_string(context.double(0)))
+}
+
+fn to_string(context: &mut NativeContext) -> RustMethodReturn {
+    let string = context.this().with_parent_instance(
+        "StringBuilder",
+        |instance: &StringBuilderInstance| instance.string.borrow().clone(),
+    );
+
+    RustMethodReturn::Value(FieldValue::Reference(Some(Rc::new(
+        context.heap.new_string(string),
+    ))))
+}
+
+impl Class for StringBuilder {
+    fn methods(&self) -> &[Rc<Method>] {
+        self.methods.as_slice()
+    }
+
+    fn static_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn instance_fields(&self) -> &[FieldDescriptor] {
+        &[]
+    }
+
+    fn class_identifier(&self) -> &crate::class::ClassIdentifier {
+        &self.class_identifier
+    }
+
+    fn super_class(&self) -> Option<Rc<dyn Class>> {
+        Some(self.object_class.clone())
+    }
+
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
+        &[]
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn new_instance(&self, cls: Rc<dyn Class>) -> Rc<dyn ClassInstance> {
+        // make sure that self and cls really are equal
+        let _cls_ref: &Self =
+            cls.as_ref().as_any().downcast_ref::<Self>().unwrap();
+
+        Rc::new(StringBuilderInstance {
+            class: cls,
+            object_instance: self
+                .object_class
+                .new_instance(self.object_class.clone()),
+            string: RefCell::new(String::new()),
+        })
+    }
+}
+
+pub struct StringBuilderInstance {
+    class: Rc<dyn Class>,
+    object_instance: Rc<dyn ClassInstance>,
+    string: RefCell<String>,
+}
+
+impl ClassInstance for StringBuilderInstance {
+    fn class(&self) -> Rc<dyn Class> {
+        self.class.clone()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn instance_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn parent_instance(&self) -> Option<Rc<dyn ClassInstance>> {
+        Some(self.object_instance.clone())
+    }
+}
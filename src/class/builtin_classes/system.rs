@@ -1,49 +1,173 @@
 use std::{any::Any, cell::RefCell, rc::Rc};
 
-use crate::class::{
-    builtin_classes::{InputStream, PrintStream},
-    class_identifier, Class, ClassIdentifier, ClassInstance, Field,
-    FieldDescriptor, FieldValue, Method,
+use crate::{
+    class::{
+        access_flags::{FieldAccessFlag, MethodAccessFlag},
+        builtin_classes::{FileInputStream, PrintStream},
+        class_identifier, ArgumentKind, Class, ClassIdentifier, ClassInstance,
+        Field, FieldDescriptor, FieldValue, Method, MethodCode,
+        RustMethodReturn, SimpleArgumentKind,
+    },
+    executor::native_context::NativeContext,
 };
 
 pub struct SystemClass {
     class_identifier: ClassIdentifier,
     object_class: Rc<dyn Class>,
     fields: Vec<Rc<Field>>,
+    methods: Vec<Rc<Method>>,
 }
 
 impl SystemClass {
     pub fn new(
         print_stream_class: &Rc<PrintStream>,
-        // TODO: replace with FileInputStream when InputStream becomes abstract
-        file_input_stream_class: &Rc<InputStream>,
+        file_input_stream_class: &Rc<FileInputStream>,
         object_class: Rc<dyn Class>,
     ) -> Self {
         let fields = vec![
             Rc::new(Field {
                 name: "out".into(),
+                access_flags: FieldAccessFlag::Public
+                    | FieldAccessFlag::Static
+                    | FieldAccessFlag::Final,
+                attributes: Default::default(),
+                field_type: ArgumentKind::Simple(SimpleArgumentKind::Class(
+                    "java/io/PrintStream".to_string(),
+                )),
                 value: RefCell::new(FieldValue::Reference(Some(Rc::new(
                     print_stream_class.new_instance(),
                 )))),
             }),
             Rc::new(Field {
                 name: "in".into(),
+                access_flags: FieldAccessFlag::Public
+                    | FieldAccessFlag::Static
+                    | FieldAccessFlag::Final,
+                attributes: Default::default(),
+                field_type: ArgumentKind::Simple(SimpleArgumentKind::Class(
+                    "java/io/InputStream".to_string(),
+                )),
                 value: RefCell::new(FieldValue::Reference(Some(Rc::new(
                     file_input_stream_class.new_instance(),
                 )))),
             }),
         ];
+        let string_kind = ArgumentKind::Simple(SimpleArgumentKind::Class(
+            "java/lang/String".to_string(),
+        ));
+        let methods = vec![
+            Rc::new(Method {
+                code: MethodCode::Rust(get_property),
+                name: "getProperty".to_owned(),
+                parameters: vec![string_kind.clone()],
+                return_type: Some(string_kind.clone()),
+                is_static: true,
+                access_flags: MethodAccessFlag::Public
+                    | MethodAccessFlag::Static,
+                attributes: Default::default(),
+            }),
+            Rc::new(Method {
+                code: MethodCode::Rust(get_property_with_default),
+                name: "getProperty".to_owned(),
+                parameters: vec![string_kind.clone(), string_kind.clone()],
+                return_type: Some(string_kind),
+                is_static: true,
+                access_flags: MethodAccessFlag::Public
+                    | MethodAccessFlag::Static,
+                attributes: Default::default(),
+            }),
+            Rc::new(Method {
+                code: MethodCode::Rust(exit),
+                name: "exit".to_owned(),
+                parameters: vec![ArgumentKind::Simple(SimpleArgumentKind::Int)],
+                return_type: None,
+                is_static: true,
+                access_flags: MethodAccessFlag::Public
+                    | MethodAccessFlag::Static,
+                attributes: Default::default(),
+            }),
+        ];
         Self {
             class_identifier: class_identifier!(java / lang, System),
             fields,
             object_class,
+            methods,
         }
     }
 }
 
+/// Throw the `NullPointerException` of `System.getProperty` for a null key.
+fn null_key(context: &mut NativeContext) -> RustMethodReturn {
+    context.throw_new(
+        &class_identifier!(java / lang, NullPointerException),
+        "key can't be null",
+    )
+}
+
+fn get_property(context: &mut NativeContext) -> RustMethodReturn {
+    let Some(key) = context.string(0) else {
+        return null_key(context);
+    };
+    let value = context.heap.system_property(&key).map(str::to_string);
+
+    RustMethodReturn::Value(FieldValue::Reference(
+        value.map(|v| Rc::new(context.heap.new_string(v)) as _),
+    ))
+}
+
+fn get_property_with_default(context: &mut NativeContext) -> RustMethodReturn {
+    let Some(key) = context.string(0) else {
+        return null_key(context);
+    };
+    match context.heap.system_property(&key) {
+        Some(value) => {
+            let value = Rc::new(context.heap.new_string(value.to_string()));
+            RustMethodReturn::Value(FieldValue::Reference(Some(value)))
+        },
+        None => {
+            RustMethodReturn::Value(FieldValue::Reference(context.reference(1)))
+        },
+    }
+}
+
+fn exit(context: &mut NativeContext) -> RustMethodReturn {
+    RustMethodReturn::Throw(Rc::new(SystemExit {
+        class: context.class.clone(),
+        status: context.int(0),
+    }))
+}
+
+/// Thrown by `System.exit()` to terminate the program.
+///
+/// This is not a java object: It unwinds the whole call stack
+/// without being caught by any exception handler
+/// (`finally` blocks aren't executed either, just like in java).
+pub struct SystemExit {
+    class: Rc<dyn Class>,
+    pub status: i32,
+}
+
+impl ClassInstance for SystemExit {
+    fn class(&self) -> Rc<dyn Class> {
+        self.class.clone()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn instance_fields(&self) -> &[Rc<Field>] {
+        &[]
+    }
+
+    fn parent_instance(&self) -> Option<Rc<dyn ClassInstance>> {
+        None
+    }
+}
+
 impl Class for SystemClass {
     fn methods(&self) -> &[Rc<Method>] {
-        &[]
+        self.methods.as_slice()
     }
 
     fn static_fields(&self) -> &[Rc<Field>] {
@@ -62,7 +186,7 @@ impl Class for SystemClass {
         Some(self.object_class.clone())
     }
 
-    fn interfaces(&self) -> &[Rc<dyn std::any::Any>] {
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
         &[]
     }
 
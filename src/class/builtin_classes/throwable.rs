@@ -2,15 +2,17 @@ use std::{any::Any, cell::OnceCell, rc::Rc};
 
 use crate::{
     class::{
+        access_flags::MethodAccessFlag, builtin_classes::StringInstance,
         class_identifier, ArgumentKind, Class, ClassIdentifier, ClassInstance,
         Field, FieldDescriptor, FieldValue, Method, MethodCode,
         RustMethodReturn, SimpleArgumentKind,
     },
-    executor::{local_variables::VariableValueOrValue, Frame},
+    executor::native_context::NativeContext,
 };
 
 pub struct ThrowableClass {
     class_identifier: ClassIdentifier,
+    super_class: Rc<dyn Class>,
     object_class: Rc<dyn Class>,
     methods: Vec<Rc<Method>>,
 }
@@ -18,12 +20,22 @@ pub struct ThrowableClass {
 impl ThrowableClass {
     pub fn new(object_class: Rc<dyn Class>) -> Self {
         Self {
+            super_class: object_class.clone(),
             object_class,
             class_identifier: class_identifier!(java / lang, Throwable),
             methods: vec![
                 Rc::new(Method {
                     code: MethodCode::Rust(init),
                     name: "<init>".to_owned(),
+                    parameters: vec![],
+                    return_type: None,
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(init_message),
+                    name: "<init>".to_owned(),
                     parameters: vec![ArgumentKind::Simple(
                         SimpleArgumentKind::Class(
                             "java/lang/String".to_string(),
@@ -31,6 +43,8 @@ impl ThrowableClass {
                     )],
                     return_type: None,
                     is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
                 }),
                 Rc::new(Method {
                     code: MethodCode::Rust(get_message),
@@ -42,19 +56,44 @@ impl ThrowableClass {
                         ),
                     )),
                     is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
+                }),
+                Rc::new(Method {
+                    code: MethodCode::Rust(get_cause),
+                    name: "getCause".to_owned(),
+                    parameters: vec![],
+                    return_type: Some(ArgumentKind::Simple(
+                        SimpleArgumentKind::Class(
+                            "java/lang/Throwable".to_string(),
+                        ),
+                    )),
+                    is_static: false,
+                    access_flags: MethodAccessFlag::Public.into(),
+                    attributes: Default::default(),
                 }),
             ],
         }
     }
-}
 
-fn get_message(frame: &mut Frame) -> RustMethodReturn {
-    let instance: Rc<dyn ClassInstance> = match frame.local_variables.get(0) {
-        VariableValueOrValue::Reference(s) => s.expect("null pointer"),
-        _ => panic!("local variables have reference at index 0"),
-    };
+    /// Create a builtin subclass of `super_class`
+    /// (e.g. `java.lang.Exception`), which doesn't add
+    /// any methods to the ones inherited from `super_class`.
+    pub fn new_subclass(
+        class_identifier: ClassIdentifier,
+        super_class: Rc<ThrowableClass>,
+    ) -> Self {
+        Self {
+            class_identifier,
+            object_class: super_class.object_class.clone(),
+            super_class,
+            methods: vec![],
+        }
+    }
+}
 
-    let message = instance.with_parent_instance(
+fn get_message(context: &mut NativeContext) -> RustMethodReturn {
+    let message = context.this().with_parent_instance(
         "Throwable",
         |instance: &ThrowableInstance| {
             instance
@@ -68,17 +107,73 @@ fn get_message(frame: &mut Frame) -> RustMethodReturn {
     RustMethodReturn::Value(FieldValue::Reference(message))
 }
 
-fn init(frame: &mut Frame) -> RustMethodReturn {
-    let instance: Rc<dyn ClassInstance> = match frame.local_variables.get(0) {
-        VariableValueOrValue::Reference(s) => s.expect("null pointer"),
-        _ => panic!("local variables have reference at index 0"),
-    };
-    let message: Option<Rc<dyn ClassInstance>> =
-        match frame.local_variables.get(1) {
-            VariableValueOrValue::Reference(s) => s,
-            _ => panic!("local variables have message at index 1"),
-        };
+/// Describe the throwable `instance` like `Throwable.toString()`,
+/// i.e. by its class name followed by its message (if there is one),
+/// e.g. `java.lang.Exception: Oops`.
+pub fn throwable_description(instance: &Rc<dyn ClassInstance>) -> String {
+    let class_name = instance.class().class_identifier().java_name();
+    let message = instance
+        .with_parent_instance("Throwable", |instance: &ThrowableInstance| {
+            instance.message.get().cloned()
+        });
+    match message.flatten().and_then(|message| {
+        message
+            .as_any()
+            .downcast_ref::<StringInstance>()
+            .map(|message| message.string.clone())
+    }) {
+        Some(message) => format!("{}: {}", class_name, message),
+        None => class_name,
+    }
+}
 
+/// Returns the cause, or null if it is unknown.
+fn get_cause(context: &mut NativeContext) -> RustMethodReturn {
+    let cause = context
+        .this()
+        .with_parent_instance("Throwable", |instance: &ThrowableInstance| {
+            instance.cause.get().cloned()
+        });
+
+    RustMethodReturn::Value(FieldValue::Reference(cause))
+}
+
+/// Set the cause of the throwable `instance`,
+/// e.g. the exception wrapped by an `InvocationTargetException`.
+///
+/// # Panics
+///
+/// This function will panic if the cause has already been set.
+pub fn set_cause(
+    instance: &Rc<dyn ClassInstance>,
+    cause: Rc<dyn ClassInstance>,
+) {
+    instance.with_parent_instance(
+        "Throwable",
+        |instance: &ThrowableInstance| {
+            if instance.cause.set(cause.clone()).is_err() {
+                panic!("the cause has already been set");
+            }
+        },
+    );
+}
+
+fn init(context: &mut NativeContext) -> RustMethodReturn {
+    set_message(context.this(), None);
+
+    RustMethodReturn::Void
+}
+
+fn init_message(context: &mut NativeContext) -> RustMethodReturn {
+    set_message(context.this(), context.reference(0));
+
+    RustMethodReturn::Void
+}
+
+fn set_message(
+    instance: Rc<dyn ClassInstance>,
+    message: Option<Rc<dyn ClassInstance>>,
+) {
     instance.with_parent_instance(
         "Throwable",
         |instance: &ThrowableInstance| {
@@ -88,8 +183,6 @@ fn init(frame: &mut Frame) -> RustMethodReturn {
                 .expect("message has not been set");
         },
     );
-
-    RustMethodReturn::Void
 }
 
 impl Class for ThrowableClass {
@@ -110,10 +203,10 @@ impl Class for ThrowableClass {
     }
 
     fn super_class(&self) -> Option<Rc<dyn Class>> {
-        None
+        Some(self.super_class.clone())
     }
 
-    fn interfaces(&self) -> &[Rc<dyn std::any::Any>] {
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
         &[]
     }
 
@@ -132,6 +225,7 @@ impl Class for ThrowableClass {
                 .object_class
                 .new_instance(self.object_class.clone()),
             message: OnceCell::new(),
+            cause: OnceCell::new(),
         })
     }
 }
@@ -140,6 +234,7 @@ pub struct ThrowableInstance {
     class: Rc<dyn Class>,
     object_instance: Rc<dyn ClassInstance>,
     message: OnceCell<Option<Rc<dyn ClassInstance>>>,
+    cause: OnceCell<Rc<dyn ClassInstance>>,
 }
 
 impl ClassInstance for ThrowableInstance {
@@ -1,9 +1,14 @@
 use std::{any::Any, cell::RefCell, rc::Rc};
 
+use enumflags2::BitFlags;
+
 use super::access_flags::ClassAccessFlag;
-use crate::class::{
-    BytecodeClass, Class, ClassInstance, Field, FieldDescriptor, FieldKind,
-    FieldValue, Method,
+use crate::{
+    class::{
+        BytecodeClass, Class, ClassInstance, Field, FieldDescriptor,
+        FieldValue, Method,
+    },
+    heap::ClassLoaderId,
 };
 
 impl Class for BytecodeClass {
@@ -27,7 +32,7 @@ impl Class for BytecodeClass {
         Some(self.super_class.clone())
     }
 
-    fn interfaces(&self) -> &[Rc<dyn std::any::Any>] {
+    fn interfaces(&self) -> &[Rc<dyn Class>] {
         self.interfaces.as_slice()
     }
 
@@ -35,6 +40,14 @@ impl Class for BytecodeClass {
         self
     }
 
+    fn class_loader(&self) -> ClassLoaderId {
+        self.class_loader
+    }
+
+    fn access_flags(&self) -> BitFlags<ClassAccessFlag> {
+        self.access_flags
+    }
+
     fn has_acc_super(&self) -> bool {
         self.access_flags.contains(ClassAccessFlag::Super)
     }
@@ -48,20 +61,12 @@ impl Class for BytecodeClass {
             .instance_fields
             .iter()
             .map(|f| {
-                let default_val = match f.kind {
-                    FieldKind::Byte => FieldValue::byte(),
-                    FieldKind::Short => FieldValue::short(),
-                    FieldKind::Int => FieldValue::int(),
-                    FieldKind::Long => FieldValue::long(),
-                    FieldKind::Char => FieldValue::char(),
-                    FieldKind::Float => FieldValue::float(),
-                    FieldKind::Double => FieldValue::double(),
-                    FieldKind::Boolean => FieldValue::boolean(),
-                    FieldKind::Reference => FieldValue::reference(),
-                };
                 Rc::new(Field {
                     name: f.name.clone(),
-                    value: RefCell::new(default_val),
+                    access_flags: f.access_flags,
+                    attributes: f.attributes.clone(),
+                    field_type: f.field_type.clone(),
+                    value: RefCell::new(FieldValue::default_for(&f.field_type)),
                 })
             })
             .collect();
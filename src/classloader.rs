@@ -1,26 +1,41 @@
 pub mod attribute_parser;
 pub mod class_creator;
+pub mod class_source;
 pub mod constant_pool;
 pub mod cp_decoder;
 pub mod file_parser;
+pub mod format_checker;
+pub mod jar;
 pub mod opcode_parser;
 pub mod raw;
+pub mod verifier;
 
-use std::{borrow::Cow, path::Path, rc::Rc, usize};
+use std::{borrow::Cow, fmt, path::Path, rc::Rc, usize};
 
+use anyhow::Context;
 use enumflags2::BitFlags;
+use thiserror::Error;
 
 use self::{
     attribute_parser::parse_attributes, class_creator::create_bytecode_class,
-    file_parser::parse,
+    file_parser::parse_bytes, format_checker::check_format, raw::RawClassFile,
+    verifier::VerifyError,
 };
 use crate::{
     class::{
         access_flags::{ClassAccessFlag, FieldAccessFlag, MethodAccessFlag},
-        Class, ClassIdentifier,
+        attributes::{
+            Annotation, BootstrapMethod, ConstantValue, ElementValue,
+            EnclosingMethod, InnerClass, LineNumber, LocalVariable,
+            RecordComponent,
+        },
+        builtin_classes::{ClassLoaderClass, ClassObject},
+        class_identifier, ArgumentKind, ArrayName, Class, ClassIdentifier,
+        ClassInstance, ClassName, SimpleArgumentKind,
     },
     classloader::constant_pool::CpInfo,
-    heap::Heap,
+    executor::{frame_stack::StackValue, invoke_virtual, ReturnValue},
+    heap::{ClassLoaderId, Heap},
 };
 
 pub fn parse_class_identifier(name: &str) -> ClassIdentifier {
@@ -42,6 +57,39 @@ pub fn parse_class_identifier(name: &str) -> ClassIdentifier {
     }
 }
 
+/// Like [`parse_class_identifier`], but also accepts the names of array
+/// classes, which are their descriptors, e.g. `[Ljava/lang/String;` or `[[I`.
+pub fn parse_class_or_array_identifier(name: &str) -> ClassIdentifier {
+    let component = name.trim_start_matches('[');
+    let dimensions = name.len() - component.len();
+    if dimensions == 0 {
+        return parse_class_identifier(name);
+    }
+    let (package, name) = match component {
+        "B" => (Cow::from(&[][..]), ArrayName::Byte),
+        "C" => (Cow::from(&[][..]), ArrayName::Char),
+        "D" => (Cow::from(&[][..]), ArrayName::Double),
+        "F" => (Cow::from(&[][..]), ArrayName::Float),
+        "I" => (Cow::from(&[][..]), ArrayName::Int),
+        "J" => (Cow::from(&[][..]), ArrayName::Long),
+        "S" => (Cow::from(&[][..]), ArrayName::Short),
+        "Z" => (Cow::from(&[][..]), ArrayName::Boolean),
+        _ => {
+            let class_name = component
+                .strip_prefix('L')
+                .and_then(|c| c.strip_suffix(';'))
+                .unwrap_or(component);
+            let (package, name) =
+                parse_class_identifier(class_name).into_plain_identifier();
+            (package, ArrayName::Class(name))
+        },
+    };
+    ClassIdentifier {
+        package,
+        class_name: ClassName::Array { dimensions, name },
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum ReferenceKind {
     GetField = 1,
@@ -88,20 +136,94 @@ pub struct CodeAttribute {
     max_stack: u16,
     max_locals: u16,
     code: Vec<u8>,
+    /// Offset of `code` in the class file.
+    code_offset: usize,
     #[allow(dead_code)] // implementing Exceptions is later feature
     exception_table: Vec<ExceptionTable>,
-    #[allow(dead_code)] // optional features that may get implemented later
     attributes: Vec<AttributeInfo>,
 }
 
+/// The type of a local variable or operand stack entry
+/// in a stack map frame (JVMS §4.7.4).
+#[derive(Debug, Clone)]
+pub enum VerificationTypeInfo {
+    Top,
+    Integer,
+    Float,
+    Double,
+    Long,
+    Null,
+    UninitializedThis,
+    /// A class or array type, e.g. `java/lang/String` or `[I`.
+    Object(String),
+    /// An instance created by the `new` instruction at this code offset.
+    Uninitialized(u16),
+}
+
+/// An entry of the `StackMapTable` attribute (JVMS §4.7.4),
+/// i.e. the expected types at the start of an instruction.
+#[derive(Debug)]
+pub enum StackMapFrame {
+    /// Same locals as the previous frame and an empty stack.
+    Same { offset_delta: u16 },
+    /// Same locals as the previous frame and one stack entry.
+    SameLocals1StackItem {
+        offset_delta: u16,
+        stack: VerificationTypeInfo,
+    },
+    /// The previous locals without the last `count` ones
+    /// and an empty stack.
+    Chop { offset_delta: u16, count: u8 },
+    /// The previous locals followed by `locals` and an empty stack.
+    Append {
+        offset_delta: u16,
+        locals: Vec<VerificationTypeInfo>,
+    },
+    Full {
+        offset_delta: u16,
+        locals: Vec<VerificationTypeInfo>,
+        stack: Vec<VerificationTypeInfo>,
+    },
+}
+
+impl StackMapFrame {
+    /// The offset of the frame,
+    /// relative to the previous frame plus one (or 0 for the first frame).
+    pub fn offset_delta(&self) -> u16 {
+        match *self {
+            StackMapFrame::Same { offset_delta }
+            | StackMapFrame::SameLocals1StackItem { offset_delta, .. }
+            | StackMapFrame::Chop { offset_delta, .. }
+            | StackMapFrame::Append { offset_delta, .. }
+            | StackMapFrame::Full { offset_delta, .. } => offset_delta,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum AttributeInfo {
     Code(CodeAttribute),
-    SourceFile,
-    LineNumberTable,
-    LocalVariableTable,
+    SourceFile(String),
+    LineNumberTable(Vec<LineNumber>),
+    LocalVariableTable(Vec<LocalVariable>),
+    LocalVariableTypeTable(Vec<LocalVariable>),
     Exceptions(Vec<ClassIdentifier>),
-    StackMapTable,
+    StackMapTable(Vec<StackMapFrame>),
+    Signature(String),
+    InnerClasses(Vec<InnerClass>),
+    EnclosingMethod(EnclosingMethod),
+    BootstrapMethods(Vec<BootstrapMethod>),
+    ConstantValue(ConstantValue),
+    Deprecated,
+    NestHost(String),
+    NestMembers(Vec<String>),
+    Record(Vec<RecordComponent>),
+    PermittedSubclasses(Vec<String>),
+    RuntimeVisibleAnnotations(Vec<Annotation>),
+    RuntimeVisibleParameterAnnotations(Vec<Vec<Annotation>>),
+    AnnotationDefault(ElementValue),
+    /// An attribute, which isn't recognized and therefore ignored.
+    Unknown(String),
 }
 
 impl AttributeInfo {
@@ -112,6 +234,14 @@ impl AttributeInfo {
             None
         }
     }
+
+    pub fn as_stack_map_table(&self) -> Option<&[StackMapFrame]> {
+        if let AttributeInfo::StackMapTable(frames) = self {
+            Some(frames)
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -120,31 +250,35 @@ pub struct MethodInfo {
     name_index: u16,
     descriptor_index: u16,
     attributes: Vec<AttributeInfo>,
+    /// Offset of the structure in the class file.
+    offset: usize,
 }
 
-#[allow(dead_code)] // handling field type/access flags is an optional feature
 #[derive(Debug)]
 pub struct FieldInfo {
     access_flags: BitFlags<FieldAccessFlag>,
     name_index: u16,
     descriptor_index: u16,
     attributes: Vec<AttributeInfo>,
+    /// Offset of the structure in the class file.
+    offset: usize,
 }
 
 #[derive(Debug)]
 pub struct ClassFile {
+    minor_version: u16,
+    major_version: u16,
     constant_pool: Vec<CpInfo>,
-    #[allow(dead_code)] // handling access flags is an optional feature
+    /// Offsets of the constant pool entries in the class file.
+    constant_pool_offsets: Vec<usize>,
     access_flags: BitFlags<ClassAccessFlag>,
     this_class: u16,
-    #[allow(dead_code)] // inheritance is a later feature
+    /// Offset of `this_class` in the class file, followed by `super_class`.
+    this_class_offset: usize,
     super_class: u16,
-    #[allow(dead_code)] // inheritance is a later feature
     interfaces: Vec<u16>,
-    #[allow(dead_code)] // object creation is handled later
     fields: Vec<FieldInfo>,
     methods: Vec<MethodInfo>,
-    #[allow(dead_code)] // ?, not relevant for current feature set
     attributes: Vec<AttributeInfo>,
 }
 
@@ -157,18 +291,278 @@ impl ClassFile {
     }
 }
 
+/// Load the class file at `path_to_file`, defined by `loader`.
 pub fn load_class<P: AsRef<Path>>(
     path_to_file: P,
+    loader: ClassLoaderId,
+    heap: &mut Heap,
+) -> Result<Rc<dyn Class>, ClassLoadingError> {
+    let path = path_to_file.as_ref();
+    let content = std::fs::read(path)
+        .with_context(|| format!("{} can not be read", path.display()))?;
+    let raw_class = parse_bytes(&content)
+        .map_err(|e| e.in_file(path.display().to_string()))?;
+    define_class(raw_class, loader, heap)
+}
+
+/// Load a class from the content of a class file, defined by `loader`.
+pub fn load_class_from_bytes(
+    bytes: &[u8],
+    loader: ClassLoaderId,
+    heap: &mut Heap,
+) -> Result<Rc<dyn Class>, ClassLoadingError> {
+    let raw_class = parse_bytes(bytes)?;
+    define_class(raw_class, loader, heap)
+}
+
+/// A class file violating the class file format, e.g. a truncated one.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub struct ClassFormatError {
+    /// Name of the class file, if known.
+    pub file: Option<String>,
+    /// Offset of the malformed structure in the class file, if known.
+    pub offset: Option<usize>,
+    pub reason: String,
+}
+
+impl ClassFormatError {
+    pub fn new(reason: impl Into<String>) -> Self {
+        ClassFormatError {
+            file: None,
+            offset: None,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn at(offset: usize, reason: impl Into<String>) -> Self {
+        ClassFormatError {
+            offset: Some(offset),
+            ..Self::new(reason)
+        }
+    }
+
+    /// Set the name of the class file, unless it is already known.
+    pub fn in_file(mut self, file: impl Into<String>) -> Self {
+        self.file.get_or_insert_with(|| file.into());
+        self
+    }
+}
+
+impl fmt::Display for ClassFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}: ", file)?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, "offset {}: ", offset)?;
+        }
+        write!(f, "{}", self.reason)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ClassLoadingError {
+    #[error("class {0} not found")]
+    NotFound(String),
+    #[error(transparent)]
+    Format(#[from] ClassFormatError),
+    #[error(transparent)]
+    Verify(#[from] VerifyError),
+    #[error(transparent)]
+    Invalid(#[from] anyhow::Error),
+    /// Thrown by Java code of a class loader.
+    #[error("exception while loading class: {0:?}")]
+    Exception(Rc<dyn ClassInstance>),
+}
+
+/// Load the class `name` (e.g. `org/example/Main`) with `loader`
+/// as initiating loader, i.e. like the JVM resolves a class
+/// referenced by a class defined by `loader`.
+///
+/// If `loader` has been created by Java code,
+/// which overrides `loadClass`, it is called to load the class.
+/// Otherwise, the class is loaded by [`delegate_class_loading`].
+pub fn load_class_by_name(
+    name: &str,
+    loader: ClassLoaderId,
     heap: &mut Heap,
-) -> Rc<dyn Class> {
-    let raw_class = parse(path_to_file).unwrap();
-    let class = parse_attributes(raw_class);
-    let bytecode_class: Rc<dyn Class> =
-        Rc::new(create_bytecode_class(&class, heap));
-
-    heap.add_class(
-        bytecode_class.class_identifier().clone(),
-        bytecode_class.clone(),
+) -> Result<Rc<dyn Class>, ClassLoadingError> {
+    if let Some(class) = heap.find_class(loader, &parse_class_identifier(name))
+    {
+        return Ok(class.clone());
+    }
+    // cyclic reference: `name` is loaded as soon as its dependencies are
+    if heap.is_loading(loader, name) {
+        return Err(ClassLoadingError::NotFound(name.to_string()));
+    }
+
+    let class = match overridden_loader_method(heap, loader, "loadClass") {
+        Some(instance) => {
+            invoke_loader_method(heap, instance, "loadClass", name)?
+        },
+        None => delegate_class_loading(name, loader, heap)?,
+    };
+    heap.add_class(loader, class.clone());
+    Ok(class)
+}
+
+/// The default implementation of `ClassLoader.loadClass`:
+/// Load the class `name` with the parent of `loader`
+/// and, if the parent can't find it, with `loader` itself
+/// (from its class sources or `findClass` overridden by Java code).
+pub fn delegate_class_loading(
+    name: &str,
+    loader: ClassLoaderId,
+    heap: &mut Heap,
+) -> Result<Rc<dyn Class>, ClassLoadingError> {
+    if let Some(class) = heap.find_class(loader, &parse_class_identifier(name))
+    {
+        return Ok(class.clone());
+    }
+    if let Some(parent) = heap.class_loader_parent(loader) {
+        match load_class_by_name(name, parent, heap) {
+            Err(ClassLoadingError::NotFound(_)) => (),
+            result => return result,
+        }
+    }
+
+    let mut content = None;
+    for source in heap.class_sources(loader) {
+        content = source.read_class(name)?;
+        if content.is_some() {
+            break;
+        }
+    }
+    if let Some(content) = content {
+        let raw_class = parse_bytes(&content)
+            .map_err(|e| e.in_file(format!("{}.class", name)))?;
+        return define_class(raw_class, loader, heap);
+    }
+
+    match overridden_loader_method(heap, loader, "findClass") {
+        Some(instance) => {
+            invoke_loader_method(heap, instance, "findClass", name)
+        },
+        None => Err(ClassLoadingError::NotFound(name.to_string())),
+    }
+}
+
+/// Returns the Java instance of `loader`,
+/// if its class overrides the `ClassLoader` method `name`.
+fn overridden_loader_method(
+    heap: &Heap,
+    loader: ClassLoaderId,
+    name: &str,
+) -> Option<Rc<dyn ClassInstance>> {
+    let instance = heap.class_loader_instance(loader)?;
+    let (parameters, return_type) = loader_method_descriptor();
+    let (_, defining_class) = instance.class().find_method(
+        name,
+        (&parameters, Some(&return_type)),
+        true,
+    )?;
+    if defining_class.as_any().is::<ClassLoaderClass>() {
+        None
+    } else {
+        Some(instance)
+    }
+}
+
+/// The descriptor of `loadClass` and `findClass`:
+/// `(Ljava/lang/String;)Ljava/lang/Class;`
+fn loader_method_descriptor() -> (Vec<ArgumentKind>, ArgumentKind) {
+    (
+        vec![ArgumentKind::Simple(SimpleArgumentKind::Class(
+            "java/lang/String".to_string(),
+        ))],
+        ArgumentKind::Simple(SimpleArgumentKind::Class(
+            "java/lang/Class".to_string(),
+        )),
+    )
+}
+
+/// Call the `ClassLoader` method `name` (`loadClass` or `findClass`)
+/// on `instance` to load the class `class_name`.
+fn invoke_loader_method(
+    heap: &mut Heap,
+    instance: Rc<dyn ClassInstance>,
+    name: &str,
+    class_name: &str,
+) -> Result<Rc<dyn Class>, ClassLoadingError> {
+    let binary_name: Rc<dyn ClassInstance> =
+        Rc::new(heap.new_string(class_name.replace('/', ".")));
+    let not_found = || ClassLoadingError::NotFound(class_name.to_string());
+
+    let (parameters, return_type) = loader_method_descriptor();
+    match invoke_virtual(
+        heap,
+        instance,
+        name,
+        (&parameters, Some(&return_type)),
+        vec![StackValue::Reference(Some(binary_name))],
+    ) {
+        Ok(ReturnValue::Reference(Some(class))) => class
+            .as_any()
+            .downcast_ref::<ClassObject>()
+            .map(|class| class.represented.clone())
+            .ok_or_else(not_found),
+        Ok(_) => Err(not_found()),
+        Err(e) if is_class_not_found(heap, &e) => Err(not_found()),
+        Err(e) => Err(ClassLoadingError::Exception(e)),
+    }
+}
+
+fn is_class_not_found(heap: &Heap, e: &Rc<dyn ClassInstance>) -> bool {
+    let class_not_found = heap
+        .find_class(
+            ClassLoaderId::BOOTSTRAP,
+            &class_identifier!(java / lang, ClassNotFoundException),
+        )
+        .expect("ClassNotFoundException is a builtin class");
+    e.class().is_same_class(class_not_found.as_ref())
+        || e.class().is_sub_class_of(class_not_found)
+}
+
+/// Create the class described by `raw_class`, defined by `loader`,
+/// and add it to `heap`.
+///
+/// The class file is checked by [`check_format`] first.
+/// Since some references are resolved when a class is created,
+/// the classes referenced by `raw_class` are loaded first
+/// (as far as `loader` can find them).
+pub fn define_class(
+    raw_class: RawClassFile,
+    loader: ClassLoaderId,
+    heap: &mut Heap,
+) -> Result<Rc<dyn Class>, ClassLoadingError> {
+    let name = raw_class
+        .class_name()
+        .ok_or_else(|| ClassFormatError::new("invalid this_class"))?
+        .to_string();
+
+    let referenced_classes = raw_class.referenced_classes();
+    let class = parse_attributes(raw_class)
+        .and_then(|class| check_format(&class).map(|_| class))
+        .map_err(|e| e.in_file(format!("{}.class", name)))?;
+
+    heap.begin_loading(loader, &name);
+    let dependencies = referenced_classes.iter().try_for_each(|dependency| {
+        match load_class_by_name(dependency, loader, heap) {
+            Ok(_) | Err(ClassLoadingError::NotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    });
+    heap.end_loading(loader, &name);
+    dependencies?;
+
+    let bytecode_class: Rc<dyn Class> = Rc::new(
+        create_bytecode_class(&class, loader, heap).map_err(|e| match e {
+            ClassLoadingError::Format(e) => {
+                ClassLoadingError::Format(e.in_file(format!("{}.class", name)))
+            },
+            e => e,
+        })?,
     );
-    bytecode_class
+    heap.add_class(loader, bytecode_class.clone());
+    Ok(bytecode_class)
 }
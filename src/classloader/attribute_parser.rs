@@ -1,38 +1,53 @@
 use nom::{
+    combinator::map,
     multi::length_count,
     number::complete::{be_u16, be_u32, be_u8},
-    IResult,
 };
 
 use crate::{
-    class::ClassIdentifier,
+    class::{
+        attributes::{
+            Annotation, BootstrapMethod, ConstantValue, ElementValue,
+            EnclosingMethod, InnerClass, LineNumber, LocalVariable,
+            RecordComponent,
+        },
+        ClassIdentifier,
+    },
     classloader::{
-        file_parser::parse_attribute_info as parse_raw_attribute_info,
+        constant_pool::{get_class_name, get_entry, get_utf8, CpInfo},
+        file_parser::{
+            parse_access_flags,
+            parse_attribute_info as parse_raw_attribute_info, ParseError,
+            ParseResult,
+        },
         parse_class_identifier,
         raw::{RawAttributeInfo, RawClassFile},
-        AttributeInfo, ClassFile, CodeAttribute, CpInfo, ExceptionTable,
-        FieldInfo, MethodInfo,
+        AttributeInfo, ClassFile, ClassFormatError, CodeAttribute,
+        ExceptionTable, FieldInfo, MethodInfo, StackMapFrame,
+        VerificationTypeInfo,
     },
 };
 
-fn parse_attribute_info<'a, 'p, T, G, P>(
-    raw_attribute: &'p RawAttributeInfo,
-    raw_class_file: &'p RawClassFile,
-    parse: G,
-) -> T
+fn parse_attribute_info<'a, T, P>(
+    raw_attribute: &'a RawAttributeInfo,
+    parse: P,
+) -> Result<T, ClassFormatError>
 where
-    P: Fn(&'a [u8]) -> IResult<&'a [u8], T>,
-    G: Fn(&'p RawAttributeInfo, &'p RawClassFile) -> P,
-    'p: 'a,
+    P: Fn(&'a [u8]) -> ParseResult<'a, T>,
 {
-    parse(raw_attribute, raw_class_file)(raw_attribute.info.as_ref())
-        .unwrap()
-        .1
+    let info = raw_attribute.info.as_slice();
+    parse(info).map(|(_, value)| value).map_err(|e| {
+        ParseError::into_class_format_error(
+            e,
+            info,
+            raw_attribute.info_offset(),
+        )
+    })
 }
 
 fn parse_exception_table(
     current_content: &[u8],
-) -> IResult<&[u8], ExceptionTable> {
+) -> ParseResult<ExceptionTable> {
     let (current_content, start_pc) = be_u16(current_content)?;
     let (current_content, end_pc) = be_u16(current_content)?;
     let (current_content, handler_pc) = be_u16(current_content)?;
@@ -48,142 +63,748 @@ fn parse_exception_table(
     ))
 }
 
-fn parse_code_attribute<'a>(
-    _raw_attribute: &'a RawAttributeInfo,
-    raw_class_file: &'a RawClassFile,
-) -> impl Fn(&[u8]) -> IResult<&[u8], CodeAttribute> + 'a {
+fn parse_code_attribute(
+    raw_attribute: &RawAttributeInfo,
+    raw_class_file: &RawClassFile,
+) -> Result<CodeAttribute, ClassFormatError> {
+    let info = raw_attribute.info.as_slice();
+    let (max_stack, max_locals, codes, exception_table, raw_attributes) =
+        parse_attribute_info(raw_attribute, |current_content| {
+            let (current_content, max_stack) = be_u16(current_content)?;
+            let (current_content, max_locals) = be_u16(current_content)?;
+            let (current_content, codes) =
+                length_count(be_u32, be_u8)(current_content)?;
+            let (current_content, exception_table) =
+                length_count(be_u16, parse_exception_table)(current_content)?;
+            let (current_content, raw_attributes) =
+                length_count(be_u16, |c| {
+                    parse_raw_attribute_info(
+                        info,
+                        raw_attribute.info_offset(),
+                        c,
+                    )
+                })(current_content)?;
+            Ok((
+                current_content,
+                (
+                    max_stack,
+                    max_locals,
+                    codes,
+                    exception_table,
+                    raw_attributes,
+                ),
+            ))
+        })?;
+    let attributes = parse_attribute_list(&raw_attributes, raw_class_file)?;
+    Ok(CodeAttribute {
+        max_stack: (max_stack),
+        max_locals: (max_locals),
+        code: (codes),
+        // after max_stack, max_locals and code_length
+        code_offset: raw_attribute.info_offset() + 8,
+        exception_table: (exception_table),
+        attributes: (attributes),
+    })
+}
+
+fn parse_exception_index_table_entry(
+    raw_class_file: &RawClassFile,
+) -> impl Fn(&[u8]) -> ParseResult<ClassIdentifier> + '_ {
     move |current_content: &[u8]| {
-        let (current_content, max_stack) = be_u16(current_content)?;
-        let (current_content, max_locals) = be_u16(current_content)?;
-        let (current_content, codes) =
-            length_count(be_u32, be_u8)(current_content)?;
-        let (current_content, exception_table) =
-            length_count(be_u16, parse_exception_table)(current_content)?;
-        let (current_content, raw_attributes) =
-            length_count(be_u16, parse_raw_attribute_info)(current_content)?;
-        let attributes = raw_attributes
-            .into_iter()
-            .map(|a| parse_attribute(&a, raw_class_file))
-            .collect();
-        Ok((
+        let index_content = current_content;
+        let (current_content, index) = be_u16(current_content)?;
+        // the offset is determined by the input of the parse error
+        match get_class_name(&raw_class_file.constant_pool, index, 0) {
+            Ok(class_name) => {
+                Ok((current_content, parse_class_identifier(class_name)))
+            },
+            Err(e) => ParseError::fail(index_content, e.reason),
+        }
+    }
+}
+
+fn parse_exceptions_attribute(
+    raw_attribute: &RawAttributeInfo,
+    raw_class_file: &RawClassFile,
+) -> Result<Vec<ClassIdentifier>, ClassFormatError> {
+    parse_attribute_info(raw_attribute, |current_content| {
+        length_count(be_u16, parse_exception_index_table_entry(raw_class_file))(
             current_content,
-            CodeAttribute {
-                max_stack: (max_stack),
-                max_locals: (max_locals),
-                code: (codes),
-                exception_table: (exception_table),
-                attributes: (attributes),
+        )
+    })
+}
+
+fn parse_verification_type_info(
+    raw_class_file: &RawClassFile,
+) -> impl Fn(&[u8]) -> ParseResult<VerificationTypeInfo> + '_ {
+    move |current_content: &[u8]| {
+        let tag_content = current_content;
+        let (current_content, tag) = be_u8(current_content)?;
+        match tag {
+            0 => Ok((current_content, VerificationTypeInfo::Top)),
+            1 => Ok((current_content, VerificationTypeInfo::Integer)),
+            2 => Ok((current_content, VerificationTypeInfo::Float)),
+            3 => Ok((current_content, VerificationTypeInfo::Double)),
+            4 => Ok((current_content, VerificationTypeInfo::Long)),
+            5 => Ok((current_content, VerificationTypeInfo::Null)),
+            6 => Ok((current_content, VerificationTypeInfo::UninitializedThis)),
+            7 => {
+                let (current_content, index) = be_u16(current_content)?;
+                // the offset is determined by the input of the parse error
+                match get_class_name(&raw_class_file.constant_pool, index, 0) {
+                    Ok(class_name) => Ok((
+                        current_content,
+                        VerificationTypeInfo::Object(class_name.to_string()),
+                    )),
+                    Err(e) => ParseError::fail(tag_content, e.reason),
+                }
             },
-        ))
+            8 => {
+                let (current_content, offset) = be_u16(current_content)?;
+                Ok((
+                    current_content,
+                    VerificationTypeInfo::Uninitialized(offset),
+                ))
+            },
+            _ => ParseError::fail(
+                tag_content,
+                format!("invalid verification type tag {}", tag),
+            ),
+        }
     }
 }
 
-fn parse_exception_index_table_entry(
+fn parse_stack_map_frame(
     raw_class_file: &RawClassFile,
-) -> impl Fn(&[u8]) -> IResult<&[u8], ClassIdentifier> + '_ {
+) -> impl Fn(&[u8]) -> ParseResult<StackMapFrame> + '_ {
     move |current_content: &[u8]| {
+        let frame_content = current_content;
+        let type_info = parse_verification_type_info(raw_class_file);
+        let (current_content, frame_type) = be_u8(current_content)?;
+        match frame_type {
+            0..=63 => Ok((
+                current_content,
+                StackMapFrame::Same {
+                    offset_delta: frame_type.into(),
+                },
+            )),
+            64..=127 => {
+                let (current_content, stack) = type_info(current_content)?;
+                Ok((
+                    current_content,
+                    StackMapFrame::SameLocals1StackItem {
+                        offset_delta: (frame_type - 64).into(),
+                        stack,
+                    },
+                ))
+            },
+            247 => {
+                let (current_content, offset_delta) = be_u16(current_content)?;
+                let (current_content, stack) = type_info(current_content)?;
+                Ok((
+                    current_content,
+                    StackMapFrame::SameLocals1StackItem {
+                        offset_delta,
+                        stack,
+                    },
+                ))
+            },
+            248..=250 => {
+                let (current_content, offset_delta) = be_u16(current_content)?;
+                Ok((
+                    current_content,
+                    StackMapFrame::Chop {
+                        offset_delta,
+                        count: 251 - frame_type,
+                    },
+                ))
+            },
+            251 => {
+                let (current_content, offset_delta) = be_u16(current_content)?;
+                Ok((current_content, StackMapFrame::Same { offset_delta }))
+            },
+            252..=254 => {
+                let (mut current_content, offset_delta) =
+                    be_u16(current_content)?;
+                let mut locals = Vec::new();
+                for _ in 251..frame_type {
+                    let local;
+                    (current_content, local) = type_info(current_content)?;
+                    locals.push(local);
+                }
+                Ok((
+                    current_content,
+                    StackMapFrame::Append {
+                        offset_delta,
+                        locals,
+                    },
+                ))
+            },
+            255 => {
+                let (current_content, offset_delta) = be_u16(current_content)?;
+                let (current_content, locals) =
+                    length_count(be_u16, &type_info)(current_content)?;
+                let (current_content, stack) =
+                    length_count(be_u16, &type_info)(current_content)?;
+                Ok((
+                    current_content,
+                    StackMapFrame::Full {
+                        offset_delta,
+                        locals,
+                        stack,
+                    },
+                ))
+            },
+            _ => ParseError::fail(
+                frame_content,
+                format!("invalid stack map frame type {}", frame_type),
+            ),
+        }
+    }
+}
+
+fn parse_stack_map_table_attribute(
+    raw_attribute: &RawAttributeInfo,
+    raw_class_file: &RawClassFile,
+) -> Result<Vec<StackMapFrame>, ClassFormatError> {
+    parse_attribute_info(raw_attribute, |current_content| {
+        length_count(be_u16, parse_stack_map_frame(raw_class_file))(
+            current_content,
+        )
+    })
+}
+
+/// Returns a parser of a constant pool index, resolved by `get`,
+/// e.g. [`get_utf8`].
+fn cp_entry<'a, 'c, T: 'c>(
+    raw_class_file: &'c RawClassFile,
+    get: fn(&'c [CpInfo], u16, usize) -> Result<T, ClassFormatError>,
+) -> impl Fn(&'a [u8]) -> ParseResult<'a, T> + 'c {
+    move |current_content: &[u8]| {
+        let index_content = current_content;
+        let (current_content, index) = be_u16(current_content)?;
+        // the offset is determined by the input of the parse error
+        match get(&raw_class_file.constant_pool, index, 0) {
+            Ok(value) => Ok((current_content, value)),
+            Err(e) => ParseError::fail(index_content, e.reason),
+        }
+    }
+}
+
+/// Like [`cp_entry`], but the index 0 results in `None`.
+fn optional_cp_entry<'a, 'c, T: 'c>(
+    raw_class_file: &'c RawClassFile,
+    get: fn(&'c [CpInfo], u16, usize) -> Result<T, ClassFormatError>,
+) -> impl Fn(&'a [u8]) -> ParseResult<'a, Option<T>> + 'c {
+    move |current_content: &[u8]| match be_u16(current_content)? {
+        (current_content, 0) => Ok((current_content, None)),
+        _ => {
+            let (current_content, value) =
+                cp_entry(raw_class_file, get)(current_content)?;
+            Ok((current_content, Some(value)))
+        },
+    }
+}
+
+/// Returns a parser of the index of a `Utf8` constant pool entry.
+fn utf8<'a, 'c>(
+    raw_class_file: &'c RawClassFile,
+) -> impl Fn(&'a [u8]) -> ParseResult<'a, String> + 'c {
+    move |current_content: &[u8]| {
+        let (current_content, value) =
+            cp_entry(raw_class_file, get_utf8)(current_content)?;
+        Ok((current_content, value.to_string()))
+    }
+}
+
+/// Returns a parser of the index of a `Class` constant pool entry.
+fn class_name<'a, 'c>(
+    raw_class_file: &'c RawClassFile,
+) -> impl Fn(&'a [u8]) -> ParseResult<'a, String> + 'c {
+    move |current_content: &[u8]| {
+        let (current_content, value) =
+            cp_entry(raw_class_file, get_class_name)(current_content)?;
+        Ok((current_content, value.to_string()))
+    }
+}
+
+fn parse_class_list_attribute(
+    raw_attribute: &RawAttributeInfo,
+    raw_class_file: &RawClassFile,
+) -> Result<Vec<String>, ClassFormatError> {
+    parse_attribute_info(raw_attribute, |current_content| {
+        length_count(be_u16, class_name(raw_class_file))(current_content)
+    })
+}
+
+fn parse_line_number(current_content: &[u8]) -> ParseResult<LineNumber> {
+    let (current_content, start_pc) = be_u16(current_content)?;
+    let (current_content, line_number) = be_u16(current_content)?;
+    Ok((
+        current_content,
+        LineNumber {
+            start_pc,
+            line_number,
+        },
+    ))
+}
+
+fn parse_local_variable(
+    raw_class_file: &RawClassFile,
+) -> impl Fn(&[u8]) -> ParseResult<LocalVariable> + '_ {
+    move |current_content: &[u8]| {
+        let (current_content, start_pc) = be_u16(current_content)?;
+        let (current_content, length) = be_u16(current_content)?;
+        let (current_content, name) = utf8(raw_class_file)(current_content)?;
+        let (current_content, descriptor) =
+            utf8(raw_class_file)(current_content)?;
         let (current_content, index) = be_u16(current_content)?;
-        let cp_entry =
-            raw_class_file.get_java_cp_entry(index as usize).unwrap();
-        let name_index = cp_entry.as_class_info().unwrap();
-        let cp_entry = raw_class_file
-            .get_java_cp_entry(name_index as usize)
-            .unwrap();
-        let class_name = cp_entry.as_utf8_info().unwrap();
-        let identifier = parse_class_identifier(class_name);
-        Ok((current_content, identifier))
+        Ok((
+            current_content,
+            LocalVariable {
+                start_pc,
+                length,
+                name,
+                descriptor,
+                index,
+            },
+        ))
     }
 }
 
-fn parse_exceptions_attribute<'a>(
-    _raw_attribute: &'a RawAttributeInfo,
-    raw_class_file: &'a RawClassFile,
-) -> impl Fn(&[u8]) -> IResult<&[u8], Vec<ClassIdentifier>> + 'a {
+fn parse_local_variable_table_attribute(
+    raw_attribute: &RawAttributeInfo,
+    raw_class_file: &RawClassFile,
+) -> Result<Vec<LocalVariable>, ClassFormatError> {
+    parse_attribute_info(raw_attribute, |current_content| {
+        length_count(be_u16, parse_local_variable(raw_class_file))(
+            current_content,
+        )
+    })
+}
+
+fn parse_inner_class(
+    raw_class_file: &RawClassFile,
+) -> impl Fn(&[u8]) -> ParseResult<InnerClass> + '_ {
     move |current_content: &[u8]| {
-        let (current_content, classes) = length_count(
-            be_u16,
-            parse_exception_index_table_entry(raw_class_file),
+        let (current_content, inner_class) =
+            class_name(raw_class_file)(current_content)?;
+        let (current_content, outer_class) =
+            optional_cp_entry(raw_class_file, get_class_name)(current_content)?;
+        let (current_content, inner_name) =
+            optional_cp_entry(raw_class_file, get_utf8)(current_content)?;
+        let (current_content, access_flags) =
+            parse_access_flags(current_content)?;
+        Ok((
+            current_content,
+            InnerClass {
+                inner_class,
+                outer_class: outer_class.map(str::to_string),
+                inner_name: inner_name.map(str::to_string),
+                access_flags,
+            },
+        ))
+    }
+}
+
+fn parse_enclosing_method_attribute(
+    raw_attribute: &RawAttributeInfo,
+    raw_class_file: &RawClassFile,
+) -> Result<EnclosingMethod, ClassFormatError> {
+    parse_attribute_info(raw_attribute, |current_content| {
+        let (current_content, class) =
+            class_name(raw_class_file)(current_content)?;
+        let (current_content, method) = optional_cp_entry(
+            raw_class_file,
+            |constant_pool, index, offset| {
+                let (name_index, descriptor_index) = get_entry(
+                    constant_pool,
+                    index,
+                    offset,
+                    "NameAndType",
+                    CpInfo::as_name_and_type_info,
+                )?;
+                Ok((
+                    get_utf8(constant_pool, name_index, offset)?.to_string(),
+                    get_utf8(constant_pool, descriptor_index, offset)?
+                        .to_string(),
+                ))
+            },
         )(current_content)?;
-        Ok((current_content, classes))
+        Ok((current_content, EnclosingMethod { class, method }))
+    })
+}
+
+fn parse_bootstrap_method(
+    current_content: &[u8],
+) -> ParseResult<BootstrapMethod> {
+    let (current_content, method_handle) = be_u16(current_content)?;
+    let (current_content, arguments) =
+        length_count(be_u16, be_u16)(current_content)?;
+    Ok((
+        current_content,
+        BootstrapMethod {
+            method_handle,
+            arguments,
+        },
+    ))
+}
+
+fn parse_constant_value_attribute(
+    raw_attribute: &RawAttributeInfo,
+    raw_class_file: &RawClassFile,
+) -> Result<ConstantValue, ClassFormatError> {
+    parse_attribute_info(raw_attribute, |current_content| {
+        cp_entry(raw_class_file, |constant_pool, index, offset| {
+            // the value of a String entry is looked up in turn
+            get_entry(
+                constant_pool,
+                index,
+                offset,
+                "constant value",
+                |entry| match entry {
+                    CpInfo::IntegerInfo(value) => {
+                        Some(Ok(ConstantValue::Int(*value)))
+                    },
+                    CpInfo::FloatInfo(value) => {
+                        Some(Ok(ConstantValue::Float(*value)))
+                    },
+                    CpInfo::LongInfo(value) => {
+                        Some(Ok(ConstantValue::Long(*value)))
+                    },
+                    CpInfo::DoubleInfo(value) => {
+                        Some(Ok(ConstantValue::Double(*value)))
+                    },
+                    CpInfo::StringInfo { string_index } => Some(
+                        get_utf8(constant_pool, *string_index, offset)
+                            .map(|value| ConstantValue::String(value.into())),
+                    ),
+                    _ => None,
+                },
+            )?
+        })(current_content)
+    })
+}
+
+fn parse_record_attribute(
+    raw_attribute: &RawAttributeInfo,
+    raw_class_file: &RawClassFile,
+) -> Result<Vec<RecordComponent>, ClassFormatError> {
+    let info = raw_attribute.info.as_slice();
+    let raw_components =
+        parse_attribute_info(raw_attribute, |current_content| {
+            length_count(be_u16, |current_content| {
+                let (current_content, name) =
+                    utf8(raw_class_file)(current_content)?;
+                let (current_content, descriptor) =
+                    utf8(raw_class_file)(current_content)?;
+                let (current_content, raw_attributes) =
+                    length_count(be_u16, |c| {
+                        parse_raw_attribute_info(
+                            info,
+                            raw_attribute.info_offset(),
+                            c,
+                        )
+                    })(current_content)?;
+                Ok((current_content, (name, descriptor, raw_attributes)))
+            })(current_content)
+        })?;
+    raw_components
+        .into_iter()
+        .map(|(name, descriptor, raw_attributes)| {
+            let attributes =
+                parse_attribute_list(&raw_attributes, raw_class_file)?;
+            let signature =
+                attributes
+                    .into_iter()
+                    .find_map(|attribute| match attribute {
+                        AttributeInfo::Signature(signature) => Some(signature),
+                        _ => None,
+                    });
+            Ok(RecordComponent {
+                name,
+                descriptor,
+                signature,
+            })
+        })
+        .collect()
+}
+
+/// Returns a parser of the index of a numeric constant pool entry,
+/// resolved by `as_kind`, e.g. [`CpInfo::as_integer_info`].
+fn numeric_constant<'a, 'c, T: 'c>(
+    raw_class_file: &'c RawClassFile,
+    kind: &'static str,
+    as_kind: fn(&CpInfo) -> Option<T>,
+) -> impl Fn(&'a [u8]) -> ParseResult<'a, T> + 'c {
+    move |current_content: &[u8]| {
+        let index_content = current_content;
+        let (current_content, index) = be_u16(current_content)?;
+        // the offset is determined by the input of the parse error
+        match get_entry(&raw_class_file.constant_pool, index, 0, kind, as_kind)
+        {
+            Ok(value) => Ok((current_content, value)),
+            Err(e) => ParseError::fail(index_content, e.reason),
+        }
     }
 }
 
+fn parse_element_value<'a>(
+    raw_class_file: &RawClassFile,
+    current_content: &'a [u8],
+) -> ParseResult<'a, ElementValue> {
+    let tag_content = current_content;
+    let (current_content, tag) = be_u8(current_content)?;
+    let int =
+        numeric_constant(raw_class_file, "Integer", CpInfo::as_integer_info);
+    // narrower constants are stored as Integer entries and truncated
+    match tag {
+        b'B' => {
+            map(int, |value| ElementValue::Byte(value as i8))(current_content)
+        },
+        b'C' => {
+            map(int, |value| ElementValue::Char(value as u16))(current_content)
+        },
+        b'I' => map(int, ElementValue::Int)(current_content),
+        b'S' => {
+            map(int, |value| ElementValue::Short(value as i16))(current_content)
+        },
+        b'Z' => {
+            map(int, |value| ElementValue::Boolean(value != 0))(current_content)
+        },
+        b'D' => map(
+            numeric_constant(raw_class_file, "Double", CpInfo::as_double_info),
+            ElementValue::Double,
+        )(current_content),
+        b'F' => map(
+            numeric_constant(raw_class_file, "Float", CpInfo::as_float_info),
+            ElementValue::Float,
+        )(current_content),
+        b'J' => map(
+            numeric_constant(raw_class_file, "Long", CpInfo::as_long_info),
+            ElementValue::Long,
+        )(current_content),
+        b's' => {
+            map(utf8(raw_class_file), ElementValue::String)(current_content)
+        },
+        b'e' => {
+            let (current_content, type_name) =
+                utf8(raw_class_file)(current_content)?;
+            let (current_content, const_name) =
+                utf8(raw_class_file)(current_content)?;
+            Ok((
+                current_content,
+                ElementValue::Enum {
+                    type_name,
+                    const_name,
+                },
+            ))
+        },
+        b'c' => map(utf8(raw_class_file), ElementValue::Class)(current_content),
+        b'@' => map(
+            |c| parse_annotation(raw_class_file, c),
+            ElementValue::Annotation,
+        )(current_content),
+        b'[' => map(
+            length_count(be_u16, |c| parse_element_value(raw_class_file, c)),
+            ElementValue::Array,
+        )(current_content),
+        _ => ParseError::fail(
+            tag_content,
+            format!("invalid element value tag {}", tag),
+        ),
+    }
+}
+
+fn parse_annotation<'a>(
+    raw_class_file: &RawClassFile,
+    current_content: &'a [u8],
+) -> ParseResult<'a, Annotation> {
+    let (current_content, type_name) = utf8(raw_class_file)(current_content)?;
+    let (current_content, elements) = length_count(be_u16, |c| {
+        let (c, name) = utf8(raw_class_file)(c)?;
+        let (c, value) = parse_element_value(raw_class_file, c)?;
+        Ok((c, (name, value)))
+    })(current_content)?;
+    Ok((
+        current_content,
+        Annotation {
+            type_name,
+            elements,
+        },
+    ))
+}
+
+fn parse_annotations<'a>(
+    raw_class_file: &RawClassFile,
+    current_content: &'a [u8],
+) -> ParseResult<'a, Vec<Annotation>> {
+    length_count(be_u16, |c| parse_annotation(raw_class_file, c))(
+        current_content,
+    )
+}
+
 fn parse_attribute(
     raw_attribute: &RawAttributeInfo,
     raw_class_file: &RawClassFile,
-) -> AttributeInfo {
-    let name = raw_class_file
-        .get_java_cp_entry(Into::<usize>::into(
-            raw_attribute.attribute_name_index,
-        ))
-        .expect("Attribute name index is valid");
-    let name = if let CpInfo::UTF8INFO(name) = name {
-        name.as_ref()
-    } else {
-        panic!("Attribute name index does not point to UTF8Info")
-    };
-    match name {
-        "Code" => AttributeInfo::Code(parse_attribute_info(
+) -> Result<AttributeInfo, ClassFormatError> {
+    let name = get_utf8(
+        &raw_class_file.constant_pool,
+        raw_attribute.attribute_name_index,
+        raw_attribute.offset,
+    )?;
+    Ok(match name {
+        "Code" => AttributeInfo::Code(parse_code_attribute(
             raw_attribute,
             raw_class_file,
-            parse_code_attribute,
-        )),
-        "SourceFile" => AttributeInfo::SourceFile,
-        "LineNumberTable" => AttributeInfo::LineNumberTable,
-        "LocalVariableTable" => AttributeInfo::LocalVariableTable,
-        "Exceptions" => AttributeInfo::Exceptions(parse_attribute_info(
+        )?),
+        "SourceFile" => AttributeInfo::SourceFile(parse_attribute_info(
+            raw_attribute,
+            utf8(raw_class_file),
+        )?),
+        "LineNumberTable" => AttributeInfo::LineNumberTable(
+            parse_attribute_info(raw_attribute, |current_content| {
+                length_count(be_u16, parse_line_number)(current_content)
+            })?,
+        ),
+        "LocalVariableTable" => AttributeInfo::LocalVariableTable(
+            parse_local_variable_table_attribute(
+                raw_attribute,
+                raw_class_file,
+            )?,
+        ),
+        "LocalVariableTypeTable" => AttributeInfo::LocalVariableTypeTable(
+            parse_local_variable_table_attribute(
+                raw_attribute,
+                raw_class_file,
+            )?,
+        ),
+        "Exceptions" => AttributeInfo::Exceptions(parse_exceptions_attribute(
             raw_attribute,
             raw_class_file,
-            parse_exceptions_attribute,
-        )),
-        "StackMapTable" => AttributeInfo::StackMapTable,
-        _ => panic!("Unknown Attribute {}", name),
-    }
+        )?),
+        "StackMapTable" => AttributeInfo::StackMapTable(
+            parse_stack_map_table_attribute(raw_attribute, raw_class_file)?,
+        ),
+        "Signature" => AttributeInfo::Signature(parse_attribute_info(
+            raw_attribute,
+            utf8(raw_class_file),
+        )?),
+        "InnerClasses" => AttributeInfo::InnerClasses(parse_attribute_info(
+            raw_attribute,
+            |current_content| {
+                length_count(be_u16, parse_inner_class(raw_class_file))(
+                    current_content,
+                )
+            },
+        )?),
+        "EnclosingMethod" => AttributeInfo::EnclosingMethod(
+            parse_enclosing_method_attribute(raw_attribute, raw_class_file)?,
+        ),
+        "BootstrapMethods" => AttributeInfo::BootstrapMethods(
+            parse_attribute_info(raw_attribute, |current_content| {
+                length_count(be_u16, parse_bootstrap_method)(current_content)
+            })?,
+        ),
+        "ConstantValue" => AttributeInfo::ConstantValue(
+            parse_constant_value_attribute(raw_attribute, raw_class_file)?,
+        ),
+        "Deprecated" => AttributeInfo::Deprecated,
+        "NestHost" => AttributeInfo::NestHost(parse_attribute_info(
+            raw_attribute,
+            class_name(raw_class_file),
+        )?),
+        "NestMembers" => AttributeInfo::NestMembers(
+            parse_class_list_attribute(raw_attribute, raw_class_file)?,
+        ),
+        "Record" => AttributeInfo::Record(parse_record_attribute(
+            raw_attribute,
+            raw_class_file,
+        )?),
+        "PermittedSubclasses" => AttributeInfo::PermittedSubclasses(
+            parse_class_list_attribute(raw_attribute, raw_class_file)?,
+        ),
+        "RuntimeVisibleAnnotations" => {
+            AttributeInfo::RuntimeVisibleAnnotations(parse_attribute_info(
+                raw_attribute,
+                |current_content| {
+                    parse_annotations(raw_class_file, current_content)
+                },
+            )?)
+        },
+        "RuntimeVisibleParameterAnnotations" => {
+            AttributeInfo::RuntimeVisibleParameterAnnotations(
+                parse_attribute_info(raw_attribute, |current_content| {
+                    length_count(be_u8, |c| {
+                        parse_annotations(raw_class_file, c)
+                    })(current_content)
+                })?,
+            )
+        },
+        "AnnotationDefault" => AttributeInfo::AnnotationDefault(
+            parse_attribute_info(raw_attribute, |current_content| {
+                parse_element_value(raw_class_file, current_content)
+            })?,
+        ),
+        // e.g. invisible annotations, which aren't retained at runtime
+        _ => AttributeInfo::Unknown(name.to_string()),
+    })
 }
 
-pub fn parse_attributes(class_file: RawClassFile) -> ClassFile {
-    let class_attributes: Vec<AttributeInfo> = class_file
-        .attributes
+fn parse_attribute_list(
+    raw_attributes: &[RawAttributeInfo],
+    raw_class_file: &RawClassFile,
+) -> Result<Vec<AttributeInfo>, ClassFormatError> {
+    raw_attributes
         .iter()
-        .map(|a| parse_attribute(a, &class_file))
-        .collect();
-    let methods: Vec<MethodInfo> = class_file
+        .map(|a| parse_attribute(a, raw_class_file))
+        .collect()
+}
+
+pub fn parse_attributes(
+    class_file: RawClassFile,
+) -> Result<ClassFile, ClassFormatError> {
+    let class_attributes =
+        parse_attribute_list(&class_file.attributes, &class_file)?;
+    let methods = class_file
         .methods
         .iter()
-        .map(|m| MethodInfo {
-            access_flags: (m.access_flags),
-            descriptor_index: (m.descriptor_index),
-            name_index: (m.name_index),
-            attributes: (m
-                .attributes
-                .iter()
-                .map(|a| parse_attribute(a, &class_file))
-                .collect()),
+        .map(|m| {
+            Ok(MethodInfo {
+                access_flags: (m.access_flags),
+                descriptor_index: (m.descriptor_index),
+                name_index: (m.name_index),
+                attributes: parse_attribute_list(&m.attributes, &class_file)?,
+                offset: m.offset,
+            })
         })
-        .collect();
-    let fields: Vec<FieldInfo> = class_file
+        .collect::<Result<Vec<_>, ClassFormatError>>()?;
+    let fields = class_file
         .fields
         .iter()
-        .map(|f| FieldInfo {
-            access_flags: (f.access_flags),
-            descriptor_index: (f.descriptor_index),
-            name_index: (f.name_index),
-            attributes: (f
-                .attributes
-                .iter()
-                .map(|a| parse_attribute(a, &class_file))
-                .collect()),
+        .map(|f| {
+            Ok(FieldInfo {
+                access_flags: (f.access_flags),
+                descriptor_index: (f.descriptor_index),
+                name_index: (f.name_index),
+                attributes: parse_attribute_list(&f.attributes, &class_file)?,
+                offset: f.offset,
+            })
         })
-        .collect();
+        .collect::<Result<Vec<_>, ClassFormatError>>()?;
 
-    ClassFile {
+    Ok(ClassFile {
+        minor_version: (class_file.minor_version),
+        major_version: (class_file.major_version),
         access_flags: (class_file.access_flags),
         constant_pool: (class_file.constant_pool),
+        constant_pool_offsets: (class_file.constant_pool_offsets),
         this_class: (class_file.this_class),
+        this_class_offset: (class_file.this_class_offset),
         super_class: (class_file.super_class),
         interfaces: (class_file.interfaces),
         fields: (fields),
         methods: (methods),
         attributes: (class_attributes),
-    }
+    })
 }
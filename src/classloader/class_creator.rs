@@ -2,86 +2,212 @@ pub mod signature_parser;
 
 use std::{cell::RefCell, ops::Range, rc::Rc};
 
+use anyhow::anyhow;
+use nom::{error::ErrorKind, Offset};
+
 use super::parse_class_identifier;
 use crate::{
     class::{
-        access_flags::FieldAccessFlag, BytecodeClass, Code, Field,
-        FieldDescriptor, FieldKind, FieldValue, Method, MethodCode,
+        abstract_method,
+        access_flags::{ClassAccessFlag, FieldAccessFlag},
+        attributes::{
+            ClassAttributes, ConstantValue, FieldAttributes, MethodAttributes,
+        },
+        class_identifier, BytecodeClass, Class, ClassIdentifier, Code, Field,
+        FieldDescriptor, FieldValue, Method, MethodCode, RustMethodReturn,
     },
     classloader::{
-        class_creator::signature_parser::parse_method_arguments,
-        cp_decoder::{decode_constant_pool, remove_cp_offset, RuntimeCPEntry},
+        class_creator::signature_parser::{
+            parse_field_type, parse_method_arguments,
+        },
+        constant_pool::{get_class_name, get_utf8},
+        cp_decoder::{decode_constant_pool, RuntimeCPEntry},
         opcode_parser::parse_opcodes,
-        ClassFile, MethodAccessFlag, MethodInfo,
+        verifier::Verifier,
+        AttributeInfo, ClassFile, ClassFormatError, ClassLoadingError,
+        CodeAttribute, MethodAccessFlag, MethodInfo,
     },
-    heap::Heap,
+    executor::native_context::NativeContext,
+    heap::{ClassLoaderId, Heap},
 };
 
+/// Stand-in for `native` methods without a registered implementation.
+fn unsatisfied_link(context: &mut NativeContext) -> RustMethodReturn {
+    let message = format!(
+        "{}.{}",
+        context.class.class_identifier().java_name(),
+        context.method().name
+    );
+    context.throw_new(
+        &class_identifier!(java / lang, UnsatisfiedLinkError),
+        &message,
+    )
+}
+
+/// Convert the error of parsing the code of `code_attribute`.
+fn opcode_error(
+    error: nom::Err<nom::error::Error<&[u8]>>,
+    code_attribute: &CodeAttribute,
+) -> ClassFormatError {
+    let code = code_attribute.code.as_slice();
+    match error {
+        nom::Err::Error(e) | nom::Err::Failure(e) => ClassFormatError::at(
+            code_attribute.code_offset + code.offset(e.input),
+            match e.code {
+                ErrorKind::Eof => "truncated instruction",
+                ErrorKind::Verify => "branch target is no instruction boundary",
+                ErrorKind::Not => "unsupported instruction",
+                ErrorKind::MapOpt => "invalid constant pool reference",
+                ErrorKind::OneOf => "invalid array type",
+                ErrorKind::Count => "invalid array dimensions",
+                _ => "invalid opcode",
+            },
+        ),
+        nom::Err::Incomplete(_) => ClassFormatError::at(
+            code_attribute.code_offset + code.len(),
+            "truncated instruction",
+        ),
+    }
+}
+
+/// Returns the index of the opcode starting at byte `pc`,
+/// or the number of opcodes if `pc` is the end of the code.
+fn opcode_index(opcode_sizes: &[u16], pc: u16) -> Option<usize> {
+    let mut bytes_count = 0;
+    for (i, code_size) in opcode_sizes.iter().enumerate() {
+        if bytes_count == usize::from(pc) {
+            return Some(i);
+        }
+        bytes_count += usize::from(*code_size);
+    }
+    (bytes_count == usize::from(pc)).then_some(opcode_sizes.len())
+}
+
+/// Whether the methods of `class_file` are verified by type checking.
+///
+/// Class files since version 50 may contain stack map frames,
+/// since version 51 they must.
+fn is_type_checked(class_file: &ClassFile) -> bool {
+    class_file.major_version >= 51
+        || class_file.methods.iter().any(|method| {
+            method.attributes.iter().any(|attribute| {
+                attribute.as_code_attribute().is_some_and(|code| {
+                    code.attributes
+                        .iter()
+                        .any(|a| a.as_stack_map_table().is_some())
+                })
+            })
+        })
+}
+
+fn create_method_attributes(attributes: &[AttributeInfo]) -> MethodAttributes {
+    let mut method_attributes = MethodAttributes::default();
+    for attribute in attributes {
+        match attribute {
+            AttributeInfo::Exceptions(exceptions) => {
+                method_attributes.exceptions = exceptions.clone()
+            },
+            AttributeInfo::Signature(signature) => {
+                method_attributes.signature = Some(signature.clone())
+            },
+            AttributeInfo::Deprecated => method_attributes.deprecated = true,
+            AttributeInfo::RuntimeVisibleAnnotations(annotations) => {
+                method_attributes.annotations = annotations.clone()
+            },
+            AttributeInfo::RuntimeVisibleParameterAnnotations(annotations) => {
+                method_attributes.parameter_annotations = annotations.clone()
+            },
+            AttributeInfo::AnnotationDefault(value) => {
+                method_attributes.annotation_default = Some(value.clone())
+            },
+            AttributeInfo::Code(code_attribute) => {
+                for attribute in &code_attribute.attributes {
+                    // there may be multiple tables of each kind
+                    match attribute {
+                        AttributeInfo::LineNumberTable(line_numbers) => {
+                            method_attributes
+                                .line_numbers
+                                .extend_from_slice(line_numbers)
+                        },
+                        AttributeInfo::LocalVariableTable(variables) => {
+                            method_attributes
+                                .local_variables
+                                .extend_from_slice(variables)
+                        },
+                        AttributeInfo::LocalVariableTypeTable(variables) => {
+                            method_attributes
+                                .local_variable_types
+                                .extend_from_slice(variables)
+                        },
+                        _ => (),
+                    }
+                }
+            },
+            _ => (),
+        }
+    }
+    method_attributes
+}
+
 fn create_bytecode_method(
     method: &MethodInfo,
+    class_identifier: &ClassIdentifier,
+    super_class: &Rc<dyn Class>,
     class_file: &ClassFile,
     runtime_cp: &[RuntimeCPEntry],
+    loader: ClassLoaderId,
     heap: &mut Heap,
-) -> Rc<Method> {
+) -> Result<Rc<Method>, ClassLoadingError> {
     let mut byte_code = Vec::new();
+    let mut opcode_sizes = Vec::new();
+    let mut stack_map: &[_] = &[];
     let mut stack_depth = 0;
     let mut local_variable_count = 0;
-    let mut opcode_sizes;
     let mut exeption_table = Vec::new();
-    let name = class_file
-        .get_java_cp_entry(method.name_index as usize)
-        .expect("Valid CP Reference in MethodInfo")
-        .as_utf8_info()
-        .unwrap();
+    let name =
+        get_utf8(&class_file.constant_pool, method.name_index, method.offset)?;
     for element in method.attributes.iter() {
         let attribute = element.as_code_attribute();
         if let Some(code_attribute) = attribute {
             stack_depth = code_attribute.max_stack;
             local_variable_count = code_attribute.max_locals;
+            stack_map = code_attribute
+                .attributes
+                .iter()
+                .find_map(|a| a.as_stack_map_table())
+                .unwrap_or_default();
             (_, (byte_code, opcode_sizes)) = parse_opcodes(
                 &code_attribute.code,
                 class_file,
                 runtime_cp,
+                loader,
                 heap,
             )
-            .unwrap();
+            .map_err(|e| opcode_error(e, code_attribute))?;
 
             for exception in &code_attribute.exception_table {
-                let mut bytes_count = 0;
-                let mut start_pc_code = 0;
-                let mut end_pc_code = 0;
-                let mut handler_pc_code = 0;
-                for (i, code_size) in opcode_sizes.iter_mut().enumerate() {
-                    if bytes_count > exception.start_pc
-                        && bytes_count > exception.end_pc
-                        && bytes_count > exception.handler_pc
-                    {
-                        break;
-                    }
-                    if bytes_count == exception.start_pc {
-                        start_pc_code = i;
-                    }
-                    if bytes_count == exception.end_pc {
-                        end_pc_code = i;
-                    }
-                    if bytes_count == exception.handler_pc {
-                        handler_pc_code = i;
-                    }
-                    bytes_count += u16::from(*code_size);
-                }
+                let boundary_error = || {
+                    ClassFormatError::at(
+                        code_attribute.code_offset,
+                        "exception handler pc is no instruction boundary",
+                    )
+                };
+                let start_pc_code =
+                    opcode_index(&opcode_sizes, exception.start_pc)
+                        .ok_or_else(boundary_error)?;
+                let end_pc_code = opcode_index(&opcode_sizes, exception.end_pc)
+                    .ok_or_else(boundary_error)?;
+                let handler_pc_code =
+                    opcode_index(&opcode_sizes, exception.handler_pc)
+                        .ok_or_else(boundary_error)?;
                 let identifier = if exception.catch_type == 0 {
                     None
                 } else {
-                    let name_index = class_file
-                        .get_java_cp_entry(exception.catch_type as usize)
-                        .unwrap()
-                        .as_class_info()
-                        .unwrap();
-                    let name = class_file
-                        .get_java_cp_entry(name_index as usize)
-                        .unwrap()
-                        .as_utf8_info()
-                        .unwrap();
+                    let name = get_class_name(
+                        &class_file.constant_pool,
+                        exception.catch_type,
+                        method.offset,
+                    )?;
                     Some(parse_class_identifier(name))
                 };
                 exeption_table.push(crate::class::ExceptionTable {
@@ -95,135 +221,304 @@ fn create_bytecode_method(
             }
         }
     }
-    let desc_string = class_file
-        .get_java_cp_entry(method.descriptor_index as usize)
-        .expect("Valid CP Reference in MethodInfo")
-        .as_utf8_info()
-        .unwrap();
+    let desc_string = get_utf8(
+        &class_file.constant_pool,
+        method.descriptor_index,
+        method.offset,
+    )?;
     let (parameters, return_type) = parse_method_arguments(desc_string);
 
-    Rc::new(Method {
-        code: MethodCode::Bytecode(Code {
+    let code = if method.access_flags.contains(MethodAccessFlag::Native) {
+        MethodCode::Rust(
+            heap.find_native_method(class_identifier, name, desc_string)
+                .unwrap_or(unsatisfied_link),
+        )
+    } else if method.access_flags.contains(MethodAccessFlag::Abstract) {
+        MethodCode::Rust(abstract_method)
+    } else {
+        MethodCode::Bytecode(Code {
             stack_depth: stack_depth.into(),
             local_variable_count: local_variable_count.into(),
             exception_table: exeption_table,
             byte_code,
-        }),
+        })
+    };
+
+    let method = Method {
+        code,
         name: name.to_string(),
         parameters,
         return_type,
         is_static: method.access_flags.contains(MethodAccessFlag::Static),
-    })
+        access_flags: method.access_flags,
+        attributes: create_method_attributes(&method.attributes),
+    };
+    if let MethodCode::Bytecode(code) = &method.code {
+        if is_type_checked(class_file) {
+            let class_name = class_identifier.to_string();
+            Verifier::new(
+                heap,
+                loader,
+                &class_name,
+                class_file.access_flags.contains(ClassAccessFlag::Interface),
+                super_class,
+            )
+            .verify_method(
+                &method,
+                code,
+                stack_map,
+                &opcode_sizes,
+            )?;
+        }
+    }
+    Ok(Rc::new(method))
 }
 
 fn create_bytecode_methods(
+    class_identifier: &ClassIdentifier,
+    super_class: &Rc<dyn Class>,
     class_file: &ClassFile,
     runtime_cp: &[RuntimeCPEntry],
+    loader: ClassLoaderId,
     heap: &mut Heap,
-) -> Vec<Rc<Method>> {
+) -> Result<Vec<Rc<Method>>, ClassLoadingError> {
     class_file
         .methods
         .iter()
-        .map(|e| create_bytecode_method(e, class_file, runtime_cp, heap))
+        .map(|e| {
+            create_bytecode_method(
+                e,
+                class_identifier,
+                super_class,
+                class_file,
+                runtime_cp,
+                loader,
+                heap,
+            )
+        })
         .collect()
 }
 
+/// Returns the initial value of a static field of type `descriptor`
+/// given by its `ConstantValue` attribute,
+/// or `None` if the constant doesn't match the type.
+fn constant_field_value(
+    descriptor: &str,
+    constant: &ConstantValue,
+    heap: &Heap,
+) -> Option<FieldValue> {
+    Some(match (descriptor, constant) {
+        ("B", ConstantValue::Int(value)) => FieldValue::Byte(*value as i8),
+        ("C", ConstantValue::Int(value)) => FieldValue::Char(*value as u16),
+        ("I", ConstantValue::Int(value)) => FieldValue::Int(*value),
+        ("S", ConstantValue::Int(value)) => FieldValue::Short(*value as i16),
+        ("Z", ConstantValue::Int(value)) => FieldValue::Boolean(*value as u8),
+        ("F", ConstantValue::Float(value)) => FieldValue::Float(*value),
+        ("J", ConstantValue::Long(value)) => FieldValue::Long(*value),
+        ("D", ConstantValue::Double(value)) => FieldValue::Double(*value),
+        ("Ljava/lang/String;", ConstantValue::String(value)) => {
+            FieldValue::Reference(Some(Rc::new(heap.new_string(value.clone()))))
+        },
+        _ => return None,
+    })
+}
+
+fn create_field_attributes(attributes: &[AttributeInfo]) -> FieldAttributes {
+    let mut field_attributes = FieldAttributes::default();
+    for attribute in attributes {
+        match attribute {
+            AttributeInfo::ConstantValue(value) => {
+                field_attributes.constant_value = Some(value.clone())
+            },
+            AttributeInfo::Signature(signature) => {
+                field_attributes.signature = Some(signature.clone())
+            },
+            AttributeInfo::Deprecated => field_attributes.deprecated = true,
+            AttributeInfo::RuntimeVisibleAnnotations(annotations) => {
+                field_attributes.annotations = annotations.clone()
+            },
+            _ => (),
+        }
+    }
+    field_attributes
+}
+
 fn create_bytecode_fields(
     class_file: &ClassFile,
     _runtime_cp: &[RuntimeCPEntry],
-) -> (Vec<Rc<Field>>, Vec<FieldDescriptor>) {
+    heap: &Heap,
+) -> Result<(Vec<Rc<Field>>, Vec<FieldDescriptor>), ClassFormatError> {
     let mut instance_fields = Vec::new();
     let mut static_fields = Vec::new();
 
     for field_info in &class_file.fields {
+        let name = get_utf8(
+            &class_file.constant_pool,
+            field_info.name_index,
+            field_info.offset,
+        )?
+        .to_string();
+        let desciptor = get_utf8(
+            &class_file.constant_pool,
+            field_info.descriptor_index,
+            field_info.offset,
+        )?;
+        let attributes =
+            Rc::new(create_field_attributes(&field_info.attributes));
+        let field_type = parse_field_type(desciptor);
         if field_info.access_flags.contains(FieldAccessFlag::Static) {
-            let name = class_file
-                .get_java_cp_entry(field_info.name_index as usize)
-                .unwrap()
-                .as_utf8_info()
-                .unwrap()
-                .to_string();
-            let desciptor = class_file
-                .get_java_cp_entry(field_info.descriptor_index as usize)
-                .unwrap()
-                .as_utf8_info()
-                .unwrap();
-            // everything except one of B C D F I J S Z
-            // means a non primitive type wich are handled
-            // the same for default values
-            let value = match desciptor {
-                "B" => FieldValue::byte(),
-                "C" => FieldValue::char(),
-                "D" => FieldValue::double(),
-                "F" => FieldValue::float(),
-                "I" => FieldValue::int(),
-                "J" => FieldValue::long(),
-                "S" => FieldValue::short(),
-                "Z" => FieldValue::boolean(),
-                _ => FieldValue::reference(),
+            let value = match &attributes.constant_value {
+                Some(constant) => constant_field_value(
+                    desciptor, constant, heap,
+                )
+                .ok_or_else(|| {
+                    ClassFormatError::at(
+                        field_info.offset,
+                        format!(
+                            "constant value of {} doesn't match \
+its type {}",
+                            name, desciptor
+                        ),
+                    )
+                })?,
+                None => FieldValue::default_for(&field_type),
             };
             let value = RefCell::new(value);
-            static_fields.push(Rc::new(Field { name, value }));
+            static_fields.push(Rc::new(Field {
+                name,
+                access_flags: field_info.access_flags,
+                attributes,
+                field_type,
+                value,
+            }));
         } else {
-            let name = class_file
-                .get_java_cp_entry(field_info.name_index as usize)
-                .unwrap()
-                .as_utf8_info()
-                .unwrap()
-                .to_string();
-            let desciptor = class_file
-                .get_java_cp_entry(field_info.descriptor_index as usize)
-                .unwrap()
-                .as_utf8_info()
-                .unwrap();
-            // everything except one of B C D F I J S Z
-            // means a non primitive type
-            // wich are handled the same for default values
-            let kind = match desciptor {
-                "B" => FieldKind::Byte,
-                "C" => FieldKind::Char,
-                "D" => FieldKind::Double,
-                "F" => FieldKind::Float,
-                "I" => FieldKind::Int,
-                "J" => FieldKind::Long,
-                "S" => FieldKind::Short,
-                "Z" => FieldKind::Boolean,
-                _ => FieldKind::Reference,
-            };
-            instance_fields.push(FieldDescriptor { name, kind });
+            instance_fields.push(FieldDescriptor {
+                name,
+                access_flags: field_info.access_flags,
+                attributes,
+                field_type,
+            });
         }
     }
 
-    (static_fields, instance_fields)
+    Ok((static_fields, instance_fields))
 }
 
+fn create_class_attributes(attributes: &[AttributeInfo]) -> ClassAttributes {
+    let mut class_attributes = ClassAttributes::default();
+    for attribute in attributes {
+        match attribute {
+            AttributeInfo::SourceFile(source_file) => {
+                class_attributes.source_file = Some(source_file.clone())
+            },
+            AttributeInfo::Signature(signature) => {
+                class_attributes.signature = Some(signature.clone())
+            },
+            AttributeInfo::InnerClasses(inner_classes) => {
+                class_attributes.inner_classes = inner_classes.clone()
+            },
+            AttributeInfo::EnclosingMethod(enclosing_method) => {
+                class_attributes.enclosing_method =
+                    Some(enclosing_method.clone())
+            },
+            AttributeInfo::BootstrapMethods(bootstrap_methods) => {
+                class_attributes.bootstrap_methods = bootstrap_methods.clone()
+            },
+            AttributeInfo::Deprecated => class_attributes.deprecated = true,
+            AttributeInfo::RuntimeVisibleAnnotations(annotations) => {
+                class_attributes.annotations = annotations.clone()
+            },
+            AttributeInfo::NestHost(nest_host) => {
+                class_attributes.nest_host = Some(nest_host.clone())
+            },
+            AttributeInfo::NestMembers(nest_members) => {
+                class_attributes.nest_members = nest_members.clone()
+            },
+            AttributeInfo::Record(components) => {
+                class_attributes.record_components = Some(components.clone())
+            },
+            AttributeInfo::PermittedSubclasses(permitted_subclasses) => {
+                class_attributes.permitted_subclasses =
+                    permitted_subclasses.clone()
+            },
+            _ => (),
+        }
+    }
+    class_attributes
+}
+
+/// Create the class described by `class_file`, defined by `loader`.
+///
+/// Its super class has to be loaded already.
 pub fn create_bytecode_class(
     class_file: &ClassFile,
+    loader: ClassLoaderId,
     heap: &mut Heap,
-) -> BytecodeClass {
-    let runtime_cp = decode_constant_pool(class_file);
+) -> Result<BytecodeClass, ClassLoadingError> {
+    let runtime_cp = decode_constant_pool(class_file)?;
+
+    let class_name = get_class_name(
+        &class_file.constant_pool,
+        class_file.this_class,
+        class_file.this_class_offset,
+    )?;
+    let class_identifier = parse_class_identifier(class_name);
+    // module descriptors are read by the module system,
+    // but can't be loaded as classes (JVMS §5.3.5)
+    if class_file.access_flags.contains(ClassAccessFlag::Module) {
+        return Err(anyhow!("{} is a module descriptor", class_name).into());
+    }
 
-    let methods = create_bytecode_methods(class_file, &runtime_cp, heap);
+    let super_class_name = get_class_name(
+        &class_file.constant_pool,
+        class_file.super_class,
+        class_file.this_class_offset + 2,
+    )?;
+    let super_class = heap
+        .find_class(loader, &parse_class_identifier(super_class_name))
+        .ok_or_else(|| {
+            ClassLoadingError::NotFound(super_class_name.to_string())
+        })?
+        .clone();
+
+    let methods = create_bytecode_methods(
+        &class_identifier,
+        &super_class,
+        class_file,
+        &runtime_cp,
+        loader,
+        heap,
+    )?;
     let (static_fields, instance_fields) =
-        create_bytecode_fields(class_file, &runtime_cp);
-    let class: &RuntimeCPEntry =
-        &runtime_cp[remove_cp_offset(class_file.this_class as usize)];
-    let class_identifier = parse_class_identifier(class.as_class().unwrap());
-
-    let super_class_name: &RuntimeCPEntry =
-        &runtime_cp[remove_cp_offset(class_file.super_class as usize)];
-    let super_class_identifier =
-        parse_class_identifier(super_class_name.as_class().unwrap());
-    let super_class = heap.find_class(&super_class_identifier).unwrap();
-    // let super_class = None;
-    let interfaces = Vec::new();
-    BytecodeClass {
+        create_bytecode_fields(class_file, &runtime_cp, heap)?;
+
+    // interfaces, which couldn't be loaded, are skipped
+    let interfaces = class_file
+        .interfaces
+        .iter()
+        .map(|index| {
+            get_class_name(
+                &class_file.constant_pool,
+                *index,
+                class_file.this_class_offset,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter_map(|name| {
+            heap.find_class(loader, &parse_class_identifier(name))
+                .cloned()
+        })
+        .collect();
+    Ok(BytecodeClass {
         methods,
         static_fields,
         instance_fields,
         class_identifier,
-        super_class: super_class.clone(),
+        super_class,
         interfaces,
         access_flags: class_file.access_flags,
-    }
+        class_loader: loader,
+        attributes: create_class_attributes(&class_file.attributes),
+    })
 }
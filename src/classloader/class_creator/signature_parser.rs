@@ -144,6 +144,7 @@ pub fn parse_method_arguments(
                         ));
                     }
                     current_class_name = "".to_string();
+                    state = ArgumentStates::Arguments;
                 } else {
                     current_class_name.push(char);
                 }
@@ -171,3 +172,83 @@ pub fn parse_method_arguments(
     }
     (parameters, return_type)
 }
+
+/// Parse a valid field descriptor, e.g. `[Ljava/lang/String;`.
+pub fn parse_field_type(descriptor: &str) -> ArgumentKind {
+    let (mut types, _) = parse_method_arguments(&format!("({})V", descriptor));
+    types.remove(0)
+}
+
+/// Returns whether `name` is a valid unqualified name (JVMS §4.2.2),
+/// e.g. the name of a field or a segment of a class name.
+pub fn is_unqualified_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['.', ';', '[', '/'])
+}
+
+/// Returns whether `name` is a valid method name (JVMS §4.2.2).
+pub fn is_method_name(name: &str) -> bool {
+    name == "<init>"
+        || name == "<clinit>"
+        || (is_unqualified_name(name) && !name.contains(['<', '>']))
+}
+
+/// Returns whether `name` is a valid binary class name in internal form,
+/// e.g. `java/lang/Object`.
+pub fn is_class_name(name: &str) -> bool {
+    name.split('/').all(is_unqualified_name)
+}
+
+/// Returns the rest of `descriptor` after the field type at its start,
+/// or `None` if it doesn't start with a valid field type.
+fn strip_field_type(descriptor: &str) -> Option<&str> {
+    let element = descriptor.trim_start_matches('[');
+    if descriptor.len() - element.len() > 255 {
+        return None;
+    }
+    let mut chars = element.chars();
+    match chars.next()? {
+        'B' | 'C' | 'D' | 'F' | 'I' | 'J' | 'S' | 'Z' => Some(chars.as_str()),
+        'L' => {
+            let (name, rest) = chars.as_str().split_once(';')?;
+            is_class_name(name).then_some(rest)
+        },
+        _ => None,
+    }
+}
+
+/// Returns whether `descriptor` is a valid field descriptor, e.g. `[I`.
+pub fn is_field_descriptor(descriptor: &str) -> bool {
+    strip_field_type(descriptor) == Some("")
+}
+
+/// Returns whether `descriptor` is a valid method descriptor,
+/// e.g. `(ILjava/lang/String;)V`.
+pub fn is_method_descriptor(descriptor: &str) -> bool {
+    let Some(mut rest) = descriptor.strip_prefix('(') else {
+        return false;
+    };
+    loop {
+        if let Some(return_type) = rest.strip_prefix(')') {
+            return return_type == "V" || is_field_descriptor(return_type);
+        }
+        match strip_field_type(rest) {
+            Some(after_parameter) => rest = after_parameter,
+            None => return false,
+        }
+    }
+}
+
+/// Returns the number of local variable slots
+/// taken by the parameters of a valid method `descriptor`.
+pub fn parameter_slots(descriptor: &str) -> usize {
+    let (parameters, _) = parse_method_arguments(descriptor);
+    parameters
+        .iter()
+        .map(|parameter| match parameter {
+            ArgumentKind::Simple(
+                SimpleArgumentKind::Long | SimpleArgumentKind::Double,
+            ) => 2,
+            _ => 1,
+        })
+        .sum()
+}
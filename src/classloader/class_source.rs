@@ -0,0 +1,108 @@
+use std::{collections::HashMap, fmt::Debug, path::PathBuf};
+
+use anyhow::Context;
+
+use crate::classloader::jar::JarFile;
+
+/// A location the content of class files is loaded from.
+pub trait ClassSource: Debug {
+    /// Returns the content of the class file of `name`
+    /// (e.g. `org/example/Main`), or `None` if it isn't part of `self`.
+    fn read_class(&self, name: &str) -> anyhow::Result<Option<Vec<u8>>>;
+}
+
+/// A directory containing class files in package directories,
+/// e.g. `org/example/Main.class`.
+#[derive(Debug)]
+pub struct DirectoryClassSource {
+    directory: PathBuf,
+}
+
+impl DirectoryClassSource {
+    pub fn new(directory: PathBuf) -> Self {
+        DirectoryClassSource { directory }
+    }
+}
+
+impl ClassSource for DirectoryClassSource {
+    fn read_class(&self, name: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let path = self.directory.join(format!("{}.class", name));
+        if !path.is_file() {
+            return Ok(None);
+        }
+        std::fs::read(&path)
+            .with_context(|| format!("{} can not be read", path.display()))
+            .map(Some)
+    }
+}
+
+impl ClassSource for JarFile {
+    fn read_class(&self, name: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        self.read_entry(&format!("{}.class", name))
+    }
+}
+
+/// Class files kept in memory, keyed by class name
+/// (e.g. `org/example/Main`), e.g. for generated bytecode.
+#[derive(Debug, Default)]
+pub struct MemoryClassSource {
+    classes: HashMap<String, Vec<u8>>,
+}
+
+impl MemoryClassSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the class file `content` of the class `name`,
+    /// replacing a previously added one.
+    pub fn insert(&mut self, name: &str, content: Vec<u8>) {
+        self.classes.insert(name.to_string(), content);
+    }
+}
+
+impl From<HashMap<String, Vec<u8>>> for MemoryClassSource {
+    fn from(classes: HashMap<String, Vec<u8>>) -> Self {
+        MemoryClassSource { classes }
+    }
+}
+
+impl ClassSource for MemoryClassSource {
+    fn read_class(&self, name: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(self.classes.get(name).cloned())
+    }
+}
+
+/// Open the directories and JARs in `paths`.
+///
+/// Like java, the JARs listed in the `Class-Path` of a JAR's manifest
+/// are appended after the JAR itself.
+/// Entries that don't exist are skipped.
+pub fn open_class_path(
+    paths: impl IntoIterator<Item = PathBuf>,
+) -> anyhow::Result<Vec<Box<dyn ClassSource>>> {
+    let mut sources: Vec<Box<dyn ClassSource>> = Vec::new();
+    let mut opened: Vec<PathBuf> = Vec::new();
+    let mut pending: Vec<PathBuf> = paths.into_iter().collect();
+    pending.reverse();
+
+    while let Some(path) = pending.pop() {
+        if opened.contains(&path) {
+            continue;
+        }
+        opened.push(path.clone());
+        if path.is_dir() {
+            sources.push(Box::new(DirectoryClassSource::new(path)));
+        } else if path.is_file() {
+            let jar = JarFile::open(&path)?;
+            let directory =
+                path.parent().map(PathBuf::from).unwrap_or_default();
+            // process the manifest's class path before the remaining paths
+            for dependency in jar.manifest()?.class_path.iter().rev() {
+                pending.push(directory.join(dependency));
+            }
+            sources.push(Box::new(jar));
+        }
+    }
+    Ok(sources)
+}
@@ -1,4 +1,4 @@
-use crate::classloader::ReferenceKind;
+use crate::classloader::{ClassFormatError, ReferenceKind};
 
 #[derive(Debug)]
 pub enum CpInfo {
@@ -38,10 +38,21 @@ pub enum CpInfo {
     MethodTypeInfo {
         descriptor_index: u16,
     },
+    DynamicInfo {
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
+    },
     InvokeDynamicInfo {
         bootstrap_method_attr_index: u16,
         name_and_type_index: u16,
     },
+    // only used by module-info classes
+    ModuleInfo {
+        name_index: u16,
+    },
+    PackageInfo {
+        name_index: u16,
+    },
 }
 
 impl CpInfo {
@@ -169,6 +180,18 @@ impl CpInfo {
         }
     }
 
+    pub fn as_dynamic_info(&self) -> Option<(u16, u16)> {
+        if let CpInfo::DynamicInfo {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        } = *self
+        {
+            Some((bootstrap_method_attr_index, name_and_type_index))
+        } else {
+            None
+        }
+    }
+
     pub fn as_invoke_dynamic_info(&self) -> Option<(u16, u16)> {
         if let CpInfo::InvokeDynamicInfo {
             bootstrap_method_attr_index,
@@ -180,4 +203,74 @@ impl CpInfo {
             None
         }
     }
+
+    pub fn as_module_info(&self) -> Option<u16> {
+        if let CpInfo::ModuleInfo { name_index } = *self {
+            Some(name_index)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_package_info(&self) -> Option<u16> {
+        if let CpInfo::PackageInfo { name_index } = *self {
+            Some(name_index)
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the entry `index` (counting from 1) of `constant_pool`,
+/// converted by `as_kind`, e.g. [`CpInfo::as_utf8_info`].
+///
+/// `offset` is the offset of the structure referencing the entry,
+/// which is reported if the entry doesn't exist or isn't a `kind`.
+pub fn get_entry<'a, T>(
+    constant_pool: &'a [CpInfo],
+    index: u16,
+    offset: usize,
+    kind: &str,
+    as_kind: impl FnOnce(&'a CpInfo) -> Option<T>,
+) -> Result<T, ClassFormatError> {
+    let entry = (index as usize)
+        .checked_sub(1)
+        .and_then(|index| constant_pool.get(index))
+        .ok_or_else(|| {
+            ClassFormatError::at(
+                offset,
+                format!("invalid constant pool index {}", index),
+            )
+        })?;
+    as_kind(entry).ok_or_else(|| {
+        ClassFormatError::at(
+            offset,
+            format!("constant pool entry {} is no {}", index, kind),
+        )
+    })
+}
+
+/// Returns the string of the `Utf8` entry `index`, see [`get_entry`].
+pub fn get_utf8(
+    constant_pool: &[CpInfo],
+    index: u16,
+    offset: usize,
+) -> Result<&str, ClassFormatError> {
+    get_entry(constant_pool, index, offset, "Utf8", CpInfo::as_utf8_info)
+}
+
+/// Returns the name of the `Class` entry `index`, see [`get_entry`].
+pub fn get_class_name(
+    constant_pool: &[CpInfo],
+    index: u16,
+    offset: usize,
+) -> Result<&str, ClassFormatError> {
+    let name_index = get_entry(
+        constant_pool,
+        index,
+        offset,
+        "Class",
+        CpInfo::as_class_info,
+    )?;
+    get_utf8(constant_pool, name_index, offset)
 }
@@ -1,4 +1,7 @@
-use crate::classloader::{ClassFile, CpInfo, ReferenceKind};
+use crate::classloader::{
+    constant_pool::{get_class_name, get_entry, get_utf8},
+    ClassFile, ClassFormatError, CpInfo, ReferenceKind,
+};
 
 #[derive(Clone, Debug)]
 pub enum RuntimeCPEntry {
@@ -36,9 +39,21 @@ pub enum RuntimeCPEntry {
     MethodTypeInfo {
         descriptor: String,
     },
+    DynamicInfo {
+        bootstrap_method_attr_index: u16,
+        name: String,
+        descriptor: String,
+    },
     InvokeDynamicInfo {
         bootstrap_method_attr_index: u16,
-        name_and_type_index: u16,
+        name: String,
+        descriptor: String,
+    },
+    Module {
+        name: String,
+    },
+    Package {
+        name: String,
     },
     Resolved,
     // long/double values take up two slots
@@ -83,143 +98,96 @@ impl RuntimeCPEntry {
             None
         }
     }
-}
 
-fn decode_class_info(entry: &CpInfo, class_file: &ClassFile) -> String {
-    let name_index =
-        entry.as_class_info().expect("Cp_Info must be a class info");
-    let name_entry = class_file
-        .get_java_cp_entry(Into::<usize>::into(name_index))
-        .unwrap();
-    name_entry
-        .as_utf8_info()
-        .expect("CP_Info must be an UTF8_Info")
-        .to_string()
+    pub fn as_interface_ref(&self) -> Option<(&str, &str, &str)> {
+        if let RuntimeCPEntry::InterfaceRefInfo {
+            class,
+            name,
+            descriptor,
+        } = self
+        {
+            Some((class.as_str(), name.as_str(), descriptor.as_str()))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the bootstrap method index, name and descriptor
+    /// of a call site.
+    pub fn as_invoke_dynamic(&self) -> Option<(u16, &str, &str)> {
+        if let RuntimeCPEntry::InvokeDynamicInfo {
+            bootstrap_method_attr_index,
+            name,
+            descriptor,
+        } = self
+        {
+            Some((*bootstrap_method_attr_index, name, descriptor))
+        } else {
+            None
+        }
+    }
 }
 
-fn decode_name_and_type_info(
-    entry: &CpInfo,
+fn decode_class_info(
+    name_index: u16,
     class_file: &ClassFile,
-) -> (String, String) {
-    let (name_index, descriptor_index) = entry
-        .as_name_and_type_info()
-        .expect("CP_Info must be an name_and_type_Info");
-    let name = class_file
-        .get_java_cp_entry(Into::<usize>::into(name_index))
-        .unwrap()
-        .as_utf8_info()
-        .expect("CP_Info must be an UTF8_Info");
-    let descriptor = class_file
-        .get_java_cp_entry(Into::<usize>::into(descriptor_index))
-        .unwrap()
-        .as_utf8_info()
-        .expect("CP_Info must be an UTF8_Info");
-    (name.to_string(), descriptor.to_string())
+    offset: usize,
+) -> Result<String, ClassFormatError> {
+    get_utf8(&class_file.constant_pool, name_index, offset).map(str::to_string)
 }
 
-fn decode_field_ref(
-    entry: &CpInfo,
+fn decode_name_and_type_info(
+    index: u16,
     class_file: &ClassFile,
-) -> (String, String, String) {
-    let (class_index, name_and_type_index) = entry
-        .as_field_ref_info()
-        .expect("Cp_info must be an field_ref_info");
-    let class_name = decode_class_info(
-        class_file
-            .get_java_cp_entry(Into::<usize>::into(class_index))
-            .unwrap(),
-        class_file,
-    );
-    let (name, descriptor) = decode_name_and_type_info(
-        class_file
-            .get_java_cp_entry(Into::<usize>::into(name_and_type_index))
-            .unwrap(),
-        class_file,
-    );
-    (class_name, name, descriptor)
+    offset: usize,
+) -> Result<(String, String), ClassFormatError> {
+    let (name_index, descriptor_index) = get_entry(
+        &class_file.constant_pool,
+        index,
+        offset,
+        "NameAndType",
+        CpInfo::as_name_and_type_info,
+    )?;
+    let name = get_utf8(&class_file.constant_pool, name_index, offset)?;
+    let descriptor =
+        get_utf8(&class_file.constant_pool, descriptor_index, offset)?;
+    Ok((name.to_string(), descriptor.to_string()))
 }
 
-fn decode_method_ref(
-    entry: &CpInfo,
+/// Decode the class and name and type of a field, method
+/// or interface method reference.
+fn decode_member_ref(
+    class_index: u16,
+    name_and_type_index: u16,
     class_file: &ClassFile,
-) -> (String, String, String) {
-    let (class_index, name_and_type_index) = entry
-        .as_method_ref_info()
-        .expect("Cp_info must be an method_ref_info");
-    let class_name = decode_class_info(
-        class_file
-            .get_java_cp_entry(Into::<usize>::into(class_index))
-            .unwrap(),
-        class_file,
-    );
-    let (name, descriptor) = decode_name_and_type_info(
-        class_file
-            .get_java_cp_entry(Into::<usize>::into(name_and_type_index))
-            .unwrap(),
-        class_file,
-    );
-    (class_name, name, descriptor)
+    offset: usize,
+) -> Result<(String, String, String), ClassFormatError> {
+    let class_name =
+        get_class_name(&class_file.constant_pool, class_index, offset)?;
+    let (name, descriptor) =
+        decode_name_and_type_info(name_and_type_index, class_file, offset)?;
+    Ok((class_name.to_string(), name, descriptor))
 }
 
-fn decode_interface_ref(
+fn decode_entry(
     entry: &CpInfo,
     class_file: &ClassFile,
-) -> (String, String, String) {
-    let (class_index, name_and_type_index) = entry
-        .as_interface_ref_info()
-        .expect("Cp_info must be an interface_ref_info");
-    let class_name = decode_class_info(
-        class_file
-            .get_java_cp_entry(Into::<usize>::into(class_index))
-            .unwrap(),
-        class_file,
-    );
-    let (name, descriptor) = decode_name_and_type_info(
-        class_file
-            .get_java_cp_entry(Into::<usize>::into(name_and_type_index))
-            .unwrap(),
-        class_file,
-    );
-    (class_name, name, descriptor)
-}
-
-fn decode_string_info(entry: &CpInfo, class_file: &ClassFile) -> String {
-    let name_index = entry
-        .as_string_info()
-        .expect("Cp_Info must be a string info");
-    let name_entry = class_file
-        .get_java_cp_entry(Into::<usize>::into(name_index))
-        .unwrap();
-    name_entry
-        .as_utf8_info()
-        .expect("CP_Info must be an UTF8_Info")
-        .to_string()
-}
-
-fn decode_method_type_info(entry: &CpInfo, class_file: &ClassFile) -> String {
-    let index = entry
-        .as_method_type_info()
-        .expect("Cp_Info must be a methodType info");
-    let name_entry = class_file
-        .get_java_cp_entry(Into::<usize>::into(index))
-        .unwrap();
-    name_entry
-        .as_utf8_info()
-        .expect("CP_Info must be an UTF8_Info")
-        .to_string()
-}
-
-fn decode_entry(entry: &CpInfo, class_file: &ClassFile) -> RuntimeCPEntry {
-    match entry {
-        CpInfo::ClassInfo { name_index: _ } => RuntimeCPEntry::Class {
-            name: (decode_class_info(entry, class_file)),
+    offset: usize,
+) -> Result<RuntimeCPEntry, ClassFormatError> {
+    Ok(match *entry {
+        CpInfo::ClassInfo { name_index } => RuntimeCPEntry::Class {
+            name: (decode_class_info(name_index, class_file, offset)?),
         },
         CpInfo::FieldRefInfo {
-            class_index: _,
-            name_and_type_index: _,
+            class_index,
+            name_and_type_index,
         } => {
-            let (class_name, name, descriptor) =
-                decode_field_ref(entry, class_file);
+            let (class_name, name, descriptor) = decode_member_ref(
+                class_index,
+                name_and_type_index,
+                class_file,
+                offset,
+            )?;
             RuntimeCPEntry::FieldRefInfo {
                 class: (class_name),
                 name: (name),
@@ -227,11 +195,15 @@ fn decode_entry(entry: &CpInfo, class_file: &ClassFile) -> RuntimeCPEntry {
             }
         },
         CpInfo::MethodRefInfo {
-            class_index: _,
-            name_and_type_index: _,
+            class_index,
+            name_and_type_index,
         } => {
-            let (class_name, name, descriptor) =
-                decode_method_ref(entry, class_file);
+            let (class_name, name, descriptor) = decode_member_ref(
+                class_index,
+                name_and_type_index,
+                class_file,
+                offset,
+            )?;
             RuntimeCPEntry::MethodRefInfo {
                 class: (class_name),
                 name: (name),
@@ -239,62 +211,100 @@ fn decode_entry(entry: &CpInfo, class_file: &ClassFile) -> RuntimeCPEntry {
             }
         },
         CpInfo::InterfaceMethodRefInfo {
-            class_index: _,
-            name_and_type_index: _,
+            class_index,
+            name_and_type_index,
         } => {
-            let (class_name, name, descriptor) =
-                decode_interface_ref(entry, class_file);
+            let (class_name, name, descriptor) = decode_member_ref(
+                class_index,
+                name_and_type_index,
+                class_file,
+                offset,
+            )?;
             RuntimeCPEntry::InterfaceRefInfo {
                 class: (class_name),
                 name: (name),
                 descriptor: (descriptor),
             }
         },
-        CpInfo::StringInfo { string_index: _ } => {
-            RuntimeCPEntry::StringInfo(decode_string_info(entry, class_file))
-        },
-        CpInfo::IntegerInfo(_) => RuntimeCPEntry::IntegerInfo(
-            entry
-                .as_integer_info()
-                .expect("Cp_Info must be a integer info"),
-        ),
-        CpInfo::FloatInfo(_) => RuntimeCPEntry::FloatInfo(
-            entry.as_float_info().expect("Cp_Info must be a float info"),
-        ),
-        CpInfo::LongInfo(_) => RuntimeCPEntry::LongInfo(
-            entry.as_long_info().expect("Cp_Info must be a long info"),
-        ),
-        CpInfo::DoubleInfo(_) => RuntimeCPEntry::DoubleInfo(
-            entry
-                .as_double_info()
-                .expect("Cp_Info must be a double info"),
+        CpInfo::StringInfo { string_index } => RuntimeCPEntry::StringInfo(
+            get_utf8(&class_file.constant_pool, string_index, offset)?
+                .to_string(),
         ),
+        CpInfo::IntegerInfo(value) => RuntimeCPEntry::IntegerInfo(value),
+        CpInfo::FloatInfo(value) => RuntimeCPEntry::FloatInfo(value),
+        CpInfo::LongInfo(value) => RuntimeCPEntry::LongInfo(value),
+        CpInfo::DoubleInfo(value) => RuntimeCPEntry::DoubleInfo(value),
         CpInfo::NameAndTypeInfo {
             name_index: _,
             descriptor_index: _,
         } => RuntimeCPEntry::Resolved,
         CpInfo::UTF8INFO(_) => RuntimeCPEntry::Resolved,
         CpInfo::MethodHandleInfo {
-            reference_kind: _,
-            reference_index: _,
-        } => todo!(),
-        CpInfo::MethodTypeInfo {
-            descriptor_index: _,
-        } => RuntimeCPEntry::MethodTypeInfo {
-            descriptor: (decode_method_type_info(entry, class_file)),
+            reference_kind,
+            reference_index,
+        } => RuntimeCPEntry::MethodHandleInfo {
+            reference_kind,
+            reference_index,
+        },
+        CpInfo::MethodTypeInfo { descriptor_index } => {
+            RuntimeCPEntry::MethodTypeInfo {
+                descriptor: get_utf8(
+                    &class_file.constant_pool,
+                    descriptor_index,
+                    offset,
+                )?
+                .to_string(),
+            }
+        },
+        CpInfo::DynamicInfo {
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        } => {
+            let (name, descriptor) = decode_name_and_type_info(
+                name_and_type_index,
+                class_file,
+                offset,
+            )?;
+            RuntimeCPEntry::DynamicInfo {
+                bootstrap_method_attr_index,
+                name,
+                descriptor,
+            }
         },
         CpInfo::InvokeDynamicInfo {
-            bootstrap_method_attr_index: _,
-            name_and_type_index: _,
-        } => todo!(),
+            bootstrap_method_attr_index,
+            name_and_type_index,
+        } => {
+            let (name, descriptor) = decode_name_and_type_info(
+                name_and_type_index,
+                class_file,
+                offset,
+            )?;
+            RuntimeCPEntry::InvokeDynamicInfo {
+                bootstrap_method_attr_index,
+                name,
+                descriptor,
+            }
+        },
+        CpInfo::ModuleInfo { name_index } => RuntimeCPEntry::Module {
+            name: get_utf8(&class_file.constant_pool, name_index, offset)?
+                .to_string(),
+        },
+        CpInfo::PackageInfo { name_index } => RuntimeCPEntry::Package {
+            name: get_utf8(&class_file.constant_pool, name_index, offset)?
+                .to_string(),
+        },
         CpInfo::Reserved => RuntimeCPEntry::Reserved,
-    }
+    })
 }
 
-pub fn decode_constant_pool(class_file: &ClassFile) -> Vec<RuntimeCPEntry> {
+pub fn decode_constant_pool(
+    class_file: &ClassFile,
+) -> Result<Vec<RuntimeCPEntry>, ClassFormatError> {
     class_file
         .constant_pool
         .iter()
-        .map(|e| decode_entry(e, class_file))
+        .zip(&class_file.constant_pool_offsets)
+        .map(|(e, offset)| decode_entry(e, class_file, *offset))
         .collect()
 }
@@ -1,22 +1,94 @@
-use std::path::Path;
+use std::{borrow::Cow, path::Path};
 
 use anyhow::Context;
-use enumflags2::BitFlags;
+use enumflags2::{BitFlag, BitFlags};
 use nom::{
     bytes::complete::tag,
     combinator::eof,
     error::ErrorKind,
     multi::{length_count, length_value, many_till},
     number::complete::{be_f32, be_f64, be_i32, be_i64, be_u16, be_u32, be_u8},
-    IResult,
+    IResult, Offset,
 };
 
 use crate::classloader::{
     raw::{RawAttributeInfo, RawClassFile, RawFieldInfo, RawMethodInfo},
-    ClassAccessFlag, CpInfo, FieldAccessFlag, MethodAccessFlag, ReferenceKind,
+    ClassAccessFlag, ClassFormatError, CpInfo, FieldAccessFlag,
+    MethodAccessFlag, ReferenceKind,
 };
 
-fn parse_utf8_code_point(current_content: &[u8]) -> IResult<&[u8], char> {
+const MAGIC: &[u8] = b"\xCA\xFE\xBA\xBE";
+
+/// Error of the class file parsers:
+/// the input at which parsing failed and why.
+#[derive(Debug)]
+pub struct ParseError<'a> {
+    input: &'a [u8],
+    reason: Cow<'static, str>,
+}
+
+pub type ParseResult<'a, T> = IResult<&'a [u8], T, ParseError<'a>>;
+
+impl<'a> ParseError<'a> {
+    /// Stop parsing at `input` because of `reason`.
+    pub fn fail<T>(
+        input: &'a [u8],
+        reason: impl Into<Cow<'static, str>>,
+    ) -> ParseResult<'a, T> {
+        Err(nom::Err::Failure(ParseError {
+            input,
+            reason: reason.into(),
+        }))
+    }
+
+    /// Convert the error of parsing `content`,
+    /// which is located at `offset` in the class file.
+    pub fn into_class_format_error(
+        error: nom::Err<ParseError>,
+        content: &[u8],
+        offset: usize,
+    ) -> ClassFormatError {
+        match error {
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                ClassFormatError::at(offset + content.offset(e.input), e.reason)
+            },
+            // only returned by `length_value`, if the length is too large
+            nom::Err::Incomplete(_) => ClassFormatError::at(
+                offset + content.len(),
+                "truncated class file",
+            ),
+        }
+    }
+}
+
+impl<'a> nom::error::ParseError<&'a [u8]> for ParseError<'a> {
+    fn from_error_kind(input: &'a [u8], kind: ErrorKind) -> Self {
+        let reason = match kind {
+            ErrorKind::Eof => "truncated class file".into(),
+            kind => format!("invalid data ({})", kind.description()).into(),
+        };
+        ParseError { input, reason }
+    }
+
+    fn append(_input: &'a [u8], _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+pub fn parse_access_flags<F: BitFlag<Numeric = u16>>(
+    current_content: &[u8],
+) -> ParseResult<BitFlags<F>> {
+    let (new_content, bits) = be_u16(current_content)?;
+    match BitFlags::<F>::from_bits(bits) {
+        Ok(flags) => Ok((new_content, flags)),
+        Err(_) => ParseError::fail(
+            current_content,
+            format!("invalid access flags 0x{:04x}", bits),
+        ),
+    }
+}
+
+fn parse_utf8_code_point(current_content: &[u8]) -> ParseResult<char> {
     let tag_content = current_content;
     let (current_content, first_byte) = be_u8(current_content)?;
     if first_byte == 0b11101101 {
@@ -64,22 +136,19 @@ fn parse_utf8_code_point(current_content: &[u8]) -> IResult<&[u8], char> {
             char::from_u32(first_byte.into()).expect("Byte is valid utf8"),
         ))
     } else {
-        Err(nom::Err::Failure(nom::error::Error::new(
-            tag_content,
-            ErrorKind::Tag,
-        )))
+        ParseError::fail(tag_content, "invalid modified UTF-8")
     }
 }
 
 fn parse_utf8_from_constant_pool(
     current_content: &[u8],
-) -> IResult<&[u8], String> {
+) -> ParseResult<String> {
     let (current_content, (code_points, _)) =
         many_till(parse_utf8_code_point, eof)(current_content)?;
     Ok((current_content, code_points.iter().collect()))
 }
 
-fn parse_constant_pool_item(current_content: &[u8]) -> IResult<&[u8], CpInfo> {
+fn parse_constant_pool_item(current_content: &[u8]) -> ParseResult<CpInfo> {
     let tag_content = current_content;
     let (current_content, tag) = be_u8(current_content)?;
     match tag {
@@ -172,13 +241,17 @@ fn parse_constant_pool_item(current_content: &[u8]) -> IResult<&[u8], CpInfo> {
             Ok((current_content, CpInfo::UTF8INFO(string)))
         },
         15 => {
+            let kind_content = current_content;
             let (current_content, reference_kind) = be_u8(current_content)?;
+            let reference_kind = match ReferenceKind::try_from(reference_kind) {
+                Ok(reference_kind) => reference_kind,
+                Err(reason) => return ParseError::fail(kind_content, reason),
+            };
             let (current_content, reference_index) = be_u16(current_content)?;
             Ok((
                 current_content,
                 CpInfo::MethodHandleInfo {
-                    reference_kind: (ReferenceKind::try_from(reference_kind)
-                        .unwrap()),
+                    reference_kind: (reference_kind),
                     reference_index: (reference_index),
                 },
             ))
@@ -192,6 +265,19 @@ fn parse_constant_pool_item(current_content: &[u8]) -> IResult<&[u8], CpInfo> {
                 },
             ))
         },
+        17 => {
+            let (current_content, bootstrap_method_attr_index) =
+                be_u16(current_content)?;
+            let (current_content, name_and_type_index) =
+                be_u16(current_content)?;
+            Ok((
+                current_content,
+                CpInfo::DynamicInfo {
+                    bootstrap_method_attr_index,
+                    name_and_type_index,
+                },
+            ))
+        },
         18 => {
             let (current_content, bootstrap_method_attr_index) =
                 be_u16(current_content)?;
@@ -205,16 +291,29 @@ fn parse_constant_pool_item(current_content: &[u8]) -> IResult<&[u8], CpInfo> {
                 },
             ))
         },
-        _ => Err(nom::Err::Failure(nom::error::Error::new(
+        19 => {
+            let (current_content, name_index) = be_u16(current_content)?;
+            Ok((current_content, CpInfo::ModuleInfo { name_index }))
+        },
+        20 => {
+            let (current_content, name_index) = be_u16(current_content)?;
+            Ok((current_content, CpInfo::PackageInfo { name_index }))
+        },
+        _ => ParseError::fail(
             tag_content,
-            ErrorKind::Tag,
-        ))),
+            format!("invalid constant pool tag {}", tag),
+        ),
     }
 }
 
-pub fn parse_attribute_info(
-    current_content: &[u8],
-) -> IResult<&[u8], RawAttributeInfo> {
+/// Parse an attribute, which is part of `base`
+/// (located at `base_offset` in the class file).
+pub fn parse_attribute_info<'a>(
+    base: &'a [u8],
+    base_offset: usize,
+    current_content: &'a [u8],
+) -> ParseResult<'a, RawAttributeInfo> {
+    let offset = base_offset + base.offset(current_content);
     let (current_content, name_index) = be_u16(current_content)?;
     let (current_content, attributes) =
         length_count(be_u32, be_u8)(current_content)?;
@@ -223,18 +322,24 @@ pub fn parse_attribute_info(
         RawAttributeInfo {
             attribute_name_index: (name_index),
             info: (attributes),
+            offset,
         },
     ))
 }
 
-fn parse_field_info(current_content: &[u8]) -> IResult<&[u8], RawFieldInfo> {
-    let (current_content, access_flag_byte) = be_u16(current_content)?;
-    let field_access_flags =
-        BitFlags::<FieldAccessFlag>::from_bits(access_flag_byte).unwrap();
+fn parse_field_info<'a>(
+    content: &'a [u8],
+    current_content: &'a [u8],
+) -> ParseResult<'a, RawFieldInfo> {
+    let offset = content.offset(current_content);
+    let (current_content, field_access_flags) =
+        parse_access_flags::<FieldAccessFlag>(current_content)?;
     let (current_content, name_index) = be_u16(current_content)?;
     let (current_content, descriptor_index) = be_u16(current_content)?;
     let (current_content, attributes) =
-        length_count(be_u16, parse_attribute_info)(current_content)?;
+        length_count(be_u16, |c| parse_attribute_info(content, 0, c))(
+            current_content,
+        )?;
     Ok((
         current_content,
         RawFieldInfo {
@@ -242,18 +347,24 @@ fn parse_field_info(current_content: &[u8]) -> IResult<&[u8], RawFieldInfo> {
             name_index: (name_index),
             descriptor_index: (descriptor_index),
             attributes: (attributes),
+            offset,
         },
     ))
 }
 
-fn parse_method_info(current_content: &[u8]) -> IResult<&[u8], RawMethodInfo> {
-    let (current_content, access_flag_byte) = be_u16(current_content)?;
-    let method_access_flags =
-        BitFlags::<MethodAccessFlag>::from_bits(access_flag_byte).unwrap();
+fn parse_method_info<'a>(
+    content: &'a [u8],
+    current_content: &'a [u8],
+) -> ParseResult<'a, RawMethodInfo> {
+    let offset = content.offset(current_content);
+    let (current_content, method_access_flags) =
+        parse_access_flags::<MethodAccessFlag>(current_content)?;
     let (current_content, name_index) = be_u16(current_content)?;
     let (current_content, descriptor_index) = be_u16(current_content)?;
     let (current_content, attributes) =
-        length_count(be_u16, parse_attribute_info)(current_content)?;
+        length_count(be_u16, |c| parse_attribute_info(content, 0, c))(
+            current_content,
+        )?;
     Ok((
         current_content,
         RawMethodInfo {
@@ -261,20 +372,34 @@ fn parse_method_info(current_content: &[u8]) -> IResult<&[u8], RawMethodInfo> {
             name_index: (name_index),
             descriptor_index: (descriptor_index),
             attributes: (attributes),
+            offset,
         },
     ))
 }
 
-fn parse_constant_pool(current_content: &[u8]) -> IResult<&[u8], Vec<CpInfo>> {
+/// Returns the constant pool entries
+/// and their offsets in the class file `content`.
+fn parse_constant_pool<'a>(
+    content: &'a [u8],
+    current_content: &'a [u8],
+) -> ParseResult<'a, (Vec<CpInfo>, Vec<usize>)> {
     let mut items = Vec::new();
+    let mut offsets = Vec::new();
 
-    let (current_content, mut count) = be_u16(current_content)?;
+    let count_content = current_content;
+    let (current_content, count) = be_u16(current_content)?;
     // cp length is given as item count + 1
-    count -= 1;
+    let Some(count) = count.checked_sub(1) else {
+        return ParseError::fail(
+            count_content,
+            "invalid constant pool count 0",
+        );
+    };
 
     let mut current_content = current_content;
     let mut index = 0;
     while index < count {
+        let offset = content.offset(current_content);
         let cp_item = {
             let (new_content, cp_item) =
                 parse_constant_pool_item(current_content)?;
@@ -286,44 +411,63 @@ fn parse_constant_pool(current_content: &[u8]) -> IResult<&[u8], Vec<CpInfo>> {
             || matches!(cp_item, CpInfo::DoubleInfo(_));
 
         items.push(cp_item);
+        offsets.push(offset);
         if takes_two_slots {
             items.push(CpInfo::Reserved);
+            offsets.push(offset);
             index += 1;
         }
 
         index += 1;
     }
 
-    Ok((current_content, items))
+    Ok((current_content, (items, offsets)))
 }
 
-fn parse_class_file(current_content: &[u8]) -> IResult<&[u8], RawClassFile> {
-    let current_content = tag(b"\xCA\xFE\xBA\xBE")(current_content)?.0;
+fn parse_class_file(content: &[u8]) -> ParseResult<RawClassFile> {
+    let Ok((current_content, _)) = tag::<_, _, ParseError>(MAGIC)(content)
+    else {
+        if MAGIC.starts_with(content) {
+            return ParseError::fail(
+                &content[content.len()..],
+                "truncated class file",
+            );
+        }
+        return ParseError::fail(content, "invalid magic number");
+    };
     let (current_content, minor_version) = be_u16(current_content)?;
     let (current_content, major_version) = be_u16(current_content)?;
-    let (current_content, constant_pool) =
-        parse_constant_pool(current_content)?;
-    let (current_content, access_flag_byte) = be_u16(current_content)?;
-    let access_flags =
-        BitFlags::<ClassAccessFlag>::from_bits(access_flag_byte).unwrap();
+    let (current_content, (constant_pool, constant_pool_offsets)) =
+        parse_constant_pool(content, current_content)?;
+    let (current_content, access_flags) =
+        parse_access_flags::<ClassAccessFlag>(current_content)?;
+    let this_class_offset = content.offset(current_content);
     let (current_content, this_class) = be_u16(current_content)?;
     let (current_content, super_class) = be_u16(current_content)?;
     let (current_content, interfaces) =
         length_count(be_u16, be_u16)(current_content)?;
     let (current_content, fields) =
-        length_count(be_u16, parse_field_info)(current_content)?;
+        length_count(be_u16, |c| parse_field_info(content, c))(
+            current_content,
+        )?;
     let (current_content, methods) =
-        length_count(be_u16, parse_method_info)(current_content)?;
+        length_count(be_u16, |c| parse_method_info(content, c))(
+            current_content,
+        )?;
     let (current_content, attributes) =
-        length_count(be_u16, parse_attribute_info)(current_content)?;
+        length_count(be_u16, |c| parse_attribute_info(content, 0, c))(
+            current_content,
+        )?;
     Ok((
         current_content,
         RawClassFile {
             minor_version,
             major_version,
             constant_pool,
+            constant_pool_offsets,
             access_flags,
             this_class,
+            this_class_offset,
             super_class,
             interfaces,
             fields,
@@ -334,11 +478,16 @@ fn parse_class_file(current_content: &[u8]) -> IResult<&[u8], RawClassFile> {
 }
 
 pub fn parse<P: AsRef<Path>>(path_to_file: P) -> anyhow::Result<RawClassFile> {
+    let path = path_to_file.as_ref();
     // read input and magic number
-    let content =
-        std::fs::read(path_to_file).context("File can not be read")?;
-    match parse_class_file(&content) {
+    let content = std::fs::read(path).context("File can not be read")?;
+    Ok(parse_bytes(&content)
+        .map_err(|e| e.in_file(path.display().to_string()))?)
+}
+
+pub fn parse_bytes(content: &[u8]) -> Result<RawClassFile, ClassFormatError> {
+    match parse_class_file(content) {
         Ok((_, class_file)) => Ok(class_file),
-        Err(e) => Err(e.to_owned().into()),
+        Err(e) => Err(ParseError::into_class_format_error(e, content, 0)),
     }
 }
@@ -0,0 +1,903 @@
+//! Structural checks of a parsed class file (JVMS §4.8),
+//! done before the class is created, so that malformed classes
+//! are rejected with a [`ClassFormatError`] instead of failing at runtime.
+//!
+//! Branch targets and exception handlers are checked
+//! against the instruction boundaries when the code is parsed,
+//! see [`parse_opcodes`](super::opcode_parser::parse_opcodes).
+
+use std::collections::HashSet;
+
+use enumflags2::{BitFlag, BitFlags};
+
+use crate::{
+    class::access_flags::{ClassAccessFlag, FieldAccessFlag, MethodAccessFlag},
+    classloader::{
+        class_creator::signature_parser::{
+            is_class_name, is_field_descriptor, is_method_descriptor,
+            is_method_name, is_unqualified_name, parameter_slots,
+        },
+        constant_pool::{get_class_name, get_entry, get_utf8, CpInfo},
+        AttributeInfo, ClassFile, ClassFormatError, CodeAttribute, FieldInfo,
+        MethodInfo, ReferenceKind,
+    },
+};
+
+/// The supported major versions, i.e. Java 1.1 to Java 21.
+const SUPPORTED_MAJOR_VERSIONS: std::ops::RangeInclusive<u16> = 45..=65;
+
+/// The first major version (Java 12), whose minor version has to be 0,
+/// unless preview features (which aren't supported) are used.
+const MINOR_VERSION_ZERO: u16 = 56;
+
+/// Offset of `minor_version` in the class file, followed by `major_version`.
+const VERSION_OFFSET: usize = 4;
+
+// opcodes, whose operands are checked or have a length other than one byte
+const BIPUSH: u8 = 0x10;
+const SIPUSH: u8 = 0x11;
+const LDC: u8 = 0x12;
+const LDC_W: u8 = 0x13;
+const LDC2_W: u8 = 0x14;
+const ILOAD: u8 = 0x15;
+const ALOAD: u8 = 0x19;
+const ISTORE: u8 = 0x36;
+const ASTORE: u8 = 0x3a;
+const IINC: u8 = 0x84;
+const IFEQ: u8 = 0x99;
+const JSR: u8 = 0xa8;
+const RET: u8 = 0xa9;
+const TABLESWITCH: u8 = 0xaa;
+const LOOKUPSWITCH: u8 = 0xab;
+const GETSTATIC: u8 = 0xb2;
+const PUTFIELD: u8 = 0xb5;
+const INVOKEVIRTUAL: u8 = 0xb6;
+const INVOKESPECIAL: u8 = 0xb7;
+const INVOKESTATIC: u8 = 0xb8;
+const INVOKEINTERFACE: u8 = 0xb9;
+const INVOKEDYNAMIC: u8 = 0xba;
+const NEW: u8 = 0xbb;
+const NEWARRAY: u8 = 0xbc;
+const ANEWARRAY: u8 = 0xbd;
+const CHECKCAST: u8 = 0xc0;
+const INSTANCEOF: u8 = 0xc1;
+const WIDE: u8 = 0xc4;
+const MULTIANEWARRAY: u8 = 0xc5;
+const IFNULL: u8 = 0xc6;
+const IFNONNULL: u8 = 0xc7;
+const GOTO_W: u8 = 0xc8;
+const JSR_W: u8 = 0xc9;
+
+/// Check that `class_file` is well-formed.
+pub fn check_format(class_file: &ClassFile) -> Result<(), ClassFormatError> {
+    check_version(class_file)?;
+    check_constant_pool(class_file)?;
+    check_class(class_file)?;
+
+    let mut fields = HashSet::new();
+    for field in &class_file.fields {
+        let (name, descriptor) = check_field(class_file, field)?;
+        if !fields.insert((name, descriptor)) {
+            return Err(ClassFormatError::at(
+                field.offset,
+                format!("duplicate field {} {}", name, descriptor),
+            ));
+        }
+    }
+
+    let mut methods = HashSet::new();
+    for method in &class_file.methods {
+        let (name, descriptor) = check_method(class_file, method)?;
+        if !methods.insert((name, descriptor)) {
+            return Err(ClassFormatError::at(
+                method.offset,
+                format!("duplicate method {}{}", name, descriptor),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_version(class_file: &ClassFile) -> Result<(), ClassFormatError> {
+    if SUPPORTED_MAJOR_VERSIONS.contains(&class_file.major_version)
+        && (class_file.major_version < MINOR_VERSION_ZERO
+            || class_file.minor_version == 0)
+    {
+        Ok(())
+    } else {
+        Err(ClassFormatError::at(
+            VERSION_OFFSET,
+            format!(
+                "unsupported class file version {}.{}",
+                class_file.major_version, class_file.minor_version
+            ),
+        ))
+    }
+}
+
+fn illegal_flags<F: BitFlag<Numeric = u16>>(
+    offset: usize,
+    flags: BitFlags<F>,
+    owner: &str,
+) -> ClassFormatError {
+    ClassFormatError::at(
+        offset,
+        format!("illegal access flags 0x{:04x} of {}", flags.bits(), owner),
+    )
+}
+
+/// Returns the name and descriptor of the `NameAndType` entry `index`.
+fn get_name_and_type(
+    constant_pool: &[CpInfo],
+    index: u16,
+    offset: usize,
+) -> Result<(&str, &str), ClassFormatError> {
+    let (name_index, descriptor_index) = get_entry(
+        constant_pool,
+        index,
+        offset,
+        "NameAndType",
+        CpInfo::as_name_and_type_info,
+    )?;
+    Ok((
+        get_utf8(constant_pool, name_index, offset)?,
+        get_utf8(constant_pool, descriptor_index, offset)?,
+    ))
+}
+
+/// Checks the entries referenced by a `Fieldref`, `Methodref`
+/// or `InterfaceMethodref` entry and returns the name of the member.
+fn check_member_ref(
+    constant_pool: &[CpInfo],
+    (class_index, name_and_type_index): (u16, u16),
+    offset: usize,
+    is_field: bool,
+) -> Result<&str, ClassFormatError> {
+    get_class_name(constant_pool, class_index, offset)?;
+    let (name, descriptor) =
+        get_name_and_type(constant_pool, name_and_type_index, offset)?;
+    let valid = if is_field {
+        is_unqualified_name(name) && is_field_descriptor(descriptor)
+    } else {
+        is_method_name(name)
+            && name != "<clinit>"
+            && is_method_descriptor(descriptor)
+            && (name != "<init>" || descriptor.ends_with(")V"))
+    };
+    if valid {
+        Ok(name)
+    } else {
+        Err(ClassFormatError::at(
+            offset,
+            format!("invalid member reference {} {}", name, descriptor),
+        ))
+    }
+}
+
+fn check_constant_pool(class_file: &ClassFile) -> Result<(), ClassFormatError> {
+    let constant_pool = class_file.constant_pool.as_slice();
+    for (entry, &offset) in constant_pool
+        .iter()
+        .zip(class_file.constant_pool_offsets.iter())
+    {
+        match *entry {
+            CpInfo::ClassInfo { name_index } => {
+                let name = get_utf8(constant_pool, name_index, offset)?;
+                let valid = if name.starts_with('[') {
+                    is_field_descriptor(name)
+                } else {
+                    is_class_name(name)
+                };
+                if !valid {
+                    return Err(ClassFormatError::at(
+                        offset,
+                        format!("invalid class name {}", name),
+                    ));
+                }
+            },
+            CpInfo::FieldRefInfo {
+                class_index,
+                name_and_type_index,
+            } => {
+                check_member_ref(
+                    constant_pool,
+                    (class_index, name_and_type_index),
+                    offset,
+                    true,
+                )?;
+            },
+            CpInfo::MethodRefInfo {
+                class_index,
+                name_and_type_index,
+            } => {
+                check_member_ref(
+                    constant_pool,
+                    (class_index, name_and_type_index),
+                    offset,
+                    false,
+                )?;
+            },
+            CpInfo::InterfaceMethodRefInfo {
+                class_index,
+                name_and_type_index,
+            } => {
+                let name = check_member_ref(
+                    constant_pool,
+                    (class_index, name_and_type_index),
+                    offset,
+                    false,
+                )?;
+                if name == "<init>" {
+                    return Err(ClassFormatError::at(
+                        offset,
+                        "interface method reference to <init>",
+                    ));
+                }
+            },
+            CpInfo::StringInfo { string_index } => {
+                get_utf8(constant_pool, string_index, offset)?;
+            },
+            CpInfo::NameAndTypeInfo {
+                name_index,
+                descriptor_index,
+            } => {
+                get_utf8(constant_pool, name_index, offset)?;
+                get_utf8(constant_pool, descriptor_index, offset)?;
+            },
+            CpInfo::MethodHandleInfo {
+                reference_kind,
+                reference_index,
+            } => check_method_handle(
+                class_file,
+                reference_kind,
+                reference_index,
+                offset,
+            )?,
+            CpInfo::MethodTypeInfo { descriptor_index } => {
+                let descriptor =
+                    get_utf8(constant_pool, descriptor_index, offset)?;
+                if !is_method_descriptor(descriptor) {
+                    return Err(ClassFormatError::at(
+                        offset,
+                        format!("invalid method descriptor {}", descriptor),
+                    ));
+                }
+            },
+            CpInfo::DynamicInfo {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                check_tag_version(class_file, "Dynamic", 55, offset)?;
+                let (name, descriptor) = check_dynamic(
+                    class_file,
+                    bootstrap_method_attr_index,
+                    name_and_type_index,
+                    offset,
+                )?;
+                if !is_field_descriptor(descriptor) {
+                    return Err(ClassFormatError::at(
+                        offset,
+                        format!(
+                            "invalid dynamic constant {} {}",
+                            name, descriptor
+                        ),
+                    ));
+                }
+            },
+            CpInfo::InvokeDynamicInfo {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                let (name, descriptor) = check_dynamic(
+                    class_file,
+                    bootstrap_method_attr_index,
+                    name_and_type_index,
+                    offset,
+                )?;
+                if !is_method_descriptor(descriptor) {
+                    return Err(ClassFormatError::at(
+                        offset,
+                        format!("invalid call site {} {}", name, descriptor),
+                    ));
+                }
+            },
+            CpInfo::ModuleInfo { name_index }
+            | CpInfo::PackageInfo { name_index } => {
+                let kind = if entry.as_module_info().is_some() {
+                    "Module"
+                } else {
+                    "Package"
+                };
+                check_tag_version(class_file, kind, 53, offset)?;
+                if !class_file.access_flags.contains(ClassAccessFlag::Module) {
+                    return Err(ClassFormatError::at(
+                        offset,
+                        "module constant outside of a module descriptor",
+                    ));
+                }
+                get_utf8(constant_pool, name_index, offset)?;
+            },
+            CpInfo::IntegerInfo(_)
+            | CpInfo::FloatInfo(_)
+            | CpInfo::LongInfo(_)
+            | CpInfo::DoubleInfo(_)
+            | CpInfo::Reserved
+            | CpInfo::UTF8INFO(_) => (),
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the constant pool tag `kind` at `offset`
+/// may be used by the version of `class_file`.
+fn check_tag_version(
+    class_file: &ClassFile,
+    kind: &str,
+    major_version: u16,
+    offset: usize,
+) -> Result<(), ClassFormatError> {
+    if class_file.major_version >= major_version {
+        Ok(())
+    } else {
+        Err(ClassFormatError::at(
+            offset,
+            format!(
+                "{} constant requires class file version {}",
+                kind, major_version
+            ),
+        ))
+    }
+}
+
+/// Checks a `Dynamic` or `InvokeDynamic` entry
+/// and returns its name and descriptor.
+fn check_dynamic(
+    class_file: &ClassFile,
+    bootstrap_method_attr_index: u16,
+    name_and_type_index: u16,
+    offset: usize,
+) -> Result<(&str, &str), ClassFormatError> {
+    let bootstrap_methods = class_file
+        .attributes
+        .iter()
+        .find_map(|attribute| match attribute {
+            AttributeInfo::BootstrapMethods(methods) => Some(methods.len()),
+            _ => None,
+        })
+        .unwrap_or(0);
+    if usize::from(bootstrap_method_attr_index) >= bootstrap_methods {
+        return Err(ClassFormatError::at(
+            offset,
+            format!(
+                "invalid bootstrap method index {}",
+                bootstrap_method_attr_index
+            ),
+        ));
+    }
+    let (name, descriptor) = get_name_and_type(
+        &class_file.constant_pool,
+        name_and_type_index,
+        offset,
+    )?;
+    if is_unqualified_name(name) {
+        Ok((name, descriptor))
+    } else {
+        Err(ClassFormatError::at(
+            offset,
+            format!("invalid name {} of {}", name, descriptor),
+        ))
+    }
+}
+
+/// Checks that the entry referenced by a `MethodHandle` entry
+/// matches its reference kind (JVMS §4.4.8).
+fn check_method_handle(
+    class_file: &ClassFile,
+    reference_kind: ReferenceKind,
+    reference_index: u16,
+    offset: usize,
+) -> Result<(), ClassFormatError> {
+    let constant_pool = class_file.constant_pool.as_slice();
+    let entry = |kind, as_kind: fn(&CpInfo) -> Option<(u16, u16)>| {
+        get_entry(constant_pool, reference_index, offset, kind, as_kind)
+    };
+    let (_, name_and_type_index) = match reference_kind {
+        ReferenceKind::GetField
+        | ReferenceKind::GetStatic
+        | ReferenceKind::PutField
+        | ReferenceKind::PutStatic => {
+            entry("Fieldref", CpInfo::as_field_ref_info)?;
+            return Ok(());
+        },
+        ReferenceKind::InvokeVirtual | ReferenceKind::NewInvokeSpecial => {
+            entry("Methodref", CpInfo::as_method_ref_info)?
+        },
+        ReferenceKind::InvokeStatic | ReferenceKind::InvokeSpecial
+            if class_file.major_version >= 52 =>
+        {
+            entry("Methodref", |e| {
+                e.as_method_ref_info().or_else(|| e.as_interface_ref_info())
+            })?
+        },
+        ReferenceKind::InvokeStatic | ReferenceKind::InvokeSpecial => {
+            entry("Methodref", CpInfo::as_method_ref_info)?
+        },
+        ReferenceKind::InvokeInterface => {
+            entry("InterfaceMethodref", CpInfo::as_interface_ref_info)?
+        },
+    };
+    let (name, _) =
+        get_name_and_type(constant_pool, name_and_type_index, offset)?;
+    let is_constructor = name == "<init>";
+    if is_constructor
+        == matches!(reference_kind, ReferenceKind::NewInvokeSpecial)
+    {
+        Ok(())
+    } else {
+        Err(ClassFormatError::at(
+            offset,
+            format!("invalid method handle to {}", name),
+        ))
+    }
+}
+
+fn check_class(class_file: &ClassFile) -> Result<(), ClassFormatError> {
+    let constant_pool = class_file.constant_pool.as_slice();
+    let this_class_offset = class_file.this_class_offset;
+    let name = get_class_name(
+        constant_pool,
+        class_file.this_class,
+        this_class_offset,
+    )?;
+
+    let flags = class_file.access_flags;
+    if flags.contains(ClassAccessFlag::Module) {
+        return check_module(class_file, name);
+    }
+    let is_interface = flags.contains(ClassAccessFlag::Interface);
+    let legal = if is_interface {
+        flags.contains(ClassAccessFlag::Abstract)
+            && !flags.intersects(
+                ClassAccessFlag::Final
+                    | ClassAccessFlag::Super
+                    | ClassAccessFlag::Enum,
+            )
+    } else {
+        !flags.contains(ClassAccessFlag::Annotation)
+            && !flags
+                .contains(ClassAccessFlag::Final | ClassAccessFlag::Abstract)
+    };
+    if !legal {
+        // the access flags precede `this_class`
+        return Err(illegal_flags(this_class_offset - 2, flags, name));
+    }
+
+    let super_class_offset = this_class_offset + 2;
+    if class_file.super_class == 0 {
+        if name != "java/lang/Object" {
+            return Err(ClassFormatError::at(
+                super_class_offset,
+                "missing super class",
+            ));
+        }
+    } else {
+        let super_class = get_class_name(
+            constant_pool,
+            class_file.super_class,
+            super_class_offset,
+        )?;
+        if super_class.starts_with('[')
+            || (is_interface && super_class != "java/lang/Object")
+        {
+            return Err(ClassFormatError::at(
+                super_class_offset,
+                format!("invalid super class {}", super_class),
+            ));
+        }
+    }
+
+    // after `super_class` and `interfaces_count`
+    let interfaces_offset = this_class_offset + 6;
+    for (i, &interface) in class_file.interfaces.iter().enumerate() {
+        let offset = interfaces_offset + 2 * i;
+        let interface = get_class_name(constant_pool, interface, offset)?;
+        if interface.starts_with('[') {
+            return Err(ClassFormatError::at(
+                offset,
+                format!("invalid interface {}", interface),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks a module descriptor, which has no members (JVMS §4.1).
+fn check_module(
+    class_file: &ClassFile,
+    name: &str,
+) -> Result<(), ClassFormatError> {
+    let this_class_offset = class_file.this_class_offset;
+    if class_file.access_flags != ClassAccessFlag::Module
+        || name != "module-info"
+    {
+        return Err(illegal_flags(
+            this_class_offset - 2,
+            class_file.access_flags,
+            name,
+        ));
+    }
+    if class_file.super_class != 0
+        || !class_file.interfaces.is_empty()
+        || !class_file.fields.is_empty()
+        || !class_file.methods.is_empty()
+    {
+        return Err(ClassFormatError::at(
+            this_class_offset + 2,
+            "module descriptor with super class, interfaces or members",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks `field` and returns its name and descriptor.
+fn check_field<'a>(
+    class_file: &'a ClassFile,
+    field: &FieldInfo,
+) -> Result<(&'a str, &'a str), ClassFormatError> {
+    let constant_pool = class_file.constant_pool.as_slice();
+    let name = get_utf8(constant_pool, field.name_index, field.offset)?;
+    let descriptor =
+        get_utf8(constant_pool, field.descriptor_index, field.offset)?;
+    if !is_unqualified_name(name) {
+        return Err(ClassFormatError::at(
+            field.offset,
+            format!("invalid field name {}", name),
+        ));
+    }
+    if !is_field_descriptor(descriptor) {
+        return Err(ClassFormatError::at(
+            field.offset,
+            format!("invalid field descriptor {}", descriptor),
+        ));
+    }
+
+    let flags = field.access_flags;
+    let visibility = flags
+        & (FieldAccessFlag::Public
+            | FieldAccessFlag::Private
+            | FieldAccessFlag::Protected);
+    let legal = if class_file.access_flags.contains(ClassAccessFlag::Interface)
+    {
+        let constant = FieldAccessFlag::Public
+            | FieldAccessFlag::Static
+            | FieldAccessFlag::Final;
+        flags.contains(constant)
+            && (flags & !(constant | FieldAccessFlag::Synthetic)).is_empty()
+    } else {
+        visibility.iter().count() <= 1
+            && !flags
+                .contains(FieldAccessFlag::Final | FieldAccessFlag::Volatile)
+    };
+    if legal {
+        Ok((name, descriptor))
+    } else {
+        Err(illegal_flags(field.offset, flags, name))
+    }
+}
+
+/// Checks `method` and returns its name and descriptor.
+fn check_method<'a>(
+    class_file: &'a ClassFile,
+    method: &MethodInfo,
+) -> Result<(&'a str, &'a str), ClassFormatError> {
+    let constant_pool = class_file.constant_pool.as_slice();
+    let name = get_utf8(constant_pool, method.name_index, method.offset)?;
+    let descriptor =
+        get_utf8(constant_pool, method.descriptor_index, method.offset)?;
+    if !is_method_name(name) {
+        return Err(ClassFormatError::at(
+            method.offset,
+            format!("invalid method name {}", name),
+        ));
+    }
+    let is_initializer = name == "<init>" || name == "<clinit>";
+    if !is_method_descriptor(descriptor)
+        || (is_initializer && !descriptor.ends_with(")V"))
+        || (name == "<clinit>" && descriptor != "()V")
+    {
+        return Err(ClassFormatError::at(
+            method.offset,
+            format!("invalid method descriptor {} of {}", descriptor, name),
+        ));
+    }
+
+    let flags = method.access_flags;
+    if !legal_method_flags(class_file, name, flags) {
+        return Err(illegal_flags(method.offset, flags, name));
+    }
+
+    let mut code_attributes = method
+        .attributes
+        .iter()
+        .filter_map(AttributeInfo::as_code_attribute);
+    let code = code_attributes.next();
+    if code_attributes.next().is_some() {
+        return Err(ClassFormatError::at(
+            method.offset,
+            format!("duplicate Code attribute of {}", name),
+        ));
+    }
+    let without_code =
+        flags.intersects(MethodAccessFlag::Native | MethodAccessFlag::Abstract);
+    match code {
+        Some(_) if without_code => Err(ClassFormatError::at(
+            method.offset,
+            format!("unexpected Code attribute of {}", name),
+        )),
+        None if !without_code => Err(ClassFormatError::at(
+            method.offset,
+            format!("missing Code attribute of {}", name),
+        )),
+        Some(code) => {
+            let mut parameter_slots = parameter_slots(descriptor);
+            if !flags.contains(MethodAccessFlag::Static) {
+                parameter_slots += 1;
+            }
+            check_code(class_file, code, parameter_slots)?;
+            Ok((name, descriptor))
+        },
+        None => Ok((name, descriptor)),
+    }
+}
+
+/// Checks the access flags of the method `name` (JVMS §4.6).
+fn legal_method_flags(
+    class_file: &ClassFile,
+    name: &str,
+    flags: BitFlags<MethodAccessFlag>,
+) -> bool {
+    if name == "<clinit>" {
+        // other flags are ignored
+        return class_file.major_version < 51
+            || flags.contains(MethodAccessFlag::Static);
+    }
+    let visibility = flags
+        & (MethodAccessFlag::Public
+            | MethodAccessFlag::Private
+            | MethodAccessFlag::Protected);
+    if visibility.iter().count() > 1 {
+        return false;
+    }
+    let is_interface =
+        class_file.access_flags.contains(ClassAccessFlag::Interface);
+    if name == "<init>" {
+        let allowed = visibility
+            | MethodAccessFlag::Vargs
+            | MethodAccessFlag::Strict
+            | MethodAccessFlag::Synthetic;
+        return !is_interface && (flags & !allowed).is_empty();
+    }
+    if is_interface {
+        let interface_flags = if class_file.major_version < 52 {
+            flags
+                .contains(MethodAccessFlag::Public | MethodAccessFlag::Abstract)
+        } else {
+            flags.intersects(
+                MethodAccessFlag::Public | MethodAccessFlag::Private,
+            )
+        };
+        if !interface_flags
+            || flags.intersects(
+                MethodAccessFlag::Protected
+                    | MethodAccessFlag::Final
+                    | MethodAccessFlag::Synchronized
+                    | MethodAccessFlag::Native,
+            )
+        {
+            return false;
+        }
+    }
+    !flags.contains(MethodAccessFlag::Abstract)
+        || !flags.intersects(
+            MethodAccessFlag::Private
+                | MethodAccessFlag::Static
+                | MethodAccessFlag::Final
+                | MethodAccessFlag::Synchronized
+                | MethodAccessFlag::Native
+                | MethodAccessFlag::Strict,
+        )
+}
+
+/// Checks the invariants of the `Code` attribute (JVMS §4.7.3)
+/// of a method whose parameters take `parameter_slots` local variables.
+fn check_code(
+    class_file: &ClassFile,
+    code: &CodeAttribute,
+    parameter_slots: usize,
+) -> Result<(), ClassFormatError> {
+    let code_length = code.code.len();
+    if code_length == 0 || code_length >= 65536 {
+        // the length precedes the code
+        return Err(ClassFormatError::at(
+            code.code_offset - 4,
+            format!("invalid code length {}", code_length),
+        ));
+    }
+    if usize::from(code.max_locals) < parameter_slots {
+        // max_locals precedes the length
+        return Err(ClassFormatError::at(
+            code.code_offset - 6,
+            format!(
+                "max_locals {} is less than the {} parameter slots",
+                code.max_locals, parameter_slots
+            ),
+        ));
+    }
+
+    // after the code and exception_table_length
+    let exception_table_offset = code.code_offset + code_length + 2;
+    for (i, exception) in code.exception_table.iter().enumerate() {
+        let offset = exception_table_offset + 8 * i;
+        let (start, end, handler) = (
+            usize::from(exception.start_pc),
+            usize::from(exception.end_pc),
+            usize::from(exception.handler_pc),
+        );
+        if start >= end || end > code_length || handler >= code_length {
+            return Err(ClassFormatError::at(
+                offset,
+                format!(
+                    "invalid exception handler {}..{} -> {}",
+                    start, end, handler
+                ),
+            ));
+        }
+        if exception.catch_type != 0 {
+            get_class_name(
+                &class_file.constant_pool,
+                exception.catch_type,
+                offset,
+            )?;
+        }
+    }
+    check_operands(class_file, code)
+}
+
+/// Checks that the constant pool operands of the instructions in `code`
+/// reference entries of the kinds the instructions expect (JVMS §4.9.1).
+///
+/// The walk stops at the first invalid or truncated instruction,
+/// which is reported when the code is parsed.
+fn check_operands(
+    class_file: &ClassFile,
+    code: &CodeAttribute,
+) -> Result<(), ClassFormatError> {
+    let constant_pool = class_file.constant_pool.as_slice();
+    let bytes = code.code.as_slice();
+    let mut pc = 0;
+    while let Some(length) = instruction_length(bytes, pc) {
+        let opcode = bytes[pc];
+        let offset = code.code_offset + pc;
+        let index = if opcode == LDC {
+            u16::from(bytes[pc + 1])
+        } else if length >= 3 {
+            u16::from_be_bytes([bytes[pc + 1], bytes[pc + 2]])
+        } else {
+            0
+        };
+        let entry = |kind, as_kind: fn(&CpInfo) -> Option<(u16, u16)>| {
+            get_entry(constant_pool, index, offset, kind, as_kind).map(drop)
+        };
+        match opcode {
+            LDC | LDC_W | LDC2_W => {
+                let wide = opcode == LDC2_W;
+                let kind = if wide {
+                    "long or double constant"
+                } else {
+                    "loadable constant"
+                };
+                get_entry(constant_pool, index, offset, kind, |entry| {
+                    (is_wide_constant(constant_pool, entry)? == wide)
+                        .then_some(())
+                })?;
+            },
+            GETSTATIC..=PUTFIELD => {
+                entry("Fieldref", CpInfo::as_field_ref_info)?;
+            },
+            INVOKEVIRTUAL => entry("Methodref", CpInfo::as_method_ref_info)?,
+            INVOKESPECIAL | INVOKESTATIC if class_file.major_version >= 52 => {
+                entry("Methodref or InterfaceMethodref", |entry| {
+                    entry
+                        .as_method_ref_info()
+                        .or_else(|| entry.as_interface_ref_info())
+                })?;
+            },
+            INVOKESPECIAL | INVOKESTATIC => {
+                entry("Methodref", CpInfo::as_method_ref_info)?;
+            },
+            INVOKEINTERFACE => {
+                entry("InterfaceMethodref", CpInfo::as_interface_ref_info)?;
+            },
+            INVOKEDYNAMIC => {
+                entry("InvokeDynamic", CpInfo::as_invoke_dynamic_info)?;
+            },
+            NEW | ANEWARRAY | CHECKCAST | INSTANCEOF | MULTIANEWARRAY => {
+                get_class_name(constant_pool, index, offset)?;
+            },
+            _ => (),
+        }
+        pc += length;
+    }
+    Ok(())
+}
+
+/// Returns whether the loadable constant `entry` takes two slots
+/// on the operand stack, i.e. is a long or a double,
+/// or `None` if `entry` isn't loadable (JVMS §4.4).
+fn is_wide_constant(constant_pool: &[CpInfo], entry: &CpInfo) -> Option<bool> {
+    match entry {
+        CpInfo::IntegerInfo(_)
+        | CpInfo::FloatInfo(_)
+        | CpInfo::StringInfo { .. }
+        | CpInfo::ClassInfo { .. }
+        | CpInfo::MethodHandleInfo { .. }
+        | CpInfo::MethodTypeInfo { .. } => Some(false),
+        CpInfo::LongInfo(_) | CpInfo::DoubleInfo(_) => Some(true),
+        CpInfo::DynamicInfo {
+            name_and_type_index,
+            ..
+        } => {
+            let entry = |index: u16| {
+                constant_pool.get(usize::from(index).checked_sub(1)?)
+            };
+            let (_, descriptor_index) =
+                entry(*name_and_type_index)?.as_name_and_type_info()?;
+            let descriptor = entry(descriptor_index)?.as_utf8_info()?;
+            Some(descriptor == "J" || descriptor == "D")
+        },
+        _ => None,
+    }
+}
+
+/// Returns the length of the instruction at `pc` in `code`,
+/// or `None` if there is no valid instruction.
+fn instruction_length(code: &[u8], pc: usize) -> Option<usize> {
+    let int = |offset: usize| -> Option<i32> {
+        let bytes = code.get(offset..offset + 4)?;
+        Some(i32::from_be_bytes(bytes.try_into().ok()?))
+    };
+    let length = match *code.get(pc)? {
+        BIPUSH | LDC | ILOAD..=ALOAD | ISTORE..=ASTORE | RET | NEWARRAY => 2,
+        SIPUSH
+        | LDC_W
+        | LDC2_W
+        | IINC
+        | IFEQ..=JSR
+        | GETSTATIC..=INVOKESTATIC
+        | NEW
+        | ANEWARRAY
+        | CHECKCAST
+        | INSTANCEOF
+        | IFNULL
+        | IFNONNULL => 3,
+        MULTIANEWARRAY => 4,
+        INVOKEINTERFACE | INVOKEDYNAMIC | GOTO_W | JSR_W => 5,
+        WIDE if *code.get(pc + 1)? == IINC => 6,
+        WIDE => 4,
+        TABLESWITCH | LOOKUPSWITCH => {
+            // the operands are aligned to a multiple of four bytes
+            let operands = pc + 1 + (4 - (pc + 1) % 4) % 4;
+            let targets = if code[pc] == TABLESWITCH {
+                let (low, high) = (int(operands + 4)?, int(operands + 8)?);
+                3 + usize::try_from(i64::from(high) - i64::from(low) + 1)
+                    .ok()?
+            } else {
+                2 + 2 * usize::try_from(int(operands + 4)?).ok()?
+            };
+            operands - pc + 4 * targets
+        },
+        0x00..=0xc9 => 1,
+        _ => return None,
+    };
+    (pc + length <= code.len()).then_some(length)
+}
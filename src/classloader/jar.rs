@@ -0,0 +1,257 @@
+//! Reading JAR archives, i.e. ZIP files with an optional
+//! `META-INF/MANIFEST.MF`.
+//!
+//! Only what is needed to serve classes is supported:
+//! stored and deflated entries of single disk archives (no ZIP64).
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context};
+use nom::{
+    bytes::complete::{tag, take},
+    number::complete::{le_u16, le_u32},
+    IResult,
+};
+
+const END_OF_CENTRAL_DIRECTORY: &[u8] = b"PK\x05\x06";
+const CENTRAL_DIRECTORY_ENTRY: &[u8] = b"PK\x01\x02";
+const LOCAL_FILE_HEADER: &[u8] = b"PK\x03\x04";
+/// Length of the end of central directory record without the comment.
+const END_OF_CENTRAL_DIRECTORY_LENGTH: usize = 22;
+
+const COMPRESSION_STORED: u16 = 0;
+const COMPRESSION_DEFLATE: u16 = 8;
+const FLAG_ENCRYPTED: u16 = 1;
+
+const MANIFEST: &str = "META-INF/MANIFEST.MF";
+
+#[derive(Debug)]
+struct JarEntry {
+    flags: u16,
+    compression_method: u16,
+    compressed_size: usize,
+    uncompressed_size: usize,
+    local_header_offset: usize,
+}
+
+/// An opened JAR archive.
+#[derive(Debug)]
+pub struct JarFile {
+    path: PathBuf,
+    content: Vec<u8>,
+    entries: HashMap<String, JarEntry>,
+}
+
+/// The main section of `META-INF/MANIFEST.MF`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Manifest {
+    /// `Main-Class`, e.g. `org.example.Main`
+    pub main_class: Option<String>,
+    /// `Class-Path`, URLs relative to the directory of the JAR
+    pub class_path: Vec<String>,
+}
+
+struct EndOfCentralDirectory {
+    entry_count: u16,
+    offset: u32,
+}
+
+fn parse_end_of_central_directory(
+    content: &[u8],
+) -> IResult<&[u8], EndOfCentralDirectory> {
+    let (content, _) = tag(END_OF_CENTRAL_DIRECTORY)(content)?;
+    let (content, _disk) = le_u16(content)?;
+    let (content, _central_directory_disk) = le_u16(content)?;
+    let (content, _disk_entry_count) = le_u16(content)?;
+    let (content, entry_count) = le_u16(content)?;
+    let (content, _size) = le_u32(content)?;
+    let (content, offset) = le_u32(content)?;
+    Ok((
+        content,
+        EndOfCentralDirectory {
+            entry_count,
+            offset,
+        },
+    ))
+}
+
+fn parse_central_directory_entry(
+    content: &[u8],
+) -> IResult<&[u8], (String, JarEntry)> {
+    let (content, _) = tag(CENTRAL_DIRECTORY_ENTRY)(content)?;
+    let (content, _version_made_by) = le_u16(content)?;
+    let (content, _version_needed) = le_u16(content)?;
+    let (content, flags) = le_u16(content)?;
+    let (content, compression_method) = le_u16(content)?;
+    let (content, _modification_time) = le_u16(content)?;
+    let (content, _modification_date) = le_u16(content)?;
+    let (content, _crc32) = le_u32(content)?;
+    let (content, compressed_size) = le_u32(content)?;
+    let (content, uncompressed_size) = le_u32(content)?;
+    let (content, name_length) = le_u16(content)?;
+    let (content, extra_length) = le_u16(content)?;
+    let (content, comment_length) = le_u16(content)?;
+    let (content, _disk) = le_u16(content)?;
+    let (content, _internal_attributes) = le_u16(content)?;
+    let (content, _external_attributes) = le_u32(content)?;
+    let (content, local_header_offset) = le_u32(content)?;
+    let (content, name) = take(name_length)(content)?;
+    let (content, _extra) = take(extra_length)(content)?;
+    let (content, _comment) = take(comment_length)(content)?;
+    Ok((
+        content,
+        (
+            String::from_utf8_lossy(name).into_owned(),
+            JarEntry {
+                flags,
+                compression_method,
+                compressed_size: compressed_size as usize,
+                uncompressed_size: uncompressed_size as usize,
+                local_header_offset: local_header_offset as usize,
+            },
+        ),
+    ))
+}
+
+/// Returns the length of the local file header,
+/// i.e. the offset of the entry's data relative to the header.
+fn parse_local_file_header_length(content: &[u8]) -> IResult<&[u8], usize> {
+    let (content, _) = tag(LOCAL_FILE_HEADER)(content)?;
+    // version, flags, compression, time, date, crc32 and sizes
+    let (content, _) = take(22_usize)(content)?;
+    let (content, name_length) = le_u16(content)?;
+    let (content, extra_length) = le_u16(content)?;
+    Ok((content, 30 + name_length as usize + extra_length as usize))
+}
+
+impl JarFile {
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<JarFile> {
+        let path = path.as_ref();
+        let content = std::fs::read(path)
+            .with_context(|| format!("{} can not be read", path.display()))?;
+        Self::from_bytes(path.to_path_buf(), content)
+            .with_context(|| format!("{} is no valid jar", path.display()))
+    }
+
+    fn from_bytes(path: PathBuf, content: Vec<u8>) -> anyhow::Result<JarFile> {
+        // the record is at the end, followed by a comment of at most 64 KiB
+        let search_start = content.len().saturating_sub(
+            END_OF_CENTRAL_DIRECTORY_LENGTH + u16::MAX as usize,
+        );
+        let end_offset = content[search_start..]
+            .windows(END_OF_CENTRAL_DIRECTORY.len())
+            .rposition(|window| window == END_OF_CENTRAL_DIRECTORY)
+            .map(|position| search_start + position)
+            .context("end of central directory not found")?;
+        let (_, end) = parse_end_of_central_directory(&content[end_offset..])
+            .map_err(|e| e.to_owned())?;
+        if end.entry_count == u16::MAX || end.offset == u32::MAX {
+            bail!("ZIP64 archives are not supported");
+        }
+
+        let mut entries = HashMap::new();
+        let mut central_directory = content
+            .get(end.offset as usize..)
+            .context("central directory offset out of bounds")?;
+        for _ in 0..end.entry_count {
+            let (rest, (name, entry)) =
+                parse_central_directory_entry(central_directory)
+                    .map_err(|e| e.to_owned())?;
+            entries.insert(name, entry);
+            central_directory = rest;
+        }
+
+        Ok(JarFile {
+            path,
+            content,
+            entries,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the (uncompressed) content of the entry `name`,
+    /// e.g. `org/example/Main.class`,
+    /// or `None` if the archive doesn't contain it.
+    pub fn read_entry(&self, name: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let Some(entry) = self.entries.get(name) else {
+            return Ok(None);
+        };
+        self.read(entry)
+            .with_context(|| format!("entry {} can not be read", name))
+            .map(Some)
+    }
+
+    fn read(&self, entry: &JarEntry) -> anyhow::Result<Vec<u8>> {
+        if entry.flags & FLAG_ENCRYPTED != 0 {
+            bail!("encrypted entries are not supported");
+        }
+        let header = self
+            .content
+            .get(entry.local_header_offset..)
+            .context("local header offset out of bounds")?;
+        let (_, header_length) =
+            parse_local_file_header_length(header).map_err(|e| e.to_owned())?;
+        let data = header
+            .get(header_length..header_length + entry.compressed_size)
+            .context("entry data out of bounds")?;
+
+        let data = match entry.compression_method {
+            COMPRESSION_STORED => data.to_vec(),
+            COMPRESSION_DEFLATE => {
+                miniz_oxide::inflate::decompress_to_vec(data)
+                    .map_err(|e| anyhow!("invalid deflate stream: {:?}", e))?
+            },
+            method => bail!("unsupported compression method {}", method),
+        };
+        if data.len() != entry.uncompressed_size {
+            bail!(
+                "expected {} bytes, got {}",
+                entry.uncompressed_size,
+                data.len()
+            );
+        }
+        Ok(data)
+    }
+
+    /// Returns the main section of the manifest
+    /// (empty if the archive has none).
+    pub fn manifest(&self) -> anyhow::Result<Manifest> {
+        Ok(self
+            .read_entry(MANIFEST)?
+            .map(|content| parse_manifest(&String::from_utf8_lossy(&content)))
+            .unwrap_or_default())
+    }
+}
+
+fn parse_manifest(content: &str) -> Manifest {
+    // join continuation lines, which start with a single space
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix(' '), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut manifest = Manifest::default();
+    // the main section ends with the first empty line
+    for line in lines.iter().take_while(|line| !line.is_empty()) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("Main-Class") {
+            manifest.main_class = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case("Class-Path") {
+            manifest.class_path =
+                value.split_whitespace().map(str::to_string).collect();
+        }
+    }
+    manifest
+}
@@ -1,28 +1,37 @@
 use ::std::rc::Rc;
 use nom::{
+    bytes::complete::take,
+    combinator::map,
+    error::{Error, ErrorKind},
+    multi::count,
     number::complete::{be_i16, be_i32, be_i8, be_u16, be_u8},
+    sequence::pair,
     IResult,
 };
 
-use super::parse_class_identifier;
+use super::{parse_class_identifier, parse_class_or_array_identifier};
 use crate::{
-    class::{class_identifier, ArrayName, ClassIdentifier, ClassName, Field},
+    class::{ArgumentKind, ArrayName, ClassIdentifier, ClassName},
     classloader::{
-        class_creator::signature_parser::parse_method_arguments,
-        cp_decoder::{remove_cp_offset, RuntimeCPEntry},
-        ClassFile,
+        class_creator::signature_parser::{
+            parse_field_type, parse_method_arguments,
+        },
+        cp_decoder::RuntimeCPEntry,
+        AttributeInfo, ClassFile,
     },
     executor::{
+        call_site::{BootstrapArgument, CallSite},
         op_code::{
             ArrayType, Dup, FloatCmp, Ldc, MethodDescriptor, OffsetDirection,
             SymbolicMethod,
         },
         OpCode,
     },
-    heap::Heap,
+    heap::{ClassLoaderId, Heap},
 };
 
 fn parse_wide(current_content: &[u8]) -> IResult<&[u8], OpCode> {
+    let opcode_content = current_content;
     let (mut current_content, opcode) = be_u8(current_content)?;
     match opcode {
         21 => {
@@ -92,37 +101,65 @@ fn parse_wide(current_content: &[u8]) -> IResult<&[u8], OpCode> {
             current_content = new_content;
             Ok((current_content, OpCode::Ret(index.into())))
         },
-        _ => panic!(" OpCode {} is not supported with the wide Opcode", opcode),
+        // not supported with the wide opcode
+        _ => Err(nom::Err::Failure(Error::new(
+            opcode_content,
+            ErrorKind::Tag,
+        ))),
     }
 }
 
-fn parse_static_field<'a>(
+/// The error of an operand at `input`, which doesn't reference
+/// a constant pool entry of the expected kind.
+fn invalid_reference(input: &[u8]) -> nom::Err<Error<&[u8]>> {
+    nom::Err::Failure(Error::new(input, ErrorKind::MapOpt))
+}
+
+/// Returns the constant pool entry `index`, converted by `as_kind`,
+/// or fails at the operand `input` if there is no such entry of that kind.
+fn cp_entry<'a, 'b, T>(
+    input: &'a [u8],
+    runtime_cp: &'b [RuntimeCPEntry],
+    index: u16,
+    as_kind: impl FnOnce(&'b RuntimeCPEntry) -> Option<T>,
+) -> Result<T, nom::Err<Error<&'a [u8]>>> {
+    usize::from(index)
+        .checked_sub(1)
+        .and_then(|index| runtime_cp.get(index))
+        .and_then(as_kind)
+        .ok_or_else(|| invalid_reference(input))
+}
+
+/// Parse the index of a `Fieldref` entry, e.g. of `getstatic`.
+///
+/// Returns the class, name and type of the field.
+fn parse_cp_field_ref<'a>(
     current_content: &'a [u8],
-    _class_file: &ClassFile,
     runtime_cp: &[RuntimeCPEntry],
-    heap: &mut Heap,
-) -> IResult<&'a [u8], Rc<Field>> {
+) -> IResult<&'a [u8], (ClassIdentifier, String, ArgumentKind)> {
+    let operand = current_content;
     let (current_content, cp_ref) = be_u16(current_content)?;
-    let cp_entry = &runtime_cp[remove_cp_offset(cp_ref as usize)];
-    let (name, class_name, _) = cp_entry
-        .as_field_ref()
-        .unwrap_or_else(|| panic!("CPentry {:?} is FieldRefInfo", cp_entry));
-    let class = heap
-        .find_class(&parse_class_identifier(class_name))
-        .unwrap_or_else(|| panic!("Class with name  {} exists", class_name));
-    let field = class.get_static_field(name).unwrap_or_else(|| {
-        panic!("Class with name{} has method {}", class_name, name)
-    });
-    Ok((current_content, field))
+    let (name, class_name, descriptor) =
+        cp_entry(operand, runtime_cp, cp_ref, RuntimeCPEntry::as_field_ref)?;
+    Ok((
+        current_content,
+        (
+            parse_class_identifier(class_name),
+            name.to_string(),
+            parse_field_type(descriptor),
+        ),
+    ))
 }
 
 fn parse_ldc<'a>(
     current_content: &'a [u8],
-    _class_file: &ClassFile,
+    class_file: &ClassFile,
     runtime_cp: &[RuntimeCPEntry],
+    loader: ClassLoaderId,
     heap: &mut Heap,
     wide: bool,
 ) -> IResult<&'a [u8], OpCode> {
+    let operand = current_content;
     let (current_content, cp_ref) = if wide {
         be_u16(current_content)?
     } else {
@@ -130,9 +167,7 @@ fn parse_ldc<'a>(
         (new_content, new_ref.into())
     };
 
-    let cp_entry = &runtime_cp[remove_cp_offset(cp_ref as usize)];
-
-    match cp_entry {
+    match cp_entry(operand, runtime_cp, cp_ref, Some)? {
         RuntimeCPEntry::StringInfo(value) => Ok((
             current_content,
             OpCode::Ldc(Ldc::String(Rc::new(heap.new_string(value.clone())))),
@@ -149,26 +184,70 @@ fn parse_ldc<'a>(
         RuntimeCPEntry::DoubleInfo(value) => {
             Ok((current_content, OpCode::Ldc(Ldc::Double(*value))))
         },
-        _ => panic!("{:?} Unsupported Type for Ldc ", cp_entry),
+        RuntimeCPEntry::DynamicInfo {
+            bootstrap_method_attr_index,
+            name,
+            descriptor,
+        } => {
+            let call_site = create_call_site(
+                class_file,
+                runtime_cp,
+                loader,
+                *bootstrap_method_attr_index,
+                name,
+                (vec![], Some(parse_field_type(descriptor))),
+            )
+            .ok_or_else(|| invalid_reference(operand))?;
+            Ok((
+                current_content,
+                OpCode::Ldc(Ldc::Dynamic(Rc::new(call_site))),
+            ))
+        },
+        RuntimeCPEntry::Class { name } => Ok((
+            current_content,
+            OpCode::Ldc(Ldc::Class(parse_class_or_array_identifier(name))),
+        )),
+        // loadable, but not supported yet
+        RuntimeCPEntry::MethodHandleInfo { .. }
+        | RuntimeCPEntry::MethodTypeInfo { .. } => {
+            Err(nom::Err::Failure(Error::new(operand, ErrorKind::Not)))
+        },
+        _ => Err(invalid_reference(operand)),
     }
 }
 
+/// Parse the index of a `Class` entry, e.g. of `checkcast`.
+fn parse_cp_class<'a>(
+    current_content: &'a [u8],
+    runtime_cp: &[RuntimeCPEntry],
+) -> IResult<&'a [u8], ClassIdentifier> {
+    let operand = current_content;
+    let (current_content, cp_ref) = be_u16(current_content)?;
+    let class_name =
+        cp_entry(operand, runtime_cp, cp_ref, RuntimeCPEntry::as_class)?;
+    Ok((current_content, parse_class_or_array_identifier(class_name)))
+}
+
 fn parse_cp_method_ref<'a>(
     current_content: &'a [u8],
     _class_file: &ClassFile,
     runtime_cp: &[RuntimeCPEntry],
 ) -> IResult<&'a [u8], SymbolicMethod> {
+    let operand = current_content;
     let (current_content, cp_ref) = be_u16(current_content)?;
-    let cp_entry = &runtime_cp[remove_cp_offset(cp_ref as usize)];
-    let (class_name, name, descriptor) = cp_entry
-        .as_method_ref()
-        .unwrap_or_else(|| panic!("CPEntry {:?} is MethodRefInfo", cp_entry));
+    // methods of interfaces are referenced by InterfaceMethodref entries
+    let (class_name, name, descriptor) =
+        cp_entry(operand, runtime_cp, cp_ref, |entry| {
+            entry.as_method_ref().or_else(|| entry.as_interface_ref())
+        })?;
     let descriptor = parse_method_arguments(descriptor);
 
     Ok((
         current_content,
         SymbolicMethod {
-            class_name: parse_class_identifier(class_name),
+            // methods of arrays (e.g. `clone()`) are referenced by the
+            // array class
+            class_name: parse_class_or_array_identifier(class_name),
             descriptor: MethodDescriptor {
                 name: name.to_string(),
                 descriptor,
@@ -177,40 +256,240 @@ fn parse_cp_method_ref<'a>(
     ))
 }
 
+fn parse_invoke_dynamic<'a>(
+    current_content: &'a [u8],
+    class_file: &ClassFile,
+    runtime_cp: &[RuntimeCPEntry],
+    loader: ClassLoaderId,
+) -> IResult<&'a [u8], Rc<CallSite>> {
+    let operand = current_content;
+    let (current_content, cp_ref) = be_u16(current_content)?;
+    // followed by two zero bytes
+    let (current_content, _) = be_u16(current_content)?;
+    let (bootstrap_method_attr_index, name, descriptor) = cp_entry(
+        operand,
+        runtime_cp,
+        cp_ref,
+        RuntimeCPEntry::as_invoke_dynamic,
+    )?;
+    let call_site = create_call_site(
+        class_file,
+        runtime_cp,
+        loader,
+        bootstrap_method_attr_index,
+        name,
+        parse_method_arguments(descriptor),
+    )
+    .ok_or_else(|| invalid_reference(operand))?;
+    Ok((current_content, Rc::new(call_site)))
+}
+
+/// Create the call site `name` with `descriptor`,
+/// which is linked by the bootstrap method `bootstrap_method_attr_index`
+/// of the `BootstrapMethods` attribute.
+///
+/// Returns `None` if the bootstrap method or its arguments
+/// don't reference constant pool entries of the expected kinds.
+fn create_call_site(
+    class_file: &ClassFile,
+    runtime_cp: &[RuntimeCPEntry],
+    loader: ClassLoaderId,
+    bootstrap_method_attr_index: u16,
+    name: &str,
+    descriptor: (Vec<ArgumentKind>, Option<ArgumentKind>),
+) -> Option<CallSite> {
+    let bootstrap_method =
+        class_file
+            .attributes
+            .iter()
+            .find_map(|attribute| match attribute {
+                AttributeInfo::BootstrapMethods(methods) => {
+                    methods.get(usize::from(bootstrap_method_attr_index))
+                },
+                _ => None,
+            })?;
+    let (class, method_name) =
+        match bootstrap_argument(runtime_cp, bootstrap_method.method_handle)? {
+            BootstrapArgument::MethodHandle { class, name, .. } => {
+                (class, name)
+            },
+            _ => return None,
+        };
+    let arguments = bootstrap_method
+        .arguments
+        .iter()
+        .map(|&index| bootstrap_argument(runtime_cp, index))
+        .collect::<Option<_>>()?;
+    Some(CallSite::new(
+        (parse_class_identifier(&class), method_name),
+        loader,
+        name.to_string(),
+        descriptor,
+        arguments,
+    ))
+}
+
+/// Returns the constant pool entry `index` as bootstrap argument,
+/// or `None` if it is no loadable constant.
+fn bootstrap_argument(
+    runtime_cp: &[RuntimeCPEntry],
+    index: u16,
+) -> Option<BootstrapArgument> {
+    let entry = |index: u16| {
+        usize::from(index)
+            .checked_sub(1)
+            .and_then(|index| runtime_cp.get(index))
+    };
+    Some(match entry(index)? {
+        RuntimeCPEntry::StringInfo(value) => {
+            BootstrapArgument::String(value.clone())
+        },
+        RuntimeCPEntry::IntegerInfo(value) => BootstrapArgument::Int(*value),
+        RuntimeCPEntry::FloatInfo(value) => BootstrapArgument::Float(*value),
+        RuntimeCPEntry::LongInfo(value) => BootstrapArgument::Long(*value),
+        RuntimeCPEntry::DoubleInfo(value) => BootstrapArgument::Double(*value),
+        RuntimeCPEntry::Class { name } => {
+            BootstrapArgument::Class(name.clone())
+        },
+        RuntimeCPEntry::MethodTypeInfo { descriptor } => {
+            BootstrapArgument::MethodType(descriptor.clone())
+        },
+        RuntimeCPEntry::MethodHandleInfo {
+            reference_kind,
+            reference_index,
+        } => match entry(*reference_index)? {
+            RuntimeCPEntry::FieldRefInfo {
+                class,
+                name,
+                descriptor,
+            }
+            | RuntimeCPEntry::MethodRefInfo {
+                class,
+                name,
+                descriptor,
+            }
+            | RuntimeCPEntry::InterfaceRefInfo {
+                class,
+                name,
+                descriptor,
+            } => BootstrapArgument::MethodHandle {
+                kind: *reference_kind,
+                class: class.clone(),
+                name: name.clone(),
+                descriptor: descriptor.clone(),
+            },
+            _ => return None,
+        },
+        _ => return None,
+    })
+}
+
+/// Parse `tableswitch` or `lookupswitch` (given by `opcode`),
+/// which starts at byte `position` of the code.
+///
+/// The offsets of its targets are byte offsets,
+/// like the ones of other branches after parsing.
+fn parse_switch(
+    opcode: u8,
+    current_content: &[u8],
+    position: usize,
+) -> IResult<&[u8], OpCode> {
+    // the operands are aligned to a multiple of four bytes
+    let padding = (4 - (position + 1) % 4) % 4;
+    let (current_content, _) = take(padding)(current_content)?;
+    let (current_content, default) = be_i32(current_content)?;
+    let default = signed_offset_to_usize_and_direction(default);
+    if opcode == 170 {
+        let (current_content, (low, high)) =
+            pair(be_i32, be_i32)(current_content)?;
+        let length = i64::from(high) - i64::from(low) + 1;
+        let (current_content, targets) = count(
+            map(be_i32, signed_offset_to_usize_and_direction),
+            length.try_into().unwrap_or(0),
+        )(current_content)?;
+        Ok((
+            current_content,
+            OpCode::Tableswitch {
+                default,
+                low,
+                targets,
+            },
+        ))
+    } else {
+        let (current_content, length) = be_i32(current_content)?;
+        let (current_content, targets) = count(
+            pair(be_i32, map(be_i32, signed_offset_to_usize_and_direction)),
+            length.try_into().unwrap_or(0),
+        )(current_content)?;
+        Ok((current_content, OpCode::Lookupswitch { default, targets }))
+    }
+}
+
 fn signed_offset_to_usize_and_direction(
     offset: i32,
 ) -> (usize, OffsetDirection) {
+    let distance = offset.unsigned_abs() as usize;
     if offset > 0 {
-        (offset.try_into().unwrap(), OffsetDirection::Forward)
+        (distance, OffsetDirection::Forward)
     } else {
-        (offset.abs().try_into().unwrap(), OffsetDirection::Backward)
+        (distance, OffsetDirection::Backward)
     }
 }
 
+/// Returns the number of opcodes between the opcode at `index`
+/// and its branch target `byte_offset` bytes away,
+/// or `None` if the target is no instruction boundary within the code.
 fn byte_offset_to_opcode_offset(
     byte_offset: &usize,
     direction: &OffsetDirection,
     index: usize,
-    opcode_sizes: &[u8],
-) -> usize {
+    opcode_sizes: &[u16],
+) -> Option<usize> {
     let mut remaining_byte_offset = *byte_offset;
     let mut current_index = index;
     if let OffsetDirection::Forward = direction {
         while remaining_byte_offset > 0 {
-            remaining_byte_offset -= usize::from(opcode_sizes[current_index]);
+            remaining_byte_offset = remaining_byte_offset
+                .checked_sub(usize::from(*opcode_sizes.get(current_index)?))?;
             current_index += 1;
         }
-        current_index - index
+        (current_index < opcode_sizes.len()).then_some(current_index - index)
     } else {
         while remaining_byte_offset > 0 {
-            current_index -= 1;
-            remaining_byte_offset -= usize::from(opcode_sizes[current_index]);
+            current_index = current_index.checked_sub(1)?;
+            remaining_byte_offset = remaining_byte_offset
+                .checked_sub(usize::from(opcode_sizes[current_index]))?;
         }
-        index - current_index
+        Some(index - current_index)
     }
 }
 
-fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
+/// Convert the byte offset of the switch target `target`
+/// of the switch at `index` to an opcode offset.
+fn switch_target_offset(
+    target: &mut (usize, OffsetDirection),
+    index: usize,
+    opcode_sizes: &[u16],
+) -> Result<(), usize> {
+    let (byte_offset, direction) = target;
+    *byte_offset = byte_offset_to_opcode_offset(
+        byte_offset,
+        direction,
+        index,
+        opcode_sizes,
+    )
+    .ok_or(index)?;
+    Ok(())
+}
+
+/// Convert the byte offsets of branches to opcode offsets.
+///
+/// Fails with the index of the first branch
+/// whose target is no instruction boundary.
+fn parse_branch_offsets(
+    opcodes: &mut [OpCode],
+    opcode_sizes: &[u16],
+) -> Result<(), usize> {
     for (i, opcode) in opcodes.iter_mut().enumerate() {
         match opcode {
             OpCode::IfEq(byte_offset, direction) => {
@@ -220,7 +499,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -231,7 +511,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -242,7 +523,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -253,7 +535,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -264,7 +547,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -275,7 +559,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -286,7 +571,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -297,7 +583,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -308,7 +595,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -319,7 +607,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -330,7 +619,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -341,7 +631,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -352,7 +643,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -363,7 +655,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -374,7 +667,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -385,7 +679,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -396,7 +691,8 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
@@ -407,25 +703,42 @@ fn parse_branch_offsets(opcodes: &mut [OpCode], opcode_sizes: &[u8]) {
                         direction,
                         i,
                         opcode_sizes,
-                    ),
+                    )
+                    .ok_or(i)?,
                     *direction,
                 )
             },
+            OpCode::Tableswitch {
+                default, targets, ..
+            } => {
+                for target in std::iter::once(default).chain(targets) {
+                    switch_target_offset(target, i, opcode_sizes)?;
+                }
+            },
+            OpCode::Lookupswitch { default, targets } => {
+                let targets = targets.iter_mut().map(|(_, target)| target);
+                for target in std::iter::once(default).chain(targets) {
+                    switch_target_offset(target, i, opcode_sizes)?;
+                }
+            },
             _ => {},
         }
     }
+    Ok(())
 }
 
 pub fn parse_opcodes<'a>(
     code: &'a Vec<u8>,
     class_file: &ClassFile,
     runtime_cp: &[RuntimeCPEntry],
+    loader: ClassLoaderId,
     heap: &mut Heap,
-) -> IResult<&'a [u8], (Vec<OpCode>, Vec<u8>)> {
+) -> IResult<&'a [u8], (Vec<OpCode>, Vec<u16>)> {
     let mut current_content = code.as_slice();
     let mut opcodes: Vec<OpCode> = Vec::new();
-    let mut opcode_sizes: Vec<u8> = Vec::new();
+    let mut opcode_sizes: Vec<u16> = Vec::new();
     while !current_content.is_empty() {
+        let opcode_content = current_content;
         let opcode;
         (current_content, opcode) = be_u8(current_content)?;
         match opcode {
@@ -471,6 +784,7 @@ pub fn parse_opcodes<'a>(
                     current_content,
                     class_file,
                     runtime_cp,
+                    loader,
                     heap,
                     false,
                 )?;
@@ -485,6 +799,7 @@ pub fn parse_opcodes<'a>(
                     current_content,
                     class_file,
                     runtime_cp,
+                    loader,
                     heap,
                     true,
                 )?;
@@ -613,7 +928,7 @@ pub fn parse_opcodes<'a>(
             },
             67..=70 => {
                 opcode_sizes.push(1);
-                opcodes.push(OpCode::Istore((opcode - 67).into()));
+                opcodes.push(OpCode::Fstore((opcode - 67).into()));
             },
             71..=74 => {
                 opcode_sizes.push(1);
@@ -1059,11 +1374,19 @@ pub fn parse_opcodes<'a>(
                 current_content = new_content;
                 opcodes.push(OpCode::Ret(index.into()));
             },
-            170 => {
-                todo!("Tabeleswitch will not be supported")
-            },
-            171 => {
-                todo!("LookupSwitch will not be supported")
+            170 | 171 => {
+                let (new_content, switch) = parse_switch(
+                    opcode,
+                    current_content,
+                    code.len() - opcode_content.len(),
+                )?;
+                opcode_sizes.push(
+                    (current_content.len() - new_content.len() + 1)
+                        .try_into()
+                        .expect("code is shorter than 65536 bytes"),
+                );
+                current_content = new_content;
+                opcodes.push(switch);
             },
             172 => {
                 opcode_sizes.push(1);
@@ -1089,51 +1412,33 @@ pub fn parse_opcodes<'a>(
                 opcode_sizes.push(1);
                 opcodes.push(OpCode::Return);
             },
-            178 => {
+            178..=181 => {
                 opcode_sizes.push(3);
-                let (new_content, field) = parse_static_field(
-                    current_content,
-                    class_file,
-                    runtime_cp,
-                    heap,
-                )?;
-                opcodes.push(OpCode::GetStatic(field));
+                let (new_content, (class, field_name, field_type)) =
+                    parse_cp_field_ref(current_content, runtime_cp)?;
                 current_content = new_content;
-            },
-            179 => {
-                opcode_sizes.push(3);
-                let (new_content, field) = parse_static_field(
-                    current_content,
-                    class_file,
-                    runtime_cp,
-                    heap,
-                )?;
-                opcodes.push(OpCode::PutStatic(field));
-                current_content = new_content;
-            },
-            180 => {
-                opcode_sizes.push(3);
-                let (new_content, index) = be_u16(current_content)?;
-                current_content = new_content;
-                let cp_entry = &runtime_cp[remove_cp_offset(index as usize)];
-                let (field_name, class, _descriptor) =
-                    cp_entry.as_field_ref().unwrap();
-                opcodes.push(OpCode::GetField {
-                    class: parse_class_identifier(class),
-                    field_name: field_name.to_string(),
-                })
-            },
-            181 => {
-                opcode_sizes.push(3);
-                let (new_content, index) = be_u16(current_content)?;
-                current_content = new_content;
-                let cp_entry = &runtime_cp[remove_cp_offset(index as usize)];
-                let (field_name, class, _descriptor) =
-                    cp_entry.as_field_ref().unwrap();
-                opcodes.push(OpCode::PutField {
-                    class: parse_class_identifier(class),
-                    field_name: field_name.to_string(),
-                })
+                opcodes.push(match opcode {
+                    178 => OpCode::GetStatic {
+                        class,
+                        field_name,
+                        field_type,
+                    },
+                    179 => OpCode::PutStatic {
+                        class,
+                        field_name,
+                        field_type,
+                    },
+                    180 => OpCode::GetField {
+                        class,
+                        field_name,
+                        field_type,
+                    },
+                    _ => OpCode::PutField {
+                        class,
+                        field_name,
+                        field_type,
+                    },
+                });
             },
             182 => {
                 opcode_sizes.push(3);
@@ -1167,90 +1472,73 @@ pub fn parse_opcodes<'a>(
             },
             185 => {
                 opcode_sizes.push(5);
-                todo!(
-                    "InvokeInterface(Rc<dyn Any>), needs information \
-on how to resolve Interface at execution time"
-                )
+                let (new_content, method) = parse_cp_method_ref(
+                    current_content,
+                    class_file,
+                    runtime_cp,
+                )?;
+                // followed by the argument count and a zero byte
+                let (new_content, _) = be_u16(new_content)?;
+                opcodes.push(OpCode::InvokeInterface(method));
+                current_content = new_content;
             },
             186 => {
                 opcode_sizes.push(5);
-                todo!(
-                    "InvokeDynamic(Rc<dyn Any>),, needs information \
-on how to resolve at execution time"
-                )
+                let (new_content, call_site) = parse_invoke_dynamic(
+                    current_content,
+                    class_file,
+                    runtime_cp,
+                    loader,
+                )?;
+                opcodes.push(OpCode::InvokeDynamic(call_site));
+                current_content = new_content;
             },
             187 => {
                 opcode_sizes.push(3);
                 let (new_content, index) = be_u16(current_content)?;
+                let class_name = cp_entry(
+                    current_content,
+                    runtime_cp,
+                    index,
+                    RuntimeCPEntry::as_class,
+                )?;
                 current_content = new_content;
-                let cp_entry = &runtime_cp[remove_cp_offset(index as usize)];
-                let class_name = cp_entry.as_class().unwrap();
                 let identifier = parse_class_identifier(class_name);
                 opcodes.push(OpCode::New(identifier));
             },
             188 => {
                 opcode_sizes.push(2);
                 let (new_content, value) = be_u8(current_content)?;
+                let array_type =
+                    ArrayType::from_int(value).ok_or_else(|| {
+                        nom::Err::Failure(Error::new(
+                            current_content,
+                            ErrorKind::OneOf,
+                        ))
+                    })?;
                 current_content = new_content;
-                opcodes.push(OpCode::NewArray(ArrayType::from_int(value)));
+                opcodes.push(OpCode::NewArray(array_type));
             },
             189 => {
                 opcode_sizes.push(3);
-                let (new_content, index) = be_u16(current_content)?;
+                let (new_content, class_name) =
+                    parse_cp_class(current_content, runtime_cp)?;
                 current_content = new_content;
-                let cp_entry = &runtime_cp[remove_cp_offset(index as usize)];
-                let class_name = cp_entry.as_class().unwrap();
-                // get underlying component class for array
-                let array_cls_indentifier = if class_name.starts_with('[') {
-                    // array
-                    let (array_dim, kind) =
-                        class_name.rsplit_once('[').unwrap();
-
-                    // +1 for removed dim in rsplit
-                    // +1 for implicit dim in op-code anewarray
-                    let dim = array_dim.len() + 2;
-
-                    match kind {
-                        "L" => {
-                            let cls_name = &kind[1..kind.len() - 1];
-                            let (package, name) =
-                                parse_class_identifier(cls_name)
-                                    .into_plain_identifier();
-                            ClassIdentifier {
-                                package,
-                                class_name: ClassName::Array {
-                                    dimensions: dim,
-                                    name: ArrayName::Class(name),
-                                },
-                            }
-                        },
-                        "Z" => class_identifier!(bool, dim),
-                        "B" => class_identifier!(byte, dim),
-                        "C" => class_identifier!(char, dim),
-                        "D" => class_identifier!(double, dim),
-                        "F" => class_identifier!(float, dim),
-                        "J" => class_identifier!(long, dim),
-                        "I" => class_identifier!(int, dim),
-                        "S" => class_identifier!(short, dim),
-                        _ => panic!(
-                            "unexpected array class name: {}",
-                            class_name
-                        ),
-                    }
-                } else {
-                    let (package, name) = parse_class_identifier(class_name)
-                        .into_plain_identifier();
-                    ClassIdentifier {
-                        package,
-                        class_name: ClassName::Array {
-                            dimensions: 1,
-                            name: ArrayName::Class(name),
-                        },
-                    }
+                // the component class name is an array descriptor itself
+                // for multidimensional arrays
+                let (package, (dimensions, name)) = match class_name.class_name
+                {
+                    ClassName::Array { dimensions, name } => {
+                        (class_name.package, (dimensions + 1, name))
+                    },
+                    ClassName::Plain(name) => {
+                        (class_name.package, (1, ArrayName::Class(name)))
+                    },
                 };
-                let array_cls =
-                    heap.find_array_class(&array_cls_indentifier).unwrap();
-                opcodes.push(OpCode::AnewArray(array_cls));
+                opcodes.push(OpCode::AnewArray(ClassIdentifier {
+                    package,
+                    class_name: ClassName::Array { dimensions, name },
+                }));
             },
             190 => {
                 opcode_sizes.push(1);
@@ -1262,17 +1550,17 @@ on how to resolve at execution time"
             },
             192 => {
                 opcode_sizes.push(3);
-                todo!(
-                    "Checkcast(Rc<dyn Any>), needs information \
-on how to resolve at execution time"
-                )
+                let (new_content, class_name) =
+                    parse_cp_class(current_content, runtime_cp)?;
+                current_content = new_content;
+                opcodes.push(OpCode::Checkcast(class_name));
             },
             193 => {
                 opcode_sizes.push(3);
-                todo!(
-                    "InstanceOf(Rc<dyn Any>), needs information \
-on how to resolve at execution time"
-                )
+                let (new_content, class_name) =
+                    parse_cp_class(current_content, runtime_cp)?;
+                current_content = new_content;
+                opcodes.push(OpCode::InstanceOf(class_name));
             },
             194 => {
                 opcode_sizes.push(1);
@@ -1299,54 +1587,40 @@ on how to resolve at execution time"
             197 => {
                 opcode_sizes.push(4);
                 let (new_content, index) = be_u16(current_content)?;
-                let (new_content, dim) = be_u8(new_content)?;
-                let dim: usize = dim.into();
-                current_content = new_content;
-                let cp_entry = &runtime_cp[remove_cp_offset(index as usize)];
-                let class_name = cp_entry.as_class().unwrap();
-                // get underlying component class for array
-                let array_identifier = if class_name.starts_with('[') {
-                    // array
-                    let (_, kind) = class_name.rsplit_once('[').unwrap();
-                    match kind {
-                        "L" => {
-                            let cls_name = &kind[1..kind.len() - 1];
-                            let (package, name) =
-                                parse_class_identifier(cls_name)
-                                    .into_plain_identifier();
-                            ClassIdentifier {
-                                package,
-                                class_name: ClassName::Array {
-                                    dimensions: dim,
-                                    name: ArrayName::Class(name),
-                                },
-                            }
-                        },
-                        "Z" => class_identifier!(bool, dim),
-                        "B" => class_identifier!(byte, dim),
-                        "C" => class_identifier!(char, dim),
-                        "D" => class_identifier!(double, dim),
-                        "F" => class_identifier!(float, dim),
-                        "J" => class_identifier!(long, dim),
-                        "I" => class_identifier!(int, dim),
-                        "S" => class_identifier!(short, dim),
-                        _ => panic!(
-                            "unexpected array class name: {}",
-                            class_name
-                        ),
-                    }
-                } else {
-                    let (package, name) = parse_class_identifier(class_name)
-                        .into_plain_identifier();
-                    ClassIdentifier {
-                        package,
-                        class_name: ClassName::Array {
-                            dimensions: dim,
-                            name: ArrayName::Class(name),
+                let (new_content, dimensions) = be_u8(new_content)?;
+                let class_name = cp_entry(
+                    current_content,
+                    runtime_cp,
+                    index,
+                    RuntimeCPEntry::as_class,
+                )?;
+                // the created array has `dimensions` dimensions
+                // of the component class
+                let ClassIdentifier {
+                    package,
+                    class_name:
+                        ClassName::Array {
+                            dimensions: max,
+                            name,
                         },
-                    }
+                } = parse_class_or_array_identifier(class_name)
+                else {
+                    return Err(invalid_reference(current_content));
                 };
-                opcodes.push(OpCode::MultiAnewArray(array_identifier));
+                if dimensions == 0 || usize::from(dimensions) > max {
+                    return Err(nom::Err::Failure(Error::new(
+                        &current_content[2..],
+                        ErrorKind::Count,
+                    )));
+                }
+                current_content = new_content;
+                opcodes.push(OpCode::MultiAnewArray(ClassIdentifier {
+                    package,
+                    class_name: ClassName::Array {
+                        dimensions: dimensions.into(),
+                        name,
+                    },
+                }));
             },
             198 => {
                 opcode_sizes.push(3);
@@ -1381,10 +1655,20 @@ on how to resolve at execution time"
                 opcodes.push(OpCode::Jsr(offset, direction));
             },
             _ => {
-                panic!("{} is not a valid Opcode", opcode)
+                return Err(nom::Err::Failure(Error::new(
+                    opcode_content,
+                    ErrorKind::Tag,
+                )));
             },
         }
     }
-    parse_branch_offsets(&mut opcodes, &opcode_sizes);
+    if let Err(index) = parse_branch_offsets(&mut opcodes, &opcode_sizes) {
+        let position: usize =
+            opcode_sizes[..index].iter().copied().map(usize::from).sum();
+        return Err(nom::Err::Failure(Error::new(
+            &code[position..],
+            ErrorKind::Verify,
+        )));
+    }
     Ok((current_content, (opcodes, opcode_sizes)))
 }
@@ -2,13 +2,23 @@ use enumflags2::BitFlags;
 
 use crate::{
     class::access_flags::{ClassAccessFlag, FieldAccessFlag, MethodAccessFlag},
-    classloader::CpInfo,
+    classloader::{constant_pool::get_class_name, CpInfo},
 };
 
 #[derive(Debug)]
 pub struct RawAttributeInfo {
     pub(super) attribute_name_index: u16,
     pub(super) info: Vec<u8>,
+    /// Offset of the attribute in the class file.
+    pub(super) offset: usize,
+}
+
+impl RawAttributeInfo {
+    /// Offset of `info` in the class file,
+    /// after the name index and the length.
+    pub(super) fn info_offset(&self) -> usize {
+        self.offset + 6
+    }
 }
 
 #[derive(Debug)]
@@ -17,6 +27,8 @@ pub struct RawMethodInfo {
     pub(super) name_index: u16,
     pub(super) descriptor_index: u16,
     pub(super) attributes: Vec<RawAttributeInfo>,
+    /// Offset of the structure in the class file.
+    pub(super) offset: usize,
 }
 
 #[derive(Debug)]
@@ -25,17 +37,21 @@ pub struct RawFieldInfo {
     pub(super) name_index: u16,
     pub(super) descriptor_index: u16,
     pub(super) attributes: Vec<RawAttributeInfo>,
+    /// Offset of the structure in the class file.
+    pub(super) offset: usize,
 }
 
 #[derive(Debug)]
 pub struct RawClassFile {
-    #[allow(dead_code)] // verification is not a priority right now
     pub(super) minor_version: u16,
-    #[allow(dead_code)] // verification is not a priority right now
     pub(super) major_version: u16,
     pub(super) constant_pool: Vec<CpInfo>,
+    /// Offsets of the constant pool entries in the class file.
+    pub(super) constant_pool_offsets: Vec<usize>,
     pub(super) access_flags: BitFlags<ClassAccessFlag>,
     pub(super) this_class: u16,
+    /// Offset of `this_class` in the class file, followed by `super_class`.
+    pub(super) this_class_offset: usize,
     pub(super) super_class: u16,
     pub(super) interfaces: Vec<u16>,
     pub(super) fields: Vec<RawFieldInfo>,
@@ -50,4 +66,41 @@ impl RawClassFile {
         }
         self.constant_pool.get(reference - 1)
     }
+
+    /// Name of the class described by `self`, e.g. `org/example/Main`.
+    pub fn class_name(&self) -> Option<&str> {
+        get_class_name(&self.constant_pool, self.this_class, 0).ok()
+    }
+
+    /// Names of all (non-array) classes referenced in the constant pool,
+    /// e.g. super classes, classes of called methods and accessed fields.
+    ///
+    /// For arrays, the class of their elements is returned.
+    pub fn referenced_classes(&self) -> Vec<String> {
+        self.constant_pool
+            .iter()
+            .filter_map(|entry| match entry {
+                CpInfo::ClassInfo { name_index } if *name_index != 0 => {
+                    match self.get_java_cp_entry(*name_index as usize) {
+                        Some(CpInfo::UTF8INFO(name)) => Some(name),
+                        _ => None,
+                    }
+                },
+                _ => None,
+            })
+            .filter_map(|name| {
+                let element = name.trim_start_matches('[');
+                if element.len() == name.len() {
+                    Some(name.clone())
+                } else {
+                    // only class arrays have an element class,
+                    // e.g. '[[Ljava/lang/String;'
+                    element
+                        .strip_prefix('L')
+                        .and_then(|e| e.strip_suffix(';'))
+                        .map(str::to_string)
+                }
+            })
+            .collect()
+    }
 }
@@ -0,0 +1,1432 @@
+//! Verification by type checking (JVMS §4.10.1):
+//! The types of local variables and operand stack entries
+//! are tracked through the code of a method
+//! and checked against the frames of its `StackMapTable` attribute.
+
+use std::{collections::HashMap, fmt, rc::Rc};
+
+use thiserror::Error;
+
+use crate::{
+    class::{
+        access_flags::{ClassAccessFlag, FieldAccessFlag, MethodAccessFlag},
+        ArgumentKind, ArrayName, Class, ClassIdentifier, ClassName, Code,
+        Method, SimpleArgumentKind,
+    },
+    classloader::{
+        parse_class_identifier, StackMapFrame, VerificationTypeInfo,
+    },
+    executor::{
+        op_code::{ArrayType, Dup, Ldc, OffsetDirection, SymbolicMethod},
+        OpCode,
+    },
+    heap::{ClassLoaderId, Heap},
+};
+
+/// Code violating the type rules, e.g. popping an int as a reference.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{class}.{method} @{pc}: {reason}")]
+pub struct VerifyError {
+    /// Name of the class, e.g. `org/example/Main`.
+    pub class: String,
+    /// Name and descriptor of the method, e.g. `main([Ljava/lang/String;)V`.
+    pub method: String,
+    /// Offset of the instruction in the code.
+    pub pc: usize,
+    pub reason: String,
+}
+
+/// The type of a local variable or operand stack entry.
+#[derive(Debug, Clone, PartialEq)]
+enum Type {
+    /// Any value, e.g. an unset local variable
+    /// or the second slot of a long or double.
+    Top,
+    Int,
+    Float,
+    Long,
+    Double,
+    Null,
+    /// `this` in a constructor before the super constructor is called.
+    UninitializedThis,
+    /// An instance created by the `new` instruction at this opcode index,
+    /// whose constructor hasn't been called yet.
+    Uninitialized(usize),
+    /// A class or array type, e.g. `java/lang/String` or `[I`.
+    Reference(String),
+}
+
+impl Type {
+    fn reference(name: &str) -> Type {
+        Type::Reference(name.to_string())
+    }
+
+    fn is_category2(&self) -> bool {
+        matches!(self, Type::Long | Type::Double)
+    }
+
+    /// The number of slots taken on the operand stack.
+    fn size(&self) -> usize {
+        if self.is_category2() { 2 } else { 1 }
+    }
+
+    fn is_reference(&self) -> bool {
+        matches!(
+            self,
+            Type::Null
+                | Type::UninitializedThis
+                | Type::Uninitialized(_)
+                | Type::Reference(_)
+        )
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Top => write!(f, "top"),
+            Type::Int => write!(f, "int"),
+            Type::Float => write!(f, "float"),
+            Type::Long => write!(f, "long"),
+            Type::Double => write!(f, "double"),
+            Type::Null => write!(f, "null"),
+            Type::UninitializedThis => write!(f, "uninitialized this"),
+            Type::Uninitialized(index) => {
+                write!(f, "uninitialized (new at opcode {})", index)
+            },
+            Type::Reference(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Returns the descriptor of `kind`, e.g. `Ljava/lang/String;`.
+fn simple_descriptor(kind: &SimpleArgumentKind) -> String {
+    match kind {
+        SimpleArgumentKind::Byte => "B".to_string(),
+        SimpleArgumentKind::Char => "C".to_string(),
+        SimpleArgumentKind::Double => "D".to_string(),
+        SimpleArgumentKind::Float => "F".to_string(),
+        SimpleArgumentKind::Int => "I".to_string(),
+        SimpleArgumentKind::Long => "J".to_string(),
+        SimpleArgumentKind::Class(name) => format!("L{};", name),
+        SimpleArgumentKind::Short => "S".to_string(),
+        SimpleArgumentKind::Boolean => "Z".to_string(),
+    }
+}
+
+/// Returns the descriptor of `kind`, e.g. `[I`.
+fn descriptor(kind: &ArgumentKind) -> String {
+    match kind {
+        ArgumentKind::Simple(kind) => simple_descriptor(kind),
+        ArgumentKind::Array { dimensions, kind } => {
+            format!("{}{}", "[".repeat(*dimensions), simple_descriptor(kind))
+        },
+    }
+}
+
+/// Returns the name and descriptor of `method`, e.g. `add(II)I`.
+fn method_name(method: &Method) -> String {
+    let parameters: String = method.parameters.iter().map(descriptor).collect();
+    let return_type = method
+        .return_type
+        .as_ref()
+        .map_or_else(|| "V".to_string(), descriptor);
+    format!("{}({}){}", method.name, parameters, return_type)
+}
+
+/// Returns the type of a value of `kind` on the operand stack.
+fn argument_type(kind: &ArgumentKind) -> Type {
+    match kind {
+        ArgumentKind::Simple(
+            SimpleArgumentKind::Byte
+            | SimpleArgumentKind::Char
+            | SimpleArgumentKind::Int
+            | SimpleArgumentKind::Short
+            | SimpleArgumentKind::Boolean,
+        ) => Type::Int,
+        ArgumentKind::Simple(SimpleArgumentKind::Float) => Type::Float,
+        ArgumentKind::Simple(SimpleArgumentKind::Long) => Type::Long,
+        ArgumentKind::Simple(SimpleArgumentKind::Double) => Type::Double,
+        ArgumentKind::Simple(SimpleArgumentKind::Class(name)) => {
+            Type::reference(name)
+        },
+        ArgumentKind::Array { .. } => Type::Reference(descriptor(kind)),
+    }
+}
+
+/// Returns the type of instances of the class `identifier`.
+fn class_type(identifier: &ClassIdentifier) -> Type {
+    match &identifier.class_name {
+        ClassName::Plain(_) => Type::Reference(identifier.to_string()),
+        ClassName::Array { dimensions, name } => {
+            let component = match name {
+                ArrayName::Byte => "B".to_string(),
+                ArrayName::Char => "C".to_string(),
+                ArrayName::Double => "D".to_string(),
+                ArrayName::Float => "F".to_string(),
+                ArrayName::Int => "I".to_string(),
+                ArrayName::Long => "J".to_string(),
+                ArrayName::Short => "S".to_string(),
+                ArrayName::Boolean => "Z".to_string(),
+                ArrayName::Class(name) if identifier.package.is_empty() => {
+                    format!("L{};", name)
+                },
+                ArrayName::Class(name) => {
+                    format!("L{}/{};", identifier.package.join("/"), name)
+                },
+            };
+            Type::Reference(format!("{}{}", "[".repeat(*dimensions), component))
+        },
+    }
+}
+
+/// Returns the class name of the component type of the array `component`,
+/// e.g. `java/lang/String` for `Ljava/lang/String;` or `[I` for `[I`,
+/// or `None` for primitive components.
+fn component_class(component: &str) -> Option<&str> {
+    if component.starts_with('[') {
+        Some(component)
+    } else {
+        component.strip_prefix('L')?.strip_suffix(';')
+    }
+}
+
+fn is_interface(class: &dyn Class) -> bool {
+    class.access_flags().contains(ClassAccessFlag::Interface)
+}
+
+/// The types of the local variables and the operand stack
+/// at the start of an instruction.
+#[derive(Debug, Clone, PartialEq)]
+struct Frame {
+    /// One entry per slot, i.e. longs and doubles are followed by top.
+    locals: Vec<Type>,
+    /// One entry per value.
+    stack: Vec<Type>,
+}
+
+impl fmt::Display for Frame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |types: &[Type]| {
+            types
+                .iter()
+                .map(Type::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        write!(
+            f,
+            "locals [{}], stack [{}]",
+            list(&self.locals),
+            list(&self.stack)
+        )
+    }
+}
+
+/// Verifies the methods of a class before it is added to the heap.
+pub struct Verifier<'a> {
+    heap: &'a Heap,
+    loader: ClassLoaderId,
+    /// Name of the verified class, e.g. `org/example/Main`.
+    class_name: &'a str,
+    is_interface: bool,
+    super_class: &'a Rc<dyn Class>,
+}
+
+impl<'a> Verifier<'a> {
+    pub fn new(
+        heap: &'a Heap,
+        loader: ClassLoaderId,
+        class_name: &'a str,
+        is_interface: bool,
+        super_class: &'a Rc<dyn Class>,
+    ) -> Self {
+        Verifier {
+            heap,
+            loader,
+            class_name,
+            is_interface,
+            super_class,
+        }
+    }
+
+    /// Verify `code` of `method`, parsed from bytecode
+    /// with the given `opcode_sizes`,
+    /// against the frames of its `StackMapTable` attribute.
+    pub fn verify_method(
+        &self,
+        method: &Method,
+        code: &Code,
+        stack_map: &[StackMapFrame],
+        opcode_sizes: &[u16],
+    ) -> Result<(), VerifyError> {
+        // the offset of each instruction, followed by the code length
+        let mut pcs = vec![0];
+        for size in opcode_sizes {
+            pcs.push(pcs[pcs.len() - 1] + usize::from(*size));
+        }
+        let method_verifier = MethodVerifier {
+            verifier: self,
+            method,
+            code,
+            pcs: &pcs,
+        };
+        method_verifier
+            .verify(stack_map)
+            .map_err(|(pc, reason)| VerifyError {
+                class: self.class_name.to_string(),
+                method: method_name(method),
+                pc,
+                reason,
+            })
+    }
+
+    fn find_class(&self, name: &str) -> Option<&Rc<dyn Class>> {
+        self.heap
+            .find_class(self.loader, &parse_class_identifier(name))
+    }
+
+    /// Whether a value of type `from` can be used as a `to`.
+    fn is_assignable(&self, from: &Type, to: &Type) -> bool {
+        match (from, to) {
+            (_, Type::Top) => true,
+            (from, to) if from == to => true,
+            (Type::Null, Type::Reference(_)) => true,
+            (Type::Reference(from), Type::Reference(to)) => {
+                self.is_class_assignable(from, to)
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether an instance of the class or array type `from`
+    /// can be used as a `to` (JVMS §4.10.1.2).
+    ///
+    /// Interfaces are treated like `java/lang/Object`.
+    /// Classes which aren't loaded yet are checked when they're resolved.
+    fn is_class_assignable(&self, from: &str, to: &str) -> bool {
+        if from == to || to == "java/lang/Object" {
+            return true;
+        }
+        if let Some(from_component) = from.strip_prefix('[') {
+            return match to.strip_prefix('[') {
+                Some(to_component) => match (
+                    component_class(from_component),
+                    component_class(to_component),
+                ) {
+                    (Some(from), Some(to)) => {
+                        self.is_class_assignable(from, to)
+                    },
+                    // arrays of primitives are only assignable to themselves
+                    _ => false,
+                },
+                None => {
+                    to == "java/lang/Cloneable" || to == "java/io/Serializable"
+                },
+            };
+        }
+        if to.starts_with('[') {
+            return false;
+        }
+
+        if to == self.class_name {
+            if self.is_interface {
+                return true;
+            }
+        } else {
+            match self.find_class(to) {
+                Some(class) if !is_interface(class.as_ref()) => (),
+                _ => return true,
+            }
+        }
+        let mut ancestor = if from == self.class_name {
+            Some(self.super_class.clone())
+        } else {
+            match self.find_class(from) {
+                Some(class) => class.super_class(),
+                None => return true,
+            }
+        };
+        while let Some(class) = ancestor {
+            if class.class_identifier().to_string() == to {
+                return true;
+            }
+            ancestor = class.super_class();
+        }
+        false
+    }
+
+    /// Whether accessing a member of `class` on `objectref`
+    /// passes the protected check (JVMS §4.10.1.8):
+    /// protected members declared by superclasses in other runtime packages
+    /// may only be accessed on instances of this class or its subclasses.
+    ///
+    /// `protected_member` looks up the member in the superclass `class`
+    /// and returns the class declaring it if it is protected.
+    fn passes_protected_check(
+        &self,
+        class: &ClassIdentifier,
+        objectref: &Type,
+        protected_member: impl FnOnce(&Rc<dyn Class>) -> Option<Rc<dyn Class>>,
+    ) -> bool {
+        let Some(super_class) = std::iter::successors(
+            Some(self.super_class.clone()),
+            |super_class| super_class.super_class(),
+        )
+        .find(|super_class| super_class.class_identifier() == class) else {
+            return true;
+        };
+        match protected_member(&super_class) {
+            Some(declaring_class)
+                if declaring_class.class_identifier().package
+                    != parse_class_identifier(self.class_name).package
+                    || declaring_class.class_loader() != self.loader =>
+            {
+                self.is_assignable(objectref, &Type::reference(self.class_name))
+            },
+            _ => true,
+        }
+    }
+
+    fn is_frame_assignable(&self, from: &Frame, to: &Frame) -> bool {
+        from.locals.len() == to.locals.len()
+            && from.stack.len() == to.stack.len()
+            && from
+                .locals
+                .iter()
+                .zip(to.locals.iter())
+                .chain(from.stack.iter().zip(to.stack.iter()))
+                .all(|(from, to)| self.is_assignable(from, to))
+    }
+}
+
+/// A failed check: the offset of the instruction and the reason.
+type Failure = (usize, String);
+
+struct MethodVerifier<'a> {
+    verifier: &'a Verifier<'a>,
+    method: &'a Method,
+    code: &'a Code,
+    /// The offset of each instruction, followed by the code length.
+    pcs: &'a [usize],
+}
+
+impl<'a> MethodVerifier<'a> {
+    fn verify(&self, stack_map: &[StackMapFrame]) -> Result<(), Failure> {
+        let initial_locals = self.initial_locals();
+        let frames = self.decode_stack_map(&initial_locals, stack_map)?;
+        let mut current = Some(self.expand(initial_locals, Vec::new(), 0)?);
+
+        for (index, opcode) in self.code.byte_code.iter().enumerate() {
+            let pc = self.pcs[index];
+            if let Some(frame) = frames.get(&index) {
+                if let Some(current) = &current {
+                    self.check_target(&frames, current, index)
+                        .map_err(|reason| (pc, reason))?;
+                }
+                current = Some(frame.clone());
+            }
+            let Some(frame) = current.as_mut() else {
+                return Err((pc, "expecting a stack map frame".to_string()));
+            };
+
+            for handler in &self.code.exception_table {
+                if !handler.active.contains(&index) {
+                    continue;
+                }
+                let catch_type = handler.catch_type.as_ref().map_or_else(
+                    || Type::reference("java/lang/Throwable"),
+                    class_type,
+                );
+                let handler_frame = Frame {
+                    locals: frame.locals.clone(),
+                    stack: vec![catch_type],
+                };
+                self.check_target(
+                    &frames,
+                    &handler_frame,
+                    handler.handler_position,
+                )
+                .map_err(|reason| (pc, reason))?;
+            }
+
+            let mut instruction = Instruction {
+                verifier: self.verifier,
+                method: self.method,
+                frame,
+                max_stack: self.code.stack_depth,
+                index,
+            };
+            let flow =
+                instruction.execute(opcode).map_err(|reason| (pc, reason))?;
+            for target in flow.targets {
+                self.check_target(&frames, frame, target)
+                    .map_err(|reason| (pc, reason))?;
+            }
+            if !flow.falls_through {
+                current = None;
+            }
+        }
+
+        match current {
+            Some(_) => Err((
+                self.pcs[self.pcs.len() - 1],
+                "falling off the end of the code".to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that `frame` is assignable to the stack map frame at `target`.
+    fn check_target(
+        &self,
+        frames: &HashMap<usize, Frame>,
+        frame: &Frame,
+        target: usize,
+    ) -> Result<(), String> {
+        let target_frame = frames.get(&target).ok_or_else(|| {
+            format!("missing stack map frame at {}", self.pcs[target])
+        })?;
+        if self.verifier.is_frame_assignable(frame, target_frame) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} is not assignable to the stack map frame {} at {}",
+                frame, target_frame, self.pcs[target]
+            ))
+        }
+    }
+
+    /// The types of `this` and the parameters,
+    /// one entry per value like in stack map frames.
+    fn initial_locals(&self) -> Vec<Type> {
+        let mut locals = Vec::new();
+        if !self.method.is_static {
+            locals.push(
+                if self.method.name == "<init>"
+                    && self.verifier.class_name != "java/lang/Object"
+                {
+                    Type::UninitializedThis
+                } else {
+                    Type::reference(self.verifier.class_name)
+                },
+            );
+        }
+        locals.extend(self.method.parameters.iter().map(argument_type));
+        locals
+    }
+
+    /// Convert locals and stack with one entry per value to a [`Frame`].
+    fn expand(
+        &self,
+        values: Vec<Type>,
+        stack: Vec<Type>,
+        pc: usize,
+    ) -> Result<Frame, Failure> {
+        let mut locals = Vec::new();
+        for value in values {
+            let is_category2 = value.is_category2();
+            locals.push(value);
+            if is_category2 {
+                locals.push(Type::Top);
+            }
+        }
+        if locals.len() > self.code.local_variable_count {
+            return Err((
+                pc,
+                format!(
+                    "{} local variables exceed max_locals {}",
+                    locals.len(),
+                    self.code.local_variable_count
+                ),
+            ));
+        }
+        locals.resize(self.code.local_variable_count, Type::Top);
+
+        let stack_size: usize = stack.iter().map(Type::size).sum();
+        if stack_size > self.code.stack_depth {
+            return Err((
+                pc,
+                format!(
+                    "{} operand stack slots exceed max_stack {}",
+                    stack_size, self.code.stack_depth
+                ),
+            ));
+        }
+        Ok(Frame { locals, stack })
+    }
+
+    fn opcode_index(&self, pc: usize) -> Option<usize> {
+        self.pcs[..self.pcs.len() - 1].binary_search(&pc).ok()
+    }
+
+    fn decode_type(
+        &self,
+        type_info: &VerificationTypeInfo,
+        pc: usize,
+    ) -> Result<Type, Failure> {
+        Ok(match type_info {
+            VerificationTypeInfo::Top => Type::Top,
+            VerificationTypeInfo::Integer => Type::Int,
+            VerificationTypeInfo::Float => Type::Float,
+            VerificationTypeInfo::Double => Type::Double,
+            VerificationTypeInfo::Long => Type::Long,
+            VerificationTypeInfo::Null => Type::Null,
+            VerificationTypeInfo::UninitializedThis => Type::UninitializedThis,
+            VerificationTypeInfo::Object(name) => Type::reference(name),
+            VerificationTypeInfo::Uninitialized(offset) => {
+                match self.opcode_index(usize::from(*offset)) {
+                    Some(index)
+                        if matches!(
+                            self.code.byte_code[index],
+                            OpCode::New(_)
+                        ) =>
+                    {
+                        Type::Uninitialized(index)
+                    },
+                    _ => {
+                        return Err((
+                            pc,
+                            format!(
+                                "no new instruction at {} \
+of uninitialized stack map type",
+                                offset
+                            ),
+                        ));
+                    },
+                }
+            },
+        })
+    }
+
+    fn decode_types(
+        &self,
+        type_infos: &[VerificationTypeInfo],
+        pc: usize,
+    ) -> Result<Vec<Type>, Failure> {
+        type_infos
+            .iter()
+            .map(|type_info| self.decode_type(type_info, pc))
+            .collect()
+    }
+
+    /// Returns the frames of `stack_map` by the index of their opcode.
+    fn decode_stack_map(
+        &self,
+        initial_locals: &[Type],
+        stack_map: &[StackMapFrame],
+    ) -> Result<HashMap<usize, Frame>, Failure> {
+        let mut frames = HashMap::new();
+        let mut locals = initial_locals.to_vec();
+        let mut pc = None;
+        for stack_map_frame in stack_map {
+            let offset_delta = usize::from(stack_map_frame.offset_delta());
+            let frame_pc =
+                pc.map_or(offset_delta, |pc: usize| pc + offset_delta + 1);
+            pc = Some(frame_pc);
+
+            let stack = match stack_map_frame {
+                StackMapFrame::Same { .. } => Vec::new(),
+                StackMapFrame::SameLocals1StackItem { stack, .. } => {
+                    vec![self.decode_type(stack, frame_pc)?]
+                },
+                StackMapFrame::Chop { count, .. } => {
+                    let count = usize::from(*count);
+                    if count > locals.len() {
+                        return Err((
+                            frame_pc,
+                            format!(
+                                "stack map frame chops {} of {} locals",
+                                count,
+                                locals.len()
+                            ),
+                        ));
+                    }
+                    locals.truncate(locals.len() - count);
+                    Vec::new()
+                },
+                StackMapFrame::Append {
+                    locals: appended, ..
+                } => {
+                    locals.extend(self.decode_types(appended, frame_pc)?);
+                    Vec::new()
+                },
+                StackMapFrame::Full {
+                    locals: full_locals,
+                    stack,
+                    ..
+                } => {
+                    locals = self.decode_types(full_locals, frame_pc)?;
+                    self.decode_types(stack, frame_pc)?
+                },
+            };
+
+            let index = self.opcode_index(frame_pc).ok_or_else(|| {
+                (
+                    frame_pc,
+                    "stack map frame is no instruction boundary".to_string(),
+                )
+            })?;
+            frames.insert(index, self.expand(locals.clone(), stack, frame_pc)?);
+        }
+        Ok(frames)
+    }
+}
+
+/// How execution continues after an instruction.
+struct Flow {
+    /// The opcode indices of the branch targets.
+    targets: Vec<usize>,
+    /// Whether the next instruction may be executed.
+    falls_through: bool,
+}
+
+impl Flow {
+    const END: Flow = Flow {
+        targets: Vec::new(),
+        falls_through: false,
+    };
+    const NEXT: Flow = Flow {
+        targets: Vec::new(),
+        falls_through: true,
+    };
+}
+
+/// Applies the type effect of the instruction at `index` to `frame`.
+struct Instruction<'a, 'b> {
+    verifier: &'a Verifier<'a>,
+    method: &'a Method,
+    frame: &'b mut Frame,
+    max_stack: usize,
+    index: usize,
+}
+
+impl<'a, 'b> Instruction<'a, 'b> {
+    fn pop_any(&mut self) -> Result<Type, String> {
+        self.frame
+            .stack
+            .pop()
+            .ok_or_else(|| "operand stack underflow".to_string())
+    }
+
+    fn pop(&mut self, expected: &Type) -> Result<Type, String> {
+        let value = self.pop_any()?;
+        if self.verifier.is_assignable(&value, expected) {
+            Ok(value)
+        } else {
+            Err(format!(
+                "expected {} on the operand stack, found {}",
+                expected, value
+            ))
+        }
+    }
+
+    fn pop_category1(&mut self) -> Result<Type, String> {
+        let value = self.pop_any()?;
+        if value.is_category2() {
+            Err(format!("expected a category 1 value, found {}", value))
+        } else {
+            Ok(value)
+        }
+    }
+
+    fn pop_reference(&mut self) -> Result<Type, String> {
+        let value = self.pop_any()?;
+        if value.is_reference() {
+            Ok(value)
+        } else {
+            Err(format!(
+                "expected a reference on the operand stack, found {}",
+                value
+            ))
+        }
+    }
+
+    /// Pops an array, whose type is one of `types`, or null.
+    fn pop_array(&mut self, types: &[&str]) -> Result<Type, String> {
+        let value = self.pop_any()?;
+        match &value {
+            Type::Null => Ok(value),
+            Type::Reference(name) if types.contains(&name.as_str()) => {
+                Ok(value)
+            },
+            _ => Err(format!(
+                "expected {} on the operand stack, found {}",
+                types.join(" or "),
+                value
+            )),
+        }
+    }
+
+    fn push(&mut self, value: Type) -> Result<(), String> {
+        let size: usize = self.frame.stack.iter().map(Type::size).sum();
+        if size + value.size() > self.max_stack {
+            return Err("operand stack overflow".to_string());
+        }
+        self.frame.stack.push(value);
+        Ok(())
+    }
+
+    fn push_all(&mut self, values: &[Type]) -> Result<(), String> {
+        values.iter().try_for_each(|value| self.push(value.clone()))
+    }
+
+    /// Pops `operands`, topmost last, and pushes `result`.
+    fn operation(
+        &mut self,
+        operands: &[Type],
+        result: Option<Type>,
+    ) -> Result<(), String> {
+        for operand in operands.iter().rev() {
+            self.pop(operand)?;
+        }
+        match result {
+            Some(result) => self.push(result),
+            None => Ok(()),
+        }
+    }
+
+    fn local(&self, index: usize) -> Result<&Type, String> {
+        self.frame.locals.get(index).ok_or_else(|| {
+            format!(
+                "local variable {} exceeds max_locals {}",
+                index,
+                self.frame.locals.len()
+            )
+        })
+    }
+
+    fn load(&mut self, index: usize, expected: &Type) -> Result<(), String> {
+        let value = self.local(index)?.clone();
+        let valid = match expected {
+            Type::Reference(_) => value.is_reference(),
+            expected => self.verifier.is_assignable(&value, expected),
+        };
+        if !valid {
+            return Err(format!(
+                "expected {} in local variable {}, found {}",
+                expected, index, value
+            ));
+        }
+        self.push(value)
+    }
+
+    fn store(&mut self, index: usize, expected: &Type) -> Result<(), String> {
+        let value = match expected {
+            Type::Reference(_) => self.pop_reference()?,
+            expected => self.pop(expected)?,
+        };
+        let end = index + value.size();
+        if end > self.frame.locals.len() {
+            return Err(format!(
+                "local variable {} exceeds max_locals {}",
+                end - 1,
+                self.frame.locals.len()
+            ));
+        }
+        // a long or double in the previous slot is overwritten partly
+        if index > 0 && self.frame.locals[index - 1].is_category2() {
+            self.frame.locals[index - 1] = Type::Top;
+        }
+        if value.is_category2() {
+            self.frame.locals[index + 1] = Type::Top;
+        }
+        self.frame.locals[index] = value;
+        Ok(())
+    }
+
+    /// Replace all occurrences of `uninitialized` by `initialized`.
+    fn initialize(&mut self, uninitialized: &Type, initialized: Type) {
+        for value in self
+            .frame
+            .locals
+            .iter_mut()
+            .chain(self.frame.stack.iter_mut())
+        {
+            if value == uninitialized {
+                *value = initialized.clone();
+            }
+        }
+    }
+
+    fn target(&self, offset: usize, direction: OffsetDirection) -> usize {
+        match direction {
+            OffsetDirection::Forward => self.index + offset,
+            OffsetDirection::Backward => self.index - offset,
+        }
+    }
+
+    fn branch(
+        &self,
+        offset: usize,
+        direction: OffsetDirection,
+        falls_through: bool,
+    ) -> Flow {
+        Flow {
+            targets: vec![self.target(offset, direction)],
+            falls_through,
+        }
+    }
+
+    /// The flow of a switch, which jumps to `default` or one of `targets`.
+    fn switch<'t>(
+        &self,
+        default: &'t (usize, OffsetDirection),
+        targets: impl Iterator<Item = &'t (usize, OffsetDirection)>,
+    ) -> Flow {
+        Flow {
+            targets: std::iter::once(default)
+                .chain(targets)
+                .map(|(offset, direction)| self.target(*offset, *direction))
+                .collect(),
+            falls_through: false,
+        }
+    }
+
+    /// Checks the return of a value, whose type is like `expected`.
+    fn return_value(&mut self, expected: Type) -> Result<Flow, String> {
+        let return_type = self.method.return_type.as_ref().map(argument_type);
+        match return_type {
+            Some(return_type)
+                if std::mem::discriminant(&return_type)
+                    == std::mem::discriminant(&expected) =>
+            {
+                self.pop(&return_type)?;
+                Ok(Flow::END)
+            },
+            return_type => Err(format!(
+                "return of {} in a method returning {}",
+                expected,
+                return_type
+                    .map_or_else(|| "void".to_string(), |t| t.to_string())
+            )),
+        }
+    }
+
+    fn invoke(
+        &mut self,
+        method: &SymbolicMethod,
+        is_static: bool,
+        is_special: bool,
+    ) -> Result<(), String> {
+        let (parameters, return_type) = &method.descriptor.descriptor;
+        for parameter in parameters.iter().rev() {
+            self.pop(&argument_type(parameter))?;
+        }
+        if !is_static {
+            let class = class_type(&method.class_name);
+            if method.descriptor.name == "<init>" {
+                let receiver = self.pop_any()?;
+                let class_name = method.class_name.to_string();
+                match receiver {
+                    Type::UninitializedThis
+                        if class_name == self.verifier.class_name
+                            || class_name
+                                == self
+                                    .verifier
+                                    .super_class
+                                    .class_identifier()
+                                    .to_string() =>
+                    {
+                        self.initialize(
+                            &receiver,
+                            Type::reference(self.verifier.class_name),
+                        )
+                    },
+                    Type::Uninitialized(_) => self.initialize(&receiver, class),
+                    receiver => {
+                        return Err(format!(
+                            "invalid receiver {} of {}.<init>",
+                            receiver, class_name
+                        ));
+                    },
+                }
+            } else if is_special {
+                self.pop(&Type::reference(self.verifier.class_name))?;
+            } else {
+                let objectref = self.pop(&class)?;
+                // interfaces are no superclasses,
+                // so only invokevirtual can fail the check
+                let passes = self.verifier.passes_protected_check(
+                    &method.class_name,
+                    &objectref,
+                    |class| {
+                        class
+                            .find_method(
+                                &method.descriptor.name,
+                                (parameters, return_type.as_ref()),
+                                true,
+                            )
+                            .filter(|(method, _)| {
+                                method
+                                    .access_flags
+                                    .contains(MethodAccessFlag::Protected)
+                            })
+                            .map(|(_, declaring_class)| declaring_class)
+                    },
+                );
+                if !passes {
+                    return Err(
+                        "bad access to protected data in invokevirtual"
+                            .to_string(),
+                    );
+                }
+            }
+        }
+        if let Some(return_type) = return_type {
+            self.push(argument_type(return_type))?;
+        }
+        Ok(())
+    }
+
+    /// Fails if `opcode` may not access the field `name` of `class`
+    /// on `objectref`, see [`Verifier::passes_protected_check`].
+    fn check_protected_field(
+        &self,
+        class: &ClassIdentifier,
+        name: &str,
+        objectref: &Type,
+        opcode: &str,
+    ) -> Result<(), String> {
+        let passes =
+            self.verifier
+                .passes_protected_check(class, objectref, |class| {
+                    class.lookup_field(name).filter(|declaring_class| {
+                        declaring_class.instance_fields().iter().any(|field| {
+                            field.name == name
+                                && field
+                                    .access_flags
+                                    .contains(FieldAccessFlag::Protected)
+                        })
+                    })
+                });
+        if passes {
+            Ok(())
+        } else {
+            Err(format!("bad access to protected data in {}", opcode))
+        }
+    }
+
+    fn dup(&mut self, dup: &Dup) -> Result<(), String> {
+        let value1 = self.pop_category1()?;
+        match dup {
+            Dup::Dup => self.push_all(&[value1.clone(), value1]),
+            Dup::X1 => {
+                let value2 = self.pop_category1()?;
+                self.push_all(&[value1.clone(), value2, value1])
+            },
+            Dup::X2 => {
+                let value2 = self.pop_any()?;
+                if value2.is_category2() {
+                    self.push_all(&[value1.clone(), value2, value1])
+                } else {
+                    let value3 = self.pop_category1()?;
+                    self.push_all(&[value1.clone(), value3, value2, value1])
+                }
+            },
+        }
+    }
+
+    fn dup2(&mut self, dup: &Dup) -> Result<(), String> {
+        // the duplicated values, topmost last
+        let values = {
+            let value1 = self.pop_any()?;
+            if value1.is_category2() {
+                vec![value1]
+            } else {
+                vec![self.pop_category1()?, value1]
+            }
+        };
+        let below = match dup {
+            Dup::Dup => vec![],
+            Dup::X1 => vec![self.pop_category1()?],
+            Dup::X2 => {
+                let value = self.pop_any()?;
+                if value.is_category2() {
+                    vec![value]
+                } else {
+                    vec![self.pop_category1()?, value]
+                }
+            },
+        };
+        self.push_all(&values)?;
+        self.push_all(&below)?;
+        self.push_all(&values)
+    }
+
+    fn execute(&mut self, opcode: &OpCode) -> Result<Flow, String> {
+        use Type::{Double, Float, Int, Long};
+
+        let object = || Type::reference("java/lang/Object");
+        match opcode {
+            OpCode::Aaload => {
+                self.pop(&Int)?;
+                let component = match self.pop_reference()? {
+                    Type::Null => Type::Null,
+                    Type::Reference(name) => {
+                        match name.strip_prefix('[').and_then(component_class) {
+                            Some(component) => Type::reference(component),
+                            None => {
+                                return Err(format!(
+                                    "expected an array of references \
+on the operand stack, found {}",
+                                    name
+                                ));
+                            },
+                        }
+                    },
+                    value => {
+                        return Err(format!(
+                            "expected an array of references \
+on the operand stack, found {}",
+                            value
+                        ));
+                    },
+                };
+                self.push(component)?;
+            },
+            OpCode::Aastore => {
+                self.pop(&object())?;
+                self.pop(&Int)?;
+                self.pop(&Type::reference("[Ljava/lang/Object;"))?;
+            },
+            OpCode::AconstNull => self.push(Type::Null)?,
+            OpCode::Aload(index) => self.load(*index, &object())?,
+            OpCode::AnewArray(class) => {
+                self.pop(&Int)?;
+                self.push(class_type(class))?;
+            },
+            OpCode::Areturn => return self.return_value(object()),
+            OpCode::ArrayLength => {
+                match self.pop_reference()? {
+                    Type::Null => (),
+                    Type::Reference(name) if name.starts_with('[') => (),
+                    value => {
+                        return Err(format!(
+                            "expected an array on the operand stack, found {}",
+                            value
+                        ));
+                    },
+                }
+                self.push(Int)?;
+            },
+            OpCode::Astore(index) => self.store(*index, &object())?,
+            OpCode::Athrow => {
+                self.pop(&Type::reference("java/lang/Throwable"))?;
+                return Ok(Flow::END);
+            },
+            OpCode::Baload => {
+                self.pop(&Int)?;
+                self.pop_array(&["[B", "[Z"])?;
+                self.push(Int)?;
+            },
+            OpCode::Bastore => {
+                self.pop(&Int)?;
+                self.pop(&Int)?;
+                self.pop_array(&["[B", "[Z"])?;
+            },
+            OpCode::Bipush(_) | OpCode::Sipush(_) | OpCode::Iconst(_) => {
+                self.push(Int)?
+            },
+            OpCode::Caload | OpCode::Saload | OpCode::Iaload => {
+                self.pop(&Int)?;
+                self.pop_array(match opcode {
+                    OpCode::Caload => &["[C"],
+                    OpCode::Saload => &["[S"],
+                    _ => &["[I"],
+                })?;
+                self.push(Int)?;
+            },
+            OpCode::Castore | OpCode::Sastore | OpCode::Iastore => {
+                self.pop(&Int)?;
+                self.pop(&Int)?;
+                self.pop_array(match opcode {
+                    OpCode::Castore => &["[C"],
+                    OpCode::Sastore => &["[S"],
+                    _ => &["[I"],
+                })?;
+            },
+            OpCode::Checkcast(class_name) => {
+                self.pop_reference()?;
+                self.push(class_type(class_name))?;
+            },
+            OpCode::D2f => self.operation(&[Double], Some(Float))?,
+            OpCode::D2i => self.operation(&[Double], Some(Int))?,
+            OpCode::D2l => self.operation(&[Double], Some(Long))?,
+            OpCode::Dadd
+            | OpCode::Ddiv
+            | OpCode::Dmul
+            | OpCode::Drem
+            | OpCode::Dsub => {
+                self.operation(&[Double, Double], Some(Double))?
+            },
+            OpCode::Daload => {
+                self.pop(&Int)?;
+                self.pop_array(&["[D"])?;
+                self.push(Double)?;
+            },
+            OpCode::Dastore => {
+                self.pop(&Double)?;
+                self.pop(&Int)?;
+                self.pop_array(&["[D"])?;
+            },
+            OpCode::Dcmp(_) => self.operation(&[Double, Double], Some(Int))?,
+            OpCode::Dconst(_) => self.push(Double)?,
+            OpCode::Dload(index) => self.load(*index, &Double)?,
+            OpCode::Dneg => self.operation(&[Double], Some(Double))?,
+            OpCode::Dreturn => return self.return_value(Double),
+            OpCode::Dstore(index) => self.store(*index, &Double)?,
+            OpCode::Dup(dup) => self.dup(dup)?,
+            OpCode::Dup2(dup) => self.dup2(dup)?,
+            OpCode::F2d => self.operation(&[Float], Some(Double))?,
+            OpCode::F2i => self.operation(&[Float], Some(Int))?,
+            OpCode::F2l => self.operation(&[Float], Some(Long))?,
+            OpCode::Fadd
+            | OpCode::Fdiv
+            | OpCode::Fmul
+            | OpCode::Frem
+            | OpCode::Fsub => self.operation(&[Float, Float], Some(Float))?,
+            OpCode::Faload => {
+                self.pop(&Int)?;
+                self.pop_array(&["[F"])?;
+                self.push(Float)?;
+            },
+            OpCode::Fastore => {
+                self.pop(&Float)?;
+                self.pop(&Int)?;
+                self.pop_array(&["[F"])?;
+            },
+            OpCode::Fcmp(_) => self.operation(&[Float, Float], Some(Int))?,
+            OpCode::Fconst(_) => self.push(Float)?,
+            OpCode::Fload(index) => self.load(*index, &Float)?,
+            OpCode::Fneg => self.operation(&[Float], Some(Float))?,
+            OpCode::Freturn => return self.return_value(Float),
+            OpCode::Fstore(index) => self.store(*index, &Float)?,
+            OpCode::GetField {
+                class,
+                field_name,
+                field_type,
+            } => {
+                let objectref = self.pop(&class_type(class))?;
+                self.check_protected_field(
+                    class, field_name, &objectref, "getfield",
+                )?;
+                self.push(argument_type(field_type))?;
+            },
+            OpCode::GetStatic { field_type, .. } => {
+                self.push(argument_type(field_type))?
+            },
+            OpCode::Goto(offset, direction) => {
+                return Ok(self.branch(*offset, *direction, false));
+            },
+            OpCode::I2b | OpCode::I2c | OpCode::I2s | OpCode::Ineg => {
+                self.operation(&[Int], Some(Int))?
+            },
+            OpCode::I2d => self.operation(&[Int], Some(Double))?,
+            OpCode::I2f => self.operation(&[Int], Some(Float))?,
+            OpCode::I2l => self.operation(&[Int], Some(Long))?,
+            OpCode::Iadd
+            | OpCode::Iand
+            | OpCode::Idiv
+            | OpCode::Imul
+            | OpCode::Ior
+            | OpCode::Irem
+            | OpCode::Ishl
+            | OpCode::Ishr
+            | OpCode::Isub
+            | OpCode::Iushr
+            | OpCode::Ixor => self.operation(&[Int, Int], Some(Int))?,
+            OpCode::IfacmpEq(offset, direction)
+            | OpCode::IfacmpNe(offset, direction) => {
+                self.pop_reference()?;
+                self.pop_reference()?;
+                return Ok(self.branch(*offset, *direction, true));
+            },
+            OpCode::IficmpEq(offset, direction)
+            | OpCode::IficmpNe(offset, direction)
+            | OpCode::IficmpLt(offset, direction)
+            | OpCode::IficmpGe(offset, direction)
+            | OpCode::IficmpGt(offset, direction)
+            | OpCode::IficmpLe(offset, direction) => {
+                self.operation(&[Int, Int], None)?;
+                return Ok(self.branch(*offset, *direction, true));
+            },
+            OpCode::IfEq(offset, direction)
+            | OpCode::IfNe(offset, direction)
+            | OpCode::IfLt(offset, direction)
+            | OpCode::IfGe(offset, direction)
+            | OpCode::IfGt(offset, direction)
+            | OpCode::IfLe(offset, direction) => {
+                self.pop(&Int)?;
+                return Ok(self.branch(*offset, *direction, true));
+            },
+            OpCode::IfNonNull(offset, direction)
+            | OpCode::IfNull(offset, direction) => {
+                self.pop_reference()?;
+                return Ok(self.branch(*offset, *direction, true));
+            },
+            OpCode::Iinc { index, .. } => {
+                self.load(*index, &Int)?;
+                self.pop(&Int)?;
+            },
+            OpCode::Iload(index) => self.load(*index, &Int)?,
+            OpCode::InvokeDynamic(call_site) => {
+                let (parameters, return_type) = &call_site.descriptor;
+                for parameter in parameters.iter().rev() {
+                    self.pop(&argument_type(parameter))?;
+                }
+                if let Some(return_type) = return_type {
+                    self.push(argument_type(return_type))?;
+                }
+            },
+            OpCode::InstanceOf(_) => {
+                self.pop_reference()?;
+                self.push(Int)?;
+            },
+            OpCode::InvokeInterface(method) => {
+                self.invoke(method, false, false)?
+            },
+            OpCode::InvokeSpecial(method) => {
+                self.invoke(method, false, true)?
+            },
+            OpCode::InvokeStatic(method) => self.invoke(method, true, false)?,
+            OpCode::InvokeVirtual(method) => {
+                self.invoke(method, false, false)?
+            },
+            OpCode::Ireturn => return self.return_value(Int),
+            OpCode::Istore(index) => self.store(*index, &Int)?,
+            OpCode::L2d => self.operation(&[Long], Some(Double))?,
+            OpCode::L2f => self.operation(&[Long], Some(Float))?,
+            OpCode::L2i => self.operation(&[Long], Some(Int))?,
+            OpCode::Ladd
+            | OpCode::Land
+            | OpCode::Ldiv
+            | OpCode::Lmul
+            | OpCode::Lor
+            | OpCode::Lrem
+            | OpCode::Lsub
+            | OpCode::Lxor => self.operation(&[Long, Long], Some(Long))?,
+            OpCode::Laload => {
+                self.pop(&Int)?;
+                self.pop_array(&["[J"])?;
+                self.push(Long)?;
+            },
+            OpCode::Lastore => {
+                self.pop(&Long)?;
+                self.pop(&Int)?;
+                self.pop_array(&["[J"])?;
+            },
+            OpCode::Lcmp => self.operation(&[Long, Long], Some(Int))?,
+            OpCode::Lconst(_) => self.push(Long)?,
+            OpCode::Ldc(ldc) => self.push(match ldc {
+                Ldc::Int(_) => Int,
+                Ldc::Float(_) => Float,
+                Ldc::Long(_) => Long,
+                Ldc::Double(_) => Double,
+                Ldc::String(_) => Type::reference("java/lang/String"),
+                Ldc::Class(_) => Type::reference("java/lang/Class"),
+                Ldc::Method(_) => {
+                    Type::reference("java/lang/invoke/MethodHandle")
+                },
+                Ldc::Dynamic(call_site) => argument_type(
+                    call_site.descriptor.1.as_ref().expect("constant type"),
+                ),
+            })?,
+            OpCode::Lload(index) => self.load(*index, &Long)?,
+            OpCode::Lneg => self.operation(&[Long], Some(Long))?,
+            OpCode::Lreturn => return self.return_value(Long),
+            OpCode::Lshl | OpCode::Lshr | OpCode::Lushr => {
+                self.operation(&[Long, Int], Some(Long))?
+            },
+            OpCode::Lstore(index) => self.store(*index, &Long)?,
+            OpCode::Monitorenter | OpCode::Monitorexit => {
+                self.pop_reference()?;
+            },
+            OpCode::MultiAnewArray(class) => {
+                let (dimensions, _) = class.get_array_class_name();
+                for _ in 0..dimensions {
+                    self.pop(&Int)?;
+                }
+                self.push(class_type(class))?;
+            },
+            OpCode::New(_) => self.push(Type::Uninitialized(self.index))?,
+            OpCode::NewArray(array_type) => {
+                self.pop(&Int)?;
+                self.push(Type::reference(match array_type {
+                    ArrayType::Boolean => "[Z",
+                    ArrayType::Char => "[C",
+                    ArrayType::Float => "[F",
+                    ArrayType::Double => "[D",
+                    ArrayType::Byte => "[B",
+                    ArrayType::Short => "[S",
+                    ArrayType::Int => "[I",
+                    ArrayType::Long => "[J",
+                }))?;
+            },
+            OpCode::Nop => (),
+            OpCode::Pop => {
+                self.pop_category1()?;
+            },
+            OpCode::Pop2 => {
+                if !self.pop_any()?.is_category2() {
+                    self.pop_category1()?;
+                }
+            },
+            OpCode::PutField {
+                class,
+                field_name,
+                field_type,
+            } => {
+                self.pop(&argument_type(field_type))?;
+                // fields of `this` can be set before the super constructor
+                // is called
+                if !(self.frame.stack.last() == Some(&Type::UninitializedThis)
+                    && class.to_string() == self.verifier.class_name)
+                {
+                    let objectref = self.pop(&class_type(class))?;
+                    self.check_protected_field(
+                        class, field_name, &objectref, "putfield",
+                    )?;
+                } else {
+                    self.pop_any()?;
+                }
+            },
+            OpCode::PutStatic { field_type, .. } => {
+                self.pop(&argument_type(field_type))?;
+            },
+            OpCode::Return => {
+                if self.method.return_type.is_some() {
+                    return Err("return of void in a method returning a value"
+                        .to_string());
+                }
+                if self.frame.locals.contains(&Type::UninitializedThis) {
+                    return Err(
+                        "constructor returns before calling the super \
+constructor"
+                            .to_string(),
+                    );
+                }
+                return Ok(Flow::END);
+            },
+            OpCode::Swap => {
+                let value1 = self.pop_category1()?;
+                let value2 = self.pop_category1()?;
+                self.push_all(&[value1, value2])?;
+            },
+            OpCode::Tableswitch {
+                default, targets, ..
+            } => {
+                self.pop(&Int)?;
+                return Ok(self.switch(default, targets.iter()));
+            },
+            OpCode::Lookupswitch { default, targets } => {
+                self.pop(&Int)?;
+                return Ok(self.switch(default, targets.iter().map(|(_, t)| t)));
+            },
+            OpCode::Jsr(..) | OpCode::Ret(_) => {
+                return Err("unsupported instruction".to_string());
+            },
+        }
+        Ok(Flow::NEXT)
+    }
+}
@@ -1,5 +1,7 @@
+pub mod call_site;
 pub mod frame_stack;
 pub mod local_variables;
+pub mod native_context;
 pub mod op_code;
 pub mod program_counter;
 
@@ -11,230 +13,518 @@ use self::{frame_stack::StackValue, op_code::OffsetDirection};
 pub use crate::executor::op_code::OpCode;
 use crate::{
     class::{
-        ArgumentKind, Class, ClassInstance, Code, Method, MethodCode,
-        RustMethodReturn, SimpleArgumentKind,
+        builtin_classes::SystemExit, class_identifier, ArgumentKind, ArrayName,
+        Class, ClassIdentifier, ClassInstance, ClassName, Code, ExceptionTable,
+        Method, MethodCode, RustMethodReturn, SimpleArgumentKind,
     },
+    classloader::{load_class_by_name, ClassLoadingError},
     executor::{
         frame_stack::FrameStack,
         local_variables::{LocalVariables, VariableValueOrValue},
+        native_context::NativeContext,
         program_counter::ProgramCounter,
     },
-    heap::Heap,
+    heap::{ClassLoaderId, Heap},
 };
 
 pub struct ExecutorFrame {
     frame: Frame,
     pc: ProgramCounter,
     class: Rc<dyn Class>,
+    exception_table: Vec<ExceptionTable>,
 }
 
-pub fn run(code: &Code, heap: &mut Heap, initial_class: Rc<dyn Class>) {
-    let mut frame_stack: Vec<ExecutorFrame> = Vec::new();
-    let mut current_frame: Frame = Frame {
+impl ExecutorFrame {
+    fn new(code: &Code, frame: Frame, class: Rc<dyn Class>) -> Self {
+        ExecutorFrame {
+            frame,
+            pc: ProgramCounter::new(code.byte_code.clone()),
+            class,
+            exception_table: code.exception_table.clone(),
+        }
+    }
+}
+
+/// Execute `code` to completion (without passing any arguments to it).
+///
+/// Returns the exception thrown by `code`, if it doesn't handle it.
+pub fn run(
+    code: &Code,
+    heap: &mut Heap,
+    initial_class: Rc<dyn Class>,
+) -> Result<(), Rc<dyn ClassInstance>> {
+    let frame = Frame {
         local_variables: LocalVariables::new(code.local_variable_count),
         operand_stack: FrameStack::new(code.stack_depth),
     };
-    let mut current_pc: ProgramCounter =
-        ProgramCounter::new(code.byte_code.clone());
-    let mut current_class = initial_class;
+    execute(ExecutorFrame::new(code, frame, initial_class), heap).map(|_| ())
+}
+
+/// Invoke `method` with `arguments` and run it to completion.
+///
+/// This starts a nested executor loop,
+/// which allows builtin (Rust) methods to call back into Java code,
+/// e.g. to dispatch `toString()` virtually.
+/// `arguments` have to include "this" as first element
+/// for non-static methods.
+///
+/// If `method` does not handle an exception,
+/// it is returned as error, so that the caller can handle
+/// (or rethrow) it.
+pub fn invoke_method(
+    heap: &mut Heap,
+    method: &Rc<Method>,
+    defining_class: Rc<dyn Class>,
+    arguments: Vec<StackValue>,
+) -> Result<ReturnValue, Rc<dyn ClassInstance>> {
+    let mut caller_frame = Frame {
+        local_variables: LocalVariables::new(0),
+        operand_stack: FrameStack::new(
+            arguments.iter().map(|a| a.size() as usize).sum(),
+        ),
+    };
+    for argument in arguments {
+        caller_frame.operand_stack.push(argument).unwrap();
+    }
+    let mut frame = new_frame(method);
+    prepare_parameters(
+        &mut caller_frame,
+        &mut frame,
+        method.parameters.len(),
+        method.is_static,
+    );
+
+    match &method.code {
+        MethodCode::Bytecode(code) => {
+            execute(ExecutorFrame::new(code, frame, defining_class), heap)
+        },
+        MethodCode::Rust(code) => {
+            match code(&mut NativeContext::new(
+                &mut frame,
+                method,
+                heap,
+                defining_class,
+                None,
+            )) {
+                RustMethodReturn::Throw(e) => Err(e),
+                value => Ok(value.into()),
+            }
+        },
+    }
+}
+
+/// Invoke the instance method `name` with `descriptor` on `this`,
+/// resolving it on the class of `this` (like `invokevirtual`).
+///
+/// `arguments` must not include "this".
+pub fn invoke_virtual(
+    heap: &mut Heap,
+    this: Rc<dyn ClassInstance>,
+    name: &str,
+    descriptor: (&[ArgumentKind], Option<&ArgumentKind>),
+    arguments: Vec<StackValue>,
+) -> Result<ReturnValue, Rc<dyn ClassInstance>> {
+    let (method, defining_class) =
+        this.class().get_method(name, descriptor, true);
+    let mut arguments = arguments;
+    arguments.insert(0, StackValue::Reference(Some(this)));
+
+    invoke_method(heap, &method, defining_class, arguments)
+}
+
+/// Invoke the static method `name` with `descriptor` of `class`
+/// (like `invokestatic`).
+pub fn invoke_static(
+    heap: &mut Heap,
+    class: &Rc<dyn Class>,
+    name: &str,
+    descriptor: (&[ArgumentKind], Option<&ArgumentKind>),
+    arguments: Vec<StackValue>,
+) -> Result<ReturnValue, Rc<dyn ClassInstance>> {
+    initialize_class(heap, class)?;
+    let (method, _) = class.get_method(name, descriptor, false);
+
+    invoke_method(heap, &method, class.clone(), arguments)
+}
+
+/// Initialize `class` (JVMS §5.5) before its first active use,
+/// i.e. initialize its superclass and run its static initializer.
+///
+/// Classes are initialized only once, even if their initializer fails.
+/// An exception thrown by the initializer is returned
+/// wrapped in an `ExceptionInInitializerError`, unless it is an `Error`.
+pub fn initialize_class(
+    heap: &mut Heap,
+    class: &Rc<dyn Class>,
+) -> Result<(), Rc<dyn ClassInstance>> {
+    // the class counts as initialized while its initializer runs,
+    // so that it can use the class itself
+    if !heap.begin_initialization(class.as_ref()) {
+        return Ok(());
+    }
+    if let Some(super_class) = class.super_class() {
+        initialize_class(heap, &super_class)?;
+    }
+    let Some((initializer, _)) =
+        class.find_method("<clinit>", (&[], None), false)
+    else {
+        return Ok(());
+    };
+    invoke_method(heap, &initializer, class.clone(), Vec::new())
+        .map(|_| ())
+        .map_err(|e| {
+            let error = heap
+                .find_class(
+                    ClassLoaderId::BOOTSTRAP,
+                    &class_identifier!(java / lang, Error),
+                )
+                .expect("Error is a builtin class")
+                .clone();
+            if e.as_any().is::<SystemExit>()
+                || e.class().is_sub_class_of(&error)
+            {
+                e
+            } else {
+                new_exception(
+                    heap,
+                    &class_identifier!(
+                        java / lang,
+                        ExceptionInInitializerError
+                    ),
+                    &e.class().class_identifier().java_name(),
+                )
+            }
+        })
+}
+
+/// Create an instance of the builtin throwable class `class`
+/// with `message`.
+///
+/// If its constructor throws an exception, that one is returned instead.
+pub fn new_exception(
+    heap: &mut Heap,
+    class: &ClassIdentifier,
+    message: &str,
+) -> Rc<dyn ClassInstance> {
+    let class = heap
+        .find_class(ClassLoaderId::BOOTSTRAP, class)
+        .unwrap_or_else(|| panic!("exception class {} exists", class))
+        .clone();
+    let exception = class.new_instance(class.clone());
+    let message: Rc<dyn ClassInstance> =
+        Rc::new(heap.new_string(message.to_string()));
+
+    let (init, defining_class) = class.get_method(
+        "<init>",
+        (
+            &[ArgumentKind::Simple(SimpleArgumentKind::Class(
+                "java/lang/String".to_string(),
+            ))],
+            None,
+        ),
+        true,
+    );
+    match invoke_method(
+        heap,
+        &init,
+        defining_class,
+        vec![
+            StackValue::Reference(Some(exception.clone())),
+            StackValue::Reference(Some(message)),
+        ],
+    ) {
+        // the constructor itself threw an exception
+        Err(e) => e,
+        Ok(_) => exception,
+    }
+}
 
-    'executor_loop: loop {
-        match current_pc.current().0.execute(
-            &mut current_frame,
+/// Resolve `class`, which is referenced by a class defined by `loader`,
+/// loading it if necessary.
+///
+/// Returns the exception to throw if the class can't be loaded,
+/// e.g. `NoClassDefFoundError`.
+pub fn resolve_class(
+    heap: &mut Heap,
+    loader: ClassLoaderId,
+    class: &ClassIdentifier,
+) -> Result<Rc<dyn Class>, Rc<dyn ClassInstance>> {
+    if let Some(class) = heap.find_class(loader, class) {
+        return Ok(class.clone());
+    }
+    if let ClassName::Array { name, .. } = &class.class_name {
+        // array classes are created once their component class is loaded
+        if let ArrayName::Class(component) = name {
+            resolve_class(
+                heap,
+                loader,
+                &ClassIdentifier {
+                    package: class.package.clone(),
+                    class_name: ClassName::Plain(component.clone()),
+                },
+            )?;
+        }
+        return Ok(heap
+            .find_array_class(loader, class)
+            .expect("the component class is loaded"));
+    }
+    load_class_by_name(&class.to_string(), loader, heap).map_err(|e| match e {
+        ClassLoadingError::NotFound(name) => new_exception(
+            heap,
+            &class_identifier!(java / lang, NoClassDefFoundError),
+            &name,
+        ),
+        ClassLoadingError::Format(e) => new_exception(
+            heap,
+            &class_identifier!(java / lang, ClassFormatError),
+            &e.to_string(),
+        ),
+        ClassLoadingError::Verify(e) => new_exception(
+            heap,
+            &class_identifier!(java / lang, VerifyError),
+            &e.to_string(),
+        ),
+        ClassLoadingError::Invalid(e) => new_exception(
             heap,
-            &current_class,
+            &class_identifier!(java / lang, NoClassDefFoundError),
+            &e.to_string(),
+        ),
+        ClassLoadingError::Exception(e) => e,
+    })
+}
+
+/// Create the frame needed to execute `method`.
+fn new_frame(method: &Method) -> Frame {
+    match &method.code {
+        MethodCode::Bytecode(c) => Frame {
+            local_variables: LocalVariables::new(c.local_variable_count),
+            operand_stack: FrameStack::new(c.stack_depth),
+        },
+        MethodCode::Rust(_) => {
+            // Calculate number of local variable slots needed
+            // to pass the parameters to `method`,
+            // since for builtin-methods,
+            // there's no java compiler which determines ahead of time
+            // the amount of local variable slots needed
+            // to execute the method.
+            // Note that double/long values always occupy
+            // two slots of local variables.
+            let local_variable_count: usize = method
+                .parameters
+                .iter()
+                .map(|p| {
+                    if p == &ArgumentKind::Simple(SimpleArgumentKind::Long)
+                        || p == &ArgumentKind::Simple(
+                            SimpleArgumentKind::Double,
+                        )
+                    {
+                        2
+                    } else {
+                        1
+                    }
+                })
+                .sum();
+            Frame {
+                local_variables: LocalVariables::new(
+                    // Non-Static methods receive "this"
+                    // implicitly as additional parameter
+                    (if method.is_static { 0 } else { 1 })
+                        + local_variable_count,
+                ),
+                operand_stack: FrameStack::new(0),
+            }
+        },
+    }
+}
+
+/// Run the executor loop until `initial_frame` returns
+/// or throws an exception it doesn't handle.
+fn execute(
+    initial_frame: ExecutorFrame,
+    heap: &mut Heap,
+) -> Result<ReturnValue, Rc<dyn ClassInstance>> {
+    let mut frame_stack: Vec<ExecutorFrame> = Vec::new();
+    let mut current = initial_frame;
+
+    loop {
+        match current.pc.current().0.execute(
+            &mut current.frame,
+            heap,
+            &current.class,
         ) {
-            Update::None => current_pc.next(1).unwrap(),
+            Update::None => current.pc.next(1).unwrap(),
             Update::MethodCall {
                 method,
                 is_static,
                 defining_class,
-            } => match &method.code {
-                MethodCode::Bytecode(c) => {
-                    let mut new_frame = Frame {
-                        local_variables: LocalVariables::new(
-                            c.local_variable_count,
-                        ),
-                        operand_stack: FrameStack::new(c.stack_depth),
-                    };
-                    let pc = ProgramCounter::new(c.byte_code.clone());
+            } => {
+                let mut new_frame = new_frame(&method);
+                match &method.code {
+                    MethodCode::Bytecode(c) => {
+                        assert_eq!(
+                            is_static, method.is_static,
+                            "method metadata and InvokeXXX agree"
+                        );
 
-                    assert_eq!(
-                        is_static, method.is_static,
-                        "method metadata and InvokeXXX agree"
-                    );
+                        prepare_parameters(
+                            &mut current.frame,
+                            &mut new_frame,
+                            method.parameters.len(),
+                            method.is_static,
+                        );
 
-                    prepare_parameters(
-                        &mut current_frame,
-                        &mut new_frame,
-                        method.parameters.len(),
-                        method.is_static,
-                    );
+                        let caller = std::mem::replace(
+                            &mut current,
+                            ExecutorFrame::new(c, new_frame, defining_class),
+                        );
+                        frame_stack.push(caller);
+                    },
+                    MethodCode::Rust(code) => {
+                        prepare_parameters(
+                            &mut current.frame,
+                            &mut new_frame,
+                            method.parameters.len(),
+                            method.is_static,
+                        );
 
-                    frame_stack.push(ExecutorFrame {
-                        frame: current_frame,
-                        pc: current_pc,
-                        class: current_class,
-                    });
-                    current_frame = new_frame;
-                    current_pc = pc;
-                    current_class = defining_class;
-                },
-                MethodCode::Rust(code) => {
-                    // Calculate number of local variable slots needed
-                    // to pass the parameters to `method`,
-                    // since for builtin-methods,
-                    // there's no java compiler which determines ahead of time
-                    // the amount of local variable slots needed
-                    // to execute the method.
-                    // Note that double/long values always occupy
-                    // two slots of local variables.
-                    let local_variable_count: usize = method
-                        .parameters
-                        .iter()
-                        .map(|p| {
-                            if p == &ArgumentKind::Simple(
-                                SimpleArgumentKind::Long,
-                            ) || p
-                                == &ArgumentKind::Simple(
-                                    SimpleArgumentKind::Double,
-                                )
-                            {
-                                2
-                            } else {
-                                1
-                            }
-                        })
-                        .sum();
-                    let mut new_frame = Frame {
-                        local_variables: LocalVariables::new(
-                            // Non-Static methods receive "this"
-                            // implicitly as additional parameter
-                            (if method.is_static { 0 } else { 1 })
-                                + local_variable_count,
-                        ),
-                        operand_stack: FrameStack::new(0),
-                    };
-
-                    prepare_parameters(
-                        &mut current_frame,
-                        &mut new_frame,
-                        method.parameters.len(),
-                        method.is_static,
-                    );
-
-                    match code(&mut new_frame) {
-                        RustMethodReturn::Void => (),
-                        RustMethodReturn::Value(value) => current_frame
-                            .operand_stack
-                            .push(value.into())
-                            .unwrap(),
-                    }
+                        let mut context = NativeContext::new(
+                            &mut new_frame,
+                            &method,
+                            heap,
+                            defining_class,
+                            Some(current.class.clone()),
+                        );
+                        match code(&mut context) {
+                            RustMethodReturn::Void => (),
+                            RustMethodReturn::Value(value) => current
+                                .frame
+                                .operand_stack
+                                .push(value.into())
+                                .unwrap(),
+                            RustMethodReturn::Throw(e) => {
+                                current =
+                                    unwind(e, current, &mut frame_stack, heap)?;
+                                continue;
+                            },
+                        }
 
-                    current_pc.next(1).unwrap();
-                },
+                        current.pc.next(1).unwrap();
+                    },
+                }
             },
             Update::Return(value) => {
-                (current_frame, current_pc) = match frame_stack.pop() {
-                    None => break,
-                    Some(frame) => (frame.frame, frame.pc),
+                current = match frame_stack.pop() {
+                    None => break Ok(value),
+                    Some(frame) => frame,
                 };
                 match value {
-                    ReturnValue::Int(i) => current_frame
+                    ReturnValue::Int(i) => current
+                        .frame
                         .operand_stack
                         .push(StackValue::Int(i))
                         .unwrap(),
-                    ReturnValue::Long(l) => current_frame
+                    ReturnValue::Long(l) => current
+                        .frame
                         .operand_stack
                         .push(StackValue::Long(l))
                         .unwrap(),
-                    ReturnValue::Float(f) => current_frame
+                    ReturnValue::Float(f) => current
+                        .frame
                         .operand_stack
                         .push(StackValue::Float(f))
                         .unwrap(),
-                    ReturnValue::Double(d) => current_frame
+                    ReturnValue::Double(d) => current
+                        .frame
                         .operand_stack
                         .push(StackValue::Double(d))
                         .unwrap(),
-                    ReturnValue::Reference(a) => current_frame
+                    ReturnValue::Reference(a) => current
+                        .frame
                         .operand_stack
                         .push(StackValue::Reference(a))
                         .unwrap(),
                     ReturnValue::Void => (),
                 }
-                current_pc.next(1).unwrap();
+                current.pc.next(1).unwrap();
             },
             Update::GoTo(offset, direction) => match direction {
-                OffsetDirection::Forward => current_pc.next(offset).unwrap(),
+                OffsetDirection::Forward => current.pc.next(offset).unwrap(),
                 OffsetDirection::Backward => {
-                    current_pc.previous(offset).unwrap()
+                    current.pc.previous(offset).unwrap()
                 },
             },
             Update::Exception(e) => {
-                // allow the while-loop to handle the current method
-                // without special casing the first iteration
-                frame_stack.push(ExecutorFrame {
-                    frame: current_frame,
-                    pc: current_pc,
-                    class: current_class,
-                });
-                // search the call stack for an exception handler
-                // matching the current exception
-                while let Some(ExecutorFrame { frame, pc, class }) =
-                    frame_stack.pop()
-                {
-                    current_frame = frame;
-                    current_pc = pc;
-                    current_class = class;
-                    // check all exception handler of the current method
-                    // expectation: the order is 'correct', i.e.
-                    // the first matching handler is the one that's supposed
-                    // to handle the current exception
-                    // (i.e. this code does NOT search the most specific
-                    // matching handler)
-                    for exception in code.exception_table.iter() {
-                        // is the exception handler active in the region
-                        // that is currently executed?
-                        if exception.active.contains(&current_pc.current().1) {
-                            // does the exception handler handle the
-                            // class of the thrown exception?
-                            // TODO: inheritance
-                            // (i.e. is e.class().class_identifier()
-                            //  could also be a subclass of identifer)
-                            let catch_type_match = match &exception.catch_type {
-                                // exception handler handles all exceptions
-                                None => true,
-                                Some(identifier) => {
-                                    e.class().class_identifier() == identifier
-                                        || e.class().is_sub_class_of(
-                                            heap.find_class(identifier)
-                                                .unwrap(),
-                                        )
-                                },
-                            };
-                            if catch_type_match {
-                                current_frame.operand_stack.clear();
-                                current_frame
-                                    .operand_stack
-                                    .push(StackValue::Reference(Some(
-                                        e.clone(),
-                                    )))
-                                    .unwrap();
-                                current_pc
-                                    .set(exception.handler_position)
-                                    .unwrap();
-                                continue 'executor_loop;
-                            }
-                        }
-                    }
-                }
-                // no handler has been found: terminate
-                panic!("Uncaught exception: {:?}", e);
+                current = unwind(e, current, &mut frame_stack, heap)?;
             },
         }
     }
 }
 
+/// Search the call stack (starting with `current`)
+/// for an exception handler matching the exception `e`.
+///
+/// Returns the frame containing the handler,
+/// prepared to continue execution at the handler,
+/// or `e` if no frame handles it.
+fn unwind(
+    e: Rc<dyn ClassInstance>,
+    current: ExecutorFrame,
+    frame_stack: &mut Vec<ExecutorFrame>,
+    heap: &Heap,
+) -> Result<ExecutorFrame, Rc<dyn ClassInstance>> {
+    // System.exit() must not be handled by anyone
+    if e.as_any().is::<SystemExit>() {
+        return Err(e);
+    }
+    // allow the while-loop to handle the current method
+    // without special casing the first iteration
+    frame_stack.push(current);
+    while let Some(mut current) = frame_stack.pop() {
+        // check all exception handler of the current method
+        // expectation: the order is 'correct', i.e.
+        // the first matching handler is the one that's supposed
+        // to handle the current exception
+        // (i.e. this code does NOT search the most specific
+        // matching handler)
+        for exception in current.exception_table.iter() {
+            // is the exception handler active in the region
+            // that is currently executed?
+            if exception.active.contains(&current.pc.current().1) {
+                // does the exception handler handle the
+                // class of the thrown exception?
+                let catch_type_match = match &exception.catch_type {
+                    // exception handler handles all exceptions
+                    None => true,
+                    // the catch type has been loaded
+                    // together with the class of the handler
+                    Some(identifier) => match heap
+                        .find_class(current.class.class_loader(), identifier)
+                    {
+                        Some(catch_type) => {
+                            e.class().is_same_class(catch_type.as_ref())
+                                || e.class().is_sub_class_of(catch_type)
+                        },
+                        None => false,
+                    },
+                };
+                if catch_type_match {
+                    let handler_position = exception.handler_position;
+                    current.frame.operand_stack.clear();
+                    current
+                        .frame
+                        .operand_stack
+                        .push(StackValue::Reference(Some(e.clone())))
+                        .unwrap();
+                    current.pc.set(handler_position).unwrap();
+                    return Ok(current);
+                }
+            }
+        }
+    }
+    // no handler has been found: let the caller deal with it
+    Err(e)
+}
+
 fn prepare_parameters(
     current_frame: &mut Frame,
     new_frame: &mut Frame,
@@ -289,6 +579,7 @@ pub enum Update {
     Exception(Rc<dyn ClassInstance>),
 }
 
+#[derive(Debug)]
 pub enum ReturnValue {
     // Primitive Types
     //   Integral Types
@@ -309,3 +600,24 @@ pub struct Frame {
     pub local_variables: LocalVariables,
     pub operand_stack: FrameStack,
 }
+
+impl From<RustMethodReturn> for ReturnValue {
+    fn from(value: RustMethodReturn) -> Self {
+        match value {
+            RustMethodReturn::Void => ReturnValue::Void,
+            RustMethodReturn::Value(value) => match value.into() {
+                StackValue::Int(i) => ReturnValue::Int(i),
+                StackValue::Long(l) => ReturnValue::Long(l),
+                StackValue::Float(f) => ReturnValue::Float(f),
+                StackValue::Double(d) => ReturnValue::Double(d),
+                StackValue::Reference(r) => ReturnValue::Reference(r),
+                StackValue::ReturnAddress(_) => {
+                    panic!("methods cannot return a return address")
+                },
+            },
+            RustMethodReturn::Throw(e) => {
+                panic!("cannot convert thrown exception {:?} to value", e)
+            },
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Call sites of `invokedynamic` instructions and dynamic constants,
+//! which are linked by bootstrap methods implemented in Rust.
+
+pub mod object_methods;
+pub mod string_concat;
+pub mod switch_bootstraps;
+
+use std::{cell::OnceCell, rc::Rc};
+
+use crate::{
+    class::{
+        class_identifier, ArgumentKind, Class, ClassIdentifier, ClassInstance,
+        Method,
+    },
+    classloader::ReferenceKind,
+    executor::{
+        frame_stack::StackValue, invoke_method, new_exception, ReturnValue,
+    },
+    heap::{ClassLoaderId, Heap},
+};
+
+/// Links `call_site` and returns its target,
+/// or the reason why the call site can't be linked.
+pub type RustBootstrapMethod =
+    fn(&mut Heap, &CallSite) -> Result<CallSiteTarget, String>;
+
+/// A static argument of a bootstrap method, resolved from the constant pool.
+#[derive(Debug, Clone)]
+pub enum BootstrapArgument {
+    Int(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    String(String),
+    Class(String),
+    MethodType(String),
+    MethodHandle {
+        kind: ReferenceKind,
+        class: String,
+        name: String,
+        descriptor: String,
+    },
+}
+
+/// The method a call site is linked to.
+#[derive(Debug)]
+pub struct CallSite {
+    /// Class and name of the bootstrap method.
+    pub bootstrap_method: (ClassIdentifier, String),
+    /// Class loader of the class containing the call site,
+    /// which resolves the classes named by the arguments.
+    pub loader: ClassLoaderId,
+    pub name: String,
+    /// Dynamic constants are represented
+    /// as call sites without parameters returning the constant.
+    pub descriptor: (Vec<ArgumentKind>, Option<ArgumentKind>),
+    pub arguments: Vec<BootstrapArgument>,
+    target: OnceCell<CallSiteTarget>,
+}
+
+#[derive(Debug)]
+pub struct CallSiteTarget {
+    pub method: Rc<Method>,
+    /// The class `method` is executed in.
+    pub class: Rc<dyn Class>,
+    /// Arguments passed to `method` before the ones of the call site.
+    pub bound_arguments: Vec<StackValue>,
+}
+
+impl CallSite {
+    pub fn new(
+        bootstrap_method: (ClassIdentifier, String),
+        loader: ClassLoaderId,
+        name: String,
+        descriptor: (Vec<ArgumentKind>, Option<ArgumentKind>),
+        arguments: Vec<BootstrapArgument>,
+    ) -> CallSite {
+        CallSite {
+            bootstrap_method,
+            loader,
+            name,
+            descriptor,
+            arguments,
+            target: OnceCell::new(),
+        }
+    }
+
+    /// Returns the target of the call site, linking it on first use.
+    ///
+    /// If the bootstrap method is unknown or fails,
+    /// a `BootstrapMethodError` is returned.
+    pub fn target(
+        &self,
+        heap: &mut Heap,
+    ) -> Result<&CallSiteTarget, Rc<dyn ClassInstance>> {
+        if let Some(target) = self.target.get() {
+            return Ok(target);
+        }
+        let (class, name) = &self.bootstrap_method;
+        let target = heap
+            .find_bootstrap_method(class, name)
+            .ok_or_else(|| {
+                format!("unknown bootstrap method {}.{}", class, name)
+            })
+            .and_then(|bootstrap_method| bootstrap_method(heap, self))
+            .map_err(|reason| {
+                new_exception(
+                    heap,
+                    &class_identifier!(
+                        java / lang / invoke,
+                        BootstrapMethodError
+                    ),
+                    &format!("call site {}: {}", self.name, reason),
+                )
+            })?;
+        Ok(self.target.get_or_init(|| target))
+    }
+
+    /// Invoke the target of the call site with `arguments`,
+    /// linking it if necessary.
+    pub fn invoke(
+        &self,
+        heap: &mut Heap,
+        arguments: Vec<StackValue>,
+    ) -> Result<ReturnValue, Rc<dyn ClassInstance>> {
+        let target = self.target(heap)?;
+        let method = target.method.clone();
+        let class = target.class.clone();
+        let mut all_arguments = target.bound_arguments.clone();
+        all_arguments.extend(arguments);
+        invoke_method(heap, &method, class, all_arguments)
+    }
+}
@@ -0,0 +1,274 @@
+//! Bootstrap method of `java.lang.runtime.ObjectMethods`,
+//! which javac uses for `toString`, `equals` and `hashCode` of records.
+
+use std::rc::Rc;
+
+use crate::{
+    class::{
+        access_flags::MethodAccessFlag,
+        builtin_classes::{object::is_same_object, string::string_value_of},
+        class_identifier, ArgumentKind, BytecodeClass, Class, ClassInstance,
+        FieldValue, Method, MethodCode, RustMethod, RustMethodReturn,
+        SimpleArgumentKind,
+    },
+    executor::{
+        call_site::{BootstrapArgument, CallSite, CallSiteTarget},
+        frame_stack::StackValue,
+        native_context::NativeContext,
+        ReturnValue,
+    },
+    heap::{ClassLoaderId, Heap},
+};
+
+/// Link `call_site` to the method named like it,
+/// which is implemented using the components of the record.
+///
+/// The static arguments are the record class,
+/// the names of its components separated by `;`
+/// and a getter for each component.
+pub fn bootstrap(
+    heap: &mut Heap,
+    call_site: &CallSite,
+) -> Result<CallSiteTarget, String> {
+    let (names, getters) = match call_site.arguments.as_slice() {
+        [
+            BootstrapArgument::Class(_),
+            BootstrapArgument::String(names),
+            getters @ ..,
+        ] => (names, getters),
+        _ => return Err("missing record class or component names".to_string()),
+    };
+    let names = names.split(';').filter(|name| !name.is_empty()).count();
+    if names != getters.len() {
+        return Err(format!(
+            "{} component names don't match {} getters",
+            names,
+            getters.len()
+        ));
+    }
+    let code: RustMethod = match call_site.name.as_str() {
+        "toString" => to_string,
+        "equals" => equals,
+        "hashCode" => hash_code,
+        name => return Err(format!("unsupported method {}", name)),
+    };
+
+    let class = heap
+        .find_class(
+            ClassLoaderId::BOOTSTRAP,
+            &class_identifier!(java / lang, Record),
+        )
+        .expect("Record is a builtin class")
+        .clone();
+    let method = Method {
+        code: MethodCode::Rust(code),
+        name: call_site.name.clone(),
+        parameters: call_site.descriptor.0.clone(),
+        return_type: call_site.descriptor.1.clone(),
+        is_static: true,
+        access_flags: MethodAccessFlag::Public | MethodAccessFlag::Static,
+        attributes: Default::default(),
+    };
+    Ok(CallSiteTarget {
+        method: Rc::new(method),
+        class,
+        bound_arguments: Vec::new(),
+    })
+}
+
+/// Returns the class of `record`,
+/// which declares its components in the `Record` attribute.
+fn record_class(record: &Rc<dyn ClassInstance>) -> Rc<dyn Class> {
+    let class = record.class();
+    let is_record = class
+        .as_any()
+        .downcast_ref::<BytecodeClass>()
+        .is_some_and(|class| class.attributes.record_components.is_some());
+    assert!(is_record, "{} is a record", class.class_identifier());
+    class
+}
+
+/// Returns the names and values of the components of `record`.
+fn components(record: &Rc<dyn ClassInstance>) -> Vec<(String, FieldValue)> {
+    let class = record_class(record);
+    let bytecode_class: &BytecodeClass = class.as_any().downcast_ref().unwrap();
+    bytecode_class
+        .attributes
+        .record_components
+        .iter()
+        .flatten()
+        .map(|component| {
+            let field = record.get_field(class.as_ref(), &component.name);
+            let value = field.value.borrow().clone();
+            (component.name.clone(), value)
+        })
+        .collect()
+}
+
+/// Returns `<simple name>[<component>=<value>, ...]`.
+fn to_string(context: &mut NativeContext) -> RustMethodReturn {
+    let record = context.reference(0).expect("the record is not null");
+    let class = record_class(&record);
+
+    let mut values = Vec::new();
+    for (name, value) in components(&record) {
+        let value = match value {
+            FieldValue::Byte(b) => b.to_string(),
+            FieldValue::Short(s) => s.to_string(),
+            FieldValue::Int(i) => i.to_string(),
+            FieldValue::Long(l) => l.to_string(),
+            FieldValue::Char(c) => {
+                char::decode_utf16([c]).map(|c| c.unwrap_or('?')).collect()
+            },
+            FieldValue::Float(f) => f.to_string(),
+            FieldValue::Double(d) => d.to_string(),
+            FieldValue::Boolean(z) => (z != 0).to_string(),
+            FieldValue::Reference(object) => {
+                match string_value_of(context.heap, object) {
+                    Ok(string) => string,
+                    Err(e) => return RustMethodReturn::Throw(e),
+                }
+            },
+        };
+        values.push(format!("{}={}", name, value));
+    }
+    let string = context.heap.new_string(format!(
+        "{}[{}]",
+        class.simple_name(),
+        values.join(", ")
+    ));
+    RustMethodReturn::Value(FieldValue::Reference(Some(Rc::new(string))))
+}
+
+/// Compare all components of two records of the same class,
+/// primitives by value and references by `equals()`.
+fn equals(context: &mut NativeContext) -> RustMethodReturn {
+    let record = context.reference(0).expect("the record is not null");
+    let Some(other) = context.reference(1) else {
+        return RustMethodReturn::Value(FieldValue::Boolean(0));
+    };
+    if !record_class(&record).is_same_class(other.class().as_ref()) {
+        return RustMethodReturn::Value(FieldValue::Boolean(0));
+    }
+
+    for ((_, a), (_, b)) in
+        components(&record).into_iter().zip(components(&other))
+    {
+        let equal = match (a, b) {
+            // like `Float.compare()`, which treats all NaNs as equal
+            (FieldValue::Float(a), FieldValue::Float(b)) => {
+                float_bits(a) == float_bits(b)
+            },
+            (FieldValue::Double(a), FieldValue::Double(b)) => {
+                double_bits(a) == double_bits(b)
+            },
+            (FieldValue::Reference(a), FieldValue::Reference(b)) => {
+                match object_equals(context, a, b) {
+                    Ok(equal) => equal,
+                    Err(e) => return RustMethodReturn::Throw(e),
+                }
+            },
+            (FieldValue::Byte(a), FieldValue::Byte(b)) => a == b,
+            (FieldValue::Short(a), FieldValue::Short(b)) => a == b,
+            (FieldValue::Int(a), FieldValue::Int(b)) => a == b,
+            (FieldValue::Long(a), FieldValue::Long(b)) => a == b,
+            (FieldValue::Char(a), FieldValue::Char(b)) => a == b,
+            (FieldValue::Boolean(a), FieldValue::Boolean(b)) => a == b,
+            (a, b) => panic!("components {:?} and {:?} differ in type", a, b),
+        };
+        if !equal {
+            return RustMethodReturn::Value(FieldValue::Boolean(0));
+        }
+    }
+    RustMethodReturn::Value(FieldValue::Boolean(1))
+}
+
+/// Compare `a` and `b` like `Objects.equals()`.
+fn object_equals(
+    context: &mut NativeContext,
+    a: Option<Rc<dyn ClassInstance>>,
+    b: Option<Rc<dyn ClassInstance>>,
+) -> Result<bool, Rc<dyn ClassInstance>> {
+    let a = match (a, &b) {
+        (None, None) => return Ok(true),
+        (None, Some(_)) => return Ok(false),
+        (Some(a), Some(b)) if is_same_object(&a, b) => return Ok(true),
+        (Some(a), _) => a,
+    };
+    match context.invoke_virtual(
+        a,
+        "equals",
+        (
+            &[ArgumentKind::Simple(SimpleArgumentKind::Class(
+                "java/lang/Object".to_string(),
+            ))],
+            Some(&ArgumentKind::Simple(SimpleArgumentKind::Boolean)),
+        ),
+        vec![StackValue::Reference(b)],
+    )? {
+        ReturnValue::Int(equal) => Ok(equal != 0),
+        value => panic!("equals() returned {:?}", value),
+    }
+}
+
+/// Combine the hash codes of all components,
+/// like `31 * h + hashCode(component)` starting with 0.
+fn hash_code(context: &mut NativeContext) -> RustMethodReturn {
+    let record = context.reference(0).expect("the record is not null");
+
+    let mut result: i32 = 0;
+    for (_, value) in components(&record) {
+        let hash = match value {
+            FieldValue::Byte(b) => b.into(),
+            FieldValue::Short(s) => s.into(),
+            FieldValue::Int(i) => i,
+            FieldValue::Long(l) => {
+                let bits = l as u64;
+                (bits ^ (bits >> 32)) as i32
+            },
+            FieldValue::Char(c) => c.into(),
+            FieldValue::Float(f) => float_bits(f) as i32,
+            FieldValue::Double(d) => {
+                let bits = double_bits(d);
+                (bits ^ (bits >> 32)) as i32
+            },
+            FieldValue::Boolean(z) => {
+                if z != 0 {
+                    1231
+                } else {
+                    1237
+                }
+            },
+            FieldValue::Reference(None) => 0,
+            FieldValue::Reference(Some(object)) => {
+                match context.invoke_virtual(
+                    object,
+                    "hashCode",
+                    (&[], Some(&ArgumentKind::Simple(SimpleArgumentKind::Int))),
+                    vec![],
+                ) {
+                    Ok(ReturnValue::Int(hash)) => hash,
+                    Ok(value) => panic!("hashCode() returned {:?}", value),
+                    Err(e) => return RustMethodReturn::Throw(e),
+                }
+            },
+        };
+        result = result.wrapping_mul(31).wrapping_add(hash);
+    }
+    RustMethodReturn::Value(FieldValue::Int(result))
+}
+
+/// Returns the bits of `f` like `Float.floatToIntBits()`,
+/// which maps all NaNs to the same value.
+fn float_bits(f: f32) -> u32 {
+    if f.is_nan() { 0x7fc0_0000 } else { f.to_bits() }
+}
+
+/// Returns the bits of `d` like `Double.doubleToLongBits()`.
+fn double_bits(d: f64) -> u64 {
+    if d.is_nan() {
+        0x7ff8_0000_0000_0000
+    } else {
+        d.to_bits()
+    }
+}
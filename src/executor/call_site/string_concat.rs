@@ -0,0 +1,179 @@
+//! Bootstrap methods of `java.lang.invoke.StringConcatFactory`,
+//! which javac uses for string concatenation since Java 9.
+
+use std::{iter, rc::Rc};
+
+use crate::{
+    class::{
+        access_flags::MethodAccessFlag,
+        builtin_classes::string::{floating_point_string, string_value_of},
+        class_identifier, ArgumentKind, ClassInstance, FieldValue, Method,
+        MethodCode, RustMethodReturn, SimpleArgumentKind,
+    },
+    executor::{
+        call_site::{BootstrapArgument, CallSite, CallSiteTarget},
+        frame_stack::StackValue,
+        native_context::NativeContext,
+    },
+    heap::{ClassLoaderId, Heap},
+};
+
+/// Marks an argument of the call site in a recipe.
+const ARGUMENT: char = '\u{1}';
+/// Marks a constant in a recipe.
+const CONSTANT: char = '\u{2}';
+
+/// Concatenate all arguments of the call site.
+pub fn make_concat(
+    heap: &mut Heap,
+    call_site: &CallSite,
+) -> Result<CallSiteTarget, String> {
+    let recipe = ARGUMENT.to_string().repeat(call_site.descriptor.0.len());
+    link(heap, call_site, recipe, Vec::new())
+}
+
+/// Concatenate the arguments of the call site
+/// as described by the recipe, the first static argument.
+pub fn make_concat_with_constants(
+    heap: &mut Heap,
+    call_site: &CallSite,
+) -> Result<CallSiteTarget, String> {
+    let (recipe, constants) = match call_site.arguments.split_first() {
+        Some((BootstrapArgument::String(recipe), constants)) => {
+            (recipe, constants)
+        },
+        _ => return Err("missing recipe".to_string()),
+    };
+    let constants = constants
+        .iter()
+        .map(|constant| {
+            Ok(match constant {
+                BootstrapArgument::String(string) => string.clone(),
+                BootstrapArgument::Int(i) => i.to_string(),
+                BootstrapArgument::Float(f) => floating_point_string(*f),
+                BootstrapArgument::Long(l) => l.to_string(),
+                BootstrapArgument::Double(d) => floating_point_string(*d),
+                constant => {
+                    return Err(format!("unsupported constant {:?}", constant));
+                },
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    link(heap, call_site, recipe.clone(), constants)
+}
+
+/// Link `call_site` to [`concat`], passing the recipe
+/// and the constants before the arguments of the call site.
+fn link(
+    heap: &mut Heap,
+    call_site: &CallSite,
+    recipe: String,
+    constants: Vec<String>,
+) -> Result<CallSiteTarget, String> {
+    let parameters = &call_site.descriptor.0;
+    if recipe.matches(ARGUMENT).count() != parameters.len()
+        || recipe.matches(CONSTANT).count() != constants.len()
+    {
+        return Err(format!(
+            "recipe {:?} doesn't match {} arguments and {} constants",
+            recipe,
+            parameters.len(),
+            constants.len()
+        ));
+    }
+    let string = ArgumentKind::Simple(SimpleArgumentKind::Class(
+        "java/lang/String".to_string(),
+    ));
+    let class = heap
+        .find_class(
+            ClassLoaderId::BOOTSTRAP,
+            &class_identifier!(java / lang, String),
+        )
+        .expect("String is a builtin class")
+        .clone();
+    let method = Method {
+        code: MethodCode::Rust(concat),
+        name: call_site.name.clone(),
+        parameters: iter::repeat(string.clone())
+            .take(1 + constants.len())
+            .chain(parameters.iter().cloned())
+            .collect(),
+        return_type: Some(string),
+        is_static: true,
+        access_flags: MethodAccessFlag::Public | MethodAccessFlag::Static,
+        attributes: Default::default(),
+    };
+    let bound_arguments = iter::once(recipe)
+        .chain(constants)
+        .map(|s| StackValue::Reference(Some(Rc::new(heap.new_string(s)))))
+        .collect();
+    Ok(CallSiteTarget {
+        method: Rc::new(method),
+        class,
+        bound_arguments,
+    })
+}
+
+/// Concatenate the arguments following the recipe and its constants.
+fn concat(context: &mut NativeContext) -> RustMethodReturn {
+    let recipe = context.string(0).expect("the recipe is not null");
+    let constant_count = recipe.matches(CONSTANT).count();
+    let mut constants = 1..;
+    let mut arguments = 1 + constant_count..;
+
+    let mut result = String::new();
+    for c in recipe.chars() {
+        match c {
+            ARGUMENT => {
+                let index = arguments.next().unwrap();
+                match argument_string(context, index) {
+                    Ok(string) => result.push_str(&string),
+                    Err(e) => return RustMethodReturn::Throw(e),
+                }
+            },
+            CONSTANT => {
+                let index = constants.next().unwrap();
+                result.push_str(&context.string(index).unwrap());
+            },
+            c => result.push(c),
+        }
+    }
+    RustMethodReturn::Value(FieldValue::Reference(Some(Rc::new(
+        context.heap.new_string(result),
+    ))))
+}
+
+/// Convert the argument at `index` to a string,
+/// like `String.valueOf()` does for its type.
+fn argument_string(
+    context: &mut NativeContext,
+    index: usize,
+) -> Result<String, Rc<dyn ClassInstance>> {
+    Ok(match &context.method().parameters[index] {
+        ArgumentKind::Simple(SimpleArgumentKind::Boolean) => {
+            context.boolean(index).to_string()
+        },
+        ArgumentKind::Simple(SimpleArgumentKind::Char) => {
+            context.char(index).to_string()
+        },
+        ArgumentKind::Simple(
+            SimpleArgumentKind::Byte
+            | SimpleArgumentKind::Short
+            | SimpleArgumentKind::Int,
+        ) => context.int(index).to_string(),
+        ArgumentKind::Simple(SimpleArgumentKind::Long) => {
+            context.long(index).to_string()
+        },
+        ArgumentKind::Simple(SimpleArgumentKind::Float) => {
+            floating_point_string(context.float(index))
+        },
+        ArgumentKind::Simple(SimpleArgumentKind::Double) => {
+            floating_point_string(context.double(index))
+        },
+        ArgumentKind::Simple(SimpleArgumentKind::Class(_))
+        | ArgumentKind::Array { .. } => {
+            let object = context.reference(index);
+            return string_value_of(context.heap, object);
+        },
+    })
+}
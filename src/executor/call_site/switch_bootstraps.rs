@@ -0,0 +1,224 @@
+//! Bootstrap methods of `java.lang.runtime.SwitchBootstraps`,
+//! which javac uses for `switch` with patterns.
+//!
+//! The call sites take the selector and the index of the first label
+//! to try, and return the index of the matching label,
+//! which is the input of a `tableswitch`.
+//! If a guard of the matching case fails,
+//! the call site is invoked again, restarting after that label.
+
+use std::rc::Rc;
+
+use crate::{
+    class::{
+        access_flags::MethodAccessFlag,
+        builtin_classes::{enum_class, ClassObject, StringInstance},
+        class_identifier, ArgumentKind, ClassInstance, FieldValue, Method,
+        MethodCode, RustMethod, RustMethodReturn, SimpleArgumentKind,
+    },
+    classloader::parse_class_or_array_identifier,
+    executor::{
+        call_site::{BootstrapArgument, CallSite, CallSiteTarget},
+        frame_stack::StackValue,
+        native_context::NativeContext,
+        resolve_class,
+    },
+    heap::{ClassLoaderId, Heap},
+};
+
+/// Link a switch over an object.
+///
+/// Labels are classes, matching instances of them,
+/// or strings, matching strings with the same characters.
+pub fn type_switch(
+    heap: &mut Heap,
+    call_site: &CallSite,
+) -> Result<CallSiteTarget, String> {
+    link(heap, call_site, type_switch_index)
+}
+
+/// Link a switch over an enum.
+///
+/// Labels are classes, matching instances of them,
+/// or strings, matching the constant of that name.
+pub fn enum_switch(
+    heap: &mut Heap,
+    call_site: &CallSite,
+) -> Result<CallSiteTarget, String> {
+    match call_site.descriptor.0.first() {
+        Some(ArgumentKind::Simple(SimpleArgumentKind::Class(_))) => {
+            link(heap, call_site, enum_switch_index)
+        },
+        selector => Err(format!("selector of type {:?} is no enum", selector)),
+    }
+}
+
+/// Link `call_site` to `code`,
+/// passing the labels before the selector and the restart index.
+fn link(
+    heap: &mut Heap,
+    call_site: &CallSite,
+    code: RustMethod,
+) -> Result<CallSiteTarget, String> {
+    // the selector is a reference, followed by the restart index
+    let is_switch = matches!(
+        &call_site.descriptor,
+        (parameters, Some(ArgumentKind::Simple(SimpleArgumentKind::Int)))
+            if matches!(
+                parameters.as_slice(),
+                [
+                    ArgumentKind::Simple(SimpleArgumentKind::Class(_))
+                        | ArgumentKind::Array { .. },
+                    ArgumentKind::Simple(SimpleArgumentKind::Int),
+                ]
+            )
+    );
+    if !is_switch {
+        return Err(format!(
+            "invalid type {:?} of a switch",
+            call_site.descriptor
+        ));
+    }
+
+    let mut parameters = Vec::new();
+    let mut bound_arguments = Vec::new();
+    for label in &call_site.arguments {
+        let (kind, value) = label_argument(heap, call_site.loader, label)?;
+        parameters.push(kind);
+        bound_arguments.push(value);
+    }
+    parameters.extend(call_site.descriptor.0.iter().cloned());
+
+    let class = heap
+        .find_class(
+            ClassLoaderId::BOOTSTRAP,
+            &class_identifier!(java / lang, Object),
+        )
+        .expect("Object is a builtin class")
+        .clone();
+    let method = Method {
+        code: MethodCode::Rust(code),
+        name: call_site.name.clone(),
+        parameters,
+        return_type: Some(ArgumentKind::Simple(SimpleArgumentKind::Int)),
+        is_static: true,
+        access_flags: MethodAccessFlag::Public | MethodAccessFlag::Static,
+        attributes: Default::default(),
+    };
+    Ok(CallSiteTarget {
+        method: Rc::new(method),
+        class,
+        bound_arguments,
+    })
+}
+
+/// Returns the type and value of `label` passed to the target,
+/// which is a `Class` object or a `String`.
+///
+/// Classes are resolved by `loader`.
+/// `Integer` labels aren't supported, since there is no `Integer` class.
+fn label_argument(
+    heap: &mut Heap,
+    loader: ClassLoaderId,
+    label: &BootstrapArgument,
+) -> Result<(ArgumentKind, StackValue), String> {
+    let class_kind = |name: &str| {
+        ArgumentKind::Simple(SimpleArgumentKind::Class(name.to_string()))
+    };
+    Ok(match label {
+        BootstrapArgument::Class(name) => {
+            let class = resolve_class(
+                heap,
+                loader,
+                &parse_class_or_array_identifier(name),
+            )
+            .map_err(|e| {
+                format!(
+                    "label {} can't be resolved: {}",
+                    name,
+                    e.class().class_identifier()
+                )
+            })?;
+            (
+                class_kind("java/lang/Class"),
+                StackValue::Reference(Some(heap.class_object(&class))),
+            )
+        },
+        BootstrapArgument::String(string) => (
+            class_kind("java/lang/String"),
+            StackValue::Reference(Some(Rc::new(
+                heap.new_string(string.clone()),
+            ))),
+        ),
+        label => return Err(format!("unsupported label {:?}", label)),
+    })
+}
+
+/// Returns the index of the first label, starting at the restart index,
+/// which matches the selector.
+///
+/// Class labels match instances of the class,
+/// string labels are matched by `string_matches`.
+/// If no label matches, the number of labels is returned,
+/// if the selector is null, -1.
+fn switch_index(
+    context: &mut NativeContext,
+    string_matches: fn(&Rc<dyn ClassInstance>, &str) -> bool,
+) -> RustMethodReturn {
+    let label_count = context.method().parameters.len() - 2;
+    let Some(selector) = context.reference(label_count) else {
+        return RustMethodReturn::Value(FieldValue::Int(-1));
+    };
+    let restart = context.int(label_count + 1);
+    let restart = match usize::try_from(restart) {
+        Ok(restart) if restart <= label_count => restart,
+        _ => {
+            return context.throw_new(
+                &class_identifier!(java / lang, IndexOutOfBoundsException),
+                &format!(
+                    "Index {} out of bounds for length {}",
+                    restart,
+                    label_count + 1
+                ),
+            );
+        },
+    };
+
+    for index in restart..label_count {
+        let label = context.reference(index).expect("labels are not null");
+        let matches = match label.as_any().downcast_ref::<StringInstance>() {
+            Some(string) => string_matches(&selector, &string.string),
+            None => {
+                let class = label
+                    .with_parent_instance("Class", |instance: &ClassObject| {
+                        instance.represented.clone()
+                    });
+                selector.class().is_assignable_to(class.as_ref())
+            },
+        };
+        if matches {
+            return RustMethodReturn::Value(FieldValue::Int(index as i32));
+        }
+    }
+    RustMethodReturn::Value(FieldValue::Int(label_count as i32))
+}
+
+fn type_switch_index(context: &mut NativeContext) -> RustMethodReturn {
+    switch_index(context, |selector, label| {
+        selector
+            .as_any()
+            .downcast_ref::<StringInstance>()
+            .is_some_and(|selector| selector.string == label)
+    })
+}
+
+fn enum_switch_index(context: &mut NativeContext) -> RustMethodReturn {
+    switch_index(context, |selector, label| {
+        let (name, _) = enum_class::constant(selector);
+        name.is_some_and(|name| {
+            name.as_any()
+                .downcast_ref::<StringInstance>()
+                .is_some_and(|name| name.string == label)
+        })
+    })
+}
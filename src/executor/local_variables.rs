@@ -106,6 +106,14 @@ impl LocalVariables {
         {
             self.local_variables[index - 1] = VariableValue::Invalid;
         }
+        // likewise invalidate the second part when overwriting the first,
+        // so that overwriting it later keeps the new value
+        if matches!(
+            self.local_variables[index],
+            VariableValue::LongFirst(_) | VariableValue::DoubleFirst(_)
+        ) {
+            self.local_variables[index + 1] = VariableValue::Invalid;
+        }
 
         self.local_variables[index] = match value {
             VariableValueOrValue::Int(i) => VariableValue::Int(i),
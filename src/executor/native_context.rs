@@ -0,0 +1,187 @@
+use std::rc::Rc;
+
+use crate::{
+    class::{
+        builtin_classes::StringInstance, ArgumentKind, Class, ClassIdentifier,
+        ClassInstance, Method, RustMethodReturn, SimpleArgumentKind,
+    },
+    executor::{
+        frame_stack::StackValue, invoke_static, invoke_virtual,
+        local_variables::VariableValueOrValue, new_exception, Frame,
+        ReturnValue,
+    },
+    heap::Heap,
+};
+
+/// Everything a builtin (Rust) method has access to while it is executed.
+pub struct NativeContext<'a> {
+    frame: &'a mut Frame,
+    method: &'a Method,
+    pub heap: &'a mut Heap,
+    /// The class declaring the executed method.
+    pub class: Rc<dyn Class>,
+    /// The class declaring the method which called the executed method,
+    /// or `None` if it has been called from Rust code.
+    pub caller: Option<Rc<dyn Class>>,
+}
+
+impl<'a> NativeContext<'a> {
+    pub fn new(
+        frame: &'a mut Frame,
+        method: &'a Method,
+        heap: &'a mut Heap,
+        class: Rc<dyn Class>,
+        caller: Option<Rc<dyn Class>>,
+    ) -> Self {
+        NativeContext {
+            frame,
+            method,
+            heap,
+            class,
+            caller,
+        }
+    }
+
+    /// The executed method.
+    pub fn method(&self) -> &Method {
+        self.method
+    }
+
+    /// The instance the method has been called on.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the executed method is static.
+    pub fn this(&self) -> Rc<dyn ClassInstance> {
+        assert!(
+            !self.method.is_static,
+            "static method {} has no 'this'",
+            self.method.name
+        );
+        match self.frame.local_variables.get(0) {
+            VariableValueOrValue::Reference(Some(this)) => this,
+            v => panic!("expected 'this' at index 0, got {:?}", v),
+        }
+    }
+
+    /// Returns the argument at `index` (not counting "this").
+    ///
+    /// The index refers to the parameter list of the method,
+    /// so long/double values count as one argument
+    /// (although they occupy two local variable slots).
+    pub fn argument(&self, index: usize) -> VariableValueOrValue {
+        let first_slot = if self.method.is_static { 0 } else { 1 };
+        let slot: usize = first_slot
+            + self.method.parameters[..index]
+                .iter()
+                .map(|p| match p {
+                    ArgumentKind::Simple(SimpleArgumentKind::Long)
+                    | ArgumentKind::Simple(SimpleArgumentKind::Double) => 2,
+                    _ => 1,
+                })
+                .sum::<usize>();
+        self.frame.local_variables.get(slot)
+    }
+
+    pub fn int(&self, index: usize) -> i32 {
+        self.argument(index).try_into().unwrap_or_else(|e| {
+            panic!("argument {} of {}: {}", index, self.method.name, e)
+        })
+    }
+
+    pub fn long(&self, index: usize) -> i64 {
+        self.argument(index).try_into().unwrap_or_else(|e| {
+            panic!("argument {} of {}: {}", index, self.method.name, e)
+        })
+    }
+
+    pub fn float(&self, index: usize) -> f32 {
+        self.argument(index).try_into().unwrap_or_else(|e| {
+            panic!("argument {} of {}: {}", index, self.method.name, e)
+        })
+    }
+
+    pub fn double(&self, index: usize) -> f64 {
+        self.argument(index).try_into().unwrap_or_else(|e| {
+            panic!("argument {} of {}: {}", index, self.method.name, e)
+        })
+    }
+
+    pub fn boolean(&self, index: usize) -> bool {
+        match self.int(index) {
+            0 => false,
+            1 => true,
+            b => panic!("invalid boolean value encoding: '{}'", b),
+        }
+    }
+
+    pub fn char(&self, index: usize) -> char {
+        let c = self.int(index);
+        char::from_u32(c as u32).unwrap_or(char::REPLACEMENT_CHARACTER)
+    }
+
+    pub fn reference(&self, index: usize) -> Option<Rc<dyn ClassInstance>> {
+        self.argument(index).try_into().unwrap_or_else(|e| {
+            panic!("argument {} of {}: {}", index, self.method.name, e)
+        })
+    }
+
+    /// Returns the content of the String argument at `index`.
+    pub fn string(&self, index: usize) -> Option<String> {
+        self.reference(index).map(|s| {
+            match s.as_any().downcast_ref::<StringInstance>() {
+                Some(s) => s.string.clone(),
+                None => panic!(
+                    "argument {} of {} is not a string but {:?}",
+                    index, self.method.name, s
+                ),
+            }
+        })
+    }
+
+    /// Create a new instance of the throwable class `class`
+    /// with `message` and throw it.
+    pub fn throw_new(
+        &mut self,
+        class: &ClassIdentifier,
+        message: &str,
+    ) -> RustMethodReturn {
+        RustMethodReturn::Throw(new_exception(self.heap, class, message))
+    }
+
+    /// Invoke the instance method `name` with `descriptor` virtually on
+    /// `this`, see [`invoke_virtual`].
+    ///
+    /// Returns the value returned by the method
+    /// or the exception it threw.
+    pub fn invoke_virtual(
+        &mut self,
+        this: Rc<dyn ClassInstance>,
+        name: &str,
+        descriptor: (&[ArgumentKind], Option<&ArgumentKind>),
+        arguments: Vec<StackValue>,
+    ) -> Result<ReturnValue, Rc<dyn ClassInstance>> {
+        invoke_virtual(self.heap, this, name, descriptor, arguments)
+    }
+
+    /// Invoke the static method `name` with `descriptor` of `class`,
+    /// see [`invoke_static`].
+    ///
+    /// Returns the value returned by the method
+    /// or the exception it threw.
+    pub fn invoke_static(
+        &mut self,
+        class: &ClassIdentifier,
+        name: &str,
+        descriptor: (&[ArgumentKind], Option<&ArgumentKind>),
+        arguments: Vec<StackValue>,
+    ) -> Result<ReturnValue, Rc<dyn ClassInstance>> {
+        let class = self
+            .heap
+            .find_class(self.class.class_loader(), class)
+            .unwrap_or_else(|| panic!("class {} exists", class))
+            .clone();
+
+        invoke_static(self.heap, &class, name, descriptor, arguments)
+    }
+}
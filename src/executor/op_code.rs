@@ -1,7 +1,12 @@
-use std::{any::Any, cmp::Ordering, ops::Neg, rc::Rc};
+use std::{cmp::Ordering, ops::Neg, rc::Rc};
+
+use enumflags2::BitFlags;
 
 use crate::{
     class::{
+        access_flags::{
+            ClassAccessFlag, FieldAccessFlag, MemberAccess, MethodAccessFlag,
+        },
         builtin_classes::array::{
             BoolArray, BoolArrayInstance, ByteArray, ByteArrayInstance,
             CharArray, CharArrayInstance, DoubleArray, DoubleArrayInstance,
@@ -9,14 +14,16 @@ use crate::{
             LongArray, LongArrayInstance, ObjectArray, ObjectArrayInstance,
             ShortArray, ShortArrayInstance,
         },
-        ArgumentKind, ArrayName, Class, ClassIdentifier, ClassInstance,
-        ClassName, Field, FieldValue, Method,
+        class_identifier, ArgumentKind, ArrayName, Class, ClassIdentifier,
+        ClassInstance, ClassName, Field, FieldValue, Method,
+        SimpleArgumentKind,
     },
     executor::{
-        frame_stack::StackValue, local_variables::VariableValueOrValue, Frame,
-        ReturnValue, Update,
+        call_site::CallSite, frame_stack::StackValue, initialize_class,
+        local_variables::VariableValueOrValue, new_exception, resolve_class,
+        Frame, ReturnValue, Update,
     },
-    heap::Heap,
+    heap::{ClassLoaderId, Heap},
 };
 
 /// Explicitly compare only the data part of fat/trait/dyn Trait pointers.
@@ -25,6 +32,20 @@ fn trait_pointer_eq<T: ?Sized, U: ?Sized>(p: *const T, q: *const U) -> bool {
     (p as *const ()) == (q as *const ())
 }
 
+/// Push `value` returned by a method, which isn't void,
+/// on the operand stack of `frame`.
+fn push_return_value(frame: &mut Frame, value: ReturnValue) {
+    let value = match value {
+        ReturnValue::Int(i) => StackValue::Int(i),
+        ReturnValue::Long(l) => StackValue::Long(l),
+        ReturnValue::Float(f) => StackValue::Float(f),
+        ReturnValue::Double(d) => StackValue::Double(d),
+        ReturnValue::Reference(r) => StackValue::Reference(r),
+        ReturnValue::Void => return,
+    };
+    frame.operand_stack.push(value).unwrap();
+}
+
 #[derive(Clone, Debug)]
 pub struct MethodDescriptor {
     pub name: String,
@@ -42,10 +63,13 @@ pub enum Ldc {
     Int(i32),
     Float(f32),
     String(Rc<dyn ClassInstance>),
-    Class(Rc<dyn Class>),
+    /// A class, which is resolved on execution.
+    Class(ClassIdentifier),
     Method(Rc<Method>),
     Long(i64),
     Double(f64),
+    /// A dynamic constant, which is computed by its bootstrap method.
+    Dynamic(Rc<CallSite>),
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -81,8 +105,10 @@ pub enum ArrayType {
 }
 
 impl ArrayType {
-    pub fn from_int(value: u8) -> ArrayType {
-        match value {
+    /// Returns the type of the `atype` operand of `newarray`,
+    /// or `None` if it is invalid.
+    pub fn from_int(value: u8) -> Option<ArrayType> {
+        Some(match value {
             4 => ArrayType::Boolean,
             5 => ArrayType::Char,
             6 => ArrayType::Float,
@@ -91,8 +117,8 @@ impl ArrayType {
             9 => ArrayType::Short,
             10 => ArrayType::Int,
             11 => ArrayType::Long,
-            _ => panic!("{} is not a valid ArrayType", value),
-        }
+            _ => return None,
+        })
     }
 }
 
@@ -103,11 +129,8 @@ pub enum OpCode {
     AconstNull,
     /// Load reference from `index` in local variable array to stack.
     Aload(usize),
-    // "[[I"
-    // ArrayObject::new(ArrayObject::new(IntArray))
-    //
-    // executor: ArrayObject::new(anewarray_conent).new_instance()
-    AnewArray(Rc<dyn Class>),
+    /// The array class to create an instance of, e.g. `[[I`.
+    AnewArray(ClassIdentifier),
     Areturn,
     ArrayLength,
     /// Store reference to `index` in local variable array from stack.
@@ -119,7 +142,7 @@ pub enum OpCode {
     Bipush(i32),
     Caload,
     Castore,
-    Checkcast(Rc<dyn Any>),
+    Checkcast(ClassIdentifier),
     D2f,
     D2i,
     D2l,
@@ -161,8 +184,13 @@ pub enum OpCode {
     GetField {
         class: ClassIdentifier,
         field_name: String,
+        field_type: ArgumentKind,
+    },
+    GetStatic {
+        class: ClassIdentifier,
+        field_name: String,
+        field_type: ArgumentKind,
     },
-    GetStatic(Rc<Field>),
     Goto(usize, OffsetDirection),
     I2b,
     I2c,
@@ -202,9 +230,9 @@ pub enum OpCode {
     Iload(usize),
     Imul,
     Ineg,
-    InstanceOf(Rc<dyn Any>),
-    InvokeDynamic(Rc<dyn Any>),
-    InvokeInterface(Rc<dyn Any>),
+    InstanceOf(ClassIdentifier),
+    InvokeDynamic(Rc<CallSite>),
+    InvokeInterface(SymbolicMethod),
     InvokeSpecial(SymbolicMethod),
     InvokeStatic(SymbolicMethod),
     InvokeVirtual(SymbolicMethod),
@@ -234,8 +262,12 @@ pub enum OpCode {
     Lload(usize),
     Lmul,
     Lneg,
-    // unsupported for now
-    Lookupswitch,
+    /// Jump to the target of the key on the operand stack,
+    /// or to `default` if there is none.
+    Lookupswitch {
+        default: (usize, OffsetDirection),
+        targets: Vec<(i32, (usize, OffsetDirection))>,
+    },
     Lor,
     Lrem,
     Lreturn,
@@ -261,16 +293,26 @@ pub enum OpCode {
     PutField {
         class: ClassIdentifier,
         field_name: String,
+        field_type: ArgumentKind,
+    },
+    PutStatic {
+        class: ClassIdentifier,
+        field_name: String,
+        field_type: ArgumentKind,
     },
-    PutStatic(Rc<Field>),
     Ret(usize),
     Return,
     Saload,
     Sastore,
     Sipush(i32),
     Swap,
-    // unsupported for now
-    Tableswitch,
+    /// Jump to the target at the index on the operand stack minus `low`,
+    /// or to `default` if it is out of range.
+    Tableswitch {
+        default: (usize, OffsetDirection),
+        low: i32,
+        targets: Vec<(usize, OffsetDirection)>,
+    },
     // Wide: not needed,
     // since it only effects how indicies are parsed from the byteocde.
     // This has already happened for any values represented by this enum.
@@ -318,6 +360,13 @@ on top of the stack, got: {:?}",
                 array.set(index.try_into().unwrap(), value).unwrap();
                 Update::None
             },
+            Self::AconstNull => {
+                frame
+                    .operand_stack
+                    .push(StackValue::Reference(None))
+                    .unwrap();
+                Update::None
+            },
             Self::Aload(index) => {
                 frame
                     .operand_stack
@@ -325,14 +374,21 @@ on top of the stack, got: {:?}",
                     .unwrap();
                 Update::None
             },
-            Self::AnewArray(array_cls) => {
+            Self::AnewArray(class_identifier) => {
                 let size: i32 =
                     frame.operand_stack.pop().unwrap().try_into().unwrap();
 
+                let array_cls = match resolve_class(
+                    heap,
+                    current_class.class_loader(),
+                    class_identifier,
+                ) {
+                    Ok(class) => class,
+                    Err(e) => return Update::Exception(e),
+                };
                 // construct new array and put on stack
-                let array_cls_for_ref = array_cls.clone();
                 let array_ref: &ObjectArray =
-                    array_cls_for_ref.as_ref().try_into().unwrap();
+                    array_cls.as_ref().try_into().unwrap();
                 let array_inst = array_ref
                     .new_instance_from_ref(
                         size.try_into().unwrap(),
@@ -538,6 +594,36 @@ got: {:?}",
                 Update::None
             },
 
+            Self::Checkcast(class_name) => {
+                let objectref: Option<Rc<dyn ClassInstance>> =
+                    frame.operand_stack.peek(0).unwrap().try_into().unwrap();
+                // null can be cast to any type
+                let Some(objectref) = objectref else {
+                    return Update::None;
+                };
+                let class = match resolve_class(
+                    heap,
+                    current_class.class_loader(),
+                    class_name,
+                ) {
+                    Ok(class) => class,
+                    Err(e) => return Update::Exception(e),
+                };
+                if objectref.class().is_assignable_to(class.as_ref()) {
+                    Update::None
+                } else {
+                    Update::Exception(new_exception(
+                        heap,
+                        &class_identifier!(java / lang, ClassCastException),
+                        &format!(
+                            "class {} cannot be cast to class {}",
+                            objectref.class().class_identifier().java_name(),
+                            class.class_identifier().java_name()
+                        ),
+                    ))
+                }
+            },
+
             Self::D2f => {
                 let val = if let StackValue::Double(d) =
                     frame.operand_stack.pop().unwrap()
@@ -1132,11 +1218,24 @@ got: {:?}",
                 Update::None
             },
 
-            Self::GetField { field_name, class } => {
+            Self::GetField {
+                field_name, class, ..
+            } => {
                 let objectref: Rc<dyn ClassInstance> =
                     frame.operand_stack.pop().unwrap().try_into().unwrap();
+                let (_, declaring_class) = match resolve_instance_field(
+                    heap,
+                    current_class,
+                    class,
+                    field_name,
+                    &objectref.class(),
+                ) {
+                    Ok(field) => field,
+                    Err(e) => return Update::Exception(e),
+                };
 
-                let field = objectref.get_field(class, field_name);
+                let field =
+                    objectref.get_field(declaring_class.as_ref(), field_name);
                 frame
                     .operand_stack
                     .push(field.value.clone().into_inner().into())
@@ -1145,7 +1244,18 @@ got: {:?}",
                 Update::None
             },
 
-            Self::GetStatic(field) => {
+            Self::GetStatic {
+                class, field_name, ..
+            } => {
+                let (field, _) = match resolve_static_field(
+                    heap,
+                    current_class,
+                    class,
+                    field_name,
+                ) {
+                    Ok(field) => field,
+                    Err(e) => return Update::Exception(e),
+                };
                 frame
                     .operand_stack
                     .push(field.value.clone().into_inner().into())
@@ -1471,11 +1581,84 @@ got: {:?}",
                 Update::None
             },
 
+            Self::InstanceOf(class_name) => {
+                let objectref: Option<Rc<dyn ClassInstance>> =
+                    frame.operand_stack.pop().unwrap().try_into().unwrap();
+                let is_instance = match objectref {
+                    Some(objectref) => {
+                        let class = match resolve_class(
+                            heap,
+                            current_class.class_loader(),
+                            class_name,
+                        ) {
+                            Ok(class) => class,
+                            Err(e) => return Update::Exception(e),
+                        };
+                        objectref.class().is_assignable_to(class.as_ref())
+                    },
+                    None => false,
+                };
+                frame
+                    .operand_stack
+                    .push(StackValue::Int(is_instance.into()))
+                    .unwrap();
+                Update::None
+            },
+
+            Self::InvokeDynamic(call_site) => {
+                let mut arguments = Vec::new();
+                for _ in 0..call_site.descriptor.0.len() {
+                    arguments.insert(0, frame.operand_stack.pop().unwrap());
+                }
+                match call_site.invoke(heap, arguments) {
+                    Ok(value) => {
+                        push_return_value(frame, value);
+                        Update::None
+                    },
+                    Err(e) => Update::Exception(e),
+                }
+            },
+
+            Self::InvokeInterface(method) => {
+                let objectref: Rc<dyn ClassInstance> = frame
+                    .operand_stack
+                    .peek(method.descriptor.descriptor.0.len())
+                    .unwrap()
+                    .try_into()
+                    .unwrap();
+                if let Err(e) = check_virtual_method_access(
+                    heap,
+                    current_class,
+                    &objectref,
+                    method,
+                ) {
+                    return Update::Exception(e);
+                }
+                let (method, defining_class) = objectref.class().get_method(
+                    &method.descriptor.name,
+                    (
+                        method.descriptor.descriptor.0.as_slice(),
+                        method.descriptor.descriptor.1.as_ref(),
+                    ),
+                    true,
+                );
+
+                Update::MethodCall {
+                    method,
+                    is_static: false,
+                    defining_class,
+                }
+            },
+
             Self::InvokeSpecial(method) => {
-                let method_class =
-                    heap.find_class(&method.class_name).unwrap_or_else(|| {
-                        panic!("class for method call {:?} exists", method)
-                    });
+                let method_class = match resolve_class(
+                    heap,
+                    current_class.class_loader(),
+                    &method.class_name,
+                ) {
+                    Ok(class) => class,
+                    Err(e) => return Update::Exception(e),
+                };
                 // note: is_super_class only applies,
                 // if method_class is a class, not an interface.
                 // This distinction is currently unnecessary since
@@ -1486,7 +1669,7 @@ got: {:?}",
                 {
                     current_class.super_class().unwrap()
                 } else {
-                    method_class.clone()
+                    method_class
                 };
 
                 let (method, defining_class) = resolution_root.get_method(
@@ -1497,6 +1680,15 @@ got: {:?}",
                     ),
                     true,
                 );
+                if let Err(e) = check_method_access(
+                    heap,
+                    current_class,
+                    &defining_class,
+                    &method,
+                    None,
+                ) {
+                    return Update::Exception(e);
+                }
 
                 Update::MethodCall {
                     method,
@@ -1506,7 +1698,14 @@ got: {:?}",
             },
 
             Self::InvokeStatic(method) => {
-                let class = heap.find_class(&method.class_name).unwrap();
+                let class = match resolve_class(
+                    heap,
+                    current_class.class_loader(),
+                    &method.class_name,
+                ) {
+                    Ok(class) => class,
+                    Err(e) => return Update::Exception(e),
+                };
                 let (method, _) = class.get_method(
                     &method.descriptor.name,
                     (
@@ -1515,6 +1714,18 @@ got: {:?}",
                     ),
                     false,
                 );
+                if let Err(e) = check_method_access(
+                    heap,
+                    current_class,
+                    &class,
+                    &method,
+                    None,
+                ) {
+                    return Update::Exception(e);
+                }
+                if let Err(e) = initialize_class(heap, &class) {
+                    return Update::Exception(e);
+                }
 
                 Update::MethodCall {
                     method,
@@ -1530,17 +1741,42 @@ got: {:?}",
                     .unwrap()
                     .try_into()
                     .unwrap();
+                if let Err(e) = check_virtual_method_access(
+                    heap,
+                    current_class,
+                    &objectref,
+                    method,
+                ) {
+                    return Update::Exception(e);
+                }
                 let resolution_root = objectref.class();
-
-                let (method, defining_class) = resolution_root.get_method(
-                    &method.descriptor.name,
-                    (
-                        &method.descriptor.descriptor.0,
-                        method.descriptor.descriptor.1.as_ref(),
-                    ),
-                    true,
+                let descriptor = (
+                    method.descriptor.descriptor.0.as_slice(),
+                    method.descriptor.descriptor.1.as_ref(),
                 );
 
+                // private methods, which nestmates call with invokevirtual
+                // since Java 11, are selected without overriding
+                let private_method = std::iter::successors(
+                    Some(resolution_root.clone()),
+                    |class| class.super_class(),
+                )
+                .find(|class| class.class_identifier() == &method.class_name)
+                .and_then(|class| {
+                    class.find_method(&method.descriptor.name, descriptor, true)
+                })
+                .filter(|(method, _)| {
+                    method.access_flags.contains(MethodAccessFlag::Private)
+                });
+                let (method, defining_class) =
+                    private_method.unwrap_or_else(|| {
+                        resolution_root.get_method(
+                            &method.descriptor.name,
+                            descriptor,
+                            true,
+                        )
+                    });
+
                 Update::MethodCall {
                     method,
                     is_static: false,
@@ -1783,11 +2019,13 @@ got: {:?}",
 
                 let outer_array = init_array(
                     heap,
+                    current_class.class_loader(),
                     identifier,
                     array_lens[Into::<usize>::into(dimensions)],
                 );
                 init_array_rec(
                     heap,
+                    current_class.class_loader(),
                     identifier,
                     &array_lens,
                     outer_array.clone(),
@@ -1802,7 +2040,25 @@ got: {:?}",
             },
 
             Self::New(class_identifier) => {
-                let class = heap.find_class(class_identifier).unwrap();
+                let class = match resolve_class(
+                    heap,
+                    current_class.class_loader(),
+                    class_identifier,
+                ) {
+                    Ok(class) => class,
+                    Err(e) => return Update::Exception(e),
+                };
+                // interfaces are abstract as well
+                if class.access_flags().contains(ClassAccessFlag::Abstract) {
+                    return Update::Exception(new_exception(
+                        heap,
+                        &class_identifier!(java / lang, InstantiationError),
+                        &class.class_identifier().java_name(),
+                    ));
+                }
+                if let Err(e) = initialize_class(heap, &class) {
+                    return Update::Exception(e);
+                }
 
                 let instance = class.new_instance(class.clone());
 
@@ -1958,6 +2214,31 @@ got: {:?}",
                     .unwrap();
                 Update::None
             },
+            Self::Ldc(Ldc::Class(class_name)) => {
+                let class = match resolve_class(
+                    heap,
+                    current_class.class_loader(),
+                    class_name,
+                ) {
+                    Ok(class) => class,
+                    Err(e) => return Update::Exception(e),
+                };
+                let class_object = heap.class_object(&class);
+                frame
+                    .operand_stack
+                    .push(StackValue::Reference(Some(class_object)))
+                    .unwrap();
+                Update::None
+            },
+            Self::Ldc(Ldc::Dynamic(call_site)) => {
+                match call_site.invoke(heap, vec![]) {
+                    Ok(value) => {
+                        push_return_value(frame, value);
+                        Update::None
+                    },
+                    Err(e) => Update::Exception(e),
+                }
+            },
 
             Self::Ldiv => {
                 let op2 = if let StackValue::Long(l) =
@@ -2254,51 +2535,65 @@ got: {:?}",
 
             Self::Nop => Update::None,
 
-            Self::PutField { field_name, class } => {
+            Self::PutField {
+                field_name, class, ..
+            } => {
                 let value: StackValue = frame.operand_stack.pop().unwrap();
                 let objectref: Rc<dyn ClassInstance> =
                     frame.operand_stack.pop().unwrap().try_into().unwrap();
+                let (access_flags, declaring_class) =
+                    match resolve_instance_field(
+                        heap,
+                        current_class,
+                        class,
+                        field_name,
+                        &objectref.class(),
+                    ) {
+                        Ok(field) => field,
+                        Err(e) => return Update::Exception(e),
+                    };
+                if let Err(e) = check_final_field_update(
+                    heap,
+                    current_class,
+                    &declaring_class,
+                    field_name,
+                    access_flags,
+                    "non-static",
+                ) {
+                    return Update::Exception(e);
+                }
 
-                let field = objectref.get_field(class, field_name);
+                let field =
+                    objectref.get_field(declaring_class.as_ref(), field_name);
+                store_field(&field, value);
 
-                field.value.replace_with(|old| match old {
-                    FieldValue::Byte(_) => match value {
-                        StackValue::Int(b) => FieldValue::Byte(b as i8),
-                        _ => panic!(),
-                    },
-                    FieldValue::Short(_) => match value {
-                        StackValue::Int(s) => FieldValue::Short(s as i16),
-                        _ => panic!(),
-                    },
-                    FieldValue::Int(_) => match value {
-                        StackValue::Int(i) => FieldValue::Int(i),
-                        _ => panic!(),
-                    },
-                    FieldValue::Long(_) => match value {
-                        StackValue::Long(l) => FieldValue::Long(l),
-                        _ => panic!(),
-                    },
-                    FieldValue::Char(_) => match value {
-                        StackValue::Int(c) => FieldValue::Char(c as u16),
-                        _ => panic!(),
-                    },
-                    FieldValue::Float(_) => match value {
-                        StackValue::Float(f) => FieldValue::Float(f),
-                        _ => panic!(),
-                    },
-                    FieldValue::Double(_) => match value {
-                        StackValue::Double(d) => FieldValue::Double(d),
-                        _ => panic!(),
-                    },
-                    FieldValue::Boolean(_) => match value {
-                        StackValue::Int(b) => FieldValue::Boolean(b as u8),
-                        _ => panic!(),
-                    },
-                    FieldValue::Reference(_) => match value {
-                        StackValue::Reference(r) => FieldValue::Reference(r),
-                        _ => panic!(),
-                    },
-                });
+                Update::None
+            },
+
+            Self::PutStatic {
+                class, field_name, ..
+            } => {
+                let value: StackValue = frame.operand_stack.pop().unwrap();
+                let (field, declaring_class) = match resolve_static_field(
+                    heap,
+                    current_class,
+                    class,
+                    field_name,
+                ) {
+                    Ok(field) => field,
+                    Err(e) => return Update::Exception(e),
+                };
+                if let Err(e) = check_final_field_update(
+                    heap,
+                    current_class,
+                    &declaring_class,
+                    field_name,
+                    field.access_flags,
+                    "static",
+                ) {
+                    return Update::Exception(e);
+                }
+                store_field(&field, value);
 
                 Update::None
             },
@@ -2338,19 +2633,333 @@ got: {:?}",
                 Update::None
             },
 
+            Self::Tableswitch {
+                default,
+                low,
+                targets,
+            } => {
+                let index: i32 =
+                    frame.operand_stack.pop().unwrap().try_into().unwrap();
+                let (offset, direction) =
+                    usize::try_from(index.wrapping_sub(*low))
+                        .ok()
+                        .and_then(|index| targets.get(index))
+                        .unwrap_or(default);
+                Update::GoTo(*offset, *direction)
+            },
+
+            Self::Lookupswitch { default, targets } => {
+                let key: i32 =
+                    frame.operand_stack.pop().unwrap().try_into().unwrap();
+                let (offset, direction) = targets
+                    .iter()
+                    .find(|(match_key, _)| *match_key == key)
+                    .map_or(default, |(_, target)| target);
+                Update::GoTo(*offset, *direction)
+            },
+
             _ => todo!("Missing OpCode implementation for: {:?}", self),
         }
     }
 }
 
+/// An exception to throw, e.g. if resolution fails.
+type Exception = Rc<dyn ClassInstance>;
+
+/// Resolve the field `name` of `class`, referenced by `current_class`,
+/// and initialize the class declaring it.
+///
+/// Returns the field together with the class declaring it,
+/// or the exception to throw if the class can't be resolved
+/// or initialized, has no such field (`NoSuchFieldError`),
+/// the field isn't static (`IncompatibleClassChangeError`)
+/// or `current_class` may not access it (`IllegalAccessError`).
+fn resolve_static_field(
+    heap: &mut Heap,
+    current_class: &Rc<dyn Class>,
+    class: &ClassIdentifier,
+    name: &str,
+) -> Result<(Rc<Field>, Rc<dyn Class>), Exception> {
+    let class = resolve_field(heap, current_class, class, name)?;
+    let field = class.get_static_field(name).ok_or_else(|| {
+        new_exception(
+            heap,
+            &class_identifier!(java / lang, IncompatibleClassChangeError),
+            &format!(
+                "Expected static field {}.{}",
+                class.class_identifier().java_name(),
+                name
+            ),
+        )
+    })?;
+    check_access(
+        heap,
+        current_class,
+        &class,
+        field.access_flags.into(),
+        None,
+        "field",
+        name,
+    )?;
+    initialize_class(heap, &class)?;
+    Ok((field, class))
+}
+
+/// Resolve the instance field `name` of `class`,
+/// referenced by `current_class` and accessed on an `objectref_class`.
+///
+/// Returns the access flags of the field and the class declaring it,
+/// or the exception to throw if the class can't be resolved,
+/// has no such field (`NoSuchFieldError`),
+/// the field is static (`IncompatibleClassChangeError`)
+/// or `current_class` may not access it (`IllegalAccessError`).
+fn resolve_instance_field(
+    heap: &mut Heap,
+    current_class: &Rc<dyn Class>,
+    class: &ClassIdentifier,
+    name: &str,
+    objectref_class: &Rc<dyn Class>,
+) -> Result<(BitFlags<FieldAccessFlag>, Rc<dyn Class>), Exception> {
+    let class = resolve_field(heap, current_class, class, name)?;
+    let access_flags = class
+        .instance_fields()
+        .iter()
+        .find(|field| field.name == name)
+        .map(|field| field.access_flags)
+        .ok_or_else(|| {
+            new_exception(
+                heap,
+                &class_identifier!(java / lang, IncompatibleClassChangeError),
+                &format!(
+                    "Expected non-static field {}.{}",
+                    class.class_identifier().java_name(),
+                    name
+                ),
+            )
+        })?;
+    check_access(
+        heap,
+        current_class,
+        &class,
+        access_flags.into(),
+        Some(objectref_class),
+        "field",
+        name,
+    )?;
+    Ok((access_flags, class))
+}
+
+/// Resolve `class`, referenced by `current_class`,
+/// and look up its field `name` (JVMS §5.4.3.2).
+///
+/// Returns the class declaring the field,
+/// or `NoSuchFieldError` if there is no such field.
+fn resolve_field(
+    heap: &mut Heap,
+    current_class: &Rc<dyn Class>,
+    class: &ClassIdentifier,
+    name: &str,
+) -> Result<Rc<dyn Class>, Exception> {
+    let class = resolve_class(heap, current_class.class_loader(), class)?;
+    class.lookup_field(name).ok_or_else(|| {
+        new_exception(
+            heap,
+            &class_identifier!(java / lang, NoSuchFieldError),
+            name,
+        )
+    })
+}
+
+/// Returns `IllegalAccessError` if `current_class` may not access
+/// the member `name` of `declaring_class`, which is a `kind`,
+/// on an instance of `objectref_class` (`None` for static members).
+fn check_access(
+    heap: &mut Heap,
+    current_class: &Rc<dyn Class>,
+    declaring_class: &Rc<dyn Class>,
+    access: MemberAccess,
+    objectref_class: Option<&Rc<dyn Class>>,
+    kind: &str,
+    name: &str,
+) -> Result<(), Rc<dyn ClassInstance>> {
+    if current_class.can_access(declaring_class, access, objectref_class, heap)
+    {
+        return Ok(());
+    }
+    Err(new_exception(
+        heap,
+        &class_identifier!(java / lang, IllegalAccessError),
+        &format!(
+            "class {} tried to access {} {} {}.{}",
+            current_class.class_identifier().java_name(),
+            access,
+            kind,
+            declaring_class.class_identifier().java_name(),
+            name
+        ),
+    ))
+}
+
+/// Returns `IllegalAccessError` if the field `name` of `declaring_class`
+/// is final and `current_class` is another class,
+/// which therefore may not update it.
+///
+/// `kind` is either `static` or `non-static`.
+fn check_final_field_update(
+    heap: &mut Heap,
+    current_class: &Rc<dyn Class>,
+    declaring_class: &Rc<dyn Class>,
+    name: &str,
+    access_flags: BitFlags<FieldAccessFlag>,
+    kind: &str,
+) -> Result<(), Rc<dyn ClassInstance>> {
+    if !access_flags.contains(FieldAccessFlag::Final)
+        || current_class.is_same_class(declaring_class.as_ref())
+    {
+        return Ok(());
+    }
+    Err(new_exception(
+        heap,
+        &class_identifier!(java / lang, IllegalAccessError),
+        &format!(
+            "Update to {} final field {}.{} attempted from a different \
+class ({}) than the field's declaring class",
+            kind,
+            declaring_class.class_identifier().java_name(),
+            name,
+            current_class.class_identifier().java_name()
+        ),
+    ))
+}
+
+/// Returns `IllegalAccessError` if `current_class` may not access `method`,
+/// declared in `declaring_class`, on an instance of `objectref_class`
+/// (`None` for static methods and `invokespecial`).
+fn check_method_access(
+    heap: &mut Heap,
+    current_class: &Rc<dyn Class>,
+    declaring_class: &Rc<dyn Class>,
+    method: &Method,
+    objectref_class: Option<&Rc<dyn Class>>,
+) -> Result<(), Rc<dyn ClassInstance>> {
+    check_access(
+        heap,
+        current_class,
+        declaring_class,
+        method.access_flags.into(),
+        objectref_class,
+        "method",
+        &method.name,
+    )
+}
+
+/// Returns `IllegalAccessError` if `current_class` may not access
+/// the method `symbolic_method`, which is invoked on `objectref`.
+///
+/// The method is resolved in the class named by `symbolic_method`,
+/// which is found among the superclasses and interfaces
+/// of the class of `objectref`.
+fn check_virtual_method_access(
+    heap: &mut Heap,
+    current_class: &Rc<dyn Class>,
+    objectref: &Rc<dyn ClassInstance>,
+    symbolic_method: &SymbolicMethod,
+) -> Result<(), Rc<dyn ClassInstance>> {
+    fn find_class(
+        class: Rc<dyn Class>,
+        name: &ClassIdentifier,
+    ) -> Option<Rc<dyn Class>> {
+        if class.class_identifier() == name {
+            return Some(class);
+        }
+        class
+            .super_class()
+            .and_then(|super_class| find_class(super_class, name))
+            .or_else(|| {
+                class
+                    .interfaces()
+                    .iter()
+                    .find_map(|interface| find_class(interface.clone(), name))
+            })
+    }
+
+    // e.g. arrays, whose methods are all public
+    let objectref_class = objectref.class();
+    let Some(class) =
+        find_class(objectref_class.clone(), &symbolic_method.class_name)
+    else {
+        return Ok(());
+    };
+    match class.find_method(
+        &symbolic_method.descriptor.name,
+        (
+            &symbolic_method.descriptor.descriptor.0,
+            symbolic_method.descriptor.descriptor.1.as_ref(),
+        ),
+        true,
+    ) {
+        Some((method, declaring_class)) => check_method_access(
+            heap,
+            current_class,
+            &declaring_class,
+            &method,
+            Some(&objectref_class),
+        ),
+        None => Ok(()),
+    }
+}
+
+/// Store `value` from the operand stack in `field`,
+/// converting it to the type of the field.
+///
+/// Ints are truncated to bytes, shorts and chars,
+/// while booleans only keep the lowest bit (JVMS §6.5 putfield).
+fn store_field(field: &Field, value: StackValue) {
+    field.value.replace(typed_value(&field.field_type, value));
+}
+
+/// Convert `value` to a value of type `kind`,
+/// truncating ints stored as byte, short, char or boolean.
+pub fn typed_value(kind: &ArgumentKind, value: StackValue) -> FieldValue {
+    match (kind, value) {
+        (ArgumentKind::Simple(kind), StackValue::Int(i)) => match kind {
+            SimpleArgumentKind::Byte => FieldValue::Byte(i as i8),
+            SimpleArgumentKind::Short => FieldValue::Short(i as i16),
+            SimpleArgumentKind::Char => FieldValue::Char(i as u16),
+            SimpleArgumentKind::Boolean => FieldValue::Boolean((i & 1) as u8),
+            SimpleArgumentKind::Int => FieldValue::Int(i),
+            _ => panic!("int is no {:?} value", kind),
+        },
+        (
+            ArgumentKind::Simple(SimpleArgumentKind::Long),
+            StackValue::Long(l),
+        ) => FieldValue::Long(l),
+        (
+            ArgumentKind::Simple(SimpleArgumentKind::Float),
+            StackValue::Float(f),
+        ) => FieldValue::Float(f),
+        (
+            ArgumentKind::Simple(SimpleArgumentKind::Double),
+            StackValue::Double(d),
+        ) => FieldValue::Double(d),
+        (
+            ArgumentKind::Simple(SimpleArgumentKind::Class(_))
+            | ArgumentKind::Array { .. },
+            StackValue::Reference(r),
+        ) => FieldValue::Reference(r),
+        (kind, value) => panic!("{:?} is no {:?} value", value, kind),
+    }
+}
+
 /// Create new array with the type given by dim_count and component_type.
 /// Only used in opcode multianewarray.
 fn init_array(
     heap: &mut Heap,
+    loader: ClassLoaderId,
     array_identifier: &ClassIdentifier,
     array_len: usize,
 ) -> Rc<dyn ClassInstance> {
-    let array_cls = heap.find_array_class(array_identifier).unwrap();
+    let array_cls = heap.find_array_class(loader, array_identifier).unwrap();
     let (dim_count, component_type) = array_identifier.get_array_class_name();
     if dim_count > 1 || matches!(component_type, ArrayName::Class(_)) {
         // array_cls must be an obj array
@@ -2460,6 +3069,7 @@ fn init_array(
 /// array_lens is indexed 1-based!
 fn init_array_rec(
     heap: &mut Heap,
+    loader: ClassLoaderId,
     array_identifier: &ClassIdentifier,
     array_lens: &[usize],
     outer_array: Rc<dyn ClassInstance>,
@@ -2485,11 +3095,18 @@ fn init_array_rec(
     for i in 0..array_lens[Into::<usize>::into(dim)] {
         let inner_array = init_array(
             heap,
+            loader,
             &new_identifier,
             array_lens[Into::<usize>::into(dim - 1)],
         );
         // rec enter
-        init_array_rec(heap, &new_identifier, array_lens, inner_array.clone());
+        init_array_rec(
+            heap,
+            loader,
+            &new_identifier,
+            array_lens,
+            inner_array.clone(),
+        );
         outer_array.set(i, Some(inner_array)).unwrap();
     }
 }
@@ -1,20 +1,58 @@
-use std::{collections::HashMap, rc::Rc};
-
-use crate::class::{
-    builtin_classes::{
-        array::{
-            Array, BoolArray, BoolArrayInstance, ByteArray, ByteArrayInstance,
-            CharArray, CharArrayInstance, DoubleArray, DoubleArrayInstance,
-            FloatArray, FloatArrayInstance, IntArray, IntArrayInstance,
-            LongArray, LongArrayInstance, ObjectArrayKind, ShortArray,
-            ShortArrayInstance,
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use crate::{
+    class::{
+        access_flags::ClassAccessFlag,
+        builtin_classes::{
+            array::{
+                Array, ArrayKind, BoolArray, BoolArrayInstance, ByteArray,
+                ByteArrayInstance, CharArray, CharArrayInstance, DoubleArray,
+                DoubleArrayInstance, FloatArray, FloatArrayInstance, IntArray,
+                IntArrayInstance, LongArray, LongArrayInstance, ObjectArray,
+                ObjectArrayInstance, ObjectArrayKind, ShortArray,
+                ShortArrayInstance,
+            },
+            AnnotationProxyClass, BoxClass, ClassClass, ClassLoaderClass,
+            DynamicProxyClass, EnumClass, FileInputStream, InputStream,
+            InvocationHandlerInterface, MemberClass, ObjectClass,
+            PrimitiveClass, PrintStream, ProxyClass, RecordClass,
+            StringBuilder, StringClass, StringInstance, SystemClass,
+            ThrowableClass,
         },
-        FileInputStream, InputStream, ObjectClass, PrintStream, StringClass,
-        StringInstance, SystemClass, ThrowableClass,
+        class_identifier, ArrayName, Class, ClassIdentifier, ClassInstance,
+        ClassName, RustMethod, SimpleArgumentKind,
+    },
+    classloader::{class_source::ClassSource, parse_class_identifier},
+    executor::call_site::{
+        object_methods, string_concat, switch_bootstraps, RustBootstrapMethod,
     },
-    ArrayName, Class, ClassIdentifier, ClassName,
 };
 
+/// Identifies a class loader,
+/// which together with the class name determines the identity of a class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClassLoaderId(usize);
+
+impl ClassLoaderId {
+    /// Loads classes from the class path, delegating to `BOOTSTRAP`.
+    pub const APPLICATION: ClassLoaderId = ClassLoaderId(1);
+    /// Defines the builtin classes and has no parent.
+    pub const BOOTSTRAP: ClassLoaderId = ClassLoaderId(0);
+}
+
+struct ClassLoaderData {
+    parent: Option<ClassLoaderId>,
+    sources: Vec<Box<dyn ClassSource>>,
+    /// The `java.lang.ClassLoader` instance,
+    /// if the loader has been created by Java code.
+    instance: Option<Rc<dyn ClassInstance>>,
+    /// The classes this loader has loaded (or defined), by name.
+    classes: HashMap<ClassIdentifier, Rc<dyn Class>>,
+}
+
 pub struct Heap {
     string_class: Rc<StringClass>,
     object_class: Rc<ObjectClass>,
@@ -26,7 +64,37 @@ pub struct Heap {
     long_array_class: Rc<LongArray>,
     int_array_class: Rc<IntArray>,
     short_array_class: Rc<ShortArray>,
-    classes: HashMap<ClassIdentifier, Rc<dyn Class>>,
+    class_class: Rc<ClassClass>,
+    /// The classes of the primitive types, e.g. `int`,
+    /// which can't be loaded by name.
+    primitive_classes: Vec<Rc<dyn Class>>,
+    /// Indexed by [`ClassLoaderId`].
+    class_loaders: Vec<ClassLoaderData>,
+    /// Classes currently being defined, see [`Heap::begin_loading`].
+    loading: HashSet<(ClassLoaderId, String)>,
+    /// Classes whose initialization has started,
+    /// see [`Heap::begin_initialization`].
+    initialized: HashSet<(ClassLoaderId, ClassIdentifier)>,
+    /// Implementations of methods declared `native` in bytecode classes,
+    /// keyed by class, method name and method descriptor.
+    native_methods: HashMap<(ClassIdentifier, String, String), RustMethod>,
+    /// Bootstrap methods linking call sites, keyed by class and name.
+    bootstrap_methods: HashMap<(ClassIdentifier, String), RustBootstrapMethod>,
+    /// Properties returned by `System.getProperty()`.
+    system_properties: HashMap<String, String>,
+    /// The `java.lang.Class` objects created so far.
+    class_objects:
+        HashMap<(ClassLoaderId, ClassIdentifier), Rc<dyn ClassInstance>>,
+    /// The classes implementing annotation interfaces, by interface.
+    annotation_proxy_classes:
+        HashMap<(ClassLoaderId, ClassIdentifier), Rc<AnnotationProxyClass>>,
+    /// The classes created by `Proxy.newProxyInstance()`,
+    /// by defining loader and implemented interfaces.
+    proxy_classes:
+        HashMap<(ClassLoaderId, Vec<ClassIdentifier>), Rc<DynamicProxyClass>>,
+    /// The number of proxy classes created so far,
+    /// which is used to name them.
+    proxy_class_count: usize,
 }
 
 impl Heap {
@@ -51,19 +119,207 @@ impl Heap {
             Rc::new(ShortArray::new(Default::default(), object_class.clone()));
 
         let string_class = Rc::new(StringClass::new(object_class.clone()));
+        let string_builder_class =
+            Rc::new(StringBuilder::new(object_class.clone()));
         let print_stream_class =
             Rc::new(PrintStream::new(object_class.clone()));
         let input_stream_class =
             Rc::new(InputStream::new(object_class.clone()));
         let file_input_stream_class =
-            Rc::new(FileInputStream::new(object_class.clone()));
+            Rc::new(FileInputStream::new(input_stream_class.clone()));
         let system_class = Rc::new(SystemClass::new(
             &print_stream_class,
-            &input_stream_class,
+            &file_input_stream_class,
             object_class.clone(),
         ));
+        let class_class = Rc::new(ClassClass::new(object_class.clone()));
+        let primitive_classes: Vec<Rc<dyn Class>> = [
+            "boolean", "byte", "char", "double", "float", "int", "long",
+            "short", "void",
+        ]
+        .into_iter()
+        .map(|name| Rc::new(PrimitiveClass::new(name)) as Rc<dyn Class>)
+        .collect();
+        let mut class_objects: HashMap<_, Rc<dyn ClassInstance>> =
+            HashMap::new();
+        let box_classes: Vec<Rc<BoxClass>> = [
+            SimpleArgumentKind::Boolean,
+            SimpleArgumentKind::Byte,
+            SimpleArgumentKind::Char,
+            SimpleArgumentKind::Double,
+            SimpleArgumentKind::Float,
+            SimpleArgumentKind::Int,
+            SimpleArgumentKind::Long,
+            SimpleArgumentKind::Short,
+        ]
+        .into_iter()
+        .zip(&primitive_classes)
+        .map(|(primitive, primitive_class)| {
+            // the class objects of primitive types are created up front,
+            // since the wrapper classes refer to them in their TYPE field
+            let type_object: Rc<dyn ClassInstance> =
+                Rc::new(class_class.new_class_object(primitive_class.clone()));
+            class_objects.insert(
+                (
+                    ClassLoaderId::BOOTSTRAP,
+                    primitive_class.class_identifier().clone(),
+                ),
+                type_object.clone(),
+            );
+            Rc::new(BoxClass::new(primitive, type_object, object_class.clone()))
+        })
+        .collect();
+        let class_loader_class =
+            Rc::new(ClassLoaderClass::new(object_class.clone()));
+        let method_class = Rc::new(MemberClass::method(object_class.clone()));
+        let constructor_class =
+            Rc::new(MemberClass::constructor(object_class.clone()));
+        let field_class = Rc::new(MemberClass::field(object_class.clone()));
+        let invocation_handler_interface =
+            Rc::new(InvocationHandlerInterface::new(object_class.clone()));
+        let proxy_class = Rc::new(ProxyClass::new(object_class.clone()));
+        let enum_class = Rc::new(EnumClass::new(object_class.clone()));
+        let record_class = Rc::new(RecordClass::new(object_class.clone()));
         let throwable_class =
             Rc::new(ThrowableClass::new(object_class.clone()));
+        let exception_class = Rc::new(ThrowableClass::new_subclass(
+            class_identifier!(java / lang, Exception),
+            throwable_class.clone(),
+        ));
+        let runtime_exception_class = Rc::new(ThrowableClass::new_subclass(
+            class_identifier!(java / lang, RuntimeException),
+            exception_class.clone(),
+        ));
+        let error_class = Rc::new(ThrowableClass::new_subclass(
+            class_identifier!(java / lang, Error),
+            throwable_class.clone(),
+        ));
+        let linkage_error_class = Rc::new(ThrowableClass::new_subclass(
+            class_identifier!(java / lang, LinkageError),
+            error_class.clone(),
+        ));
+        let unsatisfied_link_error_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang, UnsatisfiedLinkError),
+                linkage_error_class.clone(),
+            ));
+        let incompatible_class_change_error_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang, IncompatibleClassChangeError),
+                linkage_error_class.clone(),
+            ));
+        let instantiation_error_class = Rc::new(ThrowableClass::new_subclass(
+            class_identifier!(java / lang, InstantiationError),
+            incompatible_class_change_error_class.clone(),
+        ));
+        let abstract_method_error_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang, AbstractMethodError),
+                incompatible_class_change_error_class.clone(),
+            ));
+        let illegal_access_error_class = Rc::new(ThrowableClass::new_subclass(
+            class_identifier!(java / lang, IllegalAccessError),
+            incompatible_class_change_error_class.clone(),
+        ));
+        let no_such_field_error_class = Rc::new(ThrowableClass::new_subclass(
+            class_identifier!(java / lang, NoSuchFieldError),
+            incompatible_class_change_error_class.clone(),
+        ));
+        let exception_in_initializer_error_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang, ExceptionInInitializerError),
+                linkage_error_class.clone(),
+            ));
+        let class_format_error_class = Rc::new(ThrowableClass::new_subclass(
+            class_identifier!(java / lang, ClassFormatError),
+            linkage_error_class.clone(),
+        ));
+        let verify_error_class = Rc::new(ThrowableClass::new_subclass(
+            class_identifier!(java / lang, VerifyError),
+            linkage_error_class.clone(),
+        ));
+        let bootstrap_method_error_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang / invoke, BootstrapMethodError),
+                linkage_error_class.clone(),
+            ));
+        let no_class_def_found_error_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang, NoClassDefFoundError),
+                linkage_error_class.clone(),
+            ));
+        let reflective_operation_exception_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang, ReflectiveOperationException),
+                exception_class.clone(),
+            ));
+        let class_not_found_exception_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang, ClassNotFoundException),
+                reflective_operation_exception_class.clone(),
+            ));
+        let illegal_access_exception_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang, IllegalAccessException),
+                reflective_operation_exception_class.clone(),
+            ));
+        let instantiation_exception_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang, InstantiationException),
+                reflective_operation_exception_class.clone(),
+            ));
+        let invocation_target_exception_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(
+                    java / lang / reflect,
+                    InvocationTargetException
+                ),
+                reflective_operation_exception_class.clone(),
+            ));
+        let undeclared_throwable_exception_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(
+                    java / lang / reflect,
+                    UndeclaredThrowableException
+                ),
+                runtime_exception_class.clone(),
+            ));
+        let null_pointer_exception_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang, NullPointerException),
+                runtime_exception_class.clone(),
+            ));
+        let class_cast_exception_class = Rc::new(ThrowableClass::new_subclass(
+            class_identifier!(java / lang, ClassCastException),
+            runtime_exception_class.clone(),
+        ));
+        let illegal_argument_exception_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang, IllegalArgumentException),
+                runtime_exception_class.clone(),
+            ));
+        let index_out_of_bounds_exception_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang, IndexOutOfBoundsException),
+                runtime_exception_class.clone(),
+            ));
+        let enum_constant_not_present_exception_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(java / lang, EnumConstantNotPresentException),
+                runtime_exception_class.clone(),
+            ));
+        let incomplete_annotation_exception_class =
+            Rc::new(ThrowableClass::new_subclass(
+                class_identifier!(
+                    java / lang / annotation,
+                    IncompleteAnnotationException
+                ),
+                runtime_exception_class.clone(),
+            ));
+        let io_exception_class = Rc::new(ThrowableClass::new_subclass(
+            class_identifier!(java / io, IOException),
+            exception_class.clone(),
+        ));
 
         let mut classes: HashMap<ClassIdentifier, Rc<dyn Class>> =
             HashMap::new();
@@ -71,6 +327,10 @@ impl Heap {
             string_class.class_identifier().clone(),
             string_class.clone(),
         );
+        classes.insert(
+            string_builder_class.class_identifier().clone(),
+            string_builder_class,
+        );
         classes.insert(
             print_stream_class.class_identifier().clone(),
             print_stream_class,
@@ -92,6 +352,153 @@ impl Heap {
             throwable_class.class_identifier().clone(),
             throwable_class,
         );
+        classes.insert(
+            exception_class.class_identifier().clone(),
+            exception_class,
+        );
+        classes.insert(
+            runtime_exception_class.class_identifier().clone(),
+            runtime_exception_class,
+        );
+        classes.insert(
+            class_cast_exception_class.class_identifier().clone(),
+            class_cast_exception_class,
+        );
+        classes.insert(
+            illegal_argument_exception_class.class_identifier().clone(),
+            illegal_argument_exception_class,
+        );
+        classes.insert(
+            index_out_of_bounds_exception_class
+                .class_identifier()
+                .clone(),
+            index_out_of_bounds_exception_class,
+        );
+        classes.insert(
+            enum_constant_not_present_exception_class
+                .class_identifier()
+                .clone(),
+            enum_constant_not_present_exception_class,
+        );
+        classes.insert(
+            incomplete_annotation_exception_class
+                .class_identifier()
+                .clone(),
+            incomplete_annotation_exception_class,
+        );
+        classes.insert(error_class.class_identifier().clone(), error_class);
+        classes.insert(
+            linkage_error_class.class_identifier().clone(),
+            linkage_error_class,
+        );
+        classes.insert(
+            unsatisfied_link_error_class.class_identifier().clone(),
+            unsatisfied_link_error_class,
+        );
+        classes.insert(
+            incompatible_class_change_error_class
+                .class_identifier()
+                .clone(),
+            incompatible_class_change_error_class,
+        );
+        classes.insert(
+            instantiation_error_class.class_identifier().clone(),
+            instantiation_error_class,
+        );
+        classes.insert(
+            abstract_method_error_class.class_identifier().clone(),
+            abstract_method_error_class,
+        );
+        classes.insert(
+            no_such_field_error_class.class_identifier().clone(),
+            no_such_field_error_class,
+        );
+        classes.insert(
+            illegal_access_error_class.class_identifier().clone(),
+            illegal_access_error_class,
+        );
+        classes.insert(
+            exception_in_initializer_error_class
+                .class_identifier()
+                .clone(),
+            exception_in_initializer_error_class,
+        );
+        classes.insert(
+            class_format_error_class.class_identifier().clone(),
+            class_format_error_class,
+        );
+        classes.insert(
+            verify_error_class.class_identifier().clone(),
+            verify_error_class,
+        );
+        classes.insert(
+            bootstrap_method_error_class.class_identifier().clone(),
+            bootstrap_method_error_class,
+        );
+        classes.insert(
+            no_class_def_found_error_class.class_identifier().clone(),
+            no_class_def_found_error_class,
+        );
+        classes.insert(
+            reflective_operation_exception_class
+                .class_identifier()
+                .clone(),
+            reflective_operation_exception_class,
+        );
+        classes.insert(
+            class_not_found_exception_class.class_identifier().clone(),
+            class_not_found_exception_class,
+        );
+        classes.insert(
+            illegal_access_exception_class.class_identifier().clone(),
+            illegal_access_exception_class,
+        );
+        classes.insert(
+            instantiation_exception_class.class_identifier().clone(),
+            instantiation_exception_class,
+        );
+        classes.insert(
+            invocation_target_exception_class.class_identifier().clone(),
+            invocation_target_exception_class,
+        );
+        classes.insert(
+            undeclared_throwable_exception_class
+                .class_identifier()
+                .clone(),
+            undeclared_throwable_exception_class,
+        );
+        classes.insert(
+            null_pointer_exception_class.class_identifier().clone(),
+            null_pointer_exception_class,
+        );
+        classes.insert(
+            io_exception_class.class_identifier().clone(),
+            io_exception_class,
+        );
+        classes.insert(
+            class_class.class_identifier().clone(),
+            class_class.clone(),
+        );
+        for box_class in box_classes {
+            classes.insert(box_class.class_identifier().clone(), box_class);
+        }
+        classes.insert(
+            class_loader_class.class_identifier().clone(),
+            class_loader_class,
+        );
+        classes.insert(method_class.class_identifier().clone(), method_class);
+        classes.insert(
+            constructor_class.class_identifier().clone(),
+            constructor_class,
+        );
+        classes.insert(field_class.class_identifier().clone(), field_class);
+        classes.insert(
+            invocation_handler_interface.class_identifier().clone(),
+            invocation_handler_interface,
+        );
+        classes.insert(proxy_class.class_identifier().clone(), proxy_class);
+        classes.insert(enum_class.class_identifier().clone(), enum_class);
+        classes.insert(record_class.class_identifier().clone(), record_class);
         classes.insert(
             boolean_array_class.class_identifier().clone(),
             boolean_array_class.clone(),
@@ -136,7 +543,189 @@ impl Heap {
             long_array_class,
             int_array_class,
             short_array_class,
-            classes,
+            class_class,
+            primitive_classes,
+            class_loaders: vec![
+                ClassLoaderData {
+                    parent: None,
+                    sources: Vec::new(),
+                    instance: None,
+                    classes,
+                },
+                ClassLoaderData {
+                    parent: Some(ClassLoaderId::BOOTSTRAP),
+                    sources: Vec::new(),
+                    instance: None,
+                    classes: HashMap::new(),
+                },
+            ],
+            loading: HashSet::new(),
+            initialized: HashSet::new(),
+            native_methods: HashMap::new(),
+            bootstrap_methods: HashMap::from([
+                (
+                    (
+                        class_identifier!(
+                            java / lang / invoke,
+                            StringConcatFactory
+                        ),
+                        "makeConcat".to_string(),
+                    ),
+                    string_concat::make_concat as RustBootstrapMethod,
+                ),
+                (
+                    (
+                        class_identifier!(
+                            java / lang / invoke,
+                            StringConcatFactory
+                        ),
+                        "makeConcatWithConstants".to_string(),
+                    ),
+                    string_concat::make_concat_with_constants,
+                ),
+                (
+                    (
+                        class_identifier!(java / lang / runtime, ObjectMethods),
+                        "bootstrap".to_string(),
+                    ),
+                    object_methods::bootstrap,
+                ),
+                (
+                    (
+                        class_identifier!(
+                            java / lang / runtime,
+                            SwitchBootstraps
+                        ),
+                        "typeSwitch".to_string(),
+                    ),
+                    switch_bootstraps::type_switch,
+                ),
+                (
+                    (
+                        class_identifier!(
+                            java / lang / runtime,
+                            SwitchBootstraps
+                        ),
+                        "enumSwitch".to_string(),
+                    ),
+                    switch_bootstraps::enum_switch,
+                ),
+            ]),
+            system_properties: HashMap::from([
+                ("file.separator".to_string(), "/".to_string()),
+                ("line.separator".to_string(), "\n".to_string()),
+                ("path.separator".to_string(), ":".to_string()),
+            ]),
+            class_objects,
+            annotation_proxy_classes: HashMap::new(),
+            proxy_classes: HashMap::new(),
+            proxy_class_count: 0,
+        }
+    }
+
+    /// Returns the `java.lang.Class` object representing `class`,
+    /// which is created on first use.
+    pub fn class_object(
+        &mut self,
+        class: &Rc<dyn Class>,
+    ) -> Rc<dyn ClassInstance> {
+        self.class_objects
+            .entry((class.class_loader(), class.class_identifier().clone()))
+            .or_insert_with(|| {
+                Rc::new(self.class_class.new_class_object(class.clone()))
+            })
+            .clone()
+    }
+
+    /// Returns the class of the primitive type `name`, e.g. `int`.
+    pub fn primitive_class(&self, name: &str) -> Option<Rc<dyn Class>> {
+        self.primitive_classes
+            .iter()
+            .find(|class| class.class_identifier().java_name() == name)
+            .cloned()
+    }
+
+    /// Returns the class of instances of the annotation interface
+    /// `annotation_type`, which is created on first use.
+    pub fn annotation_proxy_class(
+        &mut self,
+        annotation_type: &Rc<dyn Class>,
+    ) -> Rc<AnnotationProxyClass> {
+        let key = (
+            annotation_type.class_loader(),
+            annotation_type.class_identifier().clone(),
+        );
+        if let Some(class) = self.annotation_proxy_classes.get(&key) {
+            return class.clone();
+        }
+        let class = Rc::new(AnnotationProxyClass::new(
+            self.next_proxy_class_identifier("jdk/proxy1"),
+            annotation_type.clone(),
+            self.object_class.clone(),
+        ));
+        self.annotation_proxy_classes.insert(key, class.clone());
+        class
+    }
+
+    /// Returns the class created by `Proxy.newProxyInstance()`,
+    /// which implements `interfaces` and is defined by `loader`.
+    ///
+    /// The class is created on first use.
+    /// Like in the JDK, it is in the package of the non-public interfaces,
+    /// if there are any.
+    pub fn proxy_class(
+        &mut self,
+        loader: ClassLoaderId,
+        interfaces: Vec<Rc<dyn Class>>,
+    ) -> Rc<DynamicProxyClass> {
+        let key = (
+            loader,
+            interfaces
+                .iter()
+                .map(|interface| interface.class_identifier().clone())
+                .collect(),
+        );
+        if let Some(class) = self.proxy_classes.get(&key) {
+            return class.clone();
+        }
+        let package = interfaces
+            .iter()
+            .find(|interface| {
+                !interface.access_flags().contains(ClassAccessFlag::Public)
+            })
+            .map_or("jdk/proxy1".to_string(), |interface| {
+                interface.class_identifier().package.join("/")
+            });
+        let proxy_class = self
+            .find_class(
+                ClassLoaderId::BOOTSTRAP,
+                &class_identifier!(java / lang / reflect, Proxy),
+            )
+            .expect("Proxy is a builtin class")
+            .clone();
+        let class = Rc::new(DynamicProxyClass::new(
+            self.next_proxy_class_identifier(&package),
+            loader,
+            interfaces,
+            proxy_class,
+            self.object_class.clone(),
+        ));
+        self.proxy_classes.insert(key, class.clone());
+        class
+    }
+
+    /// Returns the name of the next proxy class in `package`,
+    /// e.g. `jdk/proxy1/$Proxy0`.
+    fn next_proxy_class_identifier(
+        &mut self,
+        package: &str,
+    ) -> ClassIdentifier {
+        let name = format!("$Proxy{}", self.proxy_class_count);
+        self.proxy_class_count += 1;
+        if package.is_empty() {
+            parse_class_identifier(&name)
+        } else {
+            parse_class_identifier(&format!("{}/{}", package, name))
         }
     }
 
@@ -172,31 +761,224 @@ impl Heap {
         self.int_array_class.new_instance(length)
     }
 
+    /// Create an array of `elements`, whose class is `component[]`.
+    pub fn new_object_array(
+        &mut self,
+        component: &Rc<dyn Class>,
+        elements: Vec<Option<Rc<dyn ClassInstance>>>,
+    ) -> ObjectArrayInstance {
+        let kind = ObjectArrayKind::new(component.clone());
+        let array_class = self
+            .find_array_class(component.class_loader(), kind.class_identifier())
+            .expect("the component class is loaded");
+        let object_array: &ObjectArray =
+            array_class.as_ref().try_into().unwrap();
+        let array = object_array
+            .new_instance_from_ref(elements.len(), array_class.clone())
+            .unwrap();
+        for (index, element) in elements.into_iter().enumerate() {
+            array.set(index, element).unwrap();
+        }
+        array
+    }
+
     pub fn new_short_array(&self, length: usize) -> ShortArrayInstance {
         self.short_array_class.new_instance(length)
     }
 
-    pub fn add_class(
+    /// Record that `loader` has loaded `class`,
+    /// so that it is found by [`find_class`](Self::find_class).
+    pub fn add_class(&mut self, loader: ClassLoaderId, class: Rc<dyn Class>) {
+        self.class_loaders[loader.0]
+            .classes
+            .insert(class.class_identifier().clone(), class);
+    }
+
+    /// Create a new class loader loading classes from `sources`
+    /// after delegating to `parent`.
+    pub fn create_class_loader(
+        &mut self,
+        parent: ClassLoaderId,
+        sources: Vec<Box<dyn ClassSource>>,
+    ) -> ClassLoaderId {
+        self.class_loaders.push(ClassLoaderData {
+            parent: Some(parent),
+            sources,
+            instance: None,
+            classes: HashMap::new(),
+        });
+        ClassLoaderId(self.class_loaders.len() - 1)
+    }
+
+    pub fn class_loader_parent(
+        &self,
+        loader: ClassLoaderId,
+    ) -> Option<ClassLoaderId> {
+        self.class_loaders[loader.0].parent
+    }
+
+    pub fn class_sources(
+        &self,
+        loader: ClassLoaderId,
+    ) -> &[Box<dyn ClassSource>] {
+        &self.class_loaders[loader.0].sources
+    }
+
+    pub fn set_class_sources(
+        &mut self,
+        loader: ClassLoaderId,
+        sources: Vec<Box<dyn ClassSource>>,
+    ) {
+        self.class_loaders[loader.0].sources = sources;
+    }
+
+    pub fn add_class_source(
+        &mut self,
+        loader: ClassLoaderId,
+        source: Box<dyn ClassSource>,
+    ) {
+        self.class_loaders[loader.0].sources.push(source);
+    }
+
+    /// The `java.lang.ClassLoader` instance of `loader`,
+    /// if it has been created by Java code.
+    pub fn class_loader_instance(
+        &self,
+        loader: ClassLoaderId,
+    ) -> Option<Rc<dyn ClassInstance>> {
+        self.class_loaders[loader.0].instance.clone()
+    }
+
+    pub fn set_class_loader_instance(
         &mut self,
-        fully_qualified_name: ClassIdentifier,
-        class: Rc<dyn Class>,
+        loader: ClassLoaderId,
+        instance: Rc<dyn ClassInstance>,
     ) {
-        self.classes.insert(fully_qualified_name, class);
+        self.class_loaders[loader.0].instance = Some(instance);
+    }
+
+    /// Mark `name` as being defined by `loader`.
+    ///
+    /// Returns false if it already is,
+    /// i.e. when a class (indirectly) references itself.
+    pub(crate) fn begin_loading(
+        &mut self,
+        loader: ClassLoaderId,
+        name: &str,
+    ) -> bool {
+        self.loading.insert((loader, name.to_string()))
+    }
+
+    pub(crate) fn is_loading(&self, loader: ClassLoaderId, name: &str) -> bool {
+        self.loading.contains(&(loader, name.to_string()))
+    }
+
+    pub(crate) fn end_loading(&mut self, loader: ClassLoaderId, name: &str) {
+        self.loading.remove(&(loader, name.to_string()));
     }
 
+    /// Mark the initialization of `class` as started.
+    ///
+    /// Returns false if it already has been,
+    /// i.e. `class` is initialized or is being initialized.
+    pub(crate) fn begin_initialization(&mut self, class: &dyn Class) -> bool {
+        self.initialized
+            .insert((class.class_loader(), class.class_identifier().clone()))
+    }
+
+    /// Bind the method `name` with `descriptor` (e.g. `(II)I`),
+    /// which is declared `native` in the class `class`
+    /// (e.g. `org/example/Main`), to `code`.
+    ///
+    /// The binding has to be registered before `class` is loaded.
+    pub fn register_native_method(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+        code: RustMethod,
+    ) {
+        self.native_methods.insert(
+            (
+                parse_class_identifier(class),
+                name.to_string(),
+                descriptor.to_string(),
+            ),
+            code,
+        );
+    }
+
+    pub fn find_native_method(
+        &self,
+        class: &ClassIdentifier,
+        name: &str,
+        descriptor: &str,
+    ) -> Option<RustMethod> {
+        self.native_methods
+            .get(&(class.clone(), name.to_string(), descriptor.to_string()))
+            .copied()
+    }
+
+    /// Implement the bootstrap method `name` of `class`
+    /// (e.g. `java/lang/invoke/StringConcatFactory`) by `bootstrap_method`.
+    pub fn register_bootstrap_method(
+        &mut self,
+        class: &str,
+        name: &str,
+        bootstrap_method: RustBootstrapMethod,
+    ) {
+        self.bootstrap_methods.insert(
+            (parse_class_identifier(class), name.to_string()),
+            bootstrap_method,
+        );
+    }
+
+    pub fn find_bootstrap_method(
+        &self,
+        class: &ClassIdentifier,
+        name: &str,
+    ) -> Option<RustBootstrapMethod> {
+        self.bootstrap_methods
+            .get(&(class.clone(), name.to_string()))
+            .copied()
+    }
+
+    pub fn set_system_property(&mut self, key: &str, value: &str) {
+        self.system_properties
+            .insert(key.to_string(), value.to_string());
+    }
+
+    pub fn system_property(&self, key: &str) -> Option<&str> {
+        self.system_properties.get(key).map(String::as_str)
+    }
+
+    /// Find a class that has already been loaded by `loader`.
+    ///
+    /// Builtin classes are found for every loader.
     pub fn find_class(
         &self,
+        loader: ClassLoaderId,
         fully_qualified_name: &ClassIdentifier,
     ) -> Option<&Rc<dyn Class>> {
-        self.classes.get(fully_qualified_name)
+        self.class_loaders[loader.0]
+            .classes
+            .get(fully_qualified_name)
+            .or_else(|| {
+                self.class_loaders[ClassLoaderId::BOOTSTRAP.0]
+                    .classes
+                    .get(fully_qualified_name)
+            })
     }
 
+    /// Find (or create) an array class,
+    /// whose element class has already been loaded by `loader`.
     pub fn find_array_class(
         &mut self,
+        loader: ClassLoaderId,
         class_identifier: &ClassIdentifier,
     ) -> Option<Rc<dyn Class>> {
         // easy case: class already exists
-        if let Some(array_class) = self.find_class(class_identifier) {
+        if let Some(array_class) = self.find_class(loader, class_identifier) {
             Some(array_class.clone())
         } else {
             let (package, (dimensions, name)) =
@@ -222,7 +1004,7 @@ impl Heap {
                 };
                 // step 1.1
                 if let Some(c) =
-                    self.find_array_class(&identifier_with_less_dim)
+                    self.find_array_class(loader, &identifier_with_less_dim)
                 {
                     c.clone()
                 } else {
@@ -236,7 +1018,8 @@ impl Heap {
                             package,
                             class_name: ClassName::Plain(c),
                         };
-                        self.find_class(&sclar_class_indentifier)?.clone()
+                        self.find_class(loader, &sclar_class_indentifier)?
+                            .clone()
                     },
                     // Note: this assumes that
                     // all 1-dimenensional, primitive array classes
@@ -256,10 +1039,7 @@ would create a 2 dimensional array here!",
                 self.object_class.clone(),
             ));
             // step 3
-            self.classes.insert(
-                array_class.class_identifier().clone(),
-                array_class.clone(),
-            );
+            self.add_class(loader, array_class.clone());
             Some(array_class)
         }
     }
@@ -2,3 +2,10 @@ pub mod class;
 pub mod classloader;
 pub mod executor;
 pub mod heap;
+pub mod vm;
+
+pub use crate::{
+    classloader::class_source::{ClassSource, MemoryClassSource},
+    heap::ClassLoaderId,
+    vm::{JavaValue, Vm, VmError},
+};
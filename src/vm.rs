@@ -0,0 +1,542 @@
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use anyhow::anyhow;
+use thiserror::Error;
+
+use crate::{
+    class::{
+        builtin_classes::{
+            array::ObjectArray, throwable::throwable_description,
+            StringInstance, SystemExit,
+        },
+        class_identifier, ArgumentKind, Class, ClassInstance, RustMethod,
+        SimpleArgumentKind,
+    },
+    classloader::{
+        class_creator::signature_parser::{
+            is_method_descriptor, parse_method_arguments,
+        },
+        class_source::{open_class_path, ClassSource},
+        load_class, load_class_by_name, load_class_from_bytes,
+        parse_class_identifier, ClassLoadingError,
+    },
+    executor::{
+        frame_stack::StackValue, initialize_class, invoke_method, ReturnValue,
+    },
+    heap::{ClassLoaderId, Heap},
+};
+
+/// A Java virtual machine, for embedding cmjava into Rust programs.
+///
+/// ```no_run
+/// use cmjava::{JavaValue, Vm};
+///
+/// let mut vm = Vm::new();
+/// vm.load_class("Main.class").unwrap();
+/// let sum = vm
+///     .invoke_static("Main", "add", "(II)I", &[40.into(), 2.into()])
+///     .unwrap();
+/// assert_eq!(sum, JavaValue::Int(42));
+/// ```
+///
+/// Classes are loaded by the application class loader,
+/// unless a loader is given, see [`Vm::create_class_loader`].
+pub struct Vm {
+    heap: Heap,
+}
+
+impl Vm {
+    pub fn new() -> Vm {
+        Vm { heap: Heap::new() }
+    }
+
+    pub fn heap(&mut self) -> &mut Heap {
+        &mut self.heap
+    }
+
+    /// Load the class file at `path`.
+    ///
+    /// Classes have to be loaded after the classes they depend on.
+    pub fn load_class<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<Rc<dyn Class>, VmError> {
+        load_class(path, ClassLoaderId::APPLICATION, &mut self.heap)
+            .map_err(VmError::from)
+    }
+
+    /// Load a class from the content of a class file.
+    ///
+    /// Classes have to be loaded after the classes they depend on.
+    pub fn load_class_from_bytes(
+        &mut self,
+        bytes: &[u8],
+    ) -> Result<Rc<dyn Class>, VmError> {
+        load_class_from_bytes(bytes, ClassLoaderId::APPLICATION, &mut self.heap)
+            .map_err(VmError::from)
+    }
+
+    /// Set the directories and JARs searched by
+    /// [`load_class_by_name`](Self::load_class_by_name).
+    ///
+    /// JARs are opened immediately,
+    /// including the ones listed in their manifest's `Class-Path`.
+    /// Class sources added before are removed.
+    pub fn set_class_path(
+        &mut self,
+        class_path: Vec<PathBuf>,
+    ) -> Result<(), VmError> {
+        let joined = std::env::join_paths(&class_path)
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let sources =
+            open_class_path(class_path).map_err(VmError::ClassLoading)?;
+        self.heap
+            .set_class_sources(ClassLoaderId::APPLICATION, sources);
+        self.heap.set_system_property("java.class.path", &joined);
+        Ok(())
+    }
+
+    /// Add `source` to the end of the class path,
+    /// e.g. a [`MemoryClassSource`](crate::MemoryClassSource)
+    /// with generated classes.
+    pub fn add_class_source(&mut self, source: impl ClassSource + 'static) {
+        self.heap
+            .add_class_source(ClassLoaderId::APPLICATION, Box::new(source));
+    }
+
+    /// Create a class loader loading classes from `sources`
+    /// after delegating to `parent`,
+    /// e.g. to give plugins their own namespace.
+    pub fn create_class_loader(
+        &mut self,
+        parent: ClassLoaderId,
+        sources: Vec<Box<dyn ClassSource>>,
+    ) -> ClassLoaderId {
+        self.heap.create_class_loader(parent, sources)
+    }
+
+    /// Load the class `name` (e.g. `org/example/Main`)
+    /// and the classes it references from the class path.
+    pub fn load_class_by_name(
+        &mut self,
+        name: &str,
+    ) -> Result<Rc<dyn Class>, VmError> {
+        self.load_class_in(ClassLoaderId::APPLICATION, name)
+    }
+
+    /// Load the class `name` (e.g. `org/example/Main`) with `loader`.
+    pub fn load_class_in(
+        &mut self,
+        loader: ClassLoaderId,
+        name: &str,
+    ) -> Result<Rc<dyn Class>, VmError> {
+        load_class_by_name(name, loader, &mut self.heap).map_err(VmError::from)
+    }
+
+    /// Set a property returned by `System.getProperty()`.
+    pub fn set_system_property(&mut self, key: &str, value: &str) {
+        self.heap.set_system_property(key, value);
+    }
+
+    /// Run `public static void main(String[] args)` of `class`.
+    ///
+    /// If the program calls `System.exit()`,
+    /// [`VmError::Exit`] is returned (even for status 0).
+    pub fn run_main(
+        &mut self,
+        class: &str,
+        args: &[String],
+    ) -> Result<(), VmError> {
+        let array_class = self
+            .heap
+            .find_array_class(
+                ClassLoaderId::BOOTSTRAP,
+                &class_identifier!(1, java / lang, String),
+            )
+            .expect("String[] can be created");
+        let array = array_class
+            .as_any()
+            .downcast_ref::<ObjectArray>()
+            .expect("String[] is an object array")
+            .new_instance_from_ref(args.len(), array_class.clone())
+            .expect("array class matches");
+        for (index, arg) in args.iter().enumerate() {
+            let arg: Rc<dyn ClassInstance> =
+                Rc::new(self.heap.new_string(arg.clone()));
+            array.set(index, Some(arg)).expect("index is in bounds");
+        }
+
+        self.invoke_static(
+            class,
+            "main",
+            "([Ljava/lang/String;)V",
+            &[JavaValue::Reference(Some(Rc::new(array)))],
+        )
+        .map(|_| ())
+    }
+
+    /// See [`Heap::register_native_method`].
+    pub fn register_native_method(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+        code: RustMethod,
+    ) {
+        self.heap
+            .register_native_method(class, name, descriptor, code);
+    }
+
+    /// Find a loaded (or builtin) class by its binary name,
+    /// e.g. `org/example/Main`.
+    pub fn find_class(&self, class: &str) -> Option<Rc<dyn Class>> {
+        self.find_class_in(ClassLoaderId::APPLICATION, class)
+    }
+
+    /// Find a class that has been loaded by `loader`, see
+    /// [`find_class`](Self::find_class).
+    pub fn find_class_in(
+        &self,
+        loader: ClassLoaderId,
+        class: &str,
+    ) -> Option<Rc<dyn Class>> {
+        self.heap
+            .find_class(loader, &parse_class_identifier(class))
+            .cloned()
+    }
+
+    /// Invoke the static method `name` with `descriptor` (e.g. `(II)I`)
+    /// of `class` (e.g. `org/example/Main`) with `arguments`.
+    ///
+    /// Returns the value returned by the method,
+    /// or the exception thrown by it as [`VmError::Exception`].
+    pub fn invoke_static(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+        arguments: &[JavaValue],
+    ) -> Result<JavaValue, VmError> {
+        self.invoke_static_in(
+            ClassLoaderId::APPLICATION,
+            class,
+            name,
+            descriptor,
+            arguments,
+        )
+    }
+
+    /// Like [`invoke_static`](Self::invoke_static),
+    /// for a class loaded by `loader`.
+    pub fn invoke_static_in(
+        &mut self,
+        loader: ClassLoaderId,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+        arguments: &[JavaValue],
+    ) -> Result<JavaValue, VmError> {
+        let class = self
+            .find_class_in(loader, class)
+            .ok_or_else(|| VmError::ClassNotFound(class.to_string()))?;
+        if !is_method_descriptor(descriptor) {
+            return Err(VmError::InvalidDescriptor(descriptor.to_string()));
+        }
+        let (parameters, return_type) = parse_method_arguments(descriptor);
+        let (method, defining_class) = class
+            .find_method(name, (&parameters, return_type.as_ref()), false)
+            .filter(|(m, _)| m.is_static)
+            .ok_or_else(|| {
+                VmError::MethodNotFound(format!(
+                    "{}.{}{}",
+                    class.class_identifier(),
+                    name,
+                    descriptor
+                ))
+            })?;
+
+        if parameters.len() != arguments.len() {
+            return Err(VmError::ArgumentCount {
+                expected: parameters.len(),
+                actual: arguments.len(),
+            });
+        }
+        let arguments = parameters
+            .iter()
+            .zip(arguments)
+            .enumerate()
+            .map(|(index, (kind, value))| {
+                value.to_stack_value(kind, &self.heap).ok_or_else(|| {
+                    VmError::ArgumentType {
+                        index,
+                        expected: kind.clone(),
+                        actual: value.clone(),
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        initialize_class(&mut self.heap, &class)
+            .map_err(VmError::from_exception)?;
+        let value =
+            invoke_method(&mut self.heap, &method, defining_class, arguments)
+                .map_err(VmError::from_exception)?;
+        Ok(JavaValue::from_return_value(value, return_type.as_ref()))
+    }
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A Java value, as passed to and returned from [`Vm::invoke_static`].
+#[derive(Debug, Clone)]
+pub enum JavaValue {
+    /// Returned by void methods.
+    Void,
+    Boolean(bool),
+    Byte(i8),
+    Char(char),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    /// Converted from/to `java.lang.String` instances.
+    String(String),
+    Reference(Option<Rc<dyn ClassInstance>>),
+}
+
+impl JavaValue {
+    /// Convert `self` to a value passed as argument of kind `kind`.
+    ///
+    /// Returns `None` if `self` can't be passed as `kind`.
+    fn to_stack_value(
+        &self,
+        kind: &ArgumentKind,
+        heap: &Heap,
+    ) -> Option<StackValue> {
+        let simple_kind = match kind {
+            ArgumentKind::Simple(simple_kind) => Some(simple_kind),
+            ArgumentKind::Array { .. } => None,
+        };
+        match (self, simple_kind) {
+            (JavaValue::Boolean(b), Some(SimpleArgumentKind::Boolean)) => {
+                Some(StackValue::Int(i32::from(*b)))
+            },
+            (JavaValue::Byte(b), Some(SimpleArgumentKind::Byte)) => {
+                Some(StackValue::Int(i32::from(*b)))
+            },
+            (JavaValue::Char(c), Some(SimpleArgumentKind::Char)) => {
+                Some(StackValue::Int(*c as i32))
+            },
+            (JavaValue::Short(s), Some(SimpleArgumentKind::Short)) => {
+                Some(StackValue::Int(i32::from(*s)))
+            },
+            (JavaValue::Int(i), Some(SimpleArgumentKind::Int)) => {
+                Some(StackValue::Int(*i))
+            },
+            (JavaValue::Long(l), Some(SimpleArgumentKind::Long)) => {
+                Some(StackValue::Long(*l))
+            },
+            (JavaValue::Float(f), Some(SimpleArgumentKind::Float)) => {
+                Some(StackValue::Float(*f))
+            },
+            (JavaValue::Double(d), Some(SimpleArgumentKind::Double)) => {
+                Some(StackValue::Double(*d))
+            },
+            (JavaValue::String(s), Some(SimpleArgumentKind::Class(class)))
+                if class == "java/lang/String"
+                    || class == "java/lang/Object" =>
+            {
+                Some(StackValue::Reference(Some(Rc::new(
+                    heap.new_string(s.clone()),
+                ))))
+            },
+            (JavaValue::Reference(r), Some(SimpleArgumentKind::Class(_)))
+            | (JavaValue::Reference(r), None) => {
+                Some(StackValue::Reference(r.clone()))
+            },
+            _ => None,
+        }
+    }
+
+    fn from_return_value(
+        value: ReturnValue,
+        return_type: Option<&ArgumentKind>,
+    ) -> JavaValue {
+        let simple_kind = match return_type {
+            Some(ArgumentKind::Simple(simple_kind)) => Some(simple_kind),
+            _ => None,
+        };
+        match (value, simple_kind) {
+            (ReturnValue::Void, _) => JavaValue::Void,
+            (ReturnValue::Int(i), Some(SimpleArgumentKind::Boolean)) => {
+                JavaValue::Boolean(i != 0)
+            },
+            (ReturnValue::Int(i), Some(SimpleArgumentKind::Byte)) => {
+                JavaValue::Byte(i as i8)
+            },
+            (ReturnValue::Int(i), Some(SimpleArgumentKind::Char)) => {
+                JavaValue::Char(
+                    char::from_u32(i as u32)
+                        .unwrap_or(char::REPLACEMENT_CHARACTER),
+                )
+            },
+            (ReturnValue::Int(i), Some(SimpleArgumentKind::Short)) => {
+                JavaValue::Short(i as i16)
+            },
+            (ReturnValue::Int(i), _) => JavaValue::Int(i),
+            (ReturnValue::Long(l), _) => JavaValue::Long(l),
+            (ReturnValue::Float(f), _) => JavaValue::Float(f),
+            (ReturnValue::Double(d), _) => JavaValue::Double(d),
+            (ReturnValue::Reference(Some(r)), _) => {
+                match r.as_any().downcast_ref::<StringInstance>() {
+                    Some(s) => JavaValue::String(s.string.clone()),
+                    None => JavaValue::Reference(Some(r)),
+                }
+            },
+            (ReturnValue::Reference(None), _) => JavaValue::Reference(None),
+        }
+    }
+}
+
+impl PartialEq for JavaValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (JavaValue::Void, JavaValue::Void) => true,
+            (JavaValue::Boolean(a), JavaValue::Boolean(b)) => a == b,
+            (JavaValue::Byte(a), JavaValue::Byte(b)) => a == b,
+            (JavaValue::Char(a), JavaValue::Char(b)) => a == b,
+            (JavaValue::Short(a), JavaValue::Short(b)) => a == b,
+            (JavaValue::Int(a), JavaValue::Int(b)) => a == b,
+            (JavaValue::Long(a), JavaValue::Long(b)) => a == b,
+            (JavaValue::Float(a), JavaValue::Float(b)) => a == b,
+            (JavaValue::Double(a), JavaValue::Double(b)) => a == b,
+            (JavaValue::String(a), JavaValue::String(b)) => a == b,
+            // references are equal if they refer to the same instance
+            (JavaValue::Reference(a), JavaValue::Reference(b)) => {
+                match (a, b) {
+                    (None, None) => true,
+                    (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+                    _ => false,
+                }
+            },
+            _ => false,
+        }
+    }
+}
+
+impl From<bool> for JavaValue {
+    fn from(value: bool) -> Self {
+        JavaValue::Boolean(value)
+    }
+}
+
+impl From<i8> for JavaValue {
+    fn from(value: i8) -> Self {
+        JavaValue::Byte(value)
+    }
+}
+
+impl From<char> for JavaValue {
+    fn from(value: char) -> Self {
+        JavaValue::Char(value)
+    }
+}
+
+impl From<i16> for JavaValue {
+    fn from(value: i16) -> Self {
+        JavaValue::Short(value)
+    }
+}
+
+impl From<i32> for JavaValue {
+    fn from(value: i32) -> Self {
+        JavaValue::Int(value)
+    }
+}
+
+impl From<i64> for JavaValue {
+    fn from(value: i64) -> Self {
+        JavaValue::Long(value)
+    }
+}
+
+impl From<f32> for JavaValue {
+    fn from(value: f32) -> Self {
+        JavaValue::Float(value)
+    }
+}
+
+impl From<f64> for JavaValue {
+    fn from(value: f64) -> Self {
+        JavaValue::Double(value)
+    }
+}
+
+impl From<&str> for JavaValue {
+    fn from(value: &str) -> Self {
+        JavaValue::String(value.to_string())
+    }
+}
+
+impl From<String> for JavaValue {
+    fn from(value: String) -> Self {
+        JavaValue::String(value)
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum VmError {
+    #[error("class could not be loaded: {0}")]
+    ClassLoading(anyhow::Error),
+    #[error("class '{0}' not found")]
+    ClassNotFound(String),
+    #[error("invalid method descriptor '{0}'")]
+    InvalidDescriptor(String),
+    #[error("static method '{0}' not found")]
+    MethodNotFound(String),
+    #[error("expected {expected} arguments, got {actual}")]
+    ArgumentCount { expected: usize, actual: usize },
+    #[error("argument {index} has to be of kind {expected:?}, got {actual:?}")]
+    ArgumentType {
+        index: usize,
+        expected: ArgumentKind,
+        actual: JavaValue,
+    },
+    /// Displayed like java reports uncaught exceptions.
+    #[error("Exception in thread \"main\" {}", throwable_description(.0))]
+    Exception(Rc<dyn ClassInstance>),
+    #[error("System.exit({0}) has been called")]
+    Exit(i32),
+}
+
+impl VmError {
+    fn from_exception(e: Rc<dyn ClassInstance>) -> VmError {
+        match e.as_any().downcast_ref::<SystemExit>() {
+            Some(exit) => VmError::Exit(exit.status),
+            None => VmError::Exception(e),
+        }
+    }
+}
+
+impl From<ClassLoadingError> for VmError {
+    fn from(error: ClassLoadingError) -> Self {
+        match error {
+            ClassLoadingError::NotFound(name) => {
+                VmError::ClassLoading(anyhow!("class {} not found", name))
+            },
+            ClassLoadingError::Format(e) => VmError::ClassLoading(e.into()),
+            ClassLoadingError::Verify(e) => VmError::ClassLoading(e.into()),
+            ClassLoadingError::Invalid(e) => VmError::ClassLoading(e),
+            ClassLoadingError::Exception(e) => VmError::from_exception(e),
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+
+#[test]
+fn abstract_classes() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/abstract_classes", "org.cmjava2023.Main"]);
+    cmd.assert().success().stdout(predicate::eq(
+        "square with area 9\n\
+rectangle with area 10\n\
+3\n\
+2\n\
+1\n\
+InstantiationError org.cmjava2023.Base\n\
+AbstractMethodError\n",
+    ));
+
+    Ok(())
+}
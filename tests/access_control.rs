@@ -0,0 +1,73 @@
+use assert_cmd::Command;
+use cmjava::{classloader::verifier::VerifyError, Vm, VmError};
+use predicates::prelude::predicate;
+
+#[test]
+fn access_control() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/access_control", "org.cmjava2023.Main"]);
+    cmd.assert().success().stdout(predicate::eq(
+        "hello from the vault\n\
+inherited 4\n\
+private static field\n\
+package-private field\n\
+protected field\n\
+final static field\n\
+final field\n\
+package-private method\n\
+private method\n\
+3 7\n\
+12 whisper\n\
+10\n\
+class org.cmjava2023.Sub cannot access a member \
+of class org.cmjava2023.other.Vault with modifiers \"protected\"\n",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn illegal_access_error() -> Result<(), Box<dyn std::error::Error>> {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/access_control".into()])?;
+    vm.load_class_by_name("org/cmjava2023/Main")?;
+
+    match vm.invoke_static("org/cmjava2023/Main", "counter", "()I", &[]) {
+        Err(VmError::Exception(e)) => assert_eq!(
+            e.class().class_identifier().to_string(),
+            "java/lang/IllegalAccessError"
+        ),
+        result => panic!("expected exception, got {:?}", result),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn protected_access_on_other_class() -> Result<(), Box<dyn std::error::Error>> {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/access_control".into()])?;
+
+    for (class, opcode) in [
+        ("ProtectedRead", "getfield"),
+        ("ProtectedWrite", "putfield"),
+        ("ProtectedCall", "invokevirtual"),
+    ] {
+        let error = match vm
+            .load_class_by_name(&format!("org/cmjava2023/{}", class))
+        {
+            Err(VmError::ClassLoading(e)) => e
+                .downcast::<VerifyError>()
+                .unwrap_or_else(|e| panic!("expected VerifyError, got {}", e)),
+            Err(e) => panic!("expected VerifyError, got {}", e),
+            Ok(_) => panic!("expected VerifyError, got class"),
+        };
+        assert_eq!(
+            error.reason,
+            format!("bad access to protected data in {}", opcode)
+        );
+    }
+
+    Ok(())
+}
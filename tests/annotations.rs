@@ -0,0 +1,141 @@
+use assert_cmd::Command;
+use cmjava::{
+    class::attributes::{Annotation, ElementValue},
+    Vm,
+};
+use common::{bytecode_class, load, method};
+use predicates::prelude::predicate;
+
+mod common;
+
+fn handler(value: &str, elements: Vec<(&str, ElementValue)>) -> Annotation {
+    Annotation {
+        type_name: "Lorg/cmjava2023/Handler;".to_string(),
+        elements: [("value", ElementValue::String(value.to_string()))]
+            .into_iter()
+            .chain(elements)
+            .map(|(name, value)| (name.to_string(), value))
+            .collect(),
+    }
+}
+
+#[test]
+fn get_annotation() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/annotations", "org.cmjava2023.Main"]);
+    cmd.assert().success().stdout(predicate::eq(
+        "main\n\
+5\n\
+true\n\
+0.5\n\
+2\n\
+b\n\
+2\n\
+java.lang.String\n\
+/main\n\
+org.cmjava2023.Handler\n\
+true\n\
+false\n\
+true\n",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn class_annotations() {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/annotations".into()])
+        .unwrap();
+    let class = load(&mut vm, "Annotated");
+
+    assert_eq!(
+        bytecode_class(&class).attributes.annotations,
+        [Annotation {
+            type_name: "Lorg/cmjava2023/Values;".to_string(),
+            elements: vec![
+                ("b".to_string(), ElementValue::Byte(1)),
+                ("c".to_string(), ElementValue::Char('x' as u16)),
+                ("s".to_string(), ElementValue::Short(-2)),
+                ("j".to_string(), ElementValue::Long(3)),
+                ("f".to_string(), ElementValue::Float(1.5)),
+                (
+                    "level".to_string(),
+                    ElementValue::Enum {
+                        type_name: "Lorg/cmjava2023/Level;".to_string(),
+                        const_name: "WARN".to_string(),
+                    }
+                ),
+                (
+                    "types".to_string(),
+                    ElementValue::Array(vec![
+                        ElementValue::Class("I".to_string()),
+                        ElementValue::Class("[Ljava/lang/String;".to_string()),
+                    ])
+                ),
+            ],
+        }]
+    );
+}
+
+#[test]
+fn member_annotations() {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/annotations".into()])
+        .unwrap();
+    let class = load(&mut vm, "Annotated");
+
+    assert_eq!(
+        bytecode_class(&class).instance_fields[0]
+            .attributes
+            .annotations,
+        [handler("field", vec![])]
+    );
+    let handle = &method(&class, "handle").attributes;
+    assert_eq!(
+        handle.annotations,
+        [handler("method", vec![("priority", ElementValue::Int(1))])]
+    );
+    assert_eq!(
+        handle.parameter_annotations,
+        [vec![handler("parameter", vec![])], vec![]]
+    );
+}
+
+#[test]
+fn annotation_defaults() {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/annotations".into()])
+        .unwrap();
+    let class = load(&mut vm, "Handler");
+
+    let default =
+        |name| method(&class, name).attributes.annotation_default.clone();
+    assert_eq!(default("value"), None);
+    assert_eq!(default("priority"), Some(ElementValue::Int(5)));
+    assert_eq!(default("enabled"), Some(ElementValue::Boolean(true)));
+    assert_eq!(default("ratio"), Some(ElementValue::Double(0.5)));
+    assert_eq!(default("tags"), Some(ElementValue::Array(vec![])));
+    assert_eq!(
+        default("codes"),
+        Some(ElementValue::Array(vec![
+            ElementValue::Int(1),
+            ElementValue::Int(2)
+        ]))
+    );
+    assert_eq!(
+        default("target"),
+        Some(ElementValue::Class("Ljava/lang/Object;".to_string()))
+    );
+    assert_eq!(
+        default("route"),
+        Some(ElementValue::Annotation(Annotation {
+            type_name: "Lorg/cmjava2023/Route;".to_string(),
+            elements: vec![(
+                "path".to_string(),
+                ElementValue::String("/".to_string())
+            )],
+        }))
+    );
+}
@@ -5,7 +5,7 @@ use predicates::prelude::predicate;
 fn array() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/array/Main.class");
+    cmd.args(["-cp", "tests/data/array", "org.cmjava2023.Main"]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("o:\nHello World"))
@@ -0,0 +1,169 @@
+use cmjava::{
+    class::{
+        access_flags::NestedClassAccessFlag,
+        attributes::{
+            ConstantValue, EnclosingMethod, InnerClass, LineNumber,
+            LocalVariable,
+        },
+        ClassIdentifier, FieldValue,
+    },
+    Vm,
+};
+use common::{bytecode_class, load, method};
+
+mod common;
+
+#[test]
+fn class_attributes() {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/attributes".into()])
+        .unwrap();
+    let class = load(&mut vm, "Described");
+    let attributes = &bytecode_class(&class).attributes;
+
+    assert_eq!(attributes.source_file.as_deref(), Some("Described.java"));
+    assert_eq!(
+        attributes.signature.as_deref(),
+        Some("<T:Ljava/lang/Object;>Ljava/lang/Object;")
+    );
+    assert_eq!(
+        attributes.inner_classes,
+        [
+            InnerClass {
+                inner_class: "org/cmjava2023/Described$Inner".to_string(),
+                outer_class: Some("org/cmjava2023/Described".to_string()),
+                inner_name: Some("Inner".to_string()),
+                access_flags: NestedClassAccessFlag::Public
+                    | NestedClassAccessFlag::Static,
+            },
+            InnerClass {
+                inner_class: "org/cmjava2023/Described$1Local".to_string(),
+                outer_class: None,
+                inner_name: Some("Local".to_string()),
+                access_flags: Default::default(),
+            },
+        ]
+    );
+    assert!(attributes.enclosing_method.is_none());
+    assert!(!attributes.deprecated);
+}
+
+#[test]
+fn enclosing_method() {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/attributes".into()])
+        .unwrap();
+    load(&mut vm, "Described");
+    let class = load(&mut vm, "Described$1Local");
+
+    assert_eq!(
+        bytecode_class(&class).attributes.enclosing_method,
+        Some(EnclosingMethod {
+            class: "org/cmjava2023/Described".to_string(),
+            method: Some(("local".to_string(), "()V".to_string())),
+        })
+    );
+}
+
+#[test]
+fn method_attributes() {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/attributes".into()])
+        .unwrap();
+    let class = load(&mut vm, "Described");
+
+    let twice = &method(&class, "twice").attributes;
+    assert!(twice.deprecated);
+    assert_eq!(
+        twice.line_numbers,
+        [
+            LineNumber {
+                start_pc: 0,
+                line_number: 14,
+            },
+            LineNumber {
+                start_pc: 4,
+                line_number: 15,
+            },
+        ]
+    );
+    assert_eq!(
+        twice.local_variables,
+        [
+            LocalVariable {
+                start_pc: 0,
+                length: 6,
+                name: "number".to_string(),
+                descriptor: "I".to_string(),
+                index: 0,
+            },
+            LocalVariable {
+                start_pc: 4,
+                length: 2,
+                name: "result".to_string(),
+                descriptor: "I".to_string(),
+                index: 1,
+            },
+        ]
+    );
+
+    let first = &method(&class, "first").attributes;
+    assert!(!first.deprecated);
+    assert_eq!(
+        first.signature.as_deref(),
+        Some("<E:Ljava/lang/Object;>([TE;)TE;")
+    );
+    assert_eq!(
+        first
+            .exceptions
+            .iter()
+            .map(ClassIdentifier::to_string)
+            .collect::<Vec<_>>(),
+        ["java/lang/Exception"]
+    );
+    assert_eq!(
+        first.local_variable_types,
+        [LocalVariable {
+            start_pc: 0,
+            length: 4,
+            name: "values".to_string(),
+            descriptor: "[TE;".to_string(),
+            index: 0,
+        }]
+    );
+}
+
+#[test]
+fn field_attributes() {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/attributes".into()])
+        .unwrap();
+    let class = load(&mut vm, "Described");
+
+    let static_field = |name: &str| {
+        class
+            .static_fields()
+            .iter()
+            .find(|field| field.name == name)
+            .unwrap_or_else(|| panic!("field {} exists", name))
+            .clone()
+    };
+    // static constants are initialized with their ConstantValue
+    let answer = static_field("ANSWER");
+    assert_eq!(
+        answer.attributes.constant_value,
+        Some(ConstantValue::Int(42))
+    );
+    assert!(matches!(*answer.value.borrow(), FieldValue::Int(42)));
+    let enabled = static_field("ENABLED");
+    assert!(matches!(*enabled.value.borrow(), FieldValue::Boolean(1)));
+    assert_eq!(
+        static_field("GREETING").attributes.constant_value,
+        Some(ConstantValue::String("hello".to_string()))
+    );
+
+    let value = &class.instance_fields()[0];
+    assert_eq!(value.name, "value");
+    assert_eq!(value.attributes.signature.as_deref(), Some("TT;"));
+    assert_eq!(value.attributes.constant_value, None);
+}
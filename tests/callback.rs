@@ -0,0 +1,17 @@
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+
+#[test]
+fn callback() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/callback", "org.cmjava2023.Main"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "[[leaf]]\n\
+caught: broken toString\n\
+caught nested: broken toString\n\
+done\n",
+    ));
+
+    Ok(())
+}
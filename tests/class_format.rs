@@ -0,0 +1,67 @@
+use assert_cmd::Command;
+use common::{class_format_error, class_offsets, CALCULATOR};
+use predicates::prelude::predicate;
+
+mod common;
+
+#[test]
+fn truncated() -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = std::fs::read(CALCULATOR)?;
+
+    for length in 0..bytes.len() {
+        let error = class_format_error(&bytes[..length]);
+        // the offset of the incomplete structure
+        assert!(error.offset.is_some_and(|o| o <= length), "{}", error);
+        assert_eq!(error.reason, "truncated class file", "{}", error);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn invalid_content() -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = std::fs::read(CALCULATOR)?;
+
+    let mut magic = bytes.clone();
+    magic[0] = 0;
+    assert_eq!(
+        class_format_error(&magic).to_string(),
+        "offset 0: invalid magic number"
+    );
+
+    // the tag of the first constant pool entry
+    let first_entry = class_offsets(&bytes).constant_pool[&1];
+    let mut tag = bytes.clone();
+    tag[first_entry] = 99;
+    assert_eq!(
+        class_format_error(&tag).to_string(),
+        format!("offset {}: invalid constant pool tag 99", first_entry)
+    );
+
+    // the class of the first entry (a Methodref) refers to a NameAndType
+    let mut class_index = bytes.clone();
+    class_index[first_entry + 2] = 3;
+    assert_eq!(
+        class_format_error(&class_index).to_string(),
+        format!(
+            "org/cmjava2023/Calculator.class: offset {}: \
+constant pool entry 3 is no Class",
+            first_entry
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn cli_diagnostic() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    // Main.class only contains the first 100 bytes of the compiled Main.java
+    cmd.args(["-cp", "tests/data/class_format", "org.cmjava2023.Main"]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "org/cmjava2023/Main.class: offset 100: truncated class file",
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use std::rc::Rc;
+
+use assert_cmd::Command;
+use cmjava::{ClassLoaderId, JavaValue, MemoryClassSource, Vm};
+use predicates::prelude::predicate;
+
+const PLUGIN: &str = "org/cmjava2023/Plugin";
+
+fn plugin_source(
+    version: &str,
+) -> Result<MemoryClassSource, Box<dyn std::error::Error>> {
+    let mut source = MemoryClassSource::new();
+    source.insert(
+        PLUGIN,
+        std::fs::read(format!(
+            "tests/data/class_loaders/plugins/{}/{}.class",
+            version, PLUGIN
+        ))?,
+    );
+    Ok(source)
+}
+
+#[test]
+fn isolated_plugins() -> Result<(), Box<dyn std::error::Error>> {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/class_loaders".into()])?;
+    let first = vm.create_class_loader(
+        ClassLoaderId::APPLICATION,
+        vec![Box::new(plugin_source("v1")?)],
+    );
+    let second = vm.create_class_loader(
+        ClassLoaderId::APPLICATION,
+        vec![Box::new(plugin_source("v2")?)],
+    );
+
+    vm.load_class_in(first, PLUGIN)?;
+    vm.load_class_in(second, PLUGIN)?;
+    assert_eq!(
+        vm.invoke_static_in(
+            first,
+            PLUGIN,
+            "name",
+            "()Ljava/lang/String;",
+            &[]
+        )?,
+        JavaValue::String("plugin v1".to_string())
+    );
+    assert_eq!(
+        vm.invoke_static_in(
+            second,
+            PLUGIN,
+            "name",
+            "()Ljava/lang/String;",
+            &[]
+        )?,
+        JavaValue::String("plugin v2".to_string())
+    );
+
+    // the API is loaded once by the common parent
+    let api = "org/cmjava2023/Api";
+    assert!(Rc::ptr_eq(
+        &vm.find_class_in(first, api).unwrap(),
+        &vm.find_class_in(second, api).unwrap()
+    ));
+    assert!(vm.find_class(PLUGIN).is_none());
+    assert!(vm.load_class_by_name(PLUGIN).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn java_class_loaders() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    // Plugin isn't part of the class path,
+    // its class file is passed to the program via stdin
+    cmd.args(["-cp", "tests/data/class_loaders", "org.cmjava2023.Main"]);
+    cmd.write_stdin(std::fs::read(
+        "tests/data/class_loaders/plugins/v1/org/cmjava2023/Plugin.class",
+    )?);
+    cmd.assert().success().stdout(predicate::str::diff(
+        "org.cmjava2023.Plugin\n\
+true\n\
+loading org.cmjava2023.Plugin\n\
+loading org.cmjava2023.Api\n\
+false\n\
+loading org.cmjava2023.Api\n\
+true\n\
+caught: org.cmjava2023.Missing\n",
+    ));
+
+    Ok(())
+}
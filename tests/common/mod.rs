@@ -0,0 +1,200 @@
+//! Helpers shared by the integration tests.
+
+// every test crate only uses some of the helpers
+#![allow(dead_code)]
+
+use std::{collections::HashMap, rc::Rc};
+
+use cmjava::{
+    class::{BytecodeClass, Class, Method},
+    classloader::ClassFormatError,
+    Vm, VmError,
+};
+
+/// Loads the class `org/cmjava2023/<name>` from the class path of `vm`.
+pub fn load(vm: &mut Vm, name: &str) -> Rc<dyn Class> {
+    vm.load_class_by_name(&format!("org/cmjava2023/{}", name))
+        .unwrap_or_else(|e| panic!("{} can't be loaded: {}", name, e))
+}
+
+/// Downcasts `class`, panicking if it wasn't loaded from a class file.
+pub fn bytecode_class(class: &Rc<dyn Class>) -> &BytecodeClass {
+    class
+        .as_any()
+        .downcast_ref()
+        .expect("class is a BytecodeClass")
+}
+
+/// Returns the method `name` of `class`.
+pub fn method<'a>(class: &'a Rc<dyn Class>, name: &str) -> &'a Method {
+    class
+        .methods()
+        .iter()
+        .find(|method| method.name == name)
+        .unwrap_or_else(|| panic!("method {} exists", name))
+}
+
+/// The class file most tests of the class loader patch.
+pub const CALCULATOR: &str = "tests/data/vm/Calculator.class";
+
+/// Returns the bytes of Calculator.class patched by `patch`.
+pub fn patched_calculator(patch: impl FnOnce(&mut Vec<u8>)) -> Vec<u8> {
+    let mut bytes =
+        std::fs::read(CALCULATOR).expect("Calculator.class is readable");
+    patch(&mut bytes);
+    bytes
+}
+
+/// Returns the error `bytes` are rejected for,
+/// panicking if it isn't a `ClassFormatError`.
+pub fn class_format_error(bytes: &[u8]) -> ClassFormatError {
+    match Vm::new().load_class_from_bytes(bytes) {
+        Err(VmError::ClassLoading(e)) => e
+            .downcast::<ClassFormatError>()
+            .unwrap_or_else(|e| panic!("expected ClassFormatError, got {}", e)),
+        Err(e) => panic!("expected ClassFormatError, got {}", e),
+        Ok(_) => panic!("expected ClassFormatError, got class"),
+    }
+}
+
+/// The offsets of the structures of a class file,
+/// see [`class_offsets`].
+pub struct ClassOffsets {
+    /// The offsets of the constant pool entries, by index.
+    pub constant_pool: HashMap<usize, usize>,
+    /// The offset of `access_flags`,
+    /// which are followed by `this_class` and `super_class`.
+    pub access_flags: usize,
+    /// The offsets of the `method_info` structures, by method name.
+    pub methods: HashMap<String, usize>,
+}
+
+/// Returns the offsets of the structures of the class file `bytes`,
+/// so that tests patching it don't depend on the layout of the file.
+pub fn class_offsets(bytes: &[u8]) -> ClassOffsets {
+    let u2 = |offset: usize| {
+        u16::from_be_bytes([bytes[offset], bytes[offset + 1]]) as usize
+    };
+
+    let mut constant_pool = HashMap::new();
+    let constant_pool_count = u2(8);
+    let mut offset = 10;
+    let mut index = 1;
+    while index < constant_pool_count {
+        constant_pool.insert(index, offset);
+        let (size, slots) = match bytes[offset] {
+            1 => (3 + u2(offset + 1), 1),
+            // Long and Double take two entries
+            5 | 6 => (9, 2),
+            3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => (5, 1),
+            15 => (4, 1),
+            7 | 8 | 16 | 19 | 20 => (3, 1),
+            tag => panic!("invalid constant pool tag {}", tag),
+        };
+        offset += size;
+        index += slots;
+    }
+    let access_flags = offset;
+
+    // access_flags, this_class, super_class and the interfaces
+    offset += 6;
+    offset += 2 + 2 * u2(offset);
+    let fields_count = u2(offset);
+    offset += 2;
+    for _ in 0..fields_count {
+        offset += 6;
+        offset = skip_attributes(bytes, offset);
+    }
+    let mut methods = HashMap::new();
+    let methods_count = u2(offset);
+    offset += 2;
+    for _ in 0..methods_count {
+        let name_offset = constant_pool[&u2(offset + 2)];
+        let length = u2(name_offset + 1);
+        let name = &bytes[name_offset + 3..name_offset + 3 + length];
+        methods.insert(String::from_utf8_lossy(name).into_owned(), offset);
+        offset += 6;
+        offset = skip_attributes(bytes, offset);
+    }
+
+    ClassOffsets {
+        constant_pool,
+        access_flags,
+        methods,
+    }
+}
+
+/// The offsets of parts of a method in a class file,
+/// see [`method_offsets`].
+pub struct MethodOffsets {
+    /// The offset of `max_stack` of the Code attribute.
+    pub max_stack: usize,
+    /// The offset of the first instruction.
+    pub code: usize,
+    /// The offset of the first entry of the StackMapTable attribute,
+    /// if there is one.
+    pub stack_map_frames: Option<usize>,
+}
+
+/// Returns the offsets of the Code attribute of the method `name`
+/// in the class file `bytes`, see [`class_offsets`].
+pub fn method_offsets(bytes: &[u8], name: &str) -> MethodOffsets {
+    let u2 = |offset: usize| {
+        u16::from_be_bytes([bytes[offset], bytes[offset + 1]]) as usize
+    };
+    let u4 = |offset: usize| {
+        u32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap())
+            as usize
+    };
+
+    let offsets = class_offsets(bytes);
+    // whether the Utf8 entry referenced at `offset` is `string`
+    let is_utf8 = |offset: usize, string: &str| {
+        let entry = offsets.constant_pool[&u2(offset)];
+        bytes[entry + 3..entry + 3 + u2(entry + 1)] == *string.as_bytes()
+    };
+    let method = *offsets
+        .methods
+        .get(name)
+        .unwrap_or_else(|| panic!("method {} not found", name));
+    let attributes_count = u2(method + 6);
+    let mut attribute = method + 8;
+    for _ in 0..attributes_count {
+        if is_utf8(attribute, "Code") {
+            let max_stack = attribute + 6;
+            let code = max_stack + 8;
+            let exception_table = code + u4(max_stack + 4);
+            let attributes = exception_table + 2 + 8 * u2(exception_table);
+            let mut code_attribute = attributes + 2;
+            let mut stack_map_frames = None;
+            for _ in 0..u2(attributes) {
+                if is_utf8(code_attribute, "StackMapTable") {
+                    // behind number_of_entries
+                    stack_map_frames = Some(code_attribute + 8);
+                }
+                code_attribute += 6 + u4(code_attribute + 2);
+            }
+            return MethodOffsets {
+                max_stack,
+                code,
+                stack_map_frames,
+            };
+        }
+        attribute += 6 + u4(attribute + 2);
+    }
+    panic!("method {} has no code", name);
+}
+
+/// Returns the offset behind the attributes
+/// whose `attributes_count` is at `offset`.
+fn skip_attributes(bytes: &[u8], mut offset: usize) -> usize {
+    let count = u16::from_be_bytes([bytes[offset], bytes[offset + 1]]);
+    offset += 2;
+    for _ in 0..count {
+        let length = u32::from_be_bytes(
+            bytes[offset + 2..offset + 6].try_into().unwrap(),
+        );
+        offset += 6 + length as usize;
+    }
+    offset
+}
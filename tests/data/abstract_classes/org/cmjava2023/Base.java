@@ -0,0 +1,6 @@
+package org.cmjava2023;
+
+// recompiled after Main and Incomplete, which don't expect it to be abstract
+public abstract class Base {
+    abstract int value();
+}
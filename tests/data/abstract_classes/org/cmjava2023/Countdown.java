@@ -0,0 +1,21 @@
+package org.cmjava2023;
+
+import java.io.InputStream;
+
+public class Countdown extends InputStream {
+    private int remaining;
+
+    Countdown(int remaining) {
+        this.remaining = remaining;
+    }
+
+    @Override
+    public int read() {
+        if (remaining == 0) {
+            return -1;
+        }
+        int value = remaining;
+        remaining = value - 1;
+        return value;
+    }
+}
@@ -0,0 +1,5 @@
+package org.cmjava2023;
+
+// compiled against a Base implementing value()
+public class Incomplete extends Base {
+}
@@ -0,0 +1,35 @@
+package org.cmjava2023;
+
+import java.io.IOException;
+import java.io.InputStream;
+
+// Main and Incomplete are compiled against a Base,
+// which is neither abstract nor has abstract methods
+public class Main {
+    static void drain(InputStream in) throws IOException {
+        int b;
+        while ((b = in.read()) != -1) {
+            System.out.println(b);
+        }
+    }
+
+    public static void main(String[] args) throws IOException {
+        Shape[] shapes = { new Square(3), new Rectangle(2, 5) };
+        for (Shape shape : shapes) {
+            System.out.println(shape.describe());
+        }
+
+        drain(new Countdown(3));
+
+        try {
+            new Base();
+        } catch (InstantiationError e) {
+            System.out.println("InstantiationError " + e.getMessage());
+        }
+        try {
+            new Incomplete().value();
+        } catch (AbstractMethodError e) {
+            System.out.println("AbstractMethodError");
+        }
+    }
+}
@@ -0,0 +1,19 @@
+package org.cmjava2023;
+
+public class Rectangle extends Shape {
+    private final int width;
+    private final int height;
+
+    Rectangle(int width, int height) {
+        this.width = width;
+        this.height = height;
+    }
+
+    int area() {
+        return width * height;
+    }
+
+    String name() {
+        return "rectangle";
+    }
+}
@@ -0,0 +1,11 @@
+package org.cmjava2023;
+
+public abstract class Shape {
+    abstract int area();
+
+    abstract String name();
+
+    String describe() {
+        return name() + " with area " + area();
+    }
+}
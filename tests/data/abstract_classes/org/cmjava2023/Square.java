@@ -0,0 +1,17 @@
+package org.cmjava2023;
+
+public class Square extends Shape {
+    private final int side;
+
+    Square(int side) {
+        this.side = side;
+    }
+
+    int area() {
+        return side * side;
+    }
+
+    String name() {
+        return "square";
+    }
+}
@@ -0,0 +1,60 @@
+package org.cmjava2023;
+
+import org.cmjava2023.other.Vault;
+
+// Main is compiled against a Vault whose members are all public
+// and not final
+public class Main {
+    static int counter() {
+        return Vault.counter;
+    }
+
+    public static void main(String[] args) {
+        Vault vault = new Vault();
+        System.out.println(vault.greet());
+        System.out.println(Sub.describe());
+
+        try {
+            System.out.println(Vault.counter);
+        } catch (IllegalAccessError e) {
+            System.out.println("private static field");
+        }
+        try {
+            System.out.println(vault.secret);
+        } catch (IllegalAccessError e) {
+            System.out.println("package-private field");
+        }
+        try {
+            System.out.println(Vault.shared);
+        } catch (IllegalAccessError e) {
+            System.out.println("protected field");
+        }
+        try {
+            Vault.limit = 5;
+        } catch (IllegalAccessError e) {
+            System.out.println("final static field");
+        }
+        try {
+            vault.id = 8;
+        } catch (IllegalAccessError e) {
+            System.out.println("final field");
+        }
+        try {
+            System.out.println(Vault.open());
+        } catch (IllegalAccessError e) {
+            System.out.println("package-private method");
+        }
+        try {
+            System.out.println(vault.peek());
+        } catch (IllegalAccessError e) {
+            System.out.println("private method");
+        }
+        System.out.println(Vault.limit + " " + vault.id);
+        System.out.println(Sub.protectedMembers(new Sub()));
+        try {
+            System.out.println(Sub.reflectProtectedField(new Sub(), vault));
+        } catch (Exception e) {
+            System.out.println(e);
+        }
+    }
+}
@@ -0,0 +1,11 @@
+package org.cmjava2023;
+
+import org.cmjava2023.other.Vault;
+
+// compiled against a Vault whose protected instance members are public;
+// protected instance members may only be accessed on a ProtectedCall
+public class ProtectedCall extends Vault {
+    public static String access(Vault vault) {
+        return vault.whisper();
+    }
+}
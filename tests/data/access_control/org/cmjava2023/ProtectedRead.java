@@ -0,0 +1,11 @@
+package org.cmjava2023;
+
+import org.cmjava2023.other.Vault;
+
+// compiled against a Vault whose protected instance members are public;
+// protected instance members may only be accessed on a ProtectedRead
+public class ProtectedRead extends Vault {
+    public static int access(Vault vault) {
+        return vault.balance;
+    }
+}
@@ -0,0 +1,11 @@
+package org.cmjava2023;
+
+import org.cmjava2023.other.Vault;
+
+// compiled against a Vault whose protected instance members are public;
+// protected instance members may only be accessed on a ProtectedWrite
+public class ProtectedWrite extends Vault {
+    public static void access(Vault vault) {
+        vault.balance = 1;
+    }
+}
@@ -0,0 +1,32 @@
+package org.cmjava2023;
+
+import java.lang.reflect.Field;
+import org.cmjava2023.other.Vault;
+
+public class Sub extends Vault {
+    public static String describe() {
+        return Vault.inherited() + " " + Vault.shared;
+    }
+
+    public static String protectedMembers(Sub sub) {
+        sub.balance = 12;
+        return sub.balance + " " + sub.whisper();
+    }
+
+    // protected instance fields may only be accessed on a Sub
+    public static String reflectProtectedField(Sub sub, Vault vault)
+            throws Exception {
+        for (Field field : Vault.class.getDeclaredFields()) {
+            if (field.getName().equals("balance")) {
+                String result = String.valueOf(field.get(sub));
+                try {
+                    field.get(vault);
+                } catch (IllegalAccessException e) {
+                    result += "\n" + e.getMessage();
+                }
+                return result;
+            }
+        }
+        return "no balance";
+    }
+}
@@ -0,0 +1,40 @@
+package org.cmjava2023.other;
+
+// Main is compiled against a version of this class
+// whose members are all public and not final
+public class Vault {
+    private static int counter = 1;
+    public static final int limit;
+    protected static int shared = 4;
+    int secret = 42;
+    protected int balance = 10;
+    public final int id;
+
+    static {
+        limit = 3;
+    }
+
+    public Vault() {
+        id = 7;
+    }
+
+    static String open() {
+        return "open";
+    }
+
+    private String peek() {
+        return "peek";
+    }
+
+    protected String whisper() {
+        return "whisper";
+    }
+
+    protected static String inherited() {
+        return "inherited";
+    }
+
+    public String greet() {
+        return "hello from the vault";
+    }
+}
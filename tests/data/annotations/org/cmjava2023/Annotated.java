@@ -0,0 +1,31 @@
+package org.cmjava2023;
+
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+
+@Retention(RetentionPolicy.RUNTIME)
+@interface Values {
+    byte b();
+
+    char c();
+
+    short s();
+
+    long j();
+
+    float f();
+
+    Level level();
+
+    Class<?>[] types();
+}
+
+@Values(b = 1, c = 'x', s = -2, j = 3L, f = 1.5f, level = Level.WARN, types = {int.class, String[].class})
+public class Annotated {
+    @Handler("field")
+    public int field;
+
+    @Handler(value = "method", priority = 1)
+    public void handle(@Handler("parameter") String request, int count) {
+    }
+}
@@ -0,0 +1,26 @@
+package org.cmjava2023;
+
+import java.lang.annotation.ElementType;
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+import java.lang.annotation.Target;
+
+@Retention(RetentionPolicy.RUNTIME)
+@Target({ElementType.TYPE, ElementType.METHOD, ElementType.FIELD, ElementType.PARAMETER})
+public @interface Handler {
+    String value();
+
+    int priority() default 5;
+
+    boolean enabled() default true;
+
+    double ratio() default 0.5;
+
+    String[] tags() default {};
+
+    int[] codes() default {1, 2};
+
+    Class<?> target() default Object.class;
+
+    Route route() default @Route(path = "/");
+}
@@ -0,0 +1,6 @@
+package org.cmjava2023;
+
+public enum Level {
+    INFO,
+    WARN
+}
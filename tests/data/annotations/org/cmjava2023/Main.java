@@ -0,0 +1,23 @@
+package org.cmjava2023;
+
+@Handler(value = "main", tags = {"a", "b"}, target = String.class, route = @Route(path = "/main"))
+@Note("invisible")
+public class Main {
+    public static void main(String[] args) {
+        Handler handler = Main.class.getAnnotation(Handler.class);
+        System.out.println(handler.value());
+        System.out.println(handler.priority());
+        System.out.println(handler.enabled());
+        System.out.println(handler.ratio());
+        String[] tags = handler.tags();
+        System.out.println(tags.length);
+        System.out.println(tags[1]);
+        System.out.println(handler.codes()[1]);
+        System.out.println(handler.target().getName());
+        System.out.println(handler.route().path());
+        System.out.println(handler.annotationType().getName());
+        System.out.println(Main.class.isAnnotationPresent(Handler.class));
+        System.out.println(Main.class.isAnnotationPresent(Note.class));
+        System.out.println(Route.class.getAnnotation(Handler.class) == null);
+    }
+}
@@ -0,0 +1,6 @@
+package org.cmjava2023;
+
+/** Not retained at runtime. */
+public @interface Note {
+    String value();
+}
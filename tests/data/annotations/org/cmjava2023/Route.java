@@ -0,0 +1,11 @@
+package org.cmjava2023;
+
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+
+@Retention(RetentionPolicy.RUNTIME)
+public @interface Route {
+    String path();
+
+    Level level() default Level.INFO;
+}
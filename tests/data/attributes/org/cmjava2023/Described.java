@@ -0,0 +1,26 @@
+package org.cmjava2023;
+
+public class Described<T> {
+    public static final int ANSWER = 42;
+    public static final String GREETING = "hello";
+    public static final boolean ENABLED = true;
+    public T value;
+
+    public static class Inner {
+    }
+
+    @Deprecated
+    public static int twice(int number) {
+        int result = number * 2;
+        return result;
+    }
+
+    public static <E> E first(E[] values) throws Exception {
+        return values[0];
+    }
+
+    public static void local() {
+        class Local {
+        }
+    }
+}
@@ -0,0 +1,7 @@
+package org.cmjava2023;
+
+public class Broken {
+    public String toString() {
+        throw new RuntimeException("broken toString");
+    }
+}
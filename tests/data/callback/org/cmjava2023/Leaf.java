@@ -0,0 +1,7 @@
+package org.cmjava2023;
+
+public class Leaf {
+    public String toString() {
+        return "leaf";
+    }
+}
@@ -0,0 +1,21 @@
+package org.cmjava2023;
+
+public class Main {
+    public static void main(String[] args) {
+        // builtin -> java -> builtin -> java -> ...
+        System.out.println(new Wrapper(new Wrapper(new Leaf())));
+
+        try {
+            System.out.println(new Broken());
+        } catch (RuntimeException e) {
+            System.out.println("caught: " + e.getMessage());
+        }
+
+        try {
+            System.out.println(new Wrapper(new Wrapper(new Broken())));
+        } catch (RuntimeException e) {
+            System.out.println("caught nested: " + e.getMessage());
+        }
+        System.out.println("done");
+    }
+}
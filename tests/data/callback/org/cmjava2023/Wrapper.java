@@ -0,0 +1,13 @@
+package org.cmjava2023;
+
+public class Wrapper {
+    private Object inner;
+
+    public Wrapper(Object inner) {
+        this.inner = inner;
+    }
+
+    public String toString() {
+        return "[" + inner + "]";
+    }
+}
@@ -0,0 +1,7 @@
+package org.cmjava2023;
+
+public class Main {
+    public static void main(String[] args) {
+        System.out.println("Hello World");
+    }
+}
@@ -0,0 +1,7 @@
+package org.cmjava2023;
+
+public class Api {
+    public static String describe(String version) {
+        return "plugin " + version;
+    }
+}
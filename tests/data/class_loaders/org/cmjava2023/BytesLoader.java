@@ -0,0 +1,20 @@
+package org.cmjava2023;
+
+public class BytesLoader extends ClassLoader {
+    private byte[] bytes;
+    private int length;
+
+    public BytesLoader(byte[] bytes, int length) {
+        this.bytes = bytes;
+        this.length = length;
+    }
+
+    protected Class findClass(String name) throws ClassNotFoundException {
+        try {
+            return defineClass(name, bytes, 0, length);
+        } catch (NoClassDefFoundError e) {
+            // the bytes contain another class
+            throw new ClassNotFoundException(name);
+        }
+    }
+}
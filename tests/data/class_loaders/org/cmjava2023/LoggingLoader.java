@@ -0,0 +1,12 @@
+package org.cmjava2023;
+
+public class LoggingLoader extends BytesLoader {
+    public LoggingLoader(byte[] bytes, int length) {
+        super(bytes, length);
+    }
+
+    public Class loadClass(String name) throws ClassNotFoundException {
+        System.out.println("loading " + name);
+        return super.loadClass(name);
+    }
+}
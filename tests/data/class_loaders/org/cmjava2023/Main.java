@@ -0,0 +1,35 @@
+package org.cmjava2023;
+
+public class Main {
+    public static void main(String[] args) throws Exception {
+        // the class file of Plugin is passed via stdin
+        byte[] buffer = new byte[4096];
+        int length = 0;
+        int b = System.in.read();
+        while (b != -1) {
+            buffer[length] = (byte) b;
+            length++;
+            b = System.in.read();
+        }
+
+        ClassLoader first = new BytesLoader(buffer, length);
+        ClassLoader second = new LoggingLoader(buffer, length);
+
+        Class plugin = first.loadClass("org.cmjava2023.Plugin");
+        System.out.println(plugin.getName());
+        System.out.println(plugin == first.loadClass("org.cmjava2023.Plugin"));
+        Class otherPlugin = second.loadClass("org.cmjava2023.Plugin");
+        System.out.println(plugin == otherPlugin);
+
+        // both loaders delegate to the application class loader
+        System.out.println(
+            first.loadClass("org.cmjava2023.Api")
+                == second.loadClass("org.cmjava2023.Api"));
+
+        try {
+            first.loadClass("org.cmjava2023.Missing");
+        } catch (ClassNotFoundException e) {
+            System.out.println("caught: " + e.getMessage());
+        }
+    }
+}
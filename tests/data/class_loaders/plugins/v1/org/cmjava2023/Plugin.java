@@ -0,0 +1,7 @@
+package org.cmjava2023;
+
+public class Plugin {
+    public static String name() {
+        return Api.describe("v1");
+    }
+}
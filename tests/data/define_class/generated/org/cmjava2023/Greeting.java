@@ -0,0 +1,7 @@
+package org.cmjava2023;
+
+public class Greeting {
+    public static String hello() {
+        return "hello from a defined class";
+    }
+}
@@ -0,0 +1,15 @@
+package org.cmjava2023;
+
+public class ByteLoader extends ClassLoader {
+    public Class define(String name, byte[] bytes, int length) {
+        return defineClass(name, bytes, 0, length);
+    }
+
+    public Class define(byte[] bytes, int offset, int length) {
+        return defineClass(null, bytes, offset, length);
+    }
+
+    public Class find(String name) throws ClassNotFoundException {
+        return findClass(name);
+    }
+}
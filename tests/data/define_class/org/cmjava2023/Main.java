@@ -0,0 +1,67 @@
+package org.cmjava2023;
+
+public class Main {
+    public static void main(String[] args) throws java.io.IOException {
+        // the class file of Greeting is passed via stdin
+        byte[] buffer = new byte[4096];
+        int length = 0;
+        int b = System.in.read();
+        while (b != -1) {
+            buffer[length] = (byte) b;
+            length++;
+            b = System.in.read();
+        }
+
+        ByteLoader loader = new ByteLoader();
+        Class greeting = loader.define("org.cmjava2023.Greeting", buffer, length);
+        System.out.println(greeting.getName());
+        // Greeting is defined by loader, Main's loader can't resolve it
+        try {
+            System.out.println(Greeting.hello());
+        } catch (NoClassDefFoundError e) {
+            System.out.println("caught: " + e.getMessage());
+        }
+
+        try {
+            loader.define("org.cmjava2023.Greeting", buffer, length);
+        } catch (LinkageError e) {
+            System.out.println("caught: " + e.getMessage());
+        }
+        try {
+            loader.define("org.cmjava2023.Other", buffer, length);
+        } catch (NoClassDefFoundError e) {
+            System.out.println("caught: " + e.getMessage());
+        }
+        try {
+            loader.define(null, buffer, 10);
+        } catch (ClassFormatError e) {
+            System.out.println("caught ClassFormatError");
+        }
+
+        try {
+            loader.define(null, 0, 0);
+        } catch (NullPointerException e) {
+            System.out.println("caught NullPointerException");
+        }
+        int[][] ranges = {{-1, 1}, {0, -1}, {4000, 100}};
+        for (int[] range : ranges) {
+            try {
+                loader.define(buffer, range[0], range[1]);
+            } catch (IndexOutOfBoundsException e) {
+                System.out.println("caught IndexOutOfBoundsException");
+            }
+        }
+        try {
+            loader.loadClass(null);
+        } catch (NullPointerException e) {
+            System.out.println("loadClass: caught NullPointerException");
+        } catch (ClassNotFoundException e) {
+            System.out.println("loadClass: caught ClassNotFoundException");
+        }
+        try {
+            loader.find(null);
+        } catch (ClassNotFoundException e) {
+            System.out.println("findClass: " + e.getMessage());
+        }
+    }
+}
@@ -0,0 +1,17 @@
+package org.cmjava2023;
+
+import java.io.IOException;
+
+public interface Calculator extends Identified {
+    int add(int a, int b);
+
+    double scale(double value, long factor);
+
+    boolean isDigit(char c);
+
+    String describe();
+
+    void log(String message);
+
+    void save() throws IOException;
+}
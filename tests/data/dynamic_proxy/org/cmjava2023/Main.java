@@ -0,0 +1,83 @@
+package org.cmjava2023;
+
+import java.io.IOException;
+import java.lang.reflect.InvocationHandler;
+import java.lang.reflect.Proxy;
+import java.lang.reflect.UndeclaredThrowableException;
+
+public class Main {
+    public static void main(String[] args) {
+        ClassLoader loader = Main.class.getClassLoader();
+        TracingHandler tracing = new TracingHandler(new SimpleCalculator());
+        Calculator calculator = (Calculator) Proxy.newProxyInstance(loader,
+                new Class<?>[] { Calculator.class }, tracing);
+        System.out.println(calculator.add(2, 3));
+        System.out.println(calculator.scale(1.5, 3L));
+        System.out.println(calculator.isDigit('7'));
+        System.out.println(calculator.describe());
+        System.out.println(calculator.id());
+        calculator.log("hello");
+        try {
+            calculator.save();
+        } catch (IOException e) {
+            System.out.println("IOException: " + e.getMessage());
+        }
+
+        System.out.println(Proxy.isProxyClass(calculator.getClass()));
+        System.out.println(Proxy.isProxyClass(SimpleCalculator.class));
+        System.out.println(Proxy.getInvocationHandler(calculator) == tracing);
+        System.out.println(calculator.getClass().getInterfaces()[0].getName());
+        System.out.println(calculator.getClass().getSuperclass().getName());
+        Object other = Proxy.newProxyInstance(loader,
+                new Class<?>[] { Calculator.class }, new RemoteHandler());
+        System.out.println(other.getClass() == calculator.getClass());
+
+        Object remote = Proxy.newProxyInstance(loader,
+                new Class<?>[] { Named.class, Calculator.class },
+                new RemoteHandler());
+        Named named = (Named) remote;
+        System.out.println(named.greeting());
+        System.out.println(remote instanceof Calculator);
+        System.out.println(remote instanceof Identified);
+        System.out.println(remote.getClass() == calculator.getClass());
+        System.out.println(remote);
+        System.out.println(remote.hashCode());
+        System.out.println(remote.equals(remote));
+        System.out.println(remote.equals(calculator));
+
+        Calculator broken = (Calculator) remote;
+        try {
+            broken.id();
+        } catch (NullPointerException e) {
+            System.out.println("NullPointerException");
+        }
+        try {
+            broken.add(1, 2);
+        } catch (ClassCastException e) {
+            System.out.println("ClassCastException");
+        }
+        try {
+            broken.describe();
+        } catch (UndeclaredThrowableException e) {
+            System.out.println("UndeclaredThrowableException: "
+                    + e.getCause().getMessage());
+        }
+        try {
+            broken.log("ignored");
+        } catch (IllegalArgumentException e) {
+            System.out.println("IllegalArgumentException: " + e.getMessage());
+        }
+
+        try {
+            Proxy.newProxyInstance(loader, new Class<?>[] { String.class },
+                    tracing);
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            Proxy.getInvocationHandler(tracing);
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+    }
+}
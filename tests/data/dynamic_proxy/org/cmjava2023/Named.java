@@ -0,0 +1,9 @@
+package org.cmjava2023;
+
+public interface Named {
+    String name();
+
+    default String greeting() {
+        return "Hello, " + name();
+    }
+}
@@ -0,0 +1,31 @@
+package org.cmjava2023;
+
+import java.lang.reflect.InvocationHandler;
+import java.lang.reflect.Method;
+
+public class RemoteHandler implements InvocationHandler {
+    public Object invoke(Object proxy, Method method, Object[] args)
+            throws Throwable {
+        switch (method.getName()) {
+            case "name":
+                return "remote";
+            case "toString":
+                return "RemoteProxy";
+            case "hashCode":
+                return 42;
+            case "equals":
+                return proxy == args[0];
+            case "id":
+                return null;
+            case "add":
+                return "three";
+            case "describe":
+                throw new Exception("connection refused");
+            case "log":
+                throw new IllegalArgumentException("not connected");
+            default:
+                System.out.println("unexpected " + method.getName());
+                return null;
+        }
+    }
+}
@@ -0,0 +1,33 @@
+package org.cmjava2023;
+
+import java.io.IOException;
+
+public class SimpleCalculator implements Calculator {
+    public int id() {
+        return 7;
+    }
+
+    public int add(int a, int b) {
+        return a + b;
+    }
+
+    public double scale(double value, long factor) {
+        return value * factor;
+    }
+
+    public boolean isDigit(char c) {
+        return c >= '0' && c <= '9';
+    }
+
+    public String describe() {
+        return "simple calculator";
+    }
+
+    public void log(String message) {
+        System.out.println("log: " + message);
+    }
+
+    public void save() throws IOException {
+        throw new IOException("disk full");
+    }
+}
@@ -0,0 +1,29 @@
+package org.cmjava2023;
+
+import java.lang.reflect.InvocationHandler;
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Method;
+
+public class TracingHandler implements InvocationHandler {
+    private final Object target;
+
+    public TracingHandler(Object target) {
+        this.target = target;
+    }
+
+    public Object invoke(Object proxy, Method method, Object[] args)
+            throws Throwable {
+        System.out.println("-> " + method.getDeclaringClass().getSimpleName()
+                + "." + method.getName());
+        if (args != null) {
+            for (Object arg : args) {
+                System.out.println("arg: " + arg.getClass().getName() + " " + arg);
+            }
+        }
+        try {
+            return method.invoke(target, args);
+        } catch (InvocationTargetException e) {
+            throw e.getCause();
+        }
+    }
+}
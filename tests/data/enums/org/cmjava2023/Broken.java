@@ -0,0 +1,17 @@
+package org.cmjava2023;
+
+public class Broken {
+    static int value = 1;
+
+    static {
+        fail();
+    }
+
+    static void fail() {
+        throw new IllegalArgumentException("broken");
+    }
+
+    public static int value() {
+        return value;
+    }
+}
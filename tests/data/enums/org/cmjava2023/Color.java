@@ -0,0 +1,22 @@
+package org.cmjava2023;
+
+public enum Color {
+    RED("#f00"),
+    GREEN("#0f0"),
+    BLUE("#00f") {
+        @Override
+        public String describe() {
+            return "blue " + super.describe();
+        }
+    };
+
+    final String hex;
+
+    Color(String hex) {
+        this.hex = hex;
+    }
+
+    public String describe() {
+        return hex;
+    }
+}
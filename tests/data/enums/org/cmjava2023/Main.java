@@ -0,0 +1,67 @@
+package org.cmjava2023;
+
+public class Main {
+    public static void main(String[] args) {
+        for (Color color : Color.values()) {
+            System.out.println(color);
+            System.out.println(color.ordinal());
+            System.out.println(color.describe());
+            System.out.println(temperature(color));
+        }
+
+        Color green = Color.valueOf("GREEN");
+        System.out.println(green.name());
+        System.out.println(green == Color.GREEN);
+        System.out.println(Color.RED.compareTo(Color.BLUE));
+        System.out.println(Color.BLUE.getDeclaringClass().getName());
+        try {
+            Color.valueOf("PURPLE");
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            Color.valueOf(null);
+        } catch (NullPointerException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            Enum.valueOf((Class<Color>) null, "RED");
+        } catch (NullPointerException e) {
+            System.out.println("valueOf of null class");
+        }
+        try {
+            Color.RED.compareTo(null);
+        } catch (NullPointerException e) {
+            System.out.println("compareTo null");
+        }
+
+        System.out.println(size(10));
+        System.out.println(size(1000));
+        System.out.println(size(7));
+    }
+
+    static String temperature(Color color) {
+        switch (color) {
+            case RED:
+                return "warm";
+            case GREEN:
+                return "mild";
+            case BLUE:
+                return "cold";
+            default:
+                return "neutral";
+        }
+    }
+
+    static String size(int n) {
+        switch (n) {
+            case 1:
+            case 10:
+                return "small";
+            case 1000:
+                return "large";
+            default:
+                return "unknown";
+        }
+    }
+}
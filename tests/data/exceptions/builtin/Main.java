@@ -0,0 +1,18 @@
+import java.io.IOException;
+
+public class Main {
+    public static void main(String[] args) {
+        try {
+            throw new IOException("Oops");
+        } catch (Exception e) {
+            System.out.println("caught e:");
+            System.out.println(e.getMessage());
+        }
+        try {
+            throw new Exception();
+        } catch (Throwable t) {
+            System.out.println("caught t:");
+            System.out.println(t.getMessage());
+        }
+    }
+}
@@ -0,0 +1,11 @@
+package org.cmjava2023;
+
+public class Base implements Limits {
+    static int count = 1;
+    int size = 2;
+    String label = "base";
+
+    static {
+        System.out.println("Base initialized");
+    }
+}
@@ -0,0 +1,8 @@
+package org.cmjava2023;
+
+public class Defaults {
+    static int max() {
+        System.out.println("Limits initialized");
+        return 10;
+    }
+}
@@ -0,0 +1,6 @@
+package org.cmjava2023;
+
+public class Derived extends Base {
+    // hides the field of Base
+    String label = "derived";
+}
@@ -0,0 +1,7 @@
+package org.cmjava2023;
+
+// Main is compiled against a version of this class
+// where flag is an instance field
+public class Flags {
+    static int flag = 1;
+}
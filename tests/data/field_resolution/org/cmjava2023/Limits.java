@@ -0,0 +1,6 @@
+package org.cmjava2023;
+
+public interface Limits {
+    // not a constant, so that it is accessed with getstatic
+    int MAX = Defaults.max();
+}
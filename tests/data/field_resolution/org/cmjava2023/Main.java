@@ -0,0 +1,24 @@
+package org.cmjava2023;
+
+public class Main {
+    static int flag() {
+        return new Flags().flag;
+    }
+
+    public static void main(String[] args) {
+        // only initializes Limits, which declares MAX
+        System.out.println(Derived.MAX);
+        System.out.println(Derived.count);
+        Derived.count = 5;
+        System.out.println(Base.count);
+
+        Derived derived = new Derived();
+        System.out.println(derived.size);
+        derived.size = 3;
+        System.out.println(((Base) derived).size);
+        System.out.println(derived.label);
+        System.out.println(((Base) derived).label);
+        ((Base) derived).label = "changed";
+        System.out.println(derived.label + " " + ((Base) derived).label);
+    }
+}
@@ -0,0 +1,10 @@
+package org.cmjava2023;
+
+public class Main {
+    public static void main(String[] args) {
+        System.out.println(args.length);
+        for (int i = 0; i < args.length; i++) {
+            System.out.println(Greeter.greet(args[i]));
+        }
+    }
+}
@@ -0,0 +1,7 @@
+package org.cmjava2023;
+
+public class Greeter {
+    public static String greet(String name) {
+        return "hello, " + name;
+    }
+}
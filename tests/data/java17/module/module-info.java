@@ -0,0 +1,3 @@
+module org.cmjava {
+    exports org.cmjava2023;
+}
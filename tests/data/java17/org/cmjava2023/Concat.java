@@ -0,0 +1,24 @@
+package org.cmjava2023;
+
+public class Concat {
+    public static void main(String[] args) {
+        int i = 42;
+        long l = 7L;
+        char c = 'x';
+        boolean b = true;
+        short s = -3;
+        Object nothing = null;
+        String string = "text";
+        float f = 1.0f;
+        double d = -0.0;
+        double large = 1.0e7;
+        System.out.println("int " + i + ", long " + l + ", char " + c);
+        System.out.println("boolean " + b + ", short " + s);
+        System.out.println("null " + nothing + ", string " + string);
+        System.out.println(new Point(1, 2) + "!");
+        // literals containing the recipe tags are passed as constants
+        System.out.println("\u0001" + i + "\u0002");
+        System.out.println(i + l);
+        System.out.println("float " + f + ", double " + d + ", large " + large);
+    }
+}
@@ -0,0 +1,44 @@
+package org.cmjava2023;
+
+public class Nest {
+    private int secret = 42;
+
+    private String describe() {
+        return "outer " + secret;
+    }
+
+    public String call() {
+        return describe();
+    }
+
+    static class Inner {
+        private final Nest outer;
+
+        Inner(Nest outer) {
+            this.outer = outer;
+        }
+
+        private int peek() {
+            return outer.secret;
+        }
+
+        String describe() {
+            return outer.describe();
+        }
+    }
+
+    static class Sub extends Nest {
+        // doesn't override Nest.describe, which is private
+        private String describe() {
+            return "sub";
+        }
+    }
+
+    public static void main(String[] args) {
+        Nest nest = new Nest();
+        Inner inner = new Inner(nest);
+        System.out.println(inner.peek());
+        System.out.println(inner.describe());
+        System.out.println(new Sub().call());
+    }
+}
@@ -0,0 +1,35 @@
+package org.cmjava2023;
+
+public class Main {
+    public static void main(String[] args) {
+        System.out.println(args.length);
+        for (int i = 0; i < args.length; i++) {
+            System.out.println(args[i]);
+        }
+        System.out.println(System.getProperty("greeting"));
+        try {
+            System.getProperty(null);
+        } catch (NullPointerException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            System.getProperty(null, "fallback");
+        } catch (NullPointerException e) {
+            System.out.println(e.getMessage());
+        }
+        System.out.println(System.getProperty("missing", "fallback"));
+        System.out.println(System.getProperty("missing"));
+        try {
+            if (args.length > 2) {
+                System.exit(args.length);
+            }
+        } catch (Throwable t) {
+            System.out.println("caught exit");
+        } finally {
+            System.out.println("finally");
+        }
+        if (args.length == 1) {
+            throw new IllegalArgumentException("unexpected " + args[0]);
+        }
+    }
+}
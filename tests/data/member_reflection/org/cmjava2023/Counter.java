@@ -0,0 +1,49 @@
+package org.cmjava2023;
+
+public class Counter {
+    public static int created;
+
+    private int count;
+    public final String name;
+    long total;
+
+    public Counter() {
+        this("default");
+    }
+
+    public Counter(String name) {
+        this.name = name;
+        created++;
+    }
+
+    private Counter(int count) {
+        this("private");
+        this.count = count;
+    }
+
+    public int add(int amount) {
+        count += amount;
+        total += amount;
+        return count;
+    }
+
+    public static long twice(long value) {
+        return value * 2;
+    }
+
+    private String secret() {
+        return "secret of " + name;
+    }
+
+    public void fail(String message) {
+        throw new CounterException(message);
+    }
+
+    public boolean isEmpty() {
+        return count == 0;
+    }
+
+    public double half() {
+        return count / 2.0;
+    }
+}
@@ -0,0 +1,7 @@
+package org.cmjava2023;
+
+public class CounterException extends RuntimeException {
+    public CounterException(String message) {
+        super(message);
+    }
+}
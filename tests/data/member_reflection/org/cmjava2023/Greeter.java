@@ -0,0 +1,5 @@
+package org.cmjava2023;
+
+public interface Greeter {
+    String greet(String who);
+}
@@ -0,0 +1,184 @@
+package org.cmjava2023;
+
+import java.lang.reflect.Constructor;
+import java.lang.reflect.Field;
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Method;
+
+public class Main {
+    static Method method(Class<?> c, String name) {
+        for (Method m : c.getDeclaredMethods()) {
+            if (m.getName().equals(name)) {
+                return m;
+            }
+        }
+        return null;
+    }
+
+    static Field field(Class<?> c, String name) {
+        for (Field f : c.getDeclaredFields()) {
+            if (f.getName().equals(name)) {
+                return f;
+            }
+        }
+        return null;
+    }
+
+    public static void main(String[] args) throws Exception {
+        Class<?> c = Counter.class;
+        System.out.println(c.getDeclaredMethods().length);
+        System.out.println(c.getDeclaredFields().length);
+        System.out.println(c.getDeclaredConstructors().length);
+
+        // constructors
+        Constructor<?> constructor = null;
+        for (Constructor<?> k : c.getDeclaredConstructors()) {
+            if (k.getParameterCount() == 1
+                    && k.getParameterTypes()[0] == String.class) {
+                constructor = k;
+            }
+        }
+        System.out.println(constructor.getName());
+        System.out.println(constructor.getModifiers());
+        Object counter = constructor.newInstance("reflective");
+
+        // methods, boxing and unboxing
+        Method add = method(c, "add");
+        System.out.println(add.getName());
+        System.out.println(add.getDeclaringClass());
+        System.out.println(add.getModifiers());
+        System.out.println(add.getReturnType());
+        System.out.println(add.getParameterTypes()[0] == int.class);
+        System.out.println(add.invoke(counter, 5));
+        System.out.println(add.invoke(counter, (byte) 2));
+        System.out.println(method(c, "twice").getModifiers());
+        System.out.println(method(c, "twice").invoke(null, 21));
+        System.out.println(method(c, "isEmpty").invoke(counter));
+        System.out.println(method(c, "half").invoke(counter));
+        System.out.println(method(c, "fail").getReturnType());
+        System.out.println(add.invoke(new SpecialCounter(), 1));
+
+        Method greet = method(Greeter.class, "greet");
+        System.out.println(greet.getModifiers());
+        System.out.println(greet.invoke(new PoliteGreeter(), "Ada"));
+
+        // exceptions
+        try {
+            method(c, "fail").invoke(counter, "broken");
+        } catch (InvocationTargetException e) {
+            System.out.println(e.getCause().getClass().getName());
+            System.out.println(e.getCause().getMessage());
+        }
+        try {
+            add.invoke(counter, "one");
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            add.invoke(counter, 1L);
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            add.invoke(counter);
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            add.invoke("no counter", 1);
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            add.invoke(null, 1);
+        } catch (NullPointerException e) {
+            System.out.println("NullPointerException");
+        }
+        try {
+            Shape.class.getDeclaredConstructors()[0].newInstance();
+        } catch (InstantiationException e) {
+            System.out.println("InstantiationException");
+        }
+
+        // access checks
+        Method secret = method(c, "secret");
+        try {
+            secret.invoke(counter);
+        } catch (IllegalAccessException e) {
+            System.out.println(e.getMessage());
+        }
+        secret.setAccessible(true);
+        System.out.println(secret.invoke(counter));
+
+        Class<?> hidden = Class.forName("org.cmjava2023.other.Hidden");
+        Constructor<?> hiddenConstructor = hidden.getDeclaredConstructors()[0];
+        try {
+            hiddenConstructor.newInstance();
+        } catch (IllegalAccessException e) {
+            System.out.println(e.getMessage());
+        }
+        hiddenConstructor.setAccessible(true);
+        Object hiddenObject = hiddenConstructor.newInstance();
+        Method reveal = method(hidden, "reveal");
+        reveal.setAccessible(true);
+        System.out.println(reveal.invoke(hiddenObject));
+
+        // fields
+        Field count = field(c, "count");
+        System.out.println(count.getModifiers());
+        System.out.println(count.getType());
+        try {
+            count.get(counter);
+        } catch (IllegalAccessException e) {
+            System.out.println(e.getMessage());
+        }
+        count.setAccessible(true);
+        System.out.println(count.get(counter));
+        count.set(counter, 40);
+        System.out.println(add.invoke(counter, 2));
+        try {
+            count.set(counter, "many");
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+        try {
+            count.get("no counter");
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+
+        Field total = field(c, "total");
+        System.out.println(total.get(counter));
+        total.set(counter, 5);
+        System.out.println(total.get(counter));
+
+        Field name = field(c, "name");
+        System.out.println(name.getType());
+        System.out.println(name.get(counter));
+        try {
+            name.set(counter, "renamed");
+        } catch (IllegalAccessException e) {
+            System.out.println(e.getMessage());
+        }
+        name.setAccessible(true);
+        name.set(counter, "renamed");
+        System.out.println(name.get(counter));
+
+        Field created = field(c, "created");
+        System.out.println(created.getModifiers());
+        System.out.println(created.get(null));
+        created.set(null, 10);
+        System.out.println(Counter.created);
+
+        // boxes
+        Integer boxed = 41;
+        int unboxed = boxed + 1;
+        System.out.println(unboxed);
+        System.out.println(boxed.equals(41));
+        System.out.println(boxed.equals(41L));
+        System.out.println(Long.valueOf(7).hashCode());
+        System.out.println(Boolean.TRUE.hashCode());
+        System.out.println(Character.valueOf('x'));
+        System.out.println(Integer.TYPE);
+    }
+}
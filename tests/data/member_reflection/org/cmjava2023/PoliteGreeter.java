@@ -0,0 +1,7 @@
+package org.cmjava2023;
+
+public class PoliteGreeter implements Greeter {
+    public String greet(String who) {
+        return "Good day, " + who;
+    }
+}
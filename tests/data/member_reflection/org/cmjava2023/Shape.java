@@ -0,0 +1,6 @@
+package org.cmjava2023;
+
+public abstract class Shape {
+    public Shape() {
+    }
+}
@@ -0,0 +1,8 @@
+package org.cmjava2023;
+
+public class SpecialCounter extends Counter {
+    @Override
+    public int add(int amount) {
+        return super.add(amount * 100);
+    }
+}
@@ -0,0 +1,10 @@
+package org.cmjava2023.other;
+
+class Hidden {
+    Hidden() {
+    }
+
+    String reveal() {
+        return "revealed";
+    }
+}
@@ -0,0 +1,41 @@
+package org.cmjava2023;
+
+// The casts in store() are removed from the class file
+// and the calls of setFlag() are patched to pass 2, 3 and 4,
+// so that the fields are assigned ints which have to be narrowed.
+public class Main {
+    static byte b;
+    static short s;
+    char c;
+    boolean flag;
+
+    static void store(Main main, int v) {
+        b = (byte) v;
+        s = (short) v;
+        main.c = (char) v;
+    }
+
+    void setFlag(boolean v) {
+        flag = v;
+    }
+
+    static void print(Main main) {
+        System.out.println(b);
+        System.out.println(s);
+        System.out.println((int) main.c);
+        System.out.println(main.flag);
+    }
+
+    public static void main(String[] args) {
+        Main main = new Main();
+        store(main, 0x12345);
+        main.setFlag(true);
+        print(main);
+        store(main, -1);
+        main.setFlag(true);
+        print(main);
+        store(main, 0x180);
+        main.setFlag(true);
+        print(main);
+    }
+}
@@ -0,0 +1,21 @@
+package org.cmjava2023;
+
+public class Main {
+    static native int add(int a, int b);
+
+    native long scale(long value, double factor);
+
+    static native void report(long value);
+
+    static native void unbound();
+
+    public static void main(String[] args) {
+        report(add(40, 2));
+        report(new Main().scale(21L, 3.0));
+        try {
+            unbound();
+        } catch (UnsatisfiedLinkError e) {
+            report(-1);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+package org.cmjava2023;
+
+// Compiled with JDK 17 and --enable-preview,
+// then the class file version was set to 65.0 (Java 21),
+// which uses the same bootstraps for these switches.
+
+public class Shapes {
+    interface Shape {}
+
+    record Circle(int radius) implements Shape {}
+
+    record Square(int side) implements Shape {}
+
+    record Triangle(int base) implements Shape {}
+
+    enum Color { RED, GREEN, BLUE }
+
+    static String describe(Object object) {
+        return switch (object) {
+            case null -> "nothing";
+            case Circle c && c.radius() > 10 -> "large circle " + c.radius();
+            case Circle c -> "circle " + c.radius();
+            case Square s -> "square " + s.side();
+            case Shape s -> "other shape";
+            case String s -> "string " + s;
+            default -> "unknown";
+        };
+    }
+
+    static String word(String word) {
+        return switch (word) {
+            case "one" -> "1";
+            case String s && s.equals("three") -> "guarded";
+            case "two" -> "2";
+            case String s -> "short";
+        };
+    }
+
+    static String color(Color color) {
+        return switch (color) {
+            case RED -> "warm";
+            case Color c && c.ordinal() > 1 -> "last";
+            case Color c -> "other";
+        };
+    }
+
+    public static void main(String[] args) {
+        System.out.println(describe(new Circle(20)));
+        System.out.println(describe(new Circle(5)));
+        System.out.println(describe(new Square(3)));
+        System.out.println(describe(new Triangle(4)));
+        System.out.println(describe("text"));
+        System.out.println(describe(new Object()));
+        System.out.println(describe(null));
+        System.out.println(word("one"));
+        System.out.println(word("two"));
+        System.out.println(word("three"));
+        System.out.println(word("six"));
+        System.out.println(color(Color.RED));
+        System.out.println(color(Color.GREEN));
+        System.out.println(color(Color.BLUE));
+    }
+}
@@ -0,0 +1,44 @@
+package org.cmjava2023;
+
+public class Main {
+    record Pair(Object first, Object second) {}
+
+    record Empty() {}
+
+    // a distinct string with the same characters
+    static String copy(String string) {
+        return string + "";
+    }
+
+    public static void main(String[] args) {
+        Range range = new Range(1, 10L, 'm', true, "depth");
+        System.out.println(range);
+        System.out.println(range.low());
+        System.out.println(range.label());
+        System.out.println(range.length());
+
+        Range same = new Range(1, 10L, 'm', true, copy("depth"));
+        Range other = new Range(1, 10L, 'm', false, "depth");
+        System.out.println(range.equals(same));
+        System.out.println(range.equals(other));
+        System.out.println(range.equals(null));
+        System.out.println(range.equals("depth"));
+        System.out.println(range.hashCode() == same.hashCode());
+        System.out.println(range.hashCode());
+
+        Pair pair = new Pair(range, null);
+        System.out.println(pair);
+        System.out.println(pair.equals(new Pair(same, null)));
+        System.out.println(pair.hashCode());
+
+        System.out.println(new Empty());
+        System.out.println(new Empty().equals(new Empty()));
+        System.out.println(new Empty().hashCode());
+
+        try {
+            new Range(2, 1L, 'm', false, null);
+        } catch (IllegalArgumentException e) {
+            System.out.println(e.getMessage());
+        }
+    }
+}
@@ -0,0 +1,13 @@
+package org.cmjava2023;
+
+public record Range(int low, long high, char unit, boolean open, String label) {
+    public Range {
+        if (low > high) {
+            throw new IllegalArgumentException("low > high");
+        }
+    }
+
+    public long length() {
+        return high - low;
+    }
+}
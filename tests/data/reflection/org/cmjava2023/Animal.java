@@ -0,0 +1,7 @@
+package org.cmjava2023;
+
+public class Animal implements Named {
+    public String name() {
+        return "animal";
+    }
+}
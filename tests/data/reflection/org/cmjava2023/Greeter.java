@@ -0,0 +1,4 @@
+package org.cmjava2023;
+
+public interface Greeter extends Named {
+}
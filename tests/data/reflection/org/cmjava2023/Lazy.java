@@ -0,0 +1,7 @@
+package org.cmjava2023;
+
+public class Lazy {
+    static {
+        System.out.println("Lazy initialized");
+    }
+}
@@ -0,0 +1,72 @@
+package org.cmjava2023;
+
+public class Main {
+    static class Inner {
+    }
+
+    public static void main(String[] args) throws Exception {
+        Dog dog = new Dog();
+        Class<?> dogClass = dog.getClass();
+        System.out.println(dogClass.getName());
+        System.out.println(dogClass == Dog.class);
+        System.out.println(dogClass.getSimpleName());
+        System.out.println(dogClass.getSuperclass().getName());
+        System.out.println(dogClass.getSuperclass().getSuperclass());
+        System.out.println(Object.class.getSuperclass() == null);
+        System.out.println(Greeter.class.getSuperclass() == null);
+        Class<?>[] interfaces = dogClass.getInterfaces();
+        System.out.println(interfaces.length);
+        System.out.println(interfaces[0]);
+        System.out.println(Greeter.class.getInterfaces()[0].getName());
+
+        System.out.println(Named.class.isInstance(dog));
+        System.out.println(Dog.class.isInstance(new Animal()));
+        System.out.println(Dog.class.isInstance(null));
+        System.out.println(Named.class.isAssignableFrom(Dog.class));
+        System.out.println(Dog.class.isAssignableFrom(Animal.class));
+
+        int[] ints = new int[2];
+        System.out.println(dogClass.isArray());
+        System.out.println(dogClass.getComponentType() == null);
+        System.out.println(ints.getClass().isArray());
+        System.out.println(ints.getClass().getName());
+        System.out.println(ints.getClass().getComponentType());
+        System.out.println(ints.getClass().getSimpleName());
+        Dog[][] dogs = new Dog[1][];
+        System.out.println(dogs.getClass().getName());
+        System.out.println(dogs.getClass().getComponentType().getName());
+        System.out.println(dogs.getClass().getSimpleName());
+
+        System.out.println(Inner.class.getName());
+        System.out.println(Inner.class.getSimpleName());
+        Object anonymous = new Object() {
+        };
+        System.out.println("[" + anonymous.getClass().getSimpleName() + "]");
+        System.out.println(String.class);
+        System.out.println(Named.class);
+
+        Class<?> lazy = Class.forName("org.cmjava2023.Lazy");
+        System.out.println(lazy == Lazy.class);
+        System.out.println(Class.forName("[Ljava.lang.String;").getSimpleName());
+        try {
+            Class.forName("org.cmjava2023.Missing");
+        } catch (ClassNotFoundException e) {
+            System.out.println("ClassNotFoundException " + e.getMessage());
+        }
+        try {
+            Class.forName(null);
+        } catch (NullPointerException e) {
+            System.out.println("NullPointerException forName");
+        }
+        try {
+            Named.class.isAssignableFrom(null);
+        } catch (NullPointerException e) {
+            System.out.println("NullPointerException isAssignableFrom");
+        }
+        try {
+            Dog.class.isAnnotationPresent(null);
+        } catch (NullPointerException e) {
+            System.out.println("NullPointerException isAnnotationPresent");
+        }
+    }
+}
@@ -0,0 +1,8 @@
+package org.cmjava2023;
+
+public class Hashed {
+    @Override
+    public int hashCode() {
+        return 42;
+    }
+}
@@ -0,0 +1,30 @@
+package org.cmjava2023;
+
+public class Main {
+    public static void main(String[] args) {
+        Point p = new Point(1, 2);
+        System.out.println(p);
+        System.out.println("p = " + p);
+        System.out.println(String.valueOf(p));
+
+        System.out.println(new Plain());
+        System.out.println(new Hashed());
+
+        Object nothing = null;
+        System.out.println("nothing: " + nothing);
+
+        float f = 1.0f;
+        System.out.println("f=" + f);
+        double[] doubles = {0.1, -0.0, 100.0 / 3, 1.0e7, 0.001, 1.0 / 0,
+                0.0 / 0, Double.MAX_VALUE, 9999999.0};
+        for (double d : doubles) {
+            System.out.println("d=" + d);
+        }
+        float[] floats = {1.0e-4f, 1234567.0f, -1.0f / 0, Float.MIN_VALUE,
+                1.0e10f};
+        for (float value : floats) {
+            System.out.println(value);
+        }
+        System.out.println(Double.valueOf(-12.0));
+    }
+}
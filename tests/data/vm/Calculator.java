@@ -0,0 +1,23 @@
+package org.cmjava2023;
+
+public class Calculator {
+    public static int add(int a, int b) {
+        return a + b;
+    }
+
+    public static double half(long value) {
+        return value / 2.0;
+    }
+
+    public static boolean isEven(int value) {
+        return value % 2 == 0;
+    }
+
+    public static String greet(String name) {
+        return "Hello, " + name + "!";
+    }
+
+    public static void fail(String message) throws Exception {
+        throw new Exception(message);
+    }
+}
@@ -0,0 +1,29 @@
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+
+#[test]
+fn define_class() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    // Greeting isn't part of the class path,
+    // its class file is passed to the program via stdin
+    cmd.args(["-cp", "tests/data/define_class", "org.cmjava2023.Main"]);
+    cmd.write_stdin(std::fs::read(
+        "tests/data/define_class/generated/org/cmjava2023/Greeting.class",
+    )?);
+    cmd.assert().success().stdout(predicate::str::diff(
+        "org.cmjava2023.Greeting\n\
+caught: org/cmjava2023/Greeting\n\
+caught: duplicate class definition for org.cmjava2023.Greeting\n\
+caught: org/cmjava2023/Greeting (wrong name: org/cmjava2023/Other)\n\
+caught ClassFormatError\n\
+caught NullPointerException\n\
+caught IndexOutOfBoundsException\n\
+caught IndexOutOfBoundsException\n\
+caught IndexOutOfBoundsException\n\
+loadClass: caught NullPointerException\n\
+findClass: null\n",
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+
+#[test]
+fn dynamic_proxy() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/dynamic_proxy", "org.cmjava2023.Main"]);
+    cmd.assert().success().stdout(predicate::eq(
+        "-> Calculator.add\n\
+arg: java.lang.Integer 2\n\
+arg: java.lang.Integer 3\n\
+5\n\
+-> Calculator.scale\n\
+arg: java.lang.Double 1.5\n\
+arg: java.lang.Long 3\n\
+4.5\n\
+-> Calculator.isDigit\n\
+arg: java.lang.Character 7\n\
+true\n\
+-> Calculator.describe\n\
+simple calculator\n\
+-> Identified.id\n\
+7\n\
+-> Calculator.log\n\
+arg: java.lang.String hello\n\
+log: hello\n\
+-> Calculator.save\n\
+IOException: disk full\n\
+true\n\
+false\n\
+true\n\
+org.cmjava2023.Calculator\n\
+java.lang.reflect.Proxy\n\
+true\n\
+unexpected greeting\n\
+null\n\
+true\n\
+true\n\
+false\n\
+RemoteProxy\n\
+42\n\
+true\n\
+false\n\
+NullPointerException\n\
+ClassCastException\n\
+UndeclaredThrowableException: connection refused\n\
+IllegalArgumentException: not connected\n\
+java.lang.String is not an interface\n\
+not a proxy instance\n",
+    ));
+
+    Ok(())
+}
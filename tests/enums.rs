@@ -0,0 +1,76 @@
+use assert_cmd::Command;
+use cmjava::{JavaValue, Vm, VmError};
+use predicates::prelude::predicate;
+
+#[test]
+fn enums() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/enums", "org.cmjava2023.Main"]);
+    cmd.assert().success().stdout(predicate::eq(
+        "RED\n\
+0\n\
+#f00\n\
+warm\n\
+GREEN\n\
+1\n\
+#0f0\n\
+mild\n\
+BLUE\n\
+2\n\
+blue #00f\n\
+cold\n\
+GREEN\n\
+true\n\
+-2\n\
+org.cmjava2023.Color\n\
+No enum constant org.cmjava2023.Color.PURPLE\n\
+Name is null\n\
+valueOf of null class\n\
+compareTo null\n\
+small\n\
+large\n\
+unknown\n",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn lookupswitch() -> Result<(), Box<dyn std::error::Error>> {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/enums".into()])?;
+    vm.load_class_by_name("org/cmjava2023/Main")?;
+
+    let mut size = |n: i32| {
+        vm.invoke_static(
+            "org/cmjava2023/Main",
+            "size",
+            "(I)Ljava/lang/String;",
+            &[n.into()],
+        )
+    };
+    assert_eq!(size(1)?, JavaValue::String("small".to_string()));
+    assert_eq!(size(10)?, JavaValue::String("small".to_string()));
+    assert_eq!(size(1000)?, JavaValue::String("large".to_string()));
+    assert_eq!(size(-1)?, JavaValue::String("unknown".to_string()));
+
+    Ok(())
+}
+
+#[test]
+fn failing_static_initializer() -> Result<(), Box<dyn std::error::Error>> {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/enums".into()])?;
+    vm.load_class_by_name("org/cmjava2023/Broken")?;
+
+    match vm.invoke_static("org/cmjava2023/Broken", "value", "()I", &[]) {
+        Err(VmError::Exception(e)) => assert_eq!(
+            e.class().class_identifier().to_string(),
+            "java/lang/ExceptionInInitializerError"
+        ),
+        result => panic!("expected exception, got {:?}", result),
+    }
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 // make testfiles in subdir visible
 mod exceptions {
+    mod builtin;
     mod nested;
     mod simple;
     mod subtype;
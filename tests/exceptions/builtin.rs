@@ -0,0 +1,14 @@
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+
+#[test]
+fn builtin_exceptions() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/exceptions/builtin", "Main"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "caught e:\nOops\ncaught t:\nnull\n",
+    ));
+
+    Ok(())
+}
@@ -5,8 +5,7 @@ use predicates::prelude::predicate;
 fn try_catch() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/exceptions/subtype/A.class");
-    cmd.arg("tests/data/exceptions/subtype/Main.class");
+    cmd.args(["-cp", "tests/data/exceptions/subtype", "Main"]);
     cmd.assert().success().stdout(predicate::str::contains(
         "caught e:\nOops\ndid not catch e:\nHuh?",
     ));
@@ -6,13 +6,17 @@ fn try_catch_finally_throwable() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
     let stderr = predicate::str::is_match(
-        "Uncaught exception: instance of Class 'java/lang/Throwable'",
+        "^Exception in thread \"main\" java.lang.Throwable: Oops\n$",
     )
     .unwrap();
 
-    cmd.arg("tests/data/exceptions/uncaught/Main.class");
+    cmd.args([
+        "-cp",
+        "tests/data/exceptions/uncaught",
+        "org.cmjava2023.Main",
+    ]);
     cmd.assert()
-        .failure()
+        .code(1)
         .stderr(stderr)
         .stdout(predicate::str::contains("anyway"));
 
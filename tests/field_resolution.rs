@@ -0,0 +1,42 @@
+use assert_cmd::Command;
+use cmjava::{Vm, VmError};
+use predicates::prelude::predicate;
+
+#[test]
+fn field_resolution() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/field_resolution", "org.cmjava2023.Main"]);
+    cmd.assert().success().stdout(predicate::eq(
+        "Limits initialized\n\
+10\n\
+Base initialized\n\
+1\n\
+5\n\
+2\n\
+3\n\
+derived\n\
+base\n\
+derived changed\n",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn static_field_accessed_as_instance_field()
+-> Result<(), Box<dyn std::error::Error>> {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/field_resolution".into()])?;
+    vm.load_class_by_name("org/cmjava2023/Main")?;
+
+    match vm.invoke_static("org/cmjava2023/Main", "flag", "()I", &[]) {
+        Err(VmError::Exception(e)) => assert_eq!(
+            e.class().class_identifier().to_string(),
+            "java/lang/IncompatibleClassChangeError"
+        ),
+        result => panic!("expected exception, got {:?}", result),
+    }
+
+    Ok(())
+}
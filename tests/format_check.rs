@@ -0,0 +1,220 @@
+use common::{
+    class_format_error, class_offsets, method_offsets, patched_calculator,
+    ClassOffsets,
+};
+
+mod common;
+
+/// Returns the reason `bytes` of Calculator.class patched by `patch`
+/// are rejected for, prefixed with the offset.
+fn check(patch: impl FnOnce(&mut Vec<u8>)) -> String {
+    let error = class_format_error(&patched_calculator(patch));
+    assert_eq!(
+        error.file.as_deref(),
+        Some("org/cmjava2023/Calculator.class")
+    );
+    format!("offset {}: {}", error.offset.unwrap(), error.reason)
+}
+
+/// Returns the bytes of the unpatched Calculator.class.
+fn calculator() -> Vec<u8> {
+    patched_calculator(|_| ())
+}
+
+/// Returns the offsets of the structures of the unpatched Calculator.class.
+fn calculator_offsets() -> ClassOffsets {
+    class_offsets(&calculator())
+}
+
+#[test]
+fn unsupported_version() {
+    // major_version 66 (Java 22)
+    assert_eq!(
+        check(|bytes| bytes[7] = 66),
+        "offset 4: unsupported class file version 66.0"
+    );
+    // preview features of Java 17
+    assert_eq!(
+        check(|bytes| {
+            bytes[4] = 0xff;
+            bytes[5] = 0xff;
+            bytes[7] = 61;
+        }),
+        "offset 4: unsupported class file version 61.65535"
+    );
+}
+
+#[test]
+fn constant_pool_consistency() {
+    let offsets = calculator_offsets();
+    let methodref = offsets.constant_pool[&1];
+    let super_class = offsets.access_flags + 4;
+    // the NameAndType of the first entry (a Methodref) refers to a Utf8
+    assert_eq!(
+        check(|bytes| bytes[methodref + 4] = 4),
+        format!(
+            "offset {}: constant pool entry 4 is no NameAndType",
+            methodref
+        )
+    );
+    // the super class refers to a Utf8
+    assert_eq!(
+        check(|bytes| bytes[super_class + 1] = 4),
+        format!("offset {}: constant pool entry 4 is no Class", super_class)
+    );
+    assert_eq!(
+        check(|bytes| bytes[super_class + 1] = 0),
+        format!("offset {}: missing super class", super_class)
+    );
+}
+
+#[test]
+fn illegal_access_flags() {
+    let offsets = calculator_offsets();
+    let access_flags = offsets.access_flags;
+    let add = offsets.methods["add"];
+    // an interface, which isn't abstract
+    assert_eq!(
+        check(|bytes| bytes[access_flags] = 0x02),
+        format!(
+            "offset {}: illegal access flags 0x0221 \
+of org/cmjava2023/Calculator",
+            access_flags
+        )
+    );
+    // public, private and static
+    assert_eq!(
+        check(|bytes| bytes[add + 1] = 0x0b),
+        format!("offset {}: illegal access flags 0x000b of add", add)
+    );
+}
+
+#[test]
+fn invalid_methods() {
+    let offsets = calculator_offsets();
+    let add = offsets.methods["add"];
+    let half = offsets.methods["half"];
+    // the descriptor of add becomes '(IIXI'
+    assert_eq!(
+        check(|bytes| {
+            let descriptor_index =
+                u16::from_be_bytes([bytes[add + 4], bytes[add + 5]]);
+            // behind the tag and length of the Utf8 entry and '(II'
+            let descriptor =
+                offsets.constant_pool[&usize::from(descriptor_index)];
+            bytes[descriptor + 6] = b'X';
+        }),
+        format!("offset {}: invalid method descriptor (IIXI of add", add)
+    );
+    // half becomes a second add(II)I
+    assert_eq!(
+        check(|bytes| bytes.copy_within(add + 2..add + 6, half + 2)),
+        format!("offset {}: duplicate method add(II)I", half)
+    );
+}
+
+#[test]
+fn code_invariants() {
+    let add = calculator_offsets().methods["add"];
+    // a native method with code
+    assert_eq!(
+        check(|bytes| bytes[add] = 0x01),
+        format!("offset {}: unexpected Code attribute of add", add)
+    );
+    // max_locals of add is 1, but it takes two int parameters
+    let max_locals = method_offsets(&calculator(), "add").max_stack + 2;
+    assert_eq!(
+        check(|bytes| bytes[max_locals + 1] = 1),
+        format!(
+            "offset {}: max_locals 1 is less than the 2 parameter slots",
+            max_locals
+        )
+    );
+}
+
+#[test]
+fn branch_targets() {
+    // isEven is iload_0, iconst_2, irem, ifne 10, iconst_1, goto 11,
+    // iconst_0 and ireturn
+    let code = method_offsets(&calculator(), "isEven").code;
+    // the target of ifne is in the middle of the following goto
+    assert_eq!(
+        check(|bytes| bytes[code + 5] = 6),
+        format!(
+            "offset {}: branch target is no instruction boundary",
+            code + 3
+        )
+    );
+    // the target of goto is behind the code
+    assert_eq!(
+        check(|bytes| bytes[code + 9] = 0x20),
+        format!(
+            "offset {}: branch target is no instruction boundary",
+            code + 7
+        )
+    );
+    // the target of goto is before the code
+    assert_eq!(
+        check(|bytes| {
+            bytes[code + 8] = 0xff;
+            bytes[code + 9] = 0xf0;
+        }),
+        format!(
+            "offset {}: branch target is no instruction boundary",
+            code + 7
+        )
+    );
+}
+
+#[test]
+fn operand_references() {
+    // greet starts with new #9, dup, invokespecial #11, ldc #12
+    // and invokevirtual #14
+    let code = method_offsets(&calculator(), "greet").code;
+    // new refers to a Utf8
+    assert_eq!(
+        check(|bytes| bytes[code + 2] = 4),
+        format!("offset {}: constant pool entry 4 is no Class", code)
+    );
+    // invokespecial refers to an entry behind the constant pool
+    assert_eq!(
+        check(|bytes| bytes[code + 5..code + 7].fill(0xff)),
+        format!("offset {}: invalid constant pool index 65535", code + 4)
+    );
+    // ldc refers to a Methodref
+    assert_eq!(
+        check(|bytes| bytes[code + 8] = 1),
+        format!(
+            "offset {}: constant pool entry 1 is no loadable constant",
+            code + 7
+        )
+    );
+    // invokevirtual refers to a Class
+    assert_eq!(
+        check(|bytes| bytes[code + 11] = 9),
+        format!("offset {}: constant pool entry 9 is no Methodref", code + 9)
+    );
+    // invokevirtual becomes getfield
+    assert_eq!(
+        check(|bytes| bytes[code + 9] = 0xb4),
+        format!("offset {}: constant pool entry 14 is no Fieldref", code + 9)
+    );
+    // invokevirtual becomes invokedynamic
+    assert_eq!(
+        check(|bytes| bytes[code + 9] = 0xba),
+        format!(
+            "offset {}: constant pool entry 14 is no InvokeDynamic",
+            code + 9
+        )
+    );
+
+    // half is lload_0, l2d and ldc2_w #7, which refers to a String
+    let code = method_offsets(&calculator(), "half").code;
+    assert_eq!(
+        check(|bytes| bytes[code + 4] = 12),
+        format!(
+            "offset {}: constant pool entry 12 is no long or double constant",
+            code + 2
+        )
+    );
+}
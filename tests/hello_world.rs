@@ -5,7 +5,7 @@ use predicates::prelude::predicate;
 fn hello_world() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/hello_world/Main.class");
+    cmd.args(["-cp", "tests/data/hello_world", "org.cmjava2023.Main"]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("Hello world!\n"));
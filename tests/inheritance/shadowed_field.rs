@@ -5,9 +5,7 @@ use predicates::prelude::predicate;
 fn shadowed_field() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/inheritance/shadowed_field/A.class");
-    cmd.arg("tests/data/inheritance/shadowed_field/B.class");
-    cmd.arg("tests/data/inheritance/shadowed_field/Main.class");
+    cmd.args(["-cp", "tests/data/inheritance/shadowed_field", "Main"]);
     cmd.assert().success().stdout(predicate::str::contains(
         "(B) thing
 10
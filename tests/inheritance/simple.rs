@@ -5,9 +5,7 @@ use predicates::prelude::predicate;
 fn simple_inheritance() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/inheritance/simple/A.class");
-    cmd.arg("tests/data/inheritance/simple/B.class");
-    cmd.arg("tests/data/inheritance/simple/Main.class");
+    cmd.args(["-cp", "tests/data/inheritance/simple", "Main"]);
     cmd.assert().success().stdout(predicate::str::contains(
         "(A) doStuff()\n(B) doStuff()\n(A) doOtherStuff()\n",
     ));
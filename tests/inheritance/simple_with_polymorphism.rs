@@ -5,9 +5,11 @@ use predicates::prelude::predicate;
 fn simple_polymorphism() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/inheritance/simple_with_polymorphism/A.class");
-    cmd.arg("tests/data/inheritance/simple_with_polymorphism/B.class");
-    cmd.arg("tests/data/inheritance/simple_with_polymorphism/Main.class");
+    cmd.args([
+        "-cp",
+        "tests/data/inheritance/simple_with_polymorphism",
+        "Main",
+    ]);
     cmd.assert().success().stdout(predicate::str::contains(
         "(A) doStuff()\n(B) doStuff()\n(A) doOtherStuff()\n",
     ));
@@ -6,7 +6,7 @@ fn input() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
     let sample_input = 'A';
 
-    cmd.arg("tests/data/input/Main.class");
+    cmd.args(["-cp", "tests/data/input", "org.cmjava2023.Main"]);
     cmd.write_stdin(format!("{}", sample_input));
     cmd.assert()
         .success()
@@ -17,3 +17,16 @@ fn input() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn input_end_of_stream() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/input", "org.cmjava2023.Main"]);
+    cmd.write_stdin("");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("-1\n"));
+
+    Ok(())
+}
@@ -5,7 +5,7 @@ use predicates::prelude::predicate;
 fn instance_function() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/instance_function/Main.class");
+    cmd.args(["-cp", "tests/data/instance_function", "org.cmjava2023.Main"]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("10\n"));
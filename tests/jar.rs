@@ -0,0 +1,45 @@
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+
+#[test]
+fn main_class_from_manifest() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    // lib.jar (stored entries) is added by the Class-Path of app.jar
+    // (deflated entries)
+    cmd.args(["-jar", "tests/data/jar/app.jar", "world", "-verbose"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::diff("2\nhello, world\nhello, -verbose\n"));
+
+    Ok(())
+}
+
+#[test]
+fn jars_in_class_path() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args([
+        "-cp",
+        "tests/data/jar/lib.jar:tests/data/jar/app.jar",
+        "org.cmjava2023.Main",
+        "jar",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::diff("1\nhello, jar\n"));
+
+    Ok(())
+}
+
+#[test]
+fn invalid_jar() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-jar", "tests/data/jar/app/org/cmjava2023/Main.java"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("is no valid jar"));
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use assert_cmd::Command;
+use cmjava::{Vm, VmError};
+use predicates::prelude::predicate;
+
+#[test]
+fn string_concat() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/java17", "org.cmjava2023.Concat"]);
+    cmd.assert().success().stdout(
+        "int 42, long 7, char x\n\
+boolean true, short -3\n\
+null null, string text\n\
+Point(1, 2)!\n\
+\u{1}42\u{2}\n\
+49\n\
+float 1.0, double -0.0, large 1.0E7\n",
+    );
+
+    Ok(())
+}
+
+#[test]
+fn nestmates() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/java17", "org.cmjava2023.Nest"]);
+    // Sub.describe() doesn't override the private Nest.describe()
+    cmd.assert()
+        .success()
+        .stdout(predicate::eq("42\nouter 42\nouter 42\n"));
+
+    Ok(())
+}
+
+#[test]
+fn nest_host() {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/java17".into()]).unwrap();
+    let nest = vm.load_class_by_name("org/cmjava2023/Nest").unwrap();
+    let inner = vm.load_class_by_name("org/cmjava2023/Nest$Inner").unwrap();
+    let point = vm.load_class_by_name("org/cmjava2023/Point").unwrap();
+
+    assert!(inner.nest_host(vm.heap()).is_same_class(nest.as_ref()));
+    assert!(nest.nest_host(vm.heap()).is_same_class(nest.as_ref()));
+    assert!(inner.is_nestmate_of(&nest, vm.heap()));
+    assert!(!point.is_nestmate_of(&nest, vm.heap()));
+}
+
+#[test]
+fn module_descriptor() {
+    // the Module and Package constants are valid,
+    // but module descriptors can't be loaded as classes
+    match Vm::new().load_class("tests/data/java17/module/module-info.class") {
+        Err(VmError::ClassLoading(e)) => {
+            assert_eq!(e.to_string(), "module-info is a module descriptor")
+        },
+        Err(e) => panic!("expected a class loading error, got {}", e),
+        Ok(_) => panic!("module-info has been loaded as class"),
+    }
+}
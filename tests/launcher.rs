@@ -0,0 +1,55 @@
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+
+#[test]
+fn arguments_and_properties() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/launcher", "-Dgreeting=hello"]);
+    cmd.args(["org.cmjava2023.Main", "a", "-cp"]);
+    cmd.assert().success().stdout(predicate::str::diff(
+        "2\na\n-cp\nhello\nkey can't be null\nkey can't be null\n\
+fallback\nnull\nfinally\n",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn exit_status() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["--class-path", "tests/data/launcher", "org.cmjava2023.Main"]);
+    cmd.args(["x", "y", "z"]);
+    // neither catch nor finally blocks are executed on exit
+    cmd.assert()
+        .code(3)
+        .stdout(predicate::str::ends_with("null\n"));
+
+    Ok(())
+}
+
+#[test]
+fn missing_main_class() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/launcher", "org.cmjava2023.Missing"]);
+    cmd.assert().code(1).stderr(predicate::str::contains(
+        "Could not find or load main class org.cmjava2023.Missing",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn uncaught_exception() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/launcher", "org.cmjava2023.Main", "x"]);
+    cmd.assert().code(1).stderr(predicate::str::diff(
+        "Exception in thread \"main\" \
+java.lang.IllegalArgumentException: unexpected x\n",
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+
+#[test]
+fn member_reflection() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/member_reflection", "org.cmjava2023.Main"]);
+    cmd.assert().success().stdout(predicate::eq(
+        "6\n\
+4\n\
+3\n\
+org.cmjava2023.Counter\n\
+1\n\
+add\n\
+class org.cmjava2023.Counter\n\
+1\n\
+int\n\
+true\n\
+5\n\
+7\n\
+9\n\
+42\n\
+false\n\
+3.5\n\
+void\n\
+100\n\
+1025\n\
+Good day, Ada\n\
+org.cmjava2023.CounterException\n\
+broken\n\
+argument type mismatch\n\
+argument type mismatch\n\
+wrong number of arguments\n\
+object is not an instance of declaring class\n\
+NullPointerException\n\
+InstantiationException\n\
+class org.cmjava2023.Main cannot access a member of class \
+org.cmjava2023.Counter with modifiers \"private\"\n\
+secret of reflective\n\
+class org.cmjava2023.Main cannot access a member of class \
+org.cmjava2023.other.Hidden with modifiers \"\"\n\
+revealed\n\
+2\n\
+int\n\
+class org.cmjava2023.Main cannot access a member of class \
+org.cmjava2023.Counter with modifiers \"private\"\n\
+7\n\
+42\n\
+Can not set int field org.cmjava2023.Counter.count to java.lang.String\n\
+Can not set int field org.cmjava2023.Counter.count to java.lang.String\n\
+9\n\
+5\n\
+class java.lang.String\n\
+reflective\n\
+Can not set final java.lang.String field org.cmjava2023.Counter.name \
+to java.lang.String\n\
+renamed\n\
+9\n\
+2\n\
+10\n\
+42\n\
+true\n\
+false\n\
+7\n\
+1231\n\
+x\n\
+int\n",
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+
+#[test]
+fn narrowing() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/narrowing", "org.cmjava2023.Main"]);
+    cmd.assert().success().stdout(predicate::eq(
+        "69\n\
+9029\n\
+9029\n\
+false\n\
+-1\n\
+-1\n\
+65535\n\
+true\n\
+-128\n\
+384\n\
+384\n\
+false\n",
+    ));
+
+    Ok(())
+}
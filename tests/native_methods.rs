@@ -0,0 +1,71 @@
+use std::cell::RefCell;
+
+use cmjava::{
+    class::{
+        ArgumentKind, FieldValue, MethodCode, RustMethodReturn,
+        SimpleArgumentKind,
+    },
+    classloader::load_class,
+    executor::{native_context::NativeContext, run},
+    heap::Heap,
+    ClassLoaderId,
+};
+
+thread_local! {
+    static REPORTED: RefCell<Vec<i64>> = RefCell::new(Vec::new());
+}
+
+fn add(context: &mut NativeContext) -> RustMethodReturn {
+    RustMethodReturn::Value(FieldValue::Int(context.int(0) + context.int(1)))
+}
+
+fn scale(context: &mut NativeContext) -> RustMethodReturn {
+    let _this = context.this();
+    let scaled = context.long(0) as f64 * context.double(1);
+
+    RustMethodReturn::Value(FieldValue::Long(scaled as i64))
+}
+
+fn report(context: &mut NativeContext) -> RustMethodReturn {
+    let value = context.long(0);
+    REPORTED.with(|r| r.borrow_mut().push(value));
+
+    RustMethodReturn::Void
+}
+
+#[test]
+fn native_methods() {
+    let mut heap = Heap::default();
+    heap.register_native_method("org/cmjava2023/Main", "add", "(II)I", add);
+    heap.register_native_method("org/cmjava2023/Main", "scale", "(JD)J", scale);
+    heap.register_native_method(
+        "org/cmjava2023/Main",
+        "report",
+        "(J)V",
+        report,
+    );
+    let main_class = load_class(
+        "tests/data/native_methods/Main.class",
+        ClassLoaderId::APPLICATION,
+        &mut heap,
+    )
+    .unwrap();
+
+    let (main, _) = main_class.get_method(
+        "main",
+        (
+            &[ArgumentKind::Array {
+                dimensions: 1,
+                kind: SimpleArgumentKind::Class("java/lang/String".to_string()),
+            }],
+            None,
+        ),
+        false,
+    );
+    let MethodCode::Bytecode(main) = &main.code else {
+        panic!("main method is not bytecode");
+    };
+    run(main, &mut heap, main_class.clone()).unwrap();
+
+    REPORTED.with(|r| assert_eq!(*r.borrow(), vec![42, 63, -1]));
+}
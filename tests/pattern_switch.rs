@@ -0,0 +1,50 @@
+use assert_cmd::Command;
+use cmjava::{JavaValue, Vm};
+use predicates::prelude::predicate;
+
+#[test]
+fn pattern_switch() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/pattern_switch", "org.cmjava2023.Shapes"]);
+    cmd.assert().success().stdout(predicate::eq(
+        "large circle 20\n\
+circle 5\n\
+square 3\n\
+other shape\n\
+string text\n\
+unknown\n\
+nothing\n\
+1\n\
+2\n\
+guarded\n\
+short\n\
+warm\n\
+other\n\
+last\n",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn guard_restarts_switch() -> Result<(), Box<dyn std::error::Error>> {
+    let mut vm = Vm::new();
+    vm.set_class_path(vec!["tests/data/pattern_switch".into()])?;
+    vm.load_class_by_name("org/cmjava2023/Shapes")?;
+
+    let mut word = |word: &str| {
+        vm.invoke_static(
+            "org/cmjava2023/Shapes",
+            "word",
+            "(Ljava/lang/String;)Ljava/lang/String;",
+            &[JavaValue::String(word.to_string())],
+        )
+    };
+    // "two" is only tried after the guard of the case before failed
+    assert_eq!(word("two")?, JavaValue::String("2".to_string()));
+    assert_eq!(word("three")?, JavaValue::String("guarded".to_string()));
+    assert_eq!(word("four")?, JavaValue::String("short".to_string()));
+
+    Ok(())
+}
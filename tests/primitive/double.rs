@@ -5,7 +5,11 @@ use predicates::prelude::predicate;
 fn conversions() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/primitive/double/conversions/Main.class");
+    cmd.args([
+        "-cp",
+        "tests/data/primitive/double/conversions",
+        "org.cmjava2023.Main",
+    ]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("d:\n10"))
@@ -23,7 +27,11 @@ fn conversions() -> Result<(), Box<dyn std::error::Error>> {
 fn mathops() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/primitive/double/mathops/Main.class");
+    cmd.args([
+        "-cp",
+        "tests/data/primitive/double/mathops",
+        "org.cmjava2023.Main",
+    ]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("d:\n10"))
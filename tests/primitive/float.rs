@@ -5,7 +5,11 @@ use predicates::prelude::predicate;
 fn conversions() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/primitive/float/conversions/Main.class");
+    cmd.args([
+        "-cp",
+        "tests/data/primitive/float/conversions",
+        "org.cmjava2023.Main",
+    ]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("f:\n10"))
@@ -23,7 +27,11 @@ fn conversions() -> Result<(), Box<dyn std::error::Error>> {
 fn mathops() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/primitive/float/mathops/Main.class");
+    cmd.args([
+        "-cp",
+        "tests/data/primitive/float/mathops",
+        "org.cmjava2023.Main",
+    ]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("f:\n10"))
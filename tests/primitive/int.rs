@@ -5,15 +5,19 @@ use predicates::prelude::predicate;
 fn conversions() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/primitive/int/conversions/Main.class");
+    cmd.args([
+        "-cp",
+        "tests/data/primitive/int/conversions",
+        "org.cmjava2023.Main",
+    ]);
     // int 10 as char is ascii for \n
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("i:\n10\n"))
         .stdout(predicate::str::contains("b:\n10\n"))
         .stdout(predicate::str::contains("c:\n\n\n"))
-        .stdout(predicate::str::contains("d:\n10\n"))
-        .stdout(predicate::str::contains("f:\n10\n"))
+        .stdout(predicate::str::contains("d:\n10.0\n"))
+        .stdout(predicate::str::contains("f:\n10.0\n"))
         .stdout(predicate::str::contains("l:\n10\n"))
         .stdout(predicate::str::contains("s:\n10\n"))
         .stdout(predicate::str::contains("force_load:\n10\n"));
@@ -25,7 +29,11 @@ fn conversions() -> Result<(), Box<dyn std::error::Error>> {
 fn mathops() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/primitive/int/mathops/Main.class");
+    cmd.args([
+        "-cp",
+        "tests/data/primitive/int/mathops",
+        "org.cmjava2023.Main",
+    ]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("i:\n10\n"))
@@ -44,7 +52,11 @@ fn mathops() -> Result<(), Box<dyn std::error::Error>> {
 fn logicops() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/primitive/int/logicops/Main.class");
+    cmd.args([
+        "-cp",
+        "tests/data/primitive/int/logicops",
+        "org.cmjava2023.Main",
+    ]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("i:\n4\n"))
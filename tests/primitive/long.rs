@@ -5,12 +5,16 @@ use predicates::prelude::predicate;
 fn conversions() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/primitive/long/conversions/Main.class");
+    cmd.args([
+        "-cp",
+        "tests/data/primitive/long/conversions",
+        "org.cmjava2023.Main",
+    ]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("l:\n10\n"))
-        .stdout(predicate::str::contains("d:\n10\n"))
-        .stdout(predicate::str::contains("f:\n10\n"))
+        .stdout(predicate::str::contains("d:\n10.0\n"))
+        .stdout(predicate::str::contains("f:\n10.0\n"))
         .stdout(predicate::str::contains("i:\n10\n"))
         .stdout(predicate::str::contains("force_load:\n10\n"))
         .stdout(predicate::str::contains("force_store:\n10\n"))
@@ -23,7 +27,11 @@ fn conversions() -> Result<(), Box<dyn std::error::Error>> {
 fn mathops() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/primitive/long/mathops/Main.class");
+    cmd.args([
+        "-cp",
+        "tests/data/primitive/long/mathops",
+        "org.cmjava2023.Main",
+    ]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("l:\n10\n"))
@@ -41,7 +49,11 @@ fn mathops() -> Result<(), Box<dyn std::error::Error>> {
 fn logicops() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/primitive/long/logicops/Main.class");
+    cmd.args([
+        "-cp",
+        "tests/data/primitive/long/logicops",
+        "org.cmjava2023.Main",
+    ]);
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("l:\n4\n"))
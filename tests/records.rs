@@ -0,0 +1,33 @@
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+
+#[test]
+fn records() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/records", "org.cmjava2023.Main"]);
+    // hash codes are the same as on HotSpot,
+    // since they are derived from the components
+    cmd.assert().success().stdout(predicate::eq(
+        "Range[low=1, high=10, unit=m, open=true, label=depth]\n\
+1\n\
+depth\n\
+9\n\
+true\n\
+false\n\
+false\n\
+false\n\
+true\n\
+96836664\n\
+Pair[first=Range[low=1, high=10, unit=m, open=true, label=depth], \
+second=null]\n\
+true\n\
+-1293030712\n\
+Empty[]\n\
+true\n\
+0\n\
+low > high\n",
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+
+#[test]
+fn class_objects() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/reflection", "org.cmjava2023.Main"]);
+    cmd.assert().success().stdout(predicate::eq(
+        "org.cmjava2023.Dog\n\
+true\n\
+Dog\n\
+org.cmjava2023.Animal\n\
+class java.lang.Object\n\
+true\n\
+true\n\
+1\n\
+interface org.cmjava2023.Greeter\n\
+org.cmjava2023.Named\n\
+true\n\
+false\n\
+false\n\
+true\n\
+false\n\
+false\n\
+true\n\
+true\n\
+[I\n\
+int\n\
+int[]\n\
+[[Lorg.cmjava2023.Dog;\n\
+[Lorg.cmjava2023.Dog;\n\
+Dog[][]\n\
+org.cmjava2023.Main$Inner\n\
+Inner\n\
+[]\n\
+class java.lang.String\n\
+interface org.cmjava2023.Named\n\
+Lazy initialized\n\
+true\n\
+String[]\n\
+ClassNotFoundException org.cmjava2023.Missing\n\
+NullPointerException forName\n\
+NullPointerException isAssignableFrom\n\
+NullPointerException isAnnotationPresent\n",
+    ));
+
+    Ok(())
+}
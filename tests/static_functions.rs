@@ -5,13 +5,13 @@ use predicates::prelude::predicate;
 fn static_functions() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/static_functions/Main.class");
-    // prints '[I@a92b32a' (i.e. @<some memory address)
-    // since memory address is unpredictable,
-    // use regex to at least make sure it looks like a memory address
+    cmd.args(["-cp", "tests/data/static_functions", "org.cmjava2023.Main"]);
+    // prints '[I@a92b32a' (i.e. @<identity hash code>)
+    // since the hash code is unpredictable,
+    // use regex to at least make sure it looks like a hex number
     let nums_output = predicate::str::is_match(
-        "\\(main\\) nums:\n\\[I@0x[a-zA-Z0-9]*
-\\(arrayArg\\) nums:\n\\[I@0x[a-zA-Z0-9]*\n",
+        "\\(main\\) nums:\n\\[I@[0-9a-f]+
+\\(arrayArg\\) nums:\n\\[I@[0-9a-f]+\n",
     )
     .unwrap();
     cmd.assert()
@@ -25,7 +25,7 @@ fn static_functions() -> Result<(), Box<dyn std::error::Error>> {
         ))
         .stdout(nums_output)
         .stdout(predicate::str::contains(
-            "(main) d:\n10\n(largePrimitiveArg) d:\n10\n",
+            "(main) d:\n10.0\n(largePrimitiveArg) d:\n10.0\n",
         ));
 
     Ok(())
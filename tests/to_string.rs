@@ -0,0 +1,29 @@
+use assert_cmd::Command;
+use predicates::prelude::predicate;
+
+#[test]
+fn to_string() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("cmjava")?;
+
+    cmd.args(["-cp", "tests/data/to_string", "org.cmjava2023.Main"]);
+    // the default hash code of Plain is unpredictable,
+    // so only check that it looks like a hex number
+    let plain_output =
+        predicate::str::is_match("\norg\\.cmjava2023\\.Plain@[0-9a-f]+\n")
+            .unwrap();
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Point(1, 2)\np = Point(1, 2)\nPoint(1, 2)\n",
+        ))
+        .stdout(plain_output)
+        .stdout(predicate::str::contains("org.cmjava2023.Hashed@2a\n"))
+        .stdout(predicate::str::contains("nothing: null\n"))
+        .stdout(predicate::str::ends_with(
+            "f=1.0\nd=0.1\nd=-0.0\nd=33.333333333333336\nd=1.0E7\n\
+d=0.001\nd=Infinity\nd=NaN\nd=1.7976931348623157E308\nd=9999999.0\n\
+1.0E-4\n1234567.0\n-Infinity\n1.4E-45\n1.0E10\n-12.0\n",
+        ));
+
+    Ok(())
+}
@@ -5,8 +5,7 @@ use predicates::prelude::predicate;
 fn two_classes() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("cmjava")?;
 
-    cmd.arg("tests/data/two_classes/Auxiliary.class");
-    cmd.arg("tests/data/two_classes/Main.class");
+    cmd.args(["-cp", "tests/data/two_classes", "Main"]);
     cmd.assert().success().stdout(predicate::str::contains(
         "Hello World from Auxiliary.java\n",
     ));
@@ -0,0 +1,130 @@
+use assert_cmd::Command;
+use cmjava::{classloader::verifier::VerifyError, Vm, VmError};
+use common::{method_offsets, patched_calculator};
+use predicates::prelude::predicate;
+
+mod common;
+
+const NOP: u8 = 0x00;
+const ACONST_NULL: u8 = 0x01;
+const ALOAD_0: u8 = 0x2a;
+const IRETURN: u8 = 0xac;
+const ARETURN: u8 = 0xb0;
+
+/// Returns the reason Calculator.class patched by `patch` is rejected for,
+/// prefixed with the method and offset.
+fn verify(patch: impl FnOnce(&mut Vec<u8>)) -> String {
+    let bytes = patched_calculator(patch);
+    let error = match Vm::new().load_class_from_bytes(&bytes) {
+        Err(VmError::ClassLoading(e)) => e
+            .downcast::<VerifyError>()
+            .unwrap_or_else(|e| panic!("expected VerifyError, got {}", e)),
+        Err(e) => panic!("expected VerifyError, got {}", e),
+        Ok(_) => panic!("expected VerifyError, got class"),
+    };
+    assert_eq!(error.class, "org/cmjava2023/Calculator");
+    format!("{} @{}: {}", error.method, error.pc, error.reason)
+}
+
+#[test]
+fn operand_types() {
+    // add is iload_0, iload_1, iadd, ireturn
+    // iload_1 becomes aconst_null
+    assert_eq!(
+        verify(|bytes| {
+            let code = method_offsets(bytes, "add").code;
+            bytes[code + 1] = ACONST_NULL;
+        }),
+        "add(II)I @2: expected int on the operand stack, found null"
+    );
+    // ireturn becomes areturn
+    assert_eq!(
+        verify(|bytes| {
+            let code = method_offsets(bytes, "add").code;
+            bytes[code + 3] = ARETURN;
+        }),
+        "add(II)I @3: return of java/lang/Object \
+in a method returning int"
+    );
+}
+
+#[test]
+fn max_stack() {
+    // max_stack of add is 2, but it pushes two ints;
+    // the low byte of the u2 becomes 1
+    assert_eq!(
+        verify(|bytes| {
+            let max_stack = method_offsets(bytes, "add").max_stack;
+            bytes[max_stack + 1] = 1;
+        }),
+        "add(II)I @1: operand stack overflow"
+    );
+}
+
+#[test]
+fn invokevirtual_receiver() {
+    // greet starts with new, dup and invokespecial (7 bytes),
+    // which become aload_0 and nops,
+    // so that append is invoked on the String argument
+    assert_eq!(
+        verify(|bytes| {
+            let code = method_offsets(bytes, "greet").code;
+            bytes[code] = ALOAD_0;
+            bytes[code + 1..code + 7].fill(NOP);
+        }),
+        "greet(Ljava/lang/String;)Ljava/lang/String; @9: \
+expected java/lang/StringBuilder on the operand stack, \
+found java/lang/String"
+    );
+}
+
+#[test]
+fn stack_map_frames() {
+    // the frames of isEven are a same_frame at 10 (1 byte)
+    // and a same_locals_1_stack_item_frame at 11 with an int (2 bytes)
+    let frames = |bytes: &[u8]| {
+        method_offsets(bytes, "isEven")
+            .stack_map_frames
+            .expect("isEven has a StackMapTable")
+    };
+    // the frame at 11 expects a float instead of an int
+    assert_eq!(
+        verify(|bytes| {
+            let frames = frames(bytes);
+            bytes[frames + 2] = 2;
+        }),
+        "isEven(I)Z @7: locals [int], stack [int] is not assignable \
+to the stack map frame locals [int], stack [float] at 11"
+    );
+    // the first frame is at 9, in the middle of the goto
+    assert_eq!(
+        verify(|bytes| {
+            let frames = frames(bytes);
+            bytes[frames] = 9;
+        }),
+        "isEven(I)Z @9: stack map frame is no instruction boundary"
+    );
+}
+
+#[test]
+fn define_class_throws_verify_error() -> Result<(), Box<dyn std::error::Error>>
+{
+    let mut bytes = std::fs::read(
+        "tests/data/define_class/generated/org/cmjava2023/Greeting.class",
+    )?;
+    // hello is ldc, areturn; areturn becomes ireturn
+    let hello = method_offsets(&bytes, "hello").code;
+    bytes[hello + 2] = IRETURN;
+
+    let mut cmd = Command::cargo_bin("cmjava")?;
+    cmd.args(["-cp", "tests/data/define_class", "org.cmjava2023.Main"]);
+    cmd.write_stdin(bytes);
+    cmd.assert().code(1).stderr(
+        predicate::str::is_match(
+            "Exception in thread \"main\" java.lang.VerifyError",
+        )
+        .unwrap(),
+    );
+
+    Ok(())
+}
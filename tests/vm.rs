@@ -0,0 +1,117 @@
+use cmjava::{JavaValue, MemoryClassSource, Vm, VmError};
+
+const CALCULATOR: &str = "org/cmjava2023/Calculator";
+
+#[test]
+fn invoke_static() -> Result<(), Box<dyn std::error::Error>> {
+    let mut vm = Vm::new();
+    vm.load_class("tests/data/vm/Calculator.class")?;
+
+    assert_eq!(
+        vm.invoke_static(CALCULATOR, "add", "(II)I", &[40.into(), 2.into()])?,
+        JavaValue::Int(42)
+    );
+    assert_eq!(
+        vm.invoke_static(CALCULATOR, "half", "(J)D", &[5i64.into()])?,
+        JavaValue::Double(2.5)
+    );
+    assert_eq!(
+        vm.invoke_static(CALCULATOR, "isEven", "(I)Z", &[4.into()])?,
+        JavaValue::Boolean(true)
+    );
+    assert_eq!(
+        vm.invoke_static(
+            CALCULATOR,
+            "greet",
+            "(Ljava/lang/String;)Ljava/lang/String;",
+            &["cmjava".into()]
+        )?,
+        JavaValue::String("Hello, cmjava!".to_string())
+    );
+
+    Ok(())
+}
+
+#[test]
+fn load_class_from_bytes() -> Result<(), Box<dyn std::error::Error>> {
+    let mut vm = Vm::new();
+    let bytes = std::fs::read("tests/data/vm/Calculator.class")?;
+    vm.load_class_from_bytes(&bytes)?;
+
+    assert_eq!(
+        vm.invoke_static(CALCULATOR, "add", "(II)I", &[1.into(), 2.into()])?,
+        JavaValue::Int(3)
+    );
+    assert!(matches!(
+        vm.load_class_from_bytes(&bytes[..10]),
+        Err(VmError::ClassLoading(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn memory_class_source() -> Result<(), Box<dyn std::error::Error>> {
+    let mut source = MemoryClassSource::new();
+    source.insert(CALCULATOR, std::fs::read("tests/data/vm/Calculator.class")?);
+    let mut vm = Vm::new();
+    vm.add_class_source(source);
+
+    vm.load_class_by_name(CALCULATOR)?;
+    assert_eq!(
+        vm.invoke_static(CALCULATOR, "add", "(II)I", &[20.into(), 1.into()])?,
+        JavaValue::Int(21)
+    );
+    assert!(matches!(
+        vm.load_class_by_name("org/cmjava2023/Missing"),
+        Err(VmError::ClassLoading(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn errors() -> Result<(), Box<dyn std::error::Error>> {
+    let mut vm = Vm::new();
+    vm.load_class("tests/data/vm/Calculator.class")?;
+
+    match vm.invoke_static(
+        CALCULATOR,
+        "fail",
+        "(Ljava/lang/String;)V",
+        &["Oops".into()],
+    ) {
+        Err(VmError::Exception(e)) => assert_eq!(
+            e.class().class_identifier().to_string(),
+            "java/lang/Exception"
+        ),
+        result => panic!("expected exception, got {:?}", result),
+    }
+    assert!(matches!(
+        vm.invoke_static("org/cmjava2023/Missing", "add", "(II)I", &[]),
+        Err(VmError::ClassNotFound(_))
+    ));
+    assert!(matches!(
+        vm.invoke_static(CALCULATOR, "sub", "(II)I", &[]),
+        Err(VmError::MethodNotFound(_))
+    ));
+    for descriptor in ["bogus", "(II", "(X)I", "(II)"] {
+        match vm.invoke_static(CALCULATOR, "add", descriptor, &[]) {
+            Err(VmError::InvalidDescriptor(d)) => assert_eq!(d, descriptor),
+            result => panic!("expected invalid descriptor, got {:?}", result),
+        }
+    }
+    assert!(matches!(
+        vm.invoke_static(CALCULATOR, "add", "(II)I", &[1.into()]),
+        Err(VmError::ArgumentCount {
+            expected: 2,
+            actual: 1
+        })
+    ));
+    assert!(matches!(
+        vm.invoke_static(CALCULATOR, "add", "(II)I", &[1.into(), 2.5.into()]),
+        Err(VmError::ArgumentType { index: 1, .. })
+    ));
+
+    Ok(())
+}